@@ -0,0 +1,546 @@
+//! Backtesting Harness
+//!
+//! Replays historical bars through the risk manager: strategies emit order
+//! intents, which are sized, risk-checked and filled against the next bar,
+//! while stops and P&L are tracked exactly as in live trading.
+
+use chrono::{DateTime, Utc};
+use common::{
+    config::RiskConfig,
+    types::{Bar, Currency, Order, OrderStatus, OrderType, Position, Price, Quantity, Side, Symbol, TimeInForce},
+    Result,
+};
+use risk_manager::{PerformanceStats, RiskManagerService, StopLossConfig};
+use serde::Serialize;
+use std::collections::HashMap;
+use tracing::{debug, info, warn};
+
+/// Positions smaller than this are treated as flat (float dust)
+const QUANTITY_EPSILON: f64 = 1e-9;
+
+/// How an intent's quantity is determined
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sizing {
+    /// A fixed number of units
+    Units(f64),
+    /// Fixed-fractional: lose `fraction` of equity if `stop` is hit. The
+    /// stop is also installed as the position's stop-loss once filled.
+    RiskFraction { fraction: f64, stop: Price },
+}
+
+/// An order a strategy wants placed
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderIntent {
+    pub symbol: Symbol,
+    pub side: Side,
+    pub sizing: Sizing,
+    /// Limit price; `None` for a market order
+    pub limit_price: Option<Price>,
+}
+
+impl OrderIntent {
+    pub fn market(symbol: Symbol, side: Side, quantity: f64) -> Self {
+        Self { symbol, side, sizing: Sizing::Units(quantity), limit_price: None }
+    }
+
+    pub fn limit(symbol: Symbol, side: Side, quantity: f64, price: Price) -> Self {
+        Self { symbol, side, sizing: Sizing::Units(quantity), limit_price: Some(price) }
+    }
+
+    /// Market order sized to risk `fraction` of equity down to `stop`
+    pub fn risk_sized(symbol: Symbol, side: Side, fraction: f64, stop: Price) -> Self {
+        Self { symbol, side, sizing: Sizing::RiskFraction { fraction, stop }, limit_price: None }
+    }
+}
+
+/// Read-only view of the account passed to `Strategy::on_bar`
+#[derive(Debug, Clone)]
+pub struct BacktestContext {
+    bar_index: usize,
+    timestamp: DateTime<Utc>,
+    cash: f64,
+    equity: f64,
+    positions: HashMap<String, f64>,
+}
+
+impl BacktestContext {
+    /// Index of the current bar in the input
+    pub fn bar_index(&self) -> usize {
+        self.bar_index
+    }
+
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    pub fn cash(&self) -> f64 {
+        self.cash
+    }
+
+    /// Cash plus open positions marked at their last close
+    pub fn equity(&self) -> f64 {
+        self.equity
+    }
+
+    /// Signed position quantity (negative when short, zero when flat)
+    pub fn position(&self, symbol: &Symbol) -> f64 {
+        self.positions.get(&symbol.0).copied().unwrap_or(0.0)
+    }
+}
+
+/// Trading strategy driven bar by bar
+pub trait Strategy {
+    fn on_bar(&mut self, bar: &Bar, ctx: &BacktestContext) -> Vec<OrderIntent>;
+}
+
+/// Backtest settings
+#[derive(Debug, Clone)]
+pub struct BacktestConfig {
+    pub initial_equity: f64,
+    /// Adverse slippage applied to every fill, in basis points
+    pub slippage_bps: f64,
+    /// Commission charged per unit filled
+    pub commission_per_unit: f64,
+    /// Bars per year, for annualizing the Sharpe ratio (252 for daily bars)
+    pub periods_per_year: f64,
+    pub risk: RiskConfig,
+}
+
+impl BacktestConfig {
+    pub fn new(risk: RiskConfig) -> Self {
+        Self {
+            initial_equity: 100_000.0,
+            slippage_bps: 0.0,
+            commission_per_unit: 0.0,
+            periods_per_year: 252.0,
+            risk,
+        }
+    }
+}
+
+/// Summary of a backtest run
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestReport {
+    pub bars: usize,
+    pub initial_equity: f64,
+    pub final_equity: f64,
+    /// `final_equity / initial_equity - 1`
+    pub total_return: f64,
+    /// Largest peak-to-trough P&L decline, from the risk manager's tracker
+    pub max_drawdown: f64,
+    /// `max_drawdown` as a fraction of initial equity
+    pub max_drawdown_pct: f64,
+    /// Fills that closed (part of) a position
+    pub closed_trades: u64,
+    pub winning_trades: u64,
+    pub win_rate: f64,
+    /// Annualized Sharpe ratio of per-bar equity returns (zero risk-free rate)
+    pub sharpe_ratio: f64,
+    /// Full return statistics of the per-bar equity curve
+    pub performance: PerformanceStats,
+    pub orders_rejected: u64,
+    pub stops_triggered: u64,
+    pub total_commission: f64,
+    /// Equity after each bar
+    pub equity_curve: Vec<(DateTime<Utc>, f64)>,
+}
+
+/// Order accepted by risk, waiting for the next bar of its symbol
+#[derive(Debug, Clone)]
+struct PendingOrder {
+    order: Order,
+    stop: Option<Price>,
+}
+
+/// Runs strategies over historical bars
+pub struct Backtester {
+    config: BacktestConfig,
+    risk: RiskManagerService,
+    cash: f64,
+    /// Signed quantity and open time per symbol
+    positions: HashMap<String, (f64, DateTime<Utc>)>,
+    last_prices: HashMap<String, Price>,
+    pending: HashMap<String, Vec<PendingOrder>>,
+    next_order_id: u64,
+    closed_trades: u64,
+    winning_trades: u64,
+    orders_rejected: u64,
+    stops_triggered: u64,
+    total_commission: f64,
+    equity_curve: Vec<(DateTime<Utc>, f64)>,
+}
+
+impl Backtester {
+    pub fn new(config: BacktestConfig) -> Result<Self> {
+        config.risk.validate()?;
+        Ok(Self {
+            risk: RiskManagerService::new(config.risk.clone())?,
+            cash: config.initial_equity,
+            config,
+            positions: HashMap::new(),
+            last_prices: HashMap::new(),
+            pending: HashMap::new(),
+            next_order_id: 0,
+            closed_trades: 0,
+            winning_trades: 0,
+            orders_rejected: 0,
+            stops_triggered: 0,
+            total_commission: 0.0,
+            equity_curve: Vec::new(),
+        })
+    }
+
+    /// Replay `bars` (in time order, any mix of symbols) through `strategy`
+    ///
+    /// For each bar: pending orders for the symbol fill against it, the
+    /// position is marked at the close and its stop checked, then the
+    /// strategy sees the bar. Orders placed on a bar fill no earlier than the
+    /// next bar of the same symbol, so strategies cannot trade on the close
+    /// they just observed. Unfilled limit orders expire after one bar.
+    pub fn run(mut self, bars: Vec<Bar>, mut strategy: impl Strategy) -> BacktestReport {
+        info!("Starting backtest over {} bars", bars.len());
+
+        for (index, bar) in bars.iter().enumerate() {
+            self.fill_pending(bar);
+            self.last_prices.insert(bar.symbol.0.clone(), bar.close);
+            self.mark_and_check_stop(bar);
+            self.equity_curve.push((bar.timestamp, self.equity()));
+
+            let ctx = self.context(index, bar.timestamp);
+            for intent in strategy.on_bar(bar, &ctx) {
+                self.submit(intent, bar);
+            }
+        }
+
+        self.report(bars.len())
+    }
+
+    fn equity(&self) -> f64 {
+        self.cash
+            + self
+                .positions
+                .iter()
+                .map(|(symbol, (qty, _))| qty * self.last_prices.get(symbol).map_or(0.0, |p| p.0))
+                .sum::<f64>()
+    }
+
+    fn context(&self, bar_index: usize, timestamp: DateTime<Utc>) -> BacktestContext {
+        BacktestContext {
+            bar_index,
+            timestamp,
+            cash: self.cash,
+            equity: self.equity(),
+            positions: self.positions.iter().map(|(s, (qty, _))| (s.clone(), *qty)).collect(),
+        }
+    }
+
+    /// Size, risk-check and queue an intent for the next bar
+    fn submit(&mut self, intent: OrderIntent, bar: &Bar) {
+        let entry = intent.limit_price.unwrap_or(bar.close);
+        let (quantity, stop) = match intent.sizing {
+            Sizing::Units(quantity) => (quantity, None),
+            Sizing::RiskFraction { fraction, stop } => {
+                let equity = self.equity();
+                let quantity = self.risk.position_sizer().fixed_fractional(equity, fraction, entry, stop);
+                (quantity.0, Some(stop))
+            }
+        };
+        if quantity <= QUANTITY_EPSILON || !quantity.is_finite() {
+            return;
+        }
+
+        self.next_order_id += 1;
+        let id = format!("bt-{}", self.next_order_id);
+        let order = Order {
+            order_id: id.clone(),
+            client_order_id: id,
+            symbol: intent.symbol.clone(),
+            side: intent.side,
+            order_type: if intent.limit_price.is_some() { OrderType::Limit } else { OrderType::Market },
+            quantity: Quantity(quantity),
+            price: intent.limit_price,
+            stop_price: None,
+            time_in_force: TimeInForce::Gtc,
+            status: OrderStatus::Pending,
+            filled_quantity: Quantity(0.0),
+            average_price: None,
+            created_at: bar.timestamp,
+            updated_at: bar.timestamp,
+        };
+
+        // Exits only reduce exposure, so they skip the limit checks
+        let held = self.position_quantity(&intent.symbol);
+        let signed = signed_quantity(intent.side, quantity);
+        let reduces = held * signed < 0.0 && quantity <= held.abs() + QUANTITY_EPSILON;
+
+        let checked = if reduces {
+            order.validate()
+        } else {
+            // Value market orders at the bar close so size limits apply
+            let mut priced = order.clone();
+            if priced.price.is_none() {
+                priced.order_type = OrderType::Limit;
+                priced.price = Some(entry);
+            }
+            self.risk.check_order(&priced).map(|_| ())
+        };
+        if let Err(e) = checked {
+            debug!("Backtest order {} rejected: {}", order.order_id, e);
+            self.orders_rejected += 1;
+            return;
+        }
+
+        self.pending
+            .entry(intent.symbol.0)
+            .or_default()
+            .push(PendingOrder { order, stop });
+    }
+
+    fn fill_pending(&mut self, bar: &Bar) {
+        let Some(orders) = self.pending.remove(&bar.symbol.0) else {
+            return;
+        };
+
+        for pending in orders {
+            let order = &pending.order;
+            let price = match (order.side, order.price) {
+                (_, None) => Some(bar.open),
+                (Side::Bid, Some(limit)) => (bar.low.0 <= limit.0).then_some(Price(bar.open.0.min(limit.0))),
+                (Side::Ask, Some(limit)) => (bar.high.0 >= limit.0).then_some(Price(bar.open.0.max(limit.0))),
+            };
+            match price {
+                Some(price) => {
+                    self.apply_fill(&order.symbol, order.side, order.quantity.0, price, bar.timestamp);
+                    if let Some(stop) = pending.stop {
+                        self.install_stop(&order.symbol, stop, bar);
+                    }
+                }
+                None => debug!("Backtest order {} expired unfilled", order.order_id),
+            }
+        }
+    }
+
+    /// Book a fill: cash, commission, FIFO P&L and position tracking
+    fn apply_fill(&mut self, symbol: &Symbol, side: Side, quantity: f64, price: Price, at: DateTime<Utc>) {
+        let slippage = price.0 * self.config.slippage_bps / 10_000.0;
+        let fill_price = match side {
+            Side::Bid => price.0 + slippage,
+            Side::Ask => price.0 - slippage,
+        };
+        let commission = quantity * self.config.commission_per_unit;
+        let signed = signed_quantity(side, quantity);
+
+        self.cash -= signed * fill_price + commission;
+        self.total_commission += commission;
+
+        let realized = self.risk.record_fill(symbol, side, Quantity(quantity), Price(fill_price));
+
+        let held = self.position_quantity(symbol);
+        if held * signed < 0.0 {
+            self.closed_trades += 1;
+            if realized > 0.0 {
+                self.winning_trades += 1;
+            }
+        }
+
+        let remaining = held + signed;
+        if remaining.abs() <= QUANTITY_EPSILON {
+            self.positions.remove(&symbol.0);
+            self.risk.close_position(symbol, at);
+        } else if held * remaining <= 0.0 {
+            // Opened, or flipped through flat: any old stop was for the other side
+            self.risk.remove_stop_loss(symbol);
+            self.positions.insert(symbol.0.clone(), (remaining, at));
+        } else if let Some(position) = self.positions.get_mut(&symbol.0) {
+            position.0 = remaining;
+        }
+    }
+
+    fn install_stop(&mut self, symbol: &Symbol, stop: Price, bar: &Bar) {
+        let Some(position) = self.position(symbol, bar.close, bar.timestamp) else {
+            return;
+        };
+        let installed = StopLossConfig::absolute_stop(stop).and_then(|config| self.risk.set_stop_loss(&position, config));
+        if let Err(e) = installed {
+            warn!("Could not install backtest stop for {}: {}", symbol.0, e);
+        }
+    }
+
+    /// Mark the bar's position at the close; exit at the close if its stop fired
+    fn mark_and_check_stop(&mut self, bar: &Bar) {
+        let Some(position) = self.position(&bar.symbol, bar.close, bar.timestamp) else {
+            return;
+        };
+        let Some(trigger) = self.risk.update_position(position) else {
+            return;
+        };
+
+        self.stops_triggered += 1;
+        let exit_side = match trigger.position.side {
+            Side::Bid => Side::Ask,
+            Side::Ask => Side::Bid,
+        };
+        let quantity = trigger.close_quantity().0.min(self.position_quantity(&bar.symbol).abs());
+        debug!("Backtest stop for {} at {}: {}", bar.symbol.0, bar.close.0, trigger.reason);
+        self.apply_fill(&bar.symbol, exit_side, quantity, bar.close, bar.timestamp);
+    }
+
+    fn position_quantity(&self, symbol: &Symbol) -> f64 {
+        self.positions.get(&symbol.0).map_or(0.0, |(qty, _)| *qty)
+    }
+
+    fn position(&self, symbol: &Symbol, price: Price, at: DateTime<Utc>) -> Option<Position> {
+        let (_, opened_at) = self.positions.get(&symbol.0)?;
+        let state = self.risk.pnl_tracker().get_position(&symbol.0)?;
+        let direction = match state.side {
+            Side::Bid => 1.0,
+            Side::Ask => -1.0,
+        };
+        Some(Position {
+            symbol: symbol.clone(),
+            side: state.side,
+            quantity: state.quantity,
+            entry_price: state.avg_entry_price,
+            current_price: price,
+            unrealized_pnl: direction * (price.0 - state.avg_entry_price.0) * state.quantity.0,
+            realized_pnl: state.realized_pnl,
+            opened_at: *opened_at,
+            updated_at: at,
+            currency: Currency::default(),
+        })
+    }
+
+    fn report(&self, bars: usize) -> BacktestReport {
+        let initial_equity = self.config.initial_equity;
+        let final_equity = self.equity();
+        let max_drawdown = self.risk.pnl_tracker().max_drawdown();
+        let equity: Vec<f64> = self.equity_curve.iter().map(|(_, e)| *e).collect();
+        let performance = PerformanceStats::from_returns_with_periods(
+            &PerformanceStats::returns_from_equity(&equity),
+            0.0,
+            self.config.periods_per_year,
+        );
+
+        BacktestReport {
+            bars,
+            initial_equity,
+            final_equity,
+            total_return: final_equity / initial_equity - 1.0,
+            max_drawdown,
+            max_drawdown_pct: max_drawdown / initial_equity,
+            closed_trades: self.closed_trades,
+            winning_trades: self.winning_trades,
+            win_rate: if self.closed_trades > 0 {
+                self.winning_trades as f64 / self.closed_trades as f64
+            } else {
+                0.0
+            },
+            sharpe_ratio: performance.sharpe,
+            performance,
+            orders_rejected: self.orders_rejected,
+            stops_triggered: self.stops_triggered,
+            total_commission: self.total_commission,
+            equity_curve: self.equity_curve.clone(),
+        }
+    }
+}
+
+fn signed_quantity(side: Side, quantity: f64) -> f64 {
+    match side {
+        Side::Bid => quantity,
+        Side::Ask => -quantity,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn risk_config() -> RiskConfig {
+        RiskConfig {
+            max_position_size: 50_000.0,
+            max_notional_exposure: 100_000.0,
+            max_open_positions: 5,
+            stop_loss_percent: 5.0,
+            trailing_stop_percent: 3.0,
+            enable_circuit_breaker: true,
+            max_loss_threshold: 10_000.0,
+            circuit_breaker_cooldown_secs: 300,
+            max_price_velocity_pct: 50.0,
+            max_leverage: 2.0,
+        }
+    }
+
+    fn bars(closes: &[f64]) -> Vec<Bar> {
+        let start = Utc::now();
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| Bar {
+                symbol: Symbol("AAPL".to_string()),
+                open: Price(close),
+                high: Price(close * 1.01),
+                low: Price(close * 0.99),
+                close: Price(close),
+                volume: Quantity(1000.0),
+                timestamp: start + Duration::days(i as i64),
+            })
+            .collect()
+    }
+
+    /// Buys 100 on the first bar and sells on bar `exit_at`
+    struct BuyAndExit {
+        exit_at: usize,
+    }
+
+    impl Strategy for BuyAndExit {
+        fn on_bar(&mut self, bar: &Bar, ctx: &BacktestContext) -> Vec<OrderIntent> {
+            if ctx.bar_index() == 0 {
+                vec![OrderIntent::market(bar.symbol.clone(), Side::Bid, 100.0)]
+            } else if ctx.bar_index() == self.exit_at && ctx.position(&bar.symbol) > 0.0 {
+                vec![OrderIntent::market(bar.symbol.clone(), Side::Ask, ctx.position(&bar.symbol))]
+            } else {
+                Vec::new()
+            }
+        }
+    }
+
+    #[test]
+    fn test_winning_round_trip() {
+        let backtester = Backtester::new(BacktestConfig::new(risk_config())).unwrap();
+        let report = backtester.run(bars(&[100.0, 101.0, 103.0, 102.0, 106.0, 107.0]), BuyAndExit { exit_at: 4 });
+
+        // Bought at bar 1's open (101), sold at bar 5's open (107)
+        assert_eq!(report.closed_trades, 1);
+        assert_eq!(report.win_rate, 1.0);
+        assert!((report.final_equity - 100_600.0).abs() < 1e-6);
+        assert!((report.total_return - 0.006).abs() < 1e-9);
+        assert_eq!(report.max_drawdown, 100.0); // 103 -> 102 on 100 shares
+        assert!(report.sharpe_ratio > 0.0);
+        assert_eq!(report.equity_curve.len(), 6);
+    }
+
+    #[test]
+    fn test_stop_loss_exits_losing_position() {
+        let backtester = Backtester::new(BacktestConfig::new(risk_config())).unwrap();
+        let report = backtester.run(bars(&[100.0, 100.0, 97.0, 90.0, 80.0]), BuyAndExit { exit_at: usize::MAX });
+
+        // The default 5% stop fires at the 90 close
+        assert_eq!(report.stops_triggered, 1);
+        assert_eq!(report.closed_trades, 1);
+        assert_eq!(report.win_rate, 0.0);
+        assert!((report.final_equity - 99_000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_risk_limits_reject_oversized_orders() {
+        let mut config = BacktestConfig::new(risk_config());
+        config.risk.max_position_size = 5_000.0;
+        let backtester = Backtester::new(config).unwrap();
+        let report = backtester.run(bars(&[100.0, 101.0]), BuyAndExit { exit_at: usize::MAX });
+
+        assert_eq!(report.orders_rejected, 1);
+        assert_eq!(report.final_equity, 100_000.0);
+    }
+}
@@ -0,0 +1,328 @@
+//! Fluent builders for domain objects, mainly for tests and fixtures
+use chrono::{DateTime, Utc};
+use crate::types::{Currency, Order, OrderStatus, OrderType, Position, Price, Quantity, Side, Symbol, TimeInForce, Trade};
+
+// Builder values are plain `f64`s so fixtures read the same with or without
+// the `common_decimal` cfg. Without it, non-finite values pass through so tests
+// can build invalid orders; `Decimal` cannot represent them.
+#[cfg(not(common_decimal))]
+fn to_price(value: f64) -> Price {
+    Price(value)
+}
+
+#[cfg(not(common_decimal))]
+fn to_quantity(value: f64) -> Quantity {
+    Quantity(value)
+}
+
+#[cfg(common_decimal)]
+fn to_price(value: f64) -> Price {
+    Price::from_f64(value).expect("builder price must be finite")
+}
+
+#[cfg(common_decimal)]
+fn to_quantity(value: f64) -> Quantity {
+    Quantity::from_f64(value).expect("builder quantity must be finite")
+}
+
+/// Builds an `Order`. Defaults to a pending 100-share AAPL market buy.
+#[derive(Debug, Clone)]
+pub struct OrderBuilder {
+    order: Order,
+}
+
+impl OrderBuilder {
+    pub fn new() -> Self {
+        let now = Utc::now();
+        Self {
+            order: Order {
+                order_id: "test-order".to_string(),
+                client_order_id: "test-client-order".to_string(),
+                symbol: Symbol("AAPL".to_string()),
+                side: Side::Bid,
+                order_type: OrderType::Market,
+                quantity: to_quantity(100.0),
+                price: None,
+                stop_price: None,
+                time_in_force: TimeInForce::Gtc,
+                status: OrderStatus::Pending,
+                filled_quantity: to_quantity(0.0),
+                average_price: None,
+                created_at: now,
+                updated_at: now,
+            },
+        }
+    }
+
+    pub fn order_id(mut self, id: &str) -> Self {
+        self.order.order_id = id.to_string();
+        self
+    }
+
+    pub fn client_order_id(mut self, id: &str) -> Self {
+        self.order.client_order_id = id.to_string();
+        self
+    }
+
+    pub fn symbol(mut self, symbol: &str) -> Self {
+        self.order.symbol = Symbol(symbol.to_string());
+        self
+    }
+
+    pub fn side(mut self, side: Side) -> Self {
+        self.order.side = side;
+        self
+    }
+
+    pub fn buy(self) -> Self {
+        self.side(Side::Bid)
+    }
+
+    pub fn sell(self) -> Self {
+        self.side(Side::Ask)
+    }
+
+    pub fn quantity(mut self, qty: f64) -> Self {
+        self.order.quantity = to_quantity(qty);
+        self
+    }
+
+    /// Market order; clears any limit and stop price
+    pub fn market(mut self) -> Self {
+        self.order.order_type = OrderType::Market;
+        self.order.price = None;
+        self.order.stop_price = None;
+        self
+    }
+
+    pub fn limit(mut self, price: f64) -> Self {
+        self.order.order_type = OrderType::Limit;
+        self.order.price = Some(to_price(price));
+        self.order.stop_price = None;
+        self
+    }
+
+    pub fn stop_market(mut self, stop_price: f64) -> Self {
+        self.order.order_type = OrderType::StopMarket;
+        self.order.price = None;
+        self.order.stop_price = Some(to_price(stop_price));
+        self
+    }
+
+    pub fn stop_limit(mut self, stop_price: f64, limit_price: f64) -> Self {
+        self.order.order_type = OrderType::StopLimit;
+        self.order.price = Some(to_price(limit_price));
+        self.order.stop_price = Some(to_price(stop_price));
+        self
+    }
+
+    pub fn time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.order.time_in_force = time_in_force;
+        self
+    }
+
+    pub fn status(mut self, status: OrderStatus) -> Self {
+        self.order.status = status;
+        self
+    }
+
+    /// Record a fill; the status becomes Filled or PartiallyFilled accordingly
+    pub fn filled(mut self, qty: f64, avg_price: f64) -> Self {
+        self.order.filled_quantity = to_quantity(qty);
+        self.order.average_price = Some(to_price(avg_price));
+        self.order.status = if qty >= self.order.quantity.to_f64() {
+            OrderStatus::Filled
+        } else {
+            OrderStatus::PartiallyFilled
+        };
+        self
+    }
+
+    pub fn created_at(mut self, at: DateTime<Utc>) -> Self {
+        self.order.created_at = at;
+        self.order.updated_at = at;
+        self
+    }
+
+    pub fn build(self) -> Order {
+        self.order
+    }
+}
+
+impl Default for OrderBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builds a `Position`, deriving unrealized P&L from the side, quantity and
+/// prices. Defaults to a long 100-share AAPL position at 150.00 marked at 155.00.
+#[derive(Debug, Clone)]
+pub struct PositionBuilder {
+    symbol: Symbol,
+    side: Side,
+    quantity: f64,
+    entry_price: f64,
+    current_price: f64,
+    realized_pnl: f64,
+    currency: Currency,
+    opened_at: DateTime<Utc>,
+}
+
+impl PositionBuilder {
+    pub fn new() -> Self {
+        Self {
+            symbol: Symbol("AAPL".to_string()),
+            side: Side::Bid,
+            quantity: 100.0,
+            entry_price: 150.0,
+            current_price: 155.0,
+            realized_pnl: 0.0,
+            currency: Currency::default(),
+            opened_at: Utc::now(),
+        }
+    }
+
+    pub fn symbol(mut self, symbol: &str) -> Self {
+        self.symbol = Symbol(symbol.to_string());
+        self
+    }
+
+    pub fn side(mut self, side: Side) -> Self {
+        self.side = side;
+        self
+    }
+
+    pub fn long(self) -> Self {
+        self.side(Side::Bid)
+    }
+
+    pub fn short(self) -> Self {
+        self.side(Side::Ask)
+    }
+
+    pub fn quantity(mut self, qty: f64) -> Self {
+        self.quantity = qty;
+        self
+    }
+
+    pub fn entry_price(mut self, price: f64) -> Self {
+        self.entry_price = price;
+        self
+    }
+
+    pub fn current_price(mut self, price: f64) -> Self {
+        self.current_price = price;
+        self
+    }
+
+    pub fn realized_pnl(mut self, pnl: f64) -> Self {
+        self.realized_pnl = pnl;
+        self
+    }
+
+    pub fn currency(mut self, currency: Currency) -> Self {
+        self.currency = currency;
+        self
+    }
+
+    pub fn opened_at(mut self, at: DateTime<Utc>) -> Self {
+        self.opened_at = at;
+        self
+    }
+
+    /// Mark the position `profit_percent` in the money
+    pub fn with_profit(self, profit_percent: f64) -> Self {
+        let direction = if self.side == Side::Bid { 1.0 } else { -1.0 };
+        let price = self.entry_price * (1.0 + direction * profit_percent / 100.0);
+        self.current_price(price)
+    }
+
+    /// Mark the position `loss_percent` out of the money
+    pub fn with_loss(self, loss_percent: f64) -> Self {
+        self.with_profit(-loss_percent)
+    }
+
+    pub fn build(self) -> Position {
+        let direction = if self.side == Side::Bid { 1.0 } else { -1.0 };
+        Position {
+            symbol: self.symbol,
+            side: self.side,
+            quantity: to_quantity(self.quantity),
+            entry_price: to_price(self.entry_price),
+            current_price: to_price(self.current_price),
+            unrealized_pnl: direction * (self.current_price - self.entry_price) * self.quantity,
+            realized_pnl: self.realized_pnl,
+            opened_at: self.opened_at,
+            updated_at: Utc::now(),
+            currency: self.currency,
+        }
+    }
+}
+
+impl Default for PositionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builds a `Trade`. Defaults to a 100-share AAPL buy at 150.00.
+#[derive(Debug, Clone)]
+pub struct TradeBuilder {
+    trade: Trade,
+}
+
+impl TradeBuilder {
+    pub fn new() -> Self {
+        Self {
+            trade: Trade {
+                symbol: Symbol("AAPL".to_string()),
+                price: to_price(150.0),
+                quantity: to_quantity(100.0),
+                side: Side::Bid,
+                timestamp: Utc::now(),
+                trade_id: "trade-1".to_string(),
+            },
+        }
+    }
+
+    pub fn trade_id(mut self, id: &str) -> Self {
+        self.trade.trade_id = id.to_string();
+        self
+    }
+
+    pub fn symbol(mut self, symbol: &str) -> Self {
+        self.trade.symbol = Symbol(symbol.to_string());
+        self
+    }
+
+    pub fn side(mut self, side: Side) -> Self {
+        self.trade.side = side;
+        self
+    }
+
+    pub fn quantity(mut self, qty: f64) -> Self {
+        self.trade.quantity = to_quantity(qty);
+        self
+    }
+
+    pub fn price(mut self, price: f64) -> Self {
+        self.trade.price = to_price(price);
+        self
+    }
+
+    pub fn timestamp(mut self, at: DateTime<Utc>) -> Self {
+        self.trade.timestamp = at;
+        self
+    }
+
+    pub fn build(self) -> Trade {
+        self.trade
+    }
+}
+
+impl Default for TradeBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,75 @@
+//! Injectable wall clock
+//!
+//! Time-based logic (stop holding periods, breaker cooldowns, feed
+//! staleness) reads the time through a `Clock` so tests can drive it with a
+//! `MockClock` instead of sleeping.
+
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Source of the current time
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Time elapsed since `since`; zero if `since` is in the future
+    fn elapsed(&self, since: DateTime<Utc>) -> Duration {
+        (self.now() - since).to_std().unwrap_or(Duration::ZERO)
+    }
+}
+
+/// Shared handle to a clock
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The real wall clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    /// Shared handle to the system clock, the default everywhere
+    pub fn shared() -> SharedClock {
+        Arc::new(SystemClock)
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Manually driven clock for tests
+#[derive(Debug)]
+pub struct MockClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl MockClock {
+    pub fn new(start: DateTime<Utc>) -> Arc<Self> {
+        Arc::new(Self {
+            now: Mutex::new(start),
+        })
+    }
+
+    /// Clock starting at the current wall time
+    pub fn starting_now() -> Arc<Self> {
+        Self::new(Utc::now())
+    }
+
+    /// Move the clock forward by `by`
+    pub fn advance(&self, by: Duration) {
+        let by = chrono::Duration::from_std(by).expect("duration out of range");
+        *self.now.lock().unwrap() += by;
+    }
+
+    /// Jump the clock to `at`
+    pub fn set(&self, at: DateTime<Utc>) {
+        *self.now.lock().unwrap() = at;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
@@ -1,6 +1,24 @@
+use chrono::NaiveTime;
+use notify::{EventKind, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
 use crate::errors::{Result, TradingError};
+use crate::types::InstrumentSpecs;
+
+/// Pause after a file event before re-reading, so editors that write in
+/// several steps are picked up as a single change
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Prefix for environment variables that override config file values
+pub const ENV_OVERRIDE_PREFIX: &str = "APP_";
+
+/// Separator between nesting levels in override variable names
+pub const ENV_OVERRIDE_SEPARATOR: &str = "__";
 
 /// Configuration for market data component
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,6 +28,60 @@ pub struct MarketDataConfig {
     pub websocket_url: String,
     pub reconnect_delay_ms: u64,
     pub zmq_publish_address: String,
+    /// Milliseconds without market data before the feed is considered stale (default: 30000)
+    #[serde(default = "default_stale_after_ms")]
+    pub stale_after_ms: u64,
+    /// Time bar windows such as "30s", "1m", "1h" or "1d" (default: 1m, 5m, 15m)
+    #[serde(default = "default_bar_windows")]
+    pub bar_windows: Vec<String>,
+    /// Exchange session that time bars align to; UTC midnight when absent
+    #[serde(default)]
+    pub session: Option<SessionConfig>,
+    /// Emit flat zero-volume bars for time windows without trades (default: false)
+    #[serde(default)]
+    pub forward_fill_bars: bool,
+}
+
+fn default_stale_after_ms() -> u64 {
+    30_000
+}
+
+fn default_bar_windows() -> Vec<String> {
+    vec!["1m".to_string(), "5m".to_string(), "15m".to_string()]
+}
+
+/// Regular trading hours of an exchange, in its local time zone
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionConfig {
+    /// IANA time zone name, e.g. "America/New_York"
+    pub timezone: String,
+    /// Session open as "HH:MM" local time
+    pub open: String,
+    /// Session close as "HH:MM" local time
+    pub close: String,
+}
+
+impl SessionConfig {
+    /// Parsed (open, close) local times
+    pub fn hours(&self) -> Result<(NaiveTime, NaiveTime)> {
+        let parse = |name: &str, value: &str| {
+            NaiveTime::parse_from_str(value, "%H:%M").map_err(|e| {
+                TradingError::Configuration(format!(
+                    "session.{} must be HH:MM, got {:?}: {}",
+                    name, value, e
+                ))
+            })
+        };
+        let open = parse("open", &self.open)?;
+        let close = parse("close", &self.close)?;
+        if open >= close {
+            return Err(TradingError::Configuration(format!(
+                "session.open ({}) must be before session.close ({})",
+                self.open, self.close
+            )));
+        }
+        Ok((open, close))
+    }
 }
 
 impl MarketDataConfig {
@@ -39,6 +111,22 @@ impl MarketDataConfig {
             ));
         }
 
+        if self.stale_after_ms < 1000 {
+            return Err(TradingError::Configuration(
+                "stale_after_ms must be at least 1000ms".to_string()
+            ));
+        }
+
+        if self.bar_windows.is_empty() {
+            return Err(TradingError::Configuration(
+                "bar_windows cannot be empty".to_string()
+            ));
+        }
+
+        if let Some(session) = &self.session {
+            session.hours()?;
+        }
+
         Ok(())
     }
 }
@@ -53,6 +141,27 @@ pub struct RiskConfig {
     pub trailing_stop_percent: f64,
     pub enable_circuit_breaker: bool,
     pub max_loss_threshold: f64,
+    /// Seconds a tripped circuit breaker stays open before allowing probes (default: 300)
+    #[serde(default = "default_circuit_breaker_cooldown_secs")]
+    pub circuit_breaker_cooldown_secs: u64,
+    /// Percent price move within the volatility window that halts a symbol (default: 10.0)
+    #[serde(default = "default_max_price_velocity_pct")]
+    pub max_price_velocity_pct: f64,
+    /// Maximum gross notional as a multiple of account equity (default: 2.0)
+    #[serde(default = "default_max_leverage")]
+    pub max_leverage: f64,
+}
+
+fn default_circuit_breaker_cooldown_secs() -> u64 {
+    300 // 5 minutes
+}
+
+fn default_max_price_velocity_pct() -> f64 {
+    10.0
+}
+
+fn default_max_leverage() -> f64 {
+    2.0 // Reg T overnight buying power
 }
 
 impl RiskConfig {
@@ -94,6 +203,18 @@ impl RiskConfig {
             ));
         }
 
+        if !self.max_price_velocity_pct.is_finite() || self.max_price_velocity_pct <= 0.0 {
+            return Err(TradingError::Configuration(
+                "max_price_velocity_pct must be positive".to_string()
+            ));
+        }
+
+        if !self.max_leverage.is_finite() || self.max_leverage <= 0.0 {
+            return Err(TradingError::Configuration(
+                "max_leverage must be positive".to_string()
+            ));
+        }
+
         Ok(())
     }
 }
@@ -111,12 +232,102 @@ pub struct ExecutionConfig {
     /// Maximum allowed slippage in basis points (default: 50.0 = 0.5%)
     #[serde(default = "default_max_slippage_bps")]
     pub max_slippage_bps: f64,
+    /// Per-symbol slippage limits in basis points, overriding `max_slippage_bps`
+    #[serde(default)]
+    pub symbol_max_slippage_bps: HashMap<String, f64>,
+    /// Commission charged on simulated (paper) fills; free when absent
+    #[serde(default)]
+    pub fee_model: FeeSchedule,
+    /// Tick and lot sizes per symbol; orders for listed symbols must conform
+    #[serde(default)]
+    pub instruments: InstrumentSpecs,
+    /// Exchange session whose close expires DAY orders; US equity hours
+    /// when absent
+    #[serde(default)]
+    pub session: Option<SessionConfig>,
+    /// Seconds after creation at which any still-open order is cancelled,
+    /// whatever its time in force; unlimited when absent
+    #[serde(default)]
+    pub max_order_age_secs: Option<u64>,
+}
+
+/// Commission schedule for simulated fills
+///
+/// In JSON: `{"type": "per_share", "rate": 0.005, "minimum": 1.0}`.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeeSchedule {
+    /// No commission
+    #[default]
+    None,
+    /// Fixed fee per order, spread pro rata over its fills
+    FlatPerOrder { fee: f64 },
+    /// Fee per share (unit) filled, with an optional per-fill minimum
+    PerShare {
+        rate: f64,
+        #[serde(default)]
+        minimum: f64,
+    },
+    /// Fee in basis points of fill notional, with an optional per-fill minimum
+    BasisPoints {
+        bps: f64,
+        #[serde(default)]
+        minimum: f64,
+    },
+}
+
+impl FeeSchedule {
+    /// Every fee amount must be finite and non-negative
+    pub fn validate(&self) -> Result<()> {
+        let amounts: &[(&str, f64)] = match self {
+            FeeSchedule::None => &[],
+            FeeSchedule::FlatPerOrder { fee } => &[("fee", *fee)],
+            FeeSchedule::PerShare { rate, minimum } => &[("rate", *rate), ("minimum", *minimum)],
+            FeeSchedule::BasisPoints { bps, minimum } => &[("bps", *bps), ("minimum", *minimum)],
+        };
+
+        for (name, value) in amounts {
+            if *value < 0.0 || !value.is_finite() {
+                return Err(TradingError::Configuration(format!(
+                    "fee_model.{} must be a finite non-negative number, got {}",
+                    name, value
+                )));
+            }
+        }
+        Ok(())
+    }
 }
 
 fn default_max_slippage_bps() -> f64 {
     50.0 // 50 basis points = 0.5%
 }
 
+/// Slippage limits must be finite, positive and <= 500 bps (5%)
+fn validate_slippage_bps(field: &str, bps: f64) -> Result<()> {
+    if !bps.is_finite() {
+        return Err(TradingError::Configuration(
+            format!("{} must be a finite number (not NaN or Infinity)", field)
+        ));
+    }
+
+    if bps <= 0.0 {
+        return Err(TradingError::Configuration(
+            format!("{} must be positive", field)
+        ));
+    }
+
+    if bps > 500.0 {
+        return Err(TradingError::Configuration(
+            format!(
+                "{} ({}) exceeds maximum allowed (500 bps = 5%)",
+                field, bps
+            )
+        ));
+    }
+
+    Ok(())
+}
+
 impl ExecutionConfig {
     /// Validate execution configuration
     pub fn validate(&self) -> Result<()> {
@@ -145,24 +356,22 @@ impl ExecutionConfig {
         }
 
         // Validate max_slippage_bps: must be finite, positive and <= 500 bps (5%)
-        if !self.max_slippage_bps.is_finite() {
-            return Err(TradingError::Configuration(
-                "max_slippage_bps must be a finite number (not NaN or Infinity)".to_string()
-            ));
+        validate_slippage_bps("max_slippage_bps", self.max_slippage_bps)?;
+
+        for (symbol, bps) in &self.symbol_max_slippage_bps {
+            validate_slippage_bps(&format!("symbol_max_slippage_bps[{}]", symbol), *bps)?;
         }
 
-        if self.max_slippage_bps <= 0.0 {
-            return Err(TradingError::Configuration(
-                "max_slippage_bps must be positive".to_string()
-            ));
+        self.fee_model.validate()?;
+        self.instruments.validate()?;
+
+        if let Some(session) = &self.session {
+            session.hours()?;
         }
 
-        if self.max_slippage_bps > 500.0 {
+        if self.max_order_age_secs == Some(0) {
             return Err(TradingError::Configuration(
-                format!(
-                    "max_slippage_bps ({}) exceeds maximum allowed (500 bps = 5%)",
-                    self.max_slippage_bps
-                )
+                "max_order_age_secs must be at least 1".to_string()
             ));
         }
 
@@ -309,12 +518,38 @@ pub struct SystemConfig {
 impl SystemConfig {
     /// Load configuration from file and validate all components
     pub fn from_file(path: &str) -> Result<Self> {
+        Self::from_value(Self::read_value(path)?, path)
+    }
+
+    /// Load configuration from file with `APP_`-prefixed environment
+    /// variables layered on top, then validate the merged result.
+    ///
+    /// Precedence: environment variables override values from the file.
+    /// Nesting levels are separated by `__` and names are case-insensitive,
+    /// so `APP_EXECUTION__RATE_LIMIT_PER_SECOND=20` sets
+    /// `execution.rate_limit_per_second`. Values are parsed as JSON where
+    /// possible (numbers, booleans, arrays) and taken as plain strings
+    /// otherwise, or when the field being overridden is a string.
+    pub fn from_file_with_env(path: &str) -> Result<Self> {
+        let mut value = Self::read_value(path)?;
+        apply_env_overrides(&mut value, std::env::vars())?;
+        Self::from_value(value, path)
+    }
+
+    fn read_value(path: &str) -> Result<serde_json::Value> {
         let content = std::fs::read_to_string(path)
             .map_err(|e| TradingError::Configuration(
                 format!("failed to read config file {}: {}", path, e)
             ))?;
 
-        let mut config: Self = serde_json::from_str(&content)
+        serde_json::from_str(&content)
+            .map_err(|e| TradingError::Configuration(
+                format!("failed to parse config file {}: {}", path, e)
+            ))
+    }
+
+    fn from_value(value: serde_json::Value, path: &str) -> Result<Self> {
+        let mut config: Self = serde_json::from_value(value)
             .map_err(|e| TradingError::Configuration(
                 format!("failed to parse config file {}: {}", path, e)
             ))?;
@@ -331,6 +566,73 @@ impl SystemConfig {
         Ok(config)
     }
 
+    /// Load configuration and watch the file for changes.
+    ///
+    /// Every change is re-parsed and validated with `from_file_with_env`, so
+    /// `APP_*` overrides keep applying across reloads; valid configs
+    /// are sent on the returned channel, invalid ones are logged and ignored
+    /// so the last good config stays in effect. Watching stops once the
+    /// receiver is dropped.
+    pub fn watch(path: &str) -> Result<(Self, mpsc::Receiver<Self>)> {
+        let config = Self::from_file_with_env(path)?;
+
+        let file = Path::new(path).to_path_buf();
+        let file_name = file.file_name().map(|n| n.to_os_string());
+        let dir = match file.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => Path::new(".").to_path_buf(),
+        };
+
+        let (event_tx, event_rx) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(event_tx)
+            .map_err(|e| TradingError::Configuration(format!("failed to create config watcher: {}", e)))?;
+        // Watch the directory rather than the file: editors often replace
+        // the file with a rename, which would silently end a file watch
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(|e| TradingError::Configuration(
+                format!("failed to watch config directory {}: {}", dir.display(), e)
+            ))?;
+
+        let (tx, rx) = mpsc::channel(8);
+        let path = path.to_string();
+        std::thread::spawn(move || {
+            // Keep the watcher alive for as long as this thread runs
+            let _watcher = watcher;
+
+            while let Ok(event) = event_rx.recv() {
+                let relevant = match event {
+                    Ok(event) => {
+                        matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                            && event.paths.iter().any(|p| p.file_name().map(|n| n.to_os_string()) == file_name)
+                    }
+                    Err(e) => {
+                        warn!("Config watcher error for {}: {}", path, e);
+                        false
+                    }
+                };
+                if !relevant {
+                    continue;
+                }
+
+                std::thread::sleep(RELOAD_DEBOUNCE);
+                while event_rx.try_recv().is_ok() {}
+
+                match Self::from_file_with_env(&path) {
+                    Ok(config) => {
+                        info!("Reloaded configuration from {}", path);
+                        if tx.blocking_send(config).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => warn!("Ignoring invalid configuration change in {}: {}", path, e),
+                }
+            }
+        });
+
+        Ok((config, rx))
+    }
+
     /// Save configuration to file
     pub fn to_file(&self, path: &str) -> Result<()> {
         let content = serde_json::to_string_pretty(self)
@@ -364,3 +666,79 @@ impl SystemConfig {
         self.execution.paper_trading
     }
 }
+
+/// Apply `APP_`-prefixed overrides from `vars` onto a parsed config.
+///
+/// Variables without the prefix are ignored. See
+/// `SystemConfig::from_file_with_env` for the naming and value rules.
+pub fn apply_env_overrides<I>(config: &mut serde_json::Value, vars: I) -> Result<()>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    for (name, raw) in vars {
+        let Some(key) = name.strip_prefix(ENV_OVERRIDE_PREFIX) else {
+            continue;
+        };
+        let path: Vec<String> = key
+            .split(ENV_OVERRIDE_SEPARATOR)
+            .map(|part| part.to_lowercase())
+            .collect();
+        if path.iter().any(|part| part.is_empty()) {
+            return Err(TradingError::Configuration(format!("invalid override variable {}", name)));
+        }
+
+        let (leaf, parents) = path.split_last().expect("split yields at least one part");
+        let mut node = &mut *config;
+        for part in parents {
+            node = node
+                .as_object_mut()
+                .ok_or_else(|| TradingError::Configuration(format!("{} does not name a config section", name)))?
+                .entry(part.clone())
+                .or_insert_with(|| serde_json::Value::Object(Default::default()));
+        }
+        let section = node
+            .as_object_mut()
+            .ok_or_else(|| TradingError::Configuration(format!("{} does not name a config section", name)))?;
+
+        let value = match section.get(leaf) {
+            Some(serde_json::Value::String(_)) => serde_json::Value::String(raw),
+            _ => serde_json::from_str(&raw).unwrap_or(serde_json::Value::String(raw)),
+        };
+        info!("Config override from {}: {}", name, path.join("."));
+        section.insert(leaf.clone(), value);
+    }
+    Ok(())
+}
+
+/// Shared, live-swappable `SystemConfig`
+///
+/// Clones share the same config, so a reload applied through one handle is
+/// seen by every reader.
+#[derive(Debug, Clone)]
+pub struct SharedConfig {
+    inner: Arc<RwLock<SystemConfig>>,
+}
+
+impl SharedConfig {
+    pub fn new(config: SystemConfig) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(config)),
+        }
+    }
+
+    /// Snapshot of the current config
+    pub fn current(&self) -> SystemConfig {
+        self.inner.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Current risk limits
+    pub fn risk(&self) -> RiskConfig {
+        self.inner.read().unwrap_or_else(|e| e.into_inner()).risk.clone()
+    }
+
+    /// Swap in a new config, returning the previous one
+    pub fn replace(&self, config: SystemConfig) -> SystemConfig {
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        std::mem::replace(&mut *guard, config)
+    }
+}
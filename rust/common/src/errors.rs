@@ -1,3 +1,4 @@
+use std::fmt::Display;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, TradingError>;
@@ -34,6 +35,9 @@ pub enum TradingError {
     #[error("Parse error: {0}")]
     Parse(String),
 
+    #[error("Validation error: {0}")]
+    Validation(String),
+
     #[error("Risk error: {0}")]
     Risk(String),
 
@@ -45,4 +49,87 @@ pub enum TradingError {
 
     #[error("Unknown error: {0}")]
     Unknown(String),
+
+    /// Another error annotated with what was being done, e.g. the symbol or
+    /// order ID involved. See `with_context`.
+    #[error("{context}: {source}")]
+    Context {
+        context: String,
+        #[source]
+        source: Box<TradingError>,
+    },
+}
+
+impl TradingError {
+    /// Whether the operation that produced this error may succeed if retried.
+    ///
+    /// Transport and exchange-side failures are transient; validation, risk,
+    /// configuration and parse errors will fail the same way again.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            TradingError::Context { source, .. } => source.is_retryable(),
+            _ => matches!(
+                self,
+                TradingError::WebSocket(_)
+                    | TradingError::Messaging(_)
+                    | TradingError::Network(_)
+                    | TradingError::Exchange(_)
+                    | TradingError::Io(_)
+            ),
+        }
+    }
+
+    /// Stable machine-readable code for alerting and programmatic handling.
+    ///
+    /// Codes never change once published; context added with `with_context`
+    /// does not affect the code.
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            TradingError::MarketData(_) => "E_MARKET_DATA",
+            TradingError::WebSocket(_) => "E_NET_WEBSOCKET",
+            TradingError::OrderValidation(_) => "E_ORDER_INVALID",
+            TradingError::RiskCheck(_) => "E_RISK_CHECK",
+            TradingError::Execution(_) => "E_EXECUTION",
+            TradingError::Messaging(_) => "E_MESSAGING",
+            TradingError::Configuration(_) => "E_CONFIG",
+            TradingError::Network(_) => "E_NET",
+            TradingError::Exchange(_) => "E_EXCHANGE",
+            TradingError::Parse(_) => "E_PARSE",
+            TradingError::Validation(_) => "E_VALIDATION",
+            TradingError::Risk(_) => "E_RISK_LIMIT",
+            TradingError::Serialization(_) => "E_SERIALIZATION",
+            TradingError::Io(_) => "E_IO",
+            TradingError::Unknown(_) => "E_UNKNOWN",
+            TradingError::Context { source, .. } => source.error_code(),
+        }
+    }
+
+    /// Prefix the message with `context` (e.g. `"order abc123 for AAPL"`),
+    /// keeping the error code and retryability of the original error
+    pub fn with_context(self, context: impl Display) -> Self {
+        TradingError::Context {
+            context: context.to_string(),
+            source: Box::new(self),
+        }
+    }
+
+    /// The innermost error, with all context removed
+    pub fn root(&self) -> &TradingError {
+        match self {
+            TradingError::Context { source, .. } => source.root(),
+            _ => self,
+        }
+    }
+}
+
+/// `with_context` for results
+pub trait ResultExt<T> {
+    /// Add context to the error, computed only on failure
+    fn with_context<C: Display>(self, context: impl FnOnce() -> C) -> Result<T>;
+}
+
+impl<T> ResultExt<T> for Result<T> {
+    fn with_context<C: Display>(self, context: impl FnOnce() -> C) -> Result<T> {
+        self.map_err(|e| e.with_context(context()))
+    }
 }
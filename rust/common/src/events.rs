@@ -0,0 +1,128 @@
+//! In-process event bus connecting components
+//!
+//! Producers (the risk manager) publish `SystemSignal`s and consumers (the
+//! execution engine) subscribe. Publishing never blocks: a consumer that
+//! falls more than the channel capacity behind skips the oldest signals and
+//! is told how many it missed.
+
+use crate::types::{Price, Quantity, Side, Signal, Symbol};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError, error::TryRecvError};
+use tracing::warn;
+
+/// Default number of signals buffered per subscriber
+pub const DEFAULT_EVENT_CAPACITY: usize = 1024;
+
+/// A stop-loss or take-profit fired; close `quantity` on `close_side`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StopTriggered {
+    pub symbol: Symbol,
+    /// Side of the closing order (opposite the position)
+    pub close_side: Side,
+    pub quantity: Quantity,
+    pub trigger_price: Price,
+    pub current_price: Price,
+    pub reason: String,
+}
+
+/// Cross-component event
+#[derive(Debug, Clone)]
+pub enum SystemSignal {
+    StopTriggered(StopTriggered),
+    /// The global circuit breaker opened; new risk should not be taken
+    CircuitBreakerTripped { reason: String },
+    SignalGenerated(Signal),
+}
+
+impl SystemSignal {
+    /// Short name for logs and metrics
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SystemSignal::StopTriggered(_) => "stop_triggered",
+            SystemSignal::CircuitBreakerTripped { .. } => "circuit_breaker_tripped",
+            SystemSignal::SignalGenerated(_) => "signal_generated",
+        }
+    }
+}
+
+/// Broadcast bus for `SystemSignal`s; clones share the same channel
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<SystemSignal>,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self { sender }
+    }
+
+    /// Publish to every current subscriber, returning how many there were.
+    /// Never blocks; with no subscribers the signal is dropped.
+    pub fn publish(&self, signal: SystemSignal) -> usize {
+        metrics::counter!("event_bus_published_total", "kind" => signal.kind()).increment(1);
+        self.sender.send(signal).unwrap_or(0)
+    }
+
+    /// Receive signals published after this call
+    pub fn subscribe(&self) -> EventReceiver {
+        EventReceiver {
+            inner: self.sender.subscribe(),
+            missed: 0,
+        }
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_CAPACITY)
+    }
+}
+
+/// Subscription to an `EventBus`
+pub struct EventReceiver {
+    inner: broadcast::Receiver<SystemSignal>,
+    missed: u64,
+}
+
+impl EventReceiver {
+    /// Next signal, or `None` once every `EventBus` handle is dropped.
+    ///
+    /// If this receiver fell behind, the signals it missed are skipped
+    /// (counted in `missed`) and the oldest still buffered is returned.
+    pub async fn recv(&mut self) -> Option<SystemSignal> {
+        loop {
+            match self.inner.recv().await {
+                Ok(signal) => return Some(signal),
+                Err(RecvError::Lagged(skipped)) => self.record_lag(skipped),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Next buffered signal without waiting
+    pub fn try_recv(&mut self) -> Option<SystemSignal> {
+        loop {
+            match self.inner.try_recv() {
+                Ok(signal) => return Some(signal),
+                Err(TryRecvError::Lagged(skipped)) => self.record_lag(skipped),
+                Err(TryRecvError::Empty | TryRecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Signals skipped because this receiver lagged
+    pub fn missed(&self) -> u64 {
+        self.missed
+    }
+
+    fn record_lag(&mut self, skipped: u64) {
+        self.missed += skipped;
+        metrics::counter!("event_bus_lagged_total").increment(skipped);
+        warn!("Event subscriber lagged, skipped {} signals", skipped);
+    }
+}
@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Timeout applied to each dependency probe
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
 
 /// Health status of a service or component
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -23,6 +29,102 @@ impl HealthStatus {
     }
 }
 
+/// Shared flag a service flips once it is fully started and able to serve
+/// traffic. Clones share the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct Readiness {
+    ready: Arc<AtomicBool>,
+}
+
+impl Readiness {
+    /// A flag that starts out not ready
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn mark_ready(&self) {
+        self.ready.store(true, Ordering::Release);
+    }
+
+    pub fn mark_not_ready(&self) {
+        self.ready.store(false, Ordering::Release);
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+}
+
+/// Probe result for a single dependency (database, exchange, ZMQ, ...)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DependencyStatus {
+    pub status: HealthStatus,
+    /// A critical dependency being down makes the whole component unhealthy;
+    /// a non-critical one only degrades it
+    pub critical: bool,
+    /// Round-trip time of the probe, when it completed
+    pub latency_ms: Option<f64>,
+    pub message: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl DependencyStatus {
+    pub fn up(latency: Duration) -> Self {
+        Self {
+            status: HealthStatus::Healthy,
+            critical: true,
+            latency_ms: Some(latency.as_secs_f64() * 1000.0),
+            message: None,
+            checked_at: Utc::now(),
+        }
+    }
+
+    pub fn degraded(message: impl Into<String>) -> Self {
+        Self {
+            status: HealthStatus::Degraded,
+            critical: true,
+            latency_ms: None,
+            message: Some(message.into()),
+            checked_at: Utc::now(),
+        }
+    }
+
+    pub fn down(message: impl Into<String>) -> Self {
+        Self {
+            status: HealthStatus::Unhealthy,
+            critical: true,
+            latency_ms: None,
+            message: Some(message.into()),
+            checked_at: Utc::now(),
+        }
+    }
+
+    /// Mark the dependency as non-critical
+    pub fn optional(mut self) -> Self {
+        self.critical = false;
+        self
+    }
+
+    /// Status this dependency contributes to its component
+    fn impact(&self) -> HealthStatus {
+        match self.status {
+            HealthStatus::Unhealthy if self.critical => HealthStatus::Unhealthy,
+            HealthStatus::Healthy => HealthStatus::Healthy,
+            _ => HealthStatus::Degraded,
+        }
+    }
+}
+
+/// The worse of two statuses
+fn worst(a: HealthStatus, b: HealthStatus) -> HealthStatus {
+    let rank = |s: HealthStatus| match s {
+        HealthStatus::Healthy => 0,
+        HealthStatus::Degraded => 1,
+        HealthStatus::Unhealthy => 2,
+    };
+    if rank(b) > rank(a) { b } else { a }
+}
+
 /// Health check result for a component
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthCheck {
@@ -36,6 +138,9 @@ pub struct HealthCheck {
     pub message: Option<String>,
     /// Additional metrics
     pub metrics: HashMap<String, String>,
+    /// Per-dependency probe results
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, DependencyStatus>,
 }
 
 impl HealthCheck {
@@ -46,6 +151,7 @@ impl HealthCheck {
             timestamp: Utc::now(),
             message: None,
             metrics: HashMap::new(),
+            dependencies: BTreeMap::new(),
         }
     }
 
@@ -60,6 +166,7 @@ impl HealthCheck {
             timestamp: Utc::now(),
             message: Some(message.into()),
             metrics: HashMap::new(),
+            dependencies: BTreeMap::new(),
         }
     }
 
@@ -70,6 +177,7 @@ impl HealthCheck {
             timestamp: Utc::now(),
             message: Some(message.into()),
             metrics: HashMap::new(),
+            dependencies: BTreeMap::new(),
         }
     }
 
@@ -82,6 +190,22 @@ impl HealthCheck {
         self.message = Some(message.into());
         self
     }
+
+    /// Record a dependency probe result. The component status is lowered to
+    /// match: a critical dependency down makes it unhealthy, anything else
+    /// short of healthy makes it degraded. It is never raised.
+    pub fn with_dependency(mut self, name: impl Into<String>, dependency: DependencyStatus) -> Self {
+        self.status = worst(self.status, dependency.impact());
+        self.dependencies.insert(name.into(), dependency);
+        self
+    }
+
+    /// Whether any critical dependency is down
+    pub fn has_critical_failure(&self) -> bool {
+        self.dependencies
+            .values()
+            .any(|d| d.critical && d.status == HealthStatus::Unhealthy)
+    }
 }
 
 /// Aggregated health status for the entire system
@@ -107,6 +231,13 @@ impl SystemHealth {
         }
     }
 
+    /// Roll up component checks into a system status (the worst component wins)
+    pub fn aggregate(components: impl IntoIterator<Item = HealthCheck>) -> Self {
+        components
+            .into_iter()
+            .fold(Self::new(), |system, check| system.add_component(check))
+    }
+
     pub fn add_component(mut self, check: HealthCheck) -> Self {
         self.components.push(check);
         self.update_status();
@@ -144,6 +275,61 @@ impl Default for SystemHealth {
     }
 }
 
+/// `host:port` of a `scheme://host:port[/path]` address, if it has a port
+fn host_port(address: &str) -> Option<String> {
+    let rest = address.split_once("://").map_or(address, |(_, rest)| rest);
+    let authority = rest.split('/').next()?;
+    if authority.is_empty() {
+        return None;
+    }
+    // Wildcard bind addresses are reached through loopback
+    let authority = authority.replacen('*', "127.0.0.1", 1);
+    authority
+        .rsplit_once(':')
+        .is_some_and(|(_, port)| port.parse::<u16>().is_ok())
+        .then_some(authority)
+}
+
+async fn probe_tcp(authority: &str) -> DependencyStatus {
+    let started = Instant::now();
+    match tokio::time::timeout(PROBE_TIMEOUT, tokio::net::TcpStream::connect(authority)).await {
+        Ok(Ok(_)) => DependencyStatus::up(started.elapsed()),
+        Ok(Err(e)) => DependencyStatus::down(format!("connect to {} failed: {}", authority, e)),
+        Err(_) => DependencyStatus::down(format!("connect to {} timed out", authority)),
+    }
+}
+
+/// Ping an exchange REST API. Any HTTP response below 500 counts as up
+/// (unauthenticated pings are often answered with 401/404); 5xx responses
+/// mean degraded, and no response means down.
+pub async fn check_exchange(url: &str) -> DependencyStatus {
+    let client = match reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => return DependencyStatus::down(format!("HTTP client error: {}", e)),
+    };
+
+    let started = Instant::now();
+    match client.get(url).send().await {
+        Ok(response) if response.status().is_server_error() => {
+            DependencyStatus::degraded(format!("{} returned {}", url, response.status()))
+        }
+        Ok(_) => DependencyStatus::up(started.elapsed()),
+        Err(e) => DependencyStatus::down(format!("{} unreachable: {}", url, e)),
+    }
+}
+
+/// Check that a ZMQ TCP endpoint (`tcp://host:port`) accepts connections.
+/// Non-TCP transports cannot be probed from outside and report degraded.
+pub async fn check_zmq(address: &str) -> DependencyStatus {
+    if !address.starts_with("tcp://") {
+        return DependencyStatus::degraded(format!("cannot probe non-TCP ZMQ endpoint {}", address));
+    }
+    match host_port(address) {
+        Some(authority) => probe_tcp(&authority).await,
+        None => DependencyStatus::down(format!("invalid ZMQ address {}", address)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -174,6 +360,41 @@ mod tests {
         assert!(system.is_operational());
     }
 
+    #[test]
+    fn test_dependency_rollup() {
+        let check = HealthCheck::healthy("execution")
+            .with_dependency("exchange", DependencyStatus::up(Duration::from_millis(12)))
+            .with_dependency("metrics_db", DependencyStatus::down("locked").optional());
+        assert_eq!(check.status, HealthStatus::Degraded);
+        assert!(!check.has_critical_failure());
+
+        let check = check.with_dependency("zmq", DependencyStatus::down("refused"));
+        assert_eq!(check.status, HealthStatus::Unhealthy);
+        assert!(check.has_critical_failure());
+        assert_eq!(check.dependencies.len(), 3);
+
+        let system = SystemHealth::aggregate(vec![HealthCheck::healthy("market-data"), check]);
+        assert_eq!(system.status, HealthStatus::Unhealthy);
+    }
+
+    #[test]
+    fn test_host_port() {
+        assert_eq!(host_port("tcp://*:5555"), Some("127.0.0.1:5555".to_string()));
+        assert_eq!(host_port("tcp://10.0.0.1:5556"), Some("10.0.0.1:5556".to_string()));
+        assert_eq!(host_port("tcp://localhost"), None);
+    }
+
+    #[tokio::test]
+    async fn test_check_zmq() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(check_zmq(&format!("tcp://127.0.0.1:{}", port)).await.status.is_healthy());
+
+        drop(listener);
+        assert_eq!(check_zmq(&format!("tcp://127.0.0.1:{}", port)).await.status, HealthStatus::Unhealthy);
+        assert_eq!(check_zmq("ipc:///tmp/feed").await.status, HealthStatus::Degraded);
+    }
+
     #[test]
     fn test_unhealthy_system() {
         let system = SystemHealth::new()
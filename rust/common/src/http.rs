@@ -1,5 +1,5 @@
 /// HTTP health check and monitoring endpoints
-use crate::health::HealthCheck;
+use crate::health::{DependencyStatus, HealthCheck, HealthStatus, Readiness};
 use crate::Result;
 use axum::{
     extract::State,
@@ -18,20 +18,24 @@ pub struct HealthResponse {
     pub component: String,
     pub message: Option<String>,
     pub metrics: std::collections::HashMap<String, String>,
+    #[serde(default)]
+    pub dependencies: std::collections::BTreeMap<String, DependencyStatus>,
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
 impl From<HealthCheck> for HealthResponse {
     fn from(health: HealthCheck) -> Self {
         Self {
-            status: if health.status.is_healthy() {
-                "healthy".to_string()
-            } else {
-                "unhealthy".to_string()
-            },
+            status: match health.status {
+                HealthStatus::Healthy => "healthy",
+                HealthStatus::Degraded => "degraded",
+                HealthStatus::Unhealthy => "unhealthy",
+            }
+            .to_string(),
             component: health.component,
             message: health.message,
             metrics: health.metrics,
+            dependencies: health.dependencies,
             timestamp: health.timestamp,
         }
     }
@@ -44,28 +48,73 @@ pub struct ReadyResponse {
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
+/// Body of the `/livez` probe
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LivezResponse {
+    pub alive: bool,
+    pub component: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// Body of the `/readyz` probe
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReadyzResponse {
+    pub ready: bool,
+    pub component: String,
+    /// Whether the service has finished starting up
+    pub initialized: bool,
+    /// Critical dependencies that are currently down
+    pub failing_dependencies: Vec<String>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
 /// Application state for health endpoints
 pub struct HealthState {
     pub health: Arc<RwLock<HealthCheck>>,
+    pub readiness: Readiness,
 }
 
 /// Create health check router
+///
+/// `/readyz` only depends on the health status here; use
+/// `create_health_router_with_readiness` to also gate it on startup.
 pub fn create_health_router(health: Arc<RwLock<HealthCheck>>) -> Router {
-    let state = Arc::new(HealthState { health });
+    let readiness = Readiness::new();
+    readiness.mark_ready();
+    create_health_router_with_readiness(health, readiness)
+}
+
+/// Create health check router whose `/readyz` also waits for `readiness`
+///
+/// - `/livez`: 200 whenever the process is up
+/// - `/readyz`: 200 once `readiness` is set, the component is operational and
+///   no critical dependency is down; 503 otherwise
+/// - `/metrics`: Prometheus text format, same registry as `start_metrics_server`
+pub fn create_health_router_with_readiness(
+    health: Arc<RwLock<HealthCheck>>,
+    readiness: Readiness,
+) -> Router {
+    let state = Arc::new(HealthState { health, readiness });
 
     Router::new()
         .route("/health", get(health_handler))
         .route("/ready", get(ready_handler))
         .route("/live", get(liveness_handler))
+        .route("/livez", get(livez_handler))
+        .route("/readyz", get(readyz_handler))
+        .route("/metrics", get(crate::metrics::metrics_handler))
         .with_state(state)
 }
 
-/// Health check endpoint (detailed status)
+/// Health check endpoint (detailed status with per-dependency results)
+///
+/// Returns 503 when the component is unhealthy or a critical dependency is
+/// down; a degraded component (e.g. an optional dependency down) stays 200.
 async fn health_handler(
     State(state): State<Arc<HealthState>>,
 ) -> (StatusCode, Json<HealthResponse>) {
     let health = state.health.read().await;
-    let status = if health.status.is_healthy() {
+    let status = if health.status.is_operational() && !health.has_critical_failure() {
         StatusCode::OK
     } else {
         StatusCode::SERVICE_UNAVAILABLE
@@ -100,6 +149,48 @@ async fn liveness_handler() -> (StatusCode, &'static str) {
     (StatusCode::OK, "alive")
 }
 
+/// Kubernetes liveness probe: the process is running
+async fn livez_handler(
+    State(state): State<Arc<HealthState>>,
+) -> (StatusCode, Json<LivezResponse>) {
+    let component = state.health.read().await.component.clone();
+
+    (StatusCode::OK, Json(LivezResponse {
+        alive: true,
+        component,
+        timestamp: chrono::Utc::now(),
+    }))
+}
+
+/// Kubernetes readiness probe: initialized and critical dependencies up
+async fn readyz_handler(
+    State(state): State<Arc<HealthState>>,
+) -> (StatusCode, Json<ReadyzResponse>) {
+    let health = state.health.read().await;
+    let initialized = state.readiness.is_ready();
+    let failing_dependencies: Vec<String> = health
+        .dependencies
+        .iter()
+        .filter(|(_, d)| d.critical && d.status == HealthStatus::Unhealthy)
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    let ready = initialized && health.status.is_operational() && failing_dependencies.is_empty();
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status, Json(ReadyzResponse {
+        ready,
+        component: health.component.clone(),
+        initialized,
+        failing_dependencies,
+        timestamp: chrono::Utc::now(),
+    }))
+}
+
 /// Start health check HTTP server
 pub async fn start_health_server(
     port: u16,
@@ -165,6 +256,100 @@ mod tests {
         assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
     }
 
+    #[tokio::test]
+    async fn test_health_reports_dependencies() {
+        let health = Arc::new(RwLock::new(
+            HealthCheck::healthy("test-service")
+                .with_dependency("metrics_db", DependencyStatus::down("locked").optional())
+        ));
+
+        let request = || axum::http::Request::builder()
+            .uri("/health")
+            .body(axum::body::Body::empty())
+            .unwrap();
+
+        // Optional dependency down: degraded but still serving
+        let response = axum::Router::into_service(create_health_router(health.clone()))
+            .call(request())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["status"], "degraded");
+        assert_eq!(json["dependencies"]["metrics_db"]["status"], "Unhealthy");
+
+        // Critical dependency down
+        {
+            let mut h = health.write().await;
+            *h = h.clone().with_dependency("exchange", DependencyStatus::down("timeout"));
+        }
+        let response = axum::Router::into_service(create_health_router(health))
+            .call(request())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    async fn fetch(router: Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let request = axum::http::Request::builder()
+            .uri(uri)
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = axum::Router::into_service(router).call(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_readyz_waits_for_initialization() {
+        let health = Arc::new(RwLock::new(HealthCheck::healthy("test-service")));
+        let readiness = Readiness::new();
+        let router = || create_health_router_with_readiness(health.clone(), readiness.clone());
+
+        let (status, body) = fetch(router(), "/readyz").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["initialized"], false);
+
+        readiness.mark_ready();
+        let (status, body) = fetch(router(), "/readyz").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["ready"], true);
+
+        {
+            let mut h = health.write().await;
+            *h = h.clone().with_dependency("exchange", DependencyStatus::down("timeout"));
+        }
+        let (status, body) = fetch(router(), "/readyz").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["failing_dependencies"][0], "exchange");
+
+        // Liveness is unaffected
+        let (status, body) = fetch(router(), "/livez").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["alive"], true);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_renders_registry() {
+        crate::metrics::prometheus_handle();
+        ::metrics::counter!("http_test_requests_total").increment(3);
+
+        let router = create_health_router(Arc::new(RwLock::new(HealthCheck::healthy("test-service"))));
+        let request = axum::http::Request::builder()
+            .uri("/metrics")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = axum::Router::into_service(router).call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains("http_test_requests_total 3"), "{}", text);
+    }
+
     #[tokio::test]
     async fn test_liveness_always_ok() {
         let health = Arc::new(RwLock::new(
@@ -3,14 +3,23 @@
 /// This crate provides core domain types, messaging protocols, and utility functions
 /// used throughout the algorithmic trading system.
 pub mod types;
+pub mod builders;
+pub mod clock;
 pub mod messaging;
 pub mod errors;
+pub mod events;
 pub mod config;
 pub mod health;
 pub mod http;
 pub mod metrics;
+pub mod shutdown;
 
 pub use types::*;
-pub use errors::{TradingError, Result};
-pub use health::{HealthCheck, HealthStatus, SystemHealth};
-pub use http::{create_health_router, start_health_server, HealthResponse};
+pub use builders::{OrderBuilder, PositionBuilder, TradeBuilder};
+pub use clock::{Clock, MockClock, SharedClock, SystemClock};
+pub use config::SharedConfig;
+pub use errors::{ResultExt, TradingError, Result};
+pub use events::{EventBus, EventReceiver, StopTriggered, SystemSignal};
+pub use health::{DependencyStatus, HealthCheck, HealthStatus, Readiness, SystemHealth};
+pub use shutdown::{DrainReport, Shutdown};
+pub use http::{create_health_router, create_health_router_with_readiness, start_health_server, HealthResponse};
@@ -1,9 +1,18 @@
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use crate::errors::{Result, TradingError};
 use crate::types::{Order, OrderBook, Trade, Bar, Signal, Position};
 
+/// Envelope format version written by this build. Bump it when a change to
+/// the envelope or its payloads is not backwards compatible.
+pub const ENVELOPE_VERSION: u16 = 1;
+
 /// Message types for inter-component communication via ZMQ
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type")]
+///
+/// Human-readable formats (JSON) tag each message with a `"type"` field.
+/// Binary formats such as bincode cannot decode internally tagged enums, so
+/// they use serde's default externally tagged layout instead.
+#[derive(Debug, Clone)]
 pub enum Message {
     /// Market data messages
     OrderBookUpdate(OrderBook),
@@ -27,12 +36,238 @@ pub enum Message {
     Shutdown,
 }
 
+/// Serde representations of `Message` for human-readable (tagged) and
+/// binary (externally tagged) formats
+macro_rules! message_serde {
+    ($($variant:ident($ty:ty)),* ; $($unit:ident),*) => {
+        #[derive(Serialize)]
+        #[serde(tag = "type")]
+        enum TaggedRef<'a> { $($variant(&'a $ty),)* $($unit,)* }
+
+        #[derive(Serialize)]
+        enum CompactRef<'a> { $($variant(&'a $ty),)* $($unit,)* }
+
+        #[derive(Deserialize)]
+        #[serde(tag = "type")]
+        enum Tagged { $($variant($ty),)* $($unit,)* }
+
+        #[derive(Deserialize)]
+        enum Compact { $($variant($ty),)* $($unit,)* }
+
+        impl Serialize for Message {
+            fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                if serializer.is_human_readable() {
+                    match self {
+                        $(Message::$variant(v) => TaggedRef::$variant(v),)*
+                        $(Message::$unit => TaggedRef::$unit,)*
+                    }
+                    .serialize(serializer)
+                } else {
+                    match self {
+                        $(Message::$variant(v) => CompactRef::$variant(v),)*
+                        $(Message::$unit => CompactRef::$unit,)*
+                    }
+                    .serialize(serializer)
+                }
+            }
+        }
+
+        impl<'de> Deserialize<'de> for Message {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+                if deserializer.is_human_readable() {
+                    Ok(match Tagged::deserialize(deserializer)? {
+                        $(Tagged::$variant(v) => Message::$variant(v),)*
+                        $(Tagged::$unit => Message::$unit,)*
+                    })
+                } else {
+                    Ok(match Compact::deserialize(deserializer)? {
+                        $(Compact::$variant(v) => Message::$variant(v),)*
+                        $(Compact::$unit => Message::$unit,)*
+                    })
+                }
+            }
+        }
+    };
+}
+
+message_serde!(
+    OrderBookUpdate(OrderBook),
+    TradeUpdate(Trade),
+    BarUpdate(Bar),
+    SignalGenerated(Signal),
+    OrderRequest(Order),
+    OrderResponse(OrderResponse),
+    PositionUpdate(Position),
+    RiskCheck(RiskCheckRequest),
+    RiskCheckResult(RiskCheckResult),
+    Heartbeat(Heartbeat);
+    Shutdown
+);
+
+/// Wire encoding of message payloads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Codec {
+    /// Human-readable and easy to debug
+    #[default]
+    Json = 0,
+    /// Compact binary encoding for high-frequency data
+    Bincode = 1,
+}
+
+impl Codec {
+    fn from_header(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Codec::Json),
+            1 => Some(Codec::Bincode),
+            _ => None,
+        }
+    }
+}
+
+/// Serialize `value` with `codec`
+pub fn encode_with<T: Serialize>(codec: Codec, value: &T) -> Result<Vec<u8>> {
+    match codec {
+        Codec::Json => Ok(serde_json::to_vec(value)?),
+        Codec::Bincode => bincode::serialize(value)
+            .map_err(|e| TradingError::Messaging(format!("Bincode encode failed: {}", e))),
+    }
+}
+
+/// Deserialize a `T` encoded with `codec`
+pub fn decode_with<T: DeserializeOwned>(codec: Codec, bytes: &[u8]) -> Result<T> {
+    match codec {
+        Codec::Json => Ok(serde_json::from_slice(bytes)?),
+        Codec::Bincode => bincode::deserialize(bytes)
+            .map_err(|e| TradingError::Messaging(format!("Bincode decode failed: {}", e))),
+    }
+}
+
+impl Message {
+    pub fn kind(&self) -> MessageKind {
+        match self {
+            Message::OrderBookUpdate(_) => MessageKind::OrderBookUpdate,
+            Message::TradeUpdate(_) => MessageKind::TradeUpdate,
+            Message::BarUpdate(_) => MessageKind::BarUpdate,
+            Message::SignalGenerated(_) => MessageKind::SignalGenerated,
+            Message::OrderRequest(_) => MessageKind::OrderRequest,
+            Message::OrderResponse(_) => MessageKind::OrderResponse,
+            Message::PositionUpdate(_) => MessageKind::PositionUpdate,
+            Message::RiskCheck(_) => MessageKind::RiskCheck,
+            Message::RiskCheckResult(_) => MessageKind::RiskCheckResult,
+            Message::Heartbeat(_) => MessageKind::Heartbeat,
+            Message::Shutdown => MessageKind::Shutdown,
+        }
+    }
+}
+
+/// Kind of payload carried by an `Envelope`, readable without decoding it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageKind {
+    OrderBookUpdate,
+    TradeUpdate,
+    BarUpdate,
+    SignalGenerated,
+    OrderRequest,
+    OrderResponse,
+    PositionUpdate,
+    RiskCheck,
+    RiskCheckResult,
+    Heartbeat,
+    Shutdown,
+}
+
+/// Versioned wrapper for messages sent between services
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Envelope<T> {
+    pub version: u16,
+    pub kind: MessageKind,
+    pub sent_at: DateTime<Utc>,
+    pub payload: T,
+}
+
+/// Envelope fields needed to decide whether the payload can be decoded
+#[derive(Deserialize)]
+struct EnvelopeHeader {
+    version: u16,
+    kind: MessageKind,
+}
+
+impl<T> Envelope<T> {
+    /// Wrap a payload at the current version, stamped now
+    pub fn new(kind: MessageKind, payload: T) -> Self {
+        Self {
+            version: ENVELOPE_VERSION,
+            kind,
+            sent_at: Utc::now(),
+            payload,
+        }
+    }
+}
+
+impl Envelope<Message> {
+    pub fn wrap(message: Message) -> Self {
+        Self::new(message.kind(), message)
+    }
+}
+
+impl<T: Serialize> Envelope<T> {
+    /// Encode as JSON
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        self.encode_with(Codec::default())
+    }
+
+    /// Encode with `codec`. The first byte records the codec so `decode`
+    /// can read any envelope without knowing the sender's configuration.
+    pub fn encode_with(&self, codec: Codec) -> Result<Vec<u8>> {
+        let mut bytes = vec![codec as u8];
+        bytes.extend(encode_with(codec, self)?);
+        Ok(bytes)
+    }
+}
+
+impl<T: DeserializeOwned> Envelope<T> {
+    /// Decode an envelope, checking its version before the payload.
+    ///
+    /// Envelopes from a newer (or zero) version are rejected with a
+    /// `Messaging` error instead of being parsed with the wrong schema.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let (&header, body) = bytes
+            .split_first()
+            .ok_or_else(|| TradingError::Messaging("Empty envelope".to_string()))?;
+        let codec = Codec::from_header(header)
+            .ok_or_else(|| TradingError::Messaging(format!("Unknown envelope codec: {}", header)))?;
+
+        let header: EnvelopeHeader = decode_with(codec, body)?;
+        if header.version == 0 || header.version > ENVELOPE_VERSION {
+            return Err(TradingError::Messaging(format!(
+                "Unsupported envelope version {} for {:?} (supported: 1..={})",
+                header.version, header.kind, ENVELOPE_VERSION
+            )));
+        }
+        decode_with(codec, body)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderResponse {
     pub order_id: String,
     pub client_order_id: String,
     pub success: bool,
     pub error: Option<String>,
+    /// Quantity filled by the exchange at the time of the response
+    #[serde(default)]
+    pub filled_quantity: f64,
+    /// Volume-weighted average fill price, if anything has filled
+    #[serde(default)]
+    pub filled_avg_price: Option<f64>,
+    /// Commission charged on the filled quantity
+    #[serde(default)]
+    pub commission: f64,
+    /// Realized slippage of the fill versus the expected price, in basis
+    /// points (positive is adverse); only reported for simulated fills
+    #[serde(default)]
+    pub slippage_bps: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,3 +296,82 @@ pub mod topics {
     pub const RISK: &str = "risk";
     pub const SYSTEM: &str = "system";
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_round_trip() {
+        let envelope = Envelope::wrap(Message::Shutdown);
+        assert_eq!(envelope.kind, MessageKind::Shutdown);
+        assert_eq!(envelope.version, ENVELOPE_VERSION);
+
+        let decoded = Envelope::<Message>::decode(&envelope.encode().unwrap()).unwrap();
+        assert!(matches!(decoded.payload, Message::Shutdown));
+        assert_eq!(decoded.sent_at, envelope.sent_at);
+    }
+
+    #[test]
+    fn test_envelope_rejects_unknown_version() {
+        let mut envelope = Envelope::wrap(Message::Shutdown);
+        envelope.version = ENVELOPE_VERSION + 1;
+
+        let err = Envelope::<Message>::decode(&envelope.encode().unwrap()).unwrap_err();
+        assert!(matches!(err, TradingError::Messaging(ref m) if m.contains("version")));
+    }
+
+    fn book_snapshot() -> OrderBook {
+        let level = |price: f64| crate::types::Level {
+            price: crate::types::Price(price),
+            quantity: crate::types::Quantity(100.0),
+            timestamp: Utc::now(),
+        };
+        OrderBook {
+            symbol: crate::types::Symbol("AAPL".to_string()),
+            bids: (0..10).map(|i| level(150.0 - i as f64 * 0.01)).collect(),
+            asks: (0..10).map(|i| level(150.01 + i as f64 * 0.01)).collect(),
+            timestamp: Utc::now(),
+            sequence: 1,
+        }
+    }
+
+    #[test]
+    fn test_book_snapshot_survives_both_codecs() {
+        let book = book_snapshot();
+        let mut sizes = Vec::new();
+
+        for codec in [Codec::Json, Codec::Bincode] {
+            let bytes = Envelope::wrap(Message::OrderBookUpdate(book.clone())).encode_with(codec).unwrap();
+            sizes.push(bytes.len());
+
+            let decoded = Envelope::<Message>::decode(&bytes).unwrap();
+            assert_eq!(decoded.kind, MessageKind::OrderBookUpdate);
+            match decoded.payload {
+                Message::OrderBookUpdate(decoded_book) => {
+                    assert_eq!(decoded_book.symbol, book.symbol);
+                    let levels = |levels: &[crate::types::Level]| {
+                        levels.iter().map(|l| (l.price, l.quantity, l.timestamp)).collect::<Vec<_>>()
+                    };
+                    assert_eq!(levels(&decoded_book.bids), levels(&book.bids));
+                    assert_eq!(levels(&decoded_book.asks), levels(&book.asks));
+                }
+                other => panic!("unexpected payload: {:?}", other),
+            }
+        }
+
+        assert!(sizes[1] < sizes[0], "bincode {} bytes vs json {} bytes", sizes[1], sizes[0]);
+    }
+
+    #[test]
+    fn test_json_keeps_type_tag() {
+        let json: serde_json::Value = serde_json::from_slice(&encode_with(Codec::Json, &Message::Shutdown).unwrap()).unwrap();
+        assert_eq!(json["type"], "Shutdown");
+    }
+
+    #[test]
+    fn test_envelope_rejects_bare_message() {
+        let bare = serde_json::to_vec(&Message::Shutdown).unwrap();
+        assert!(Envelope::<Message>::decode(&bare).is_err());
+    }
+}
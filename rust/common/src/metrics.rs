@@ -5,9 +5,38 @@
 //! Python collectors.
 
 use axum::{routing::get, Router};
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
 use std::net::SocketAddr;
+use std::sync::OnceLock;
 use tokio::task::JoinHandle;
-use tracing::{error, info};
+use tracing::{error, info, warn};
+
+static PROMETHEUS: OnceLock<Option<PrometheusHandle>> = OnceLock::new();
+
+/// Handle to the process-wide Prometheus recorder, installing it on first use.
+///
+/// Both the dedicated metrics server and the health router's `/metrics`
+/// render from this one recorder, so running both just serves the same
+/// registry on two ports. Returns `None` if another recorder was installed
+/// first (e.g. by a test harness).
+pub fn prometheus_handle() -> Option<PrometheusHandle> {
+    PROMETHEUS
+        .get_or_init(|| match PrometheusBuilder::new().install_recorder() {
+            Ok(handle) => Some(handle),
+            Err(e) => {
+                warn!("Prometheus recorder not installed: {}", e);
+                None
+            }
+        })
+        .clone()
+}
+
+/// Render all recorded `metrics` values in Prometheus text format
+pub fn render_prometheus() -> String {
+    prometheus_handle()
+        .map(|handle| handle.render())
+        .unwrap_or_default()
+}
 
 /// Metrics server configuration
 pub struct MetricsConfig {
@@ -51,6 +80,10 @@ pub fn start_metrics_server(config: MetricsConfig) -> Result<JoinHandle<()>, any
 
     info!("Starting metrics server on {}", addr);
 
+    // Install the recorder now so metrics recorded before the first scrape
+    // are kept
+    prometheus_handle();
+
     let app = Router::new().route("/metrics", get(metrics_handler));
 
     let handle = tokio::spawn(async move {
@@ -72,9 +105,7 @@ pub fn start_metrics_server(config: MetricsConfig) -> Result<JoinHandle<()>, any
 }
 
 /// Metrics endpoint handler
-async fn metrics_handler() -> String {
-    // Use metrics crate's encode function if available
-    // For now, return a simple Prometheus-formatted response
+pub(crate) async fn metrics_handler() -> String {
     let mut output = String::new();
 
     // Add standard process metrics
@@ -89,10 +120,7 @@ async fn metrics_handler() -> String {
     ));
 
     output.push('\n');
-
-    // The metrics crate will automatically append its metrics here
-    // This is a placeholder - in production, use metrics-exporter-prometheus
-    output.push_str("# Metrics exported via metrics crate\n");
+    output.push_str(&render_prometheus());
 
     output
 }
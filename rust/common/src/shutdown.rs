@@ -0,0 +1,162 @@
+//! Graceful shutdown coordination
+//!
+//! A `Shutdown` combines a cancellation token with a tracker of in-flight
+//! work. Once shutdown begins new work is refused, and `drain` waits up to
+//! the grace period for tracked work to finish.
+
+use crate::errors::{Result, TradingError};
+use std::future::Future;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+use tracing::{info, warn};
+
+/// Grace period used by `Shutdown::default`
+pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Outcome of `Shutdown::drain`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DrainReport {
+    /// Tasks in flight when draining started that finished in time
+    pub drained: usize,
+    /// Tasks still running at the grace deadline
+    pub abandoned: usize,
+}
+
+/// Shutdown coordinator shared by a service and its `main`. Clones share
+/// the same token and task set.
+#[derive(Debug, Clone)]
+pub struct Shutdown {
+    token: CancellationToken,
+    tracker: TaskTracker,
+    grace_period: Duration,
+}
+
+impl Shutdown {
+    pub fn new(grace_period: Duration) -> Self {
+        Self {
+            token: CancellationToken::new(),
+            tracker: TaskTracker::new(),
+            grace_period,
+        }
+    }
+
+    pub fn grace_period(&self) -> Duration {
+        self.grace_period
+    }
+
+    /// Token cancelled when shutdown begins, for `select!`-ing run loops
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Resolves once shutdown begins
+    pub async fn cancelled(&self) {
+        self.token.cancelled().await
+    }
+
+    /// Number of tracked tasks still running
+    pub fn in_flight(&self) -> usize {
+        self.tracker.len()
+    }
+
+    /// Run `future` as tracked in-flight work, or refuse it with an
+    /// `Execution` error if shutdown has begun
+    pub async fn track<F: Future>(&self, future: F) -> Result<F::Output> {
+        if self.is_shutting_down() {
+            return Err(TradingError::Execution("service is shutting down".to_string()));
+        }
+        Ok(self.tracker.track_future(future).await)
+    }
+
+    /// Spawn `future` as tracked in-flight work. Returns `None` if shutdown
+    /// has begun.
+    pub fn spawn<F>(&self, future: F) -> Option<JoinHandle<F::Output>>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        if self.is_shutting_down() {
+            return None;
+        }
+        Some(self.tracker.spawn(future))
+    }
+
+    /// Stop accepting new work without waiting
+    pub fn begin(&self) {
+        self.token.cancel();
+        self.tracker.close();
+    }
+
+    /// Begin shutdown and wait up to the grace period for in-flight work
+    pub async fn drain(&self) -> DrainReport {
+        self.begin();
+        let in_flight = self.tracker.len();
+        info!("Shutting down, draining {} in-flight tasks (grace period {:?})", in_flight, self.grace_period);
+
+        let _ = tokio::time::timeout(self.grace_period, self.tracker.wait()).await;
+
+        let abandoned = self.tracker.len();
+        let report = DrainReport {
+            drained: in_flight.saturating_sub(abandoned),
+            abandoned,
+        };
+        if report.abandoned > 0 {
+            warn!(
+                "Grace period elapsed: {} tasks drained, {} abandoned",
+                report.drained, report.abandoned
+            );
+        } else {
+            info!("All {} in-flight tasks drained", report.drained);
+        }
+        report
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new(DEFAULT_GRACE_PERIOD)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_drain_waits_for_in_flight_work() {
+        let shutdown = Shutdown::new(Duration::from_secs(1));
+        shutdown.spawn(tokio::time::sleep(Duration::from_millis(20))).unwrap();
+
+        let report = shutdown.drain().await;
+        assert_eq!(report, DrainReport { drained: 1, abandoned: 0 });
+        assert_eq!(shutdown.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_drain_abandons_at_deadline() {
+        let shutdown = Shutdown::new(Duration::from_millis(20));
+        shutdown.spawn(tokio::time::sleep(Duration::from_secs(60))).unwrap();
+        shutdown.spawn(async {}).unwrap();
+        tokio::task::yield_now().await;
+
+        let report = shutdown.drain().await;
+        assert_eq!(report.abandoned, 1);
+    }
+
+    #[tokio::test]
+    async fn test_refuses_work_after_shutdown() {
+        let shutdown = Shutdown::default();
+        assert_eq!(shutdown.track(async { 7 }).await.unwrap(), 7);
+
+        shutdown.begin();
+        assert!(shutdown.is_shutting_down());
+        assert!(shutdown.track(async { 7 }).await.is_err());
+        assert!(shutdown.spawn(async {}).is_none());
+    }
+}
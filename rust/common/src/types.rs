@@ -1,20 +1,71 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
+use crate::errors::{Result, TradingError};
 
 /// Represents a trading symbol (e.g., "BTCUSDT", "AAPL")
+///
+/// Prefer `Symbol::new` for external input; the tuple constructor skips
+/// validation and is meant for trusted internal values.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Symbol(pub String);
 
+impl Symbol {
+    /// Validated symbol: surrounding whitespace is trimmed, and the result
+    /// must be non-empty with no interior whitespace and at most one `/`
+    /// separating two non-empty parts (e.g. "BTC/USD").
+    pub fn new(s: &str) -> Result<Self> {
+        let symbol = s.trim();
+        if symbol.is_empty() {
+            return Err(TradingError::Validation("symbol cannot be empty".to_string()));
+        }
+        if symbol.chars().any(char::is_whitespace) {
+            return Err(TradingError::Validation(format!("symbol {:?} contains whitespace", symbol)));
+        }
+        let parts: Vec<&str> = symbol.split('/').collect();
+        if parts.len() > 2 || parts.iter().any(|part| part.is_empty()) {
+            return Err(TradingError::Validation(format!(
+                "symbol {:?} must be a ticker or a BASE/QUOTE pair",
+                symbol
+            )));
+        }
+        Ok(Symbol(symbol.to_string()))
+    }
+}
+
 impl fmt::Display for Symbol {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)
     }
 }
 
+/// Numeric representation behind `Price` and `Quantity`.
+///
+/// `f64` by default. Built with `RUSTFLAGS="--cfg common_decimal"` it is
+/// `rust_decimal::Decimal`, which represents ticks exactly, cannot be NaN,
+/// and keeps P&L free of rounding drift. Code that goes through
+/// `to_f64`/`from_f64` compiles under both.
+///
+/// The switch is staged: only this crate's library builds with
+/// `common_decimal` so far, because downstream crates and the integration
+/// tests still do `f64` arithmetic on the `.0` fields. It is a cfg rather
+/// than a cargo feature so `--all-features` builds keep working until they
+/// are migrated.
+#[cfg(not(common_decimal))]
+pub type Amount = f64;
+#[cfg(common_decimal)]
+pub type Amount = rust_decimal::Decimal;
+
 /// Price representation with high precision
+#[cfg(not(common_decimal))]
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
-pub struct Price(pub f64);
+pub struct Price(pub Amount);
+
+/// Price representation with high precision
+#[cfg(common_decimal)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Price(pub Amount);
 
 impl fmt::Display for Price {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -23,8 +74,14 @@ impl fmt::Display for Price {
 }
 
 /// Quantity/Volume representation
+#[cfg(not(common_decimal))]
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
-pub struct Quantity(pub f64);
+pub struct Quantity(pub Amount);
+
+/// Quantity/Volume representation
+#[cfg(common_decimal)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Quantity(pub Amount);
 
 impl fmt::Display for Quantity {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -32,6 +89,110 @@ impl fmt::Display for Quantity {
     }
 }
 
+#[cfg(not(common_decimal))]
+fn amount_to_f64(value: Amount) -> f64 {
+    value
+}
+
+#[cfg(not(common_decimal))]
+fn amount_from_f64(value: f64) -> Option<Amount> {
+    value.is_finite().then_some(value)
+}
+
+#[cfg(not(common_decimal))]
+fn amount_is_positive_finite(value: Amount) -> bool {
+    value > 0.0 && value.is_finite()
+}
+
+#[cfg(common_decimal)]
+fn amount_to_f64(value: Amount) -> f64 {
+    use rust_decimal::prelude::ToPrimitive;
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+#[cfg(common_decimal)]
+fn amount_from_f64(value: f64) -> Option<Amount> {
+    use rust_decimal::prelude::FromPrimitive;
+    Amount::from_f64(value)
+}
+
+/// A `Decimal` is always finite
+#[cfg(common_decimal)]
+fn amount_is_positive_finite(value: Amount) -> bool {
+    value > Amount::ZERO
+}
+
+macro_rules! amount_newtype_ops {
+    ($name:ident) => {
+        impl $name {
+            pub fn to_f64(self) -> f64 {
+                amount_to_f64(self.0)
+            }
+
+            /// Convert from `f64`; `None` for NaN or infinite values
+            pub fn from_f64(value: f64) -> Option<Self> {
+                amount_from_f64(value).map($name)
+            }
+
+            /// Strictly positive and not NaN or infinite
+            pub fn is_positive_finite(self) -> bool {
+                amount_is_positive_finite(self.0)
+            }
+        }
+
+        impl std::ops::Add for $name {
+            type Output = $name;
+
+            fn add(self, rhs: $name) -> $name {
+                $name(self.0 + rhs.0)
+            }
+        }
+
+        impl std::ops::Sub for $name {
+            type Output = $name;
+
+            fn sub(self, rhs: $name) -> $name {
+                $name(self.0 - rhs.0)
+            }
+        }
+    };
+}
+
+amount_newtype_ops!(Price);
+amount_newtype_ops!(Quantity);
+
+impl Quantity {
+    /// Validated quantity: finite and not negative.
+    /// Prefer this over the tuple constructor for external input.
+    pub fn new(value: f64) -> Result<Self> {
+        if value < 0.0 || !value.is_finite() {
+            return Err(TradingError::Validation(format!(
+                "quantity must be finite and non-negative, got {}",
+                value
+            )));
+        }
+        Quantity::from_f64(value)
+            .ok_or_else(|| TradingError::Validation(format!("quantity {} is not representable", value)))
+    }
+}
+
+impl Price {
+    /// Validated price: finite and strictly positive.
+    /// Prefer this over the tuple constructor for external input.
+    pub fn new(value: f64) -> Result<Self> {
+        if value <= 0.0 || !value.is_finite() {
+            return Err(TradingError::Validation(format!("price must be finite and positive, got {}", value)));
+        }
+        Price::from_f64(value)
+            .ok_or_else(|| TradingError::Validation(format!("price {} is not representable", value)))
+    }
+
+    /// Notional value of `quantity` at this price
+    pub fn notional(self, quantity: Quantity) -> Amount {
+        self.0 * quantity.0
+    }
+}
+
 /// Order book side
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Side {
@@ -48,6 +209,29 @@ pub enum OrderType {
     StopLimit,
 }
 
+/// How long an order works before it is cancelled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeInForce {
+    /// Until the close of the trading session it was placed in
+    Day,
+    /// Until cancelled
+    #[default]
+    Gtc,
+    /// Fill what is available immediately and cancel the rest
+    Ioc,
+    /// Fill completely immediately or cancel
+    Fok,
+    /// Until the given time
+    Gtd(DateTime<Utc>),
+}
+
+impl TimeInForce {
+    /// Orders that never rest on the book
+    pub fn is_immediate(&self) -> bool {
+        matches!(self, TimeInForce::Ioc | TimeInForce::Fok)
+    }
+}
+
 /// Order status
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OrderStatus {
@@ -78,7 +262,7 @@ pub struct Trade {
 }
 
 /// OHLCV bar
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Bar {
     pub symbol: Symbol,
     pub open: Price,
@@ -110,6 +294,9 @@ pub struct Order {
     pub quantity: Quantity,
     pub price: Option<Price>,
     pub stop_price: Option<Price>,
+    /// Orders journaled before time in force existed are good-til-cancelled
+    #[serde(default)]
+    pub time_in_force: TimeInForce,
     pub status: OrderStatus,
     pub filled_quantity: Quantity,
     pub average_price: Option<Price>,
@@ -117,6 +304,204 @@ pub struct Order {
     pub updated_at: DateTime<Utc>,
 }
 
+impl Order {
+    /// Check that the order's fields are consistent with its type.
+    ///
+    /// Limit orders need a price and market orders must not have one;
+    /// stop-market orders need a stop price and stop-limit orders need both.
+    /// Quantity must be positive and the filled quantity cannot exceed it,
+    /// and a good-til-date order must expire after it was created.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: String| Err(TradingError::Validation(format!("order {}: {}", self.client_order_id, reason)));
+
+        if !self.quantity.is_positive_finite() {
+            return invalid(format!("quantity must be positive, got {}", self.quantity.0));
+        }
+        if self.filled_quantity.0 > self.quantity.0 {
+            return invalid(format!(
+                "filled quantity {} exceeds order quantity {}",
+                self.filled_quantity.0, self.quantity.0
+            ));
+        }
+        if let TimeInForce::Gtd(expiry) = self.time_in_force {
+            if expiry <= self.created_at {
+                return invalid(format!("expiry {} is not after creation at {}", expiry, self.created_at));
+            }
+        }
+
+        match (self.order_type, self.price, self.stop_price) {
+            (OrderType::Market, Some(price), _) => invalid(format!("market order must not have a price, got {}", price)),
+            (OrderType::Limit, None, _) => invalid("limit order requires a price".to_string()),
+            (OrderType::StopMarket, _, None) => invalid("stop-market order requires a stop price".to_string()),
+            (OrderType::StopLimit, None, _) => invalid("stop-limit order requires a limit price".to_string()),
+            (OrderType::StopLimit, _, None) => invalid("stop-limit order requires a stop price".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Tolerance, in increments, for treating a value as on the grid; absorbs
+/// binary floating point error such as 0.1 + 0.2
+const INCREMENT_TOLERANCE: f64 = 1e-6;
+
+/// Number of `step` increments in `value`, snapped to a whole number when
+/// within tolerance of one
+fn increments(value: f64, step: f64) -> f64 {
+    let steps = value / step;
+    let nearest = steps.round();
+    if (steps - nearest).abs() < INCREMENT_TOLERANCE {
+        nearest
+    } else {
+        steps
+    }
+}
+
+fn is_multiple(value: f64, step: f64) -> bool {
+    let steps = increments(value, step);
+    steps == steps.round()
+}
+
+/// Exchange trading increments for one instrument
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct InstrumentSpec {
+    /// Minimum price increment
+    pub tick_size: f64,
+    /// Quantities must be a multiple of this
+    pub lot_size: f64,
+    /// Smallest order quantity accepted
+    #[serde(default)]
+    pub min_qty: f64,
+}
+
+impl InstrumentSpec {
+    pub fn new(tick_size: f64, lot_size: f64) -> Self {
+        Self { tick_size, lot_size, min_qty: 0.0 }
+    }
+
+    pub fn with_min_qty(mut self, min_qty: f64) -> Self {
+        self.min_qty = min_qty;
+        self
+    }
+
+    /// Increments must be finite and positive, the minimum finite and not negative
+    pub fn validate(&self) -> Result<()> {
+        if self.tick_size <= 0.0 || !self.tick_size.is_finite() {
+            return Err(TradingError::Configuration(format!("tick_size must be positive, got {}", self.tick_size)));
+        }
+        if self.lot_size <= 0.0 || !self.lot_size.is_finite() {
+            return Err(TradingError::Configuration(format!("lot_size must be positive, got {}", self.lot_size)));
+        }
+        if self.min_qty < 0.0 || !self.min_qty.is_finite() {
+            return Err(TradingError::Configuration(format!("min_qty must be non-negative, got {}", self.min_qty)));
+        }
+        Ok(())
+    }
+
+    /// Round a price onto the tick grid on the conservative side for `side`:
+    /// buy prices round down and sell prices round up, so the order never
+    /// crosses further than intended
+    pub fn round_price(&self, side: Side, price: Price) -> Price {
+        let steps = increments(price.to_f64(), self.tick_size);
+        let steps = match side {
+            Side::Bid => steps.floor(),
+            Side::Ask => steps.ceil(),
+        };
+        Price::from_f64(steps * self.tick_size).unwrap_or(price)
+    }
+
+    /// Round a quantity down to a whole number of lots, never increasing size
+    pub fn round_qty(&self, quantity: Quantity) -> Quantity {
+        let lots = increments(quantity.to_f64(), self.lot_size).floor();
+        Quantity::from_f64(lots * self.lot_size).unwrap_or(quantity)
+    }
+
+    /// Reject an order whose prices are off the tick grid or whose quantity
+    /// is not a whole number of lots or is below `min_qty`
+    pub fn check_order(&self, order: &Order) -> Result<()> {
+        let invalid = |reason: String| {
+            Err(TradingError::Validation(format!(
+                "order {} for {}: {}",
+                order.client_order_id, order.symbol, reason
+            )))
+        };
+
+        let quantity = order.quantity.to_f64();
+        if quantity < self.min_qty {
+            return invalid(format!("quantity {} is below the minimum {}", quantity, self.min_qty));
+        }
+        if !is_multiple(quantity, self.lot_size) {
+            return invalid(format!("quantity {} is not a multiple of lot size {}", quantity, self.lot_size));
+        }
+        for (name, price) in [("price", order.price), ("stop price", order.stop_price)] {
+            if let Some(price) = price {
+                if !is_multiple(price.to_f64(), self.tick_size) {
+                    return invalid(format!("{} {} is not a multiple of tick size {}", name, price, self.tick_size));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `InstrumentSpec`s keyed by symbol.
+///
+/// Symbols without a spec are left alone: rounding returns the input and
+/// every order passes `check_order`. In JSON this is a plain map, e.g.
+/// `{"AAPL": {"tick_size": 0.01, "lot_size": 1.0}}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct InstrumentSpecs(HashMap<String, InstrumentSpec>);
+
+impl InstrumentSpecs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_spec(mut self, symbol: impl Into<String>, spec: InstrumentSpec) -> Self {
+        self.0.insert(symbol.into(), spec);
+        self
+    }
+
+    pub fn get(&self, symbol: &Symbol) -> Option<&InstrumentSpec> {
+        self.0.get(&symbol.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        for (symbol, spec) in &self.0 {
+            spec.validate().map_err(|e| match e {
+                TradingError::Configuration(reason) => {
+                    TradingError::Configuration(format!("instruments[{}]: {}", symbol, reason))
+                }
+                other => other,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// See `InstrumentSpec::round_price`
+    pub fn round_price(&self, symbol: &Symbol, side: Side, price: Price) -> Price {
+        self.get(symbol).map_or(price, |spec| spec.round_price(side, price))
+    }
+
+    /// See `InstrumentSpec::round_qty`
+    pub fn round_qty(&self, symbol: &Symbol, quantity: Quantity) -> Quantity {
+        self.get(symbol).map_or(quantity, |spec| spec.round_qty(quantity))
+    }
+
+    /// See `InstrumentSpec::check_order`
+    pub fn check_order(&self, order: &Order) -> Result<()> {
+        self.get(&order.symbol).map_or(Ok(()), |spec| spec.check_order(order))
+    }
+}
+
 /// Position tracking
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Position {
@@ -129,6 +514,140 @@ pub struct Position {
     pub realized_pnl: f64,
     pub opened_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Currency of the prices and P&L; USD when absent from the message
+    #[serde(default)]
+    pub currency: Currency,
+}
+
+impl Position {
+    /// Flat-P&L USD position opened now at `entry_price`
+    pub fn new(symbol: Symbol, side: Side, quantity: Quantity, entry_price: Price) -> Self {
+        let now = Utc::now();
+        Self {
+            symbol,
+            side,
+            quantity,
+            entry_price,
+            current_price: entry_price,
+            unrealized_pnl: 0.0,
+            realized_pnl: 0.0,
+            opened_at: now,
+            updated_at: now,
+            currency: Currency::default(),
+        }
+    }
+
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = currency;
+        self
+    }
+
+    pub fn unrealized_pnl_money(&self) -> Money {
+        Money::new(self.unrealized_pnl, self.currency)
+    }
+
+    pub fn realized_pnl_money(&self) -> Money {
+        Money::new(self.realized_pnl, self.currency)
+    }
+}
+
+/// ISO 4217 currency of a monetary amount
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Currency {
+    #[default]
+    Usd,
+    Eur,
+    Gbp,
+    Jpy,
+    Chf,
+    Cad,
+    Aud,
+}
+
+impl Currency {
+    pub const ALL: [Currency; 7] = [
+        Currency::Usd,
+        Currency::Eur,
+        Currency::Gbp,
+        Currency::Jpy,
+        Currency::Chf,
+        Currency::Cad,
+        Currency::Aud,
+    ];
+
+    /// Parse an ISO code such as "USD" (case-insensitive)
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.code().eq_ignore_ascii_case(code))
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::Usd => "USD",
+            Currency::Eur => "EUR",
+            Currency::Gbp => "GBP",
+            Currency::Jpy => "JPY",
+            Currency::Chf => "CHF",
+            Currency::Cad => "CAD",
+            Currency::Aud => "AUD",
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/// An amount tagged with its currency, so amounts in different currencies
+/// cannot be combined by accident
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Money {
+    pub amount: f64,
+    pub currency: Currency,
+}
+
+impl Money {
+    pub fn new(amount: f64, currency: Currency) -> Self {
+        Self { amount, currency }
+    }
+
+    pub fn usd(amount: f64) -> Self {
+        Self::new(amount, Currency::Usd)
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(0.0, currency)
+    }
+
+    /// Sum of two amounts; fails if the currencies differ
+    pub fn checked_add(self, other: Money) -> Result<Money> {
+        self.ensure_same_currency(other, "add")?;
+        Ok(Money::new(self.amount + other.amount, self.currency))
+    }
+
+    /// Difference of two amounts; fails if the currencies differ
+    pub fn checked_sub(self, other: Money) -> Result<Money> {
+        self.ensure_same_currency(other, "subtract")?;
+        Ok(Money::new(self.amount - other.amount, self.currency))
+    }
+
+    fn ensure_same_currency(self, other: Money, op: &str) -> Result<()> {
+        if self.currency != other.currency {
+            return Err(TradingError::Validation(format!(
+                "cannot {} {} and {}: currency mismatch",
+                op, other, self
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2} {}", self.amount, self.currency)
+    }
 }
 
 /// Trading signal from strategy
@@ -137,10 +656,31 @@ pub struct Signal {
     pub symbol: Symbol,
     pub action: SignalAction,
     pub confidence: f64,
+    /// Feature vector; missing (NaN) values are encoded as null
+    #[serde(with = "missing_as_null")]
     pub features: Vec<f64>,
     pub timestamp: DateTime<Utc>,
 }
 
+/// Serde for float vectors that use NaN for missing values.
+///
+/// JSON has no NaN: serde_json writes it as null and then refuses to read
+/// null back as a float. Non-finite values are encoded as null (`None`) in
+/// every format and decoded as NaN.
+mod missing_as_null {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(values: &[f64], serializer: S) -> Result<S::Ok, S::Error> {
+        let values: Vec<Option<f64>> = values.iter().map(|v| v.is_finite().then_some(*v)).collect();
+        values.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<f64>, D::Error> {
+        let values = Vec::<Option<f64>>::deserialize(deserializer)?;
+        Ok(values.into_iter().map(|v| v.unwrap_or(f64::NAN)).collect())
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SignalAction {
     Buy,
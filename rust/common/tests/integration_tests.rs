@@ -1,6 +1,6 @@
 // Integration tests for common types and messaging
 use common::types::*;
-use common::messaging::*;
+use common::builders::*;
 use chrono::Utc;
 
 #[cfg(test)]
@@ -43,6 +43,160 @@ mod type_tests {
         assert!(display.contains("123.45678900"));
     }
 
+    #[test]
+    fn test_symbol_validation() {
+        assert_eq!(Symbol::new("  AAPL ").unwrap(), Symbol("AAPL".to_string()));
+        assert_eq!(Symbol::new("BTC/USD").unwrap().0, "BTC/USD");
+
+        for invalid in ["", "   ", "BR K", "A/B/C", "/USD", "BTC/"] {
+            assert!(
+                matches!(Symbol::new(invalid), Err(common::TradingError::Validation(_))),
+                "{:?} should be rejected",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_price_and_quantity_validation() {
+        assert_eq!(Price::new(150.25).unwrap().to_f64(), 150.25);
+        assert!(Price::new(0.0).is_err());
+        assert!(Price::new(-1.0).is_err());
+        assert!(Price::new(f64::NAN).is_err());
+
+        assert_eq!(Quantity::new(0.0).unwrap().to_f64(), 0.0);
+        assert!(Quantity::new(-5.0).is_err());
+        assert!(Quantity::new(f64::INFINITY).is_err());
+    }
+
+    #[test]
+    fn test_money_same_currency_arithmetic() {
+        let total = Money::usd(100.0).checked_add(Money::usd(25.5)).unwrap();
+        assert_eq!(total, Money::usd(125.5));
+        assert_eq!(total.checked_sub(Money::usd(0.5)).unwrap().amount, 125.0);
+        assert_eq!(format!("{}", total), "125.50 USD");
+    }
+
+    #[test]
+    fn test_money_currency_mismatch() {
+        let err = Money::usd(100.0).checked_add(Money::new(100.0, Currency::Eur));
+        assert!(matches!(err, Err(common::TradingError::Validation(_))));
+        assert!(Money::zero(Currency::Gbp).checked_sub(Money::usd(1.0)).is_err());
+        assert_eq!(Currency::from_code("eur"), Some(Currency::Eur));
+        assert_eq!(Currency::from_code("XYZ"), None);
+    }
+
+    #[test]
+    fn test_position_defaults_to_usd() {
+        let position = Position::new(Symbol("AAPL".to_string()), Side::Bid, Quantity(10.0), Price(150.0));
+        assert_eq!(position.currency, Currency::Usd);
+        assert_eq!(position.with_currency(Currency::Eur).realized_pnl_money(), Money::zero(Currency::Eur));
+    }
+
+    #[test]
+    fn test_order_builder() {
+        let order = OrderBuilder::new().symbol("MSFT").sell().quantity(50.0).stop_limit(99.0, 98.5).build();
+        assert_eq!(order.symbol.0, "MSFT");
+        assert_eq!(order.side, Side::Ask);
+        assert_eq!(order.order_type, OrderType::StopLimit);
+        assert_eq!(order.stop_price, Some(Price(99.0)));
+        assert_eq!(order.price, Some(Price(98.5)));
+
+        let partial = OrderBuilder::new().limit(150.0).filled(40.0, 149.9).build();
+        assert_eq!(partial.status, OrderStatus::PartiallyFilled);
+        assert_eq!(partial.filled_quantity, Quantity(40.0));
+    }
+
+    #[test]
+    fn test_position_and_trade_builders() {
+        let long = PositionBuilder::new().entry_price(100.0).with_profit(10.0).build();
+        assert!((long.unrealized_pnl - 1000.0).abs() < 1e-9);
+
+        let short = PositionBuilder::new().short().entry_price(100.0).with_loss(5.0).build();
+        assert!((short.current_price.0 - 105.0).abs() < 1e-9);
+        assert!((short.unrealized_pnl + 500.0).abs() < 1e-9);
+
+        let trade = TradeBuilder::new().trade_id("t-9").side(Side::Ask).price(151.0).build();
+        assert_eq!(trade.trade_id, "t-9");
+        assert_eq!(trade.price, Price(151.0));
+    }
+
+    fn assert_invalid(order: Order) {
+        assert!(
+            matches!(order.validate(), Err(common::TradingError::Validation(_))),
+            "{:?} should be invalid",
+            order
+        );
+    }
+
+    #[test]
+    fn test_order_validate_accepts_consistent_orders() {
+        assert!(OrderBuilder::new().market().build().validate().is_ok());
+        assert!(OrderBuilder::new().limit(150.0).build().validate().is_ok());
+        assert!(OrderBuilder::new().stop_market(145.0).build().validate().is_ok());
+        assert!(OrderBuilder::new().stop_limit(145.0, 144.5).build().validate().is_ok());
+        assert!(OrderBuilder::new().limit(150.0).filled(100.0, 150.0).build().validate().is_ok());
+    }
+
+    #[test]
+    fn test_order_validate_price_fields() {
+        let mut market_with_price = OrderBuilder::new().market().build();
+        market_with_price.price = Some(Price(150.0));
+        assert_invalid(market_with_price);
+
+        let mut limit_without_price = OrderBuilder::new().limit(150.0).build();
+        limit_without_price.price = None;
+        assert_invalid(limit_without_price);
+
+        let mut stop_without_stop = OrderBuilder::new().stop_market(145.0).build();
+        stop_without_stop.stop_price = None;
+        assert_invalid(stop_without_stop);
+
+        let mut stop_limit_without_stop = OrderBuilder::new().stop_limit(145.0, 144.5).build();
+        stop_limit_without_stop.stop_price = None;
+        assert_invalid(stop_limit_without_stop);
+
+        let mut stop_limit_without_limit = OrderBuilder::new().stop_limit(145.0, 144.5).build();
+        stop_limit_without_limit.price = None;
+        assert_invalid(stop_limit_without_limit);
+    }
+
+    #[test]
+    fn test_order_validate_quantities() {
+        assert_invalid(OrderBuilder::new().quantity(0.0).build());
+        assert_invalid(OrderBuilder::new().quantity(-10.0).build());
+        assert_invalid(OrderBuilder::new().quantity(f64::NAN).build());
+        assert_invalid(OrderBuilder::new().quantity(10.0).filled(20.0, 150.0).build());
+    }
+
+    #[test]
+    fn test_quantity_is_positive_finite() {
+        assert!(Quantity::from_f64(0.5).unwrap().is_positive_finite());
+        assert!(!Quantity::from_f64(0.0).unwrap().is_positive_finite());
+        assert!(!Quantity::from_f64(-1.0).unwrap().is_positive_finite());
+        assert!(!Quantity(f64::NAN).is_positive_finite());
+    }
+
+    #[test]
+    fn test_price_f64_conversion() {
+        let price = Price::from_f64(100.25).unwrap();
+        assert_eq!(price.to_f64(), 100.25);
+        assert!(Price::from_f64(f64::NAN).is_none());
+        assert!(Quantity::from_f64(f64::INFINITY).is_none());
+    }
+
+    #[test]
+    fn test_price_quantity_arithmetic() {
+        let a = Price::from_f64(100.5).unwrap();
+        let b = Price::from_f64(0.25).unwrap();
+        assert_eq!((a + b).to_f64(), 100.75);
+        assert_eq!((a - b).to_f64(), 100.25);
+
+        let filled = Quantity::from_f64(40.0).unwrap() + Quantity::from_f64(60.0).unwrap();
+        assert_eq!(filled.to_f64(), 100.0);
+        assert_eq!(Price::from_f64(2.5).unwrap().notional(filled), Price::from_f64(250.0).unwrap().0);
+    }
+
     #[test]
     fn test_quantity_operations() {
         let qty1 = Quantity(100.0);
@@ -309,21 +463,12 @@ mod order_tests {
 
     #[test]
     fn test_order_creation() {
-        let order = Order {
-            order_id: "order_123".to_string(),
-            client_order_id: "client_456".to_string(),
-            symbol: Symbol("AAPL".to_string()),
-            side: Side::Bid,
-            order_type: OrderType::Limit,
-            quantity: Quantity(10.0),
-            price: Some(Price(150.0)),
-            stop_price: None,
-            status: OrderStatus::Pending,
-            filled_quantity: Quantity(0.0),
-            average_price: None,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-        };
+        let order = OrderBuilder::new()
+            .order_id("order_123")
+            .client_order_id("client_456")
+            .quantity(10.0)
+            .limit(150.0)
+            .build();
 
         assert_eq!(order.symbol.0, "AAPL");
         assert_eq!(order.quantity.0, 10.0);
@@ -332,21 +477,14 @@ mod order_tests {
 
     #[test]
     fn test_market_order_no_price() {
-        let order = Order {
-            order_id: "order_789".to_string(),
-            client_order_id: "client_789".to_string(),
-            symbol: Symbol("GOOGL".to_string()),
-            side: Side::Ask,
-            order_type: OrderType::Market,
-            quantity: Quantity(5.0),
-            price: None, // Market orders don't have limit price
-            stop_price: None,
-            status: OrderStatus::Pending,
-            filled_quantity: Quantity(0.0),
-            average_price: None,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-        };
+        let order = OrderBuilder::new()
+            .order_id("order_789")
+            .client_order_id("client_789")
+            .symbol("GOOGL")
+            .sell()
+            .quantity(5.0)
+            .market()
+            .build();
 
         assert_eq!(order.order_type, OrderType::Market);
         assert!(order.price.is_none());
@@ -354,21 +492,13 @@ mod order_tests {
 
     #[test]
     fn test_order_partial_fill() {
-        let mut order = Order {
-            order_id: "order_partial".to_string(),
-            client_order_id: "client_partial".to_string(),
-            symbol: Symbol("MSFT".to_string()),
-            side: Side::Bid,
-            order_type: OrderType::Limit,
-            quantity: Quantity(100.0),
-            price: Some(Price(300.0)),
-            stop_price: None,
-            status: OrderStatus::Pending,
-            filled_quantity: Quantity(0.0),
-            average_price: None,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-        };
+        let mut order = OrderBuilder::new()
+            .order_id("order_partial")
+            .client_order_id("client_partial")
+            .symbol("MSFT")
+            .quantity(100.0)
+            .limit(300.0)
+            .build();
 
         // Simulate partial fill
         order.filled_quantity = Quantity(50.0);
@@ -379,6 +509,70 @@ mod order_tests {
         assert_eq!(order.status, OrderStatus::PartiallyFilled);
         assert!(order.filled_quantity.0 < order.quantity.0);
     }
+
+    #[test]
+    fn test_instrument_rounding_is_conservative() {
+        let specs = InstrumentSpecs::new().with_spec("BTC/USD", InstrumentSpec::new(0.5, 0.001));
+        let btc = Symbol("BTC/USD".to_string());
+
+        // Buys never pay more, sells never receive less
+        assert_eq!(specs.round_price(&btc, Side::Bid, Price(100.3)), Price(100.0));
+        assert_eq!(specs.round_price(&btc, Side::Ask, Price(100.3)), Price(100.5));
+        // Prices already on the grid are unchanged despite float error
+        assert_eq!(specs.round_price(&btc, Side::Ask, Price(0.1 + 0.2 + 99.7)), Price(100.0));
+
+        assert!((specs.round_qty(&btc, Quantity(0.0129)).0 - 0.012).abs() < 1e-12);
+
+        // Unknown symbols pass through
+        let eth = Symbol("ETH/USD".to_string());
+        assert_eq!(specs.round_price(&eth, Side::Bid, Price(100.3)), Price(100.3));
+        assert_eq!(specs.round_qty(&eth, Quantity(0.0129)), Quantity(0.0129));
+    }
+
+    #[test]
+    fn test_instrument_spec_checks_order() {
+        let spec = InstrumentSpec::new(0.01, 1.0).with_min_qty(10.0);
+        let mut order = OrderBuilder::new().symbol("AAPL").limit(150.25).quantity(10.0).build();
+        assert!(spec.check_order(&order).is_ok());
+
+        order.price = Some(Price(150.255));
+        assert!(spec.check_order(&order).is_err());
+
+        order.price = Some(Price(150.25));
+        order.quantity = Quantity(12.5);
+        assert!(spec.check_order(&order).is_err());
+
+        order.quantity = Quantity(9.0);
+        assert!(spec.check_order(&order).is_err());
+
+        assert!(InstrumentSpec::new(0.0, 1.0).validate().is_err());
+        assert!(InstrumentSpec::new(0.01, 1.0).with_min_qty(-1.0).validate().is_err());
+    }
+
+    #[test]
+    fn test_time_in_force() {
+        let now = Utc::now();
+        let order = OrderBuilder::new().created_at(now).build();
+        assert_eq!(order.time_in_force, TimeInForce::Gtc);
+
+        // Journaled orders predating time in force read back as GTC
+        let mut json = serde_json::to_value(&order).unwrap();
+        json.as_object_mut().unwrap().remove("time_in_force");
+        let restored: Order = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.time_in_force, TimeInForce::Gtc);
+
+        let expiry = now + chrono::Duration::hours(1);
+        let gtd = OrderBuilder::new().created_at(now).time_in_force(TimeInForce::Gtd(expiry)).build();
+        assert!(gtd.validate().is_ok());
+        let json = serde_json::to_string(&gtd).unwrap();
+        assert_eq!(serde_json::from_str::<Order>(&json).unwrap().time_in_force, TimeInForce::Gtd(expiry));
+
+        let stale = OrderBuilder::new().created_at(now).time_in_force(TimeInForce::Gtd(now)).build();
+        assert!(stale.validate().is_err());
+
+        assert!(TimeInForce::Fok.is_immediate());
+        assert!(!TimeInForce::Day.is_immediate());
+    }
 }
 
 #[cfg(test)]
@@ -397,6 +591,7 @@ mod position_tests {
             realized_pnl: 0.0,
             opened_at: Utc::now(),
             updated_at: Utc::now(),
+            currency: Currency::default(),
         };
 
         assert_eq!(position.symbol.0, "AAPL");
@@ -422,6 +617,7 @@ mod position_tests {
             realized_pnl: 0.0,
             opened_at: Utc::now(),
             updated_at: Utc::now(),
+            currency: Currency::default(),
         };
 
         assert_eq!(position.unrealized_pnl, 100.0);
@@ -492,4 +688,188 @@ mod signal_tests {
         assert!(signal.confidence >= 0.0);
         assert!(signal.confidence <= 1.0);
     }
+
+    #[test]
+    fn test_signal_missing_features_survive_every_codec() {
+        use common::messaging::{decode_with, encode_with, Codec};
+
+        let signal = Signal {
+            symbol: Symbol("TEST".to_string()),
+            action: SignalAction::Buy,
+            confidence: 0.75,
+            features: vec![0.5, f64::NAN, -1.0],
+            timestamp: Utc::now(),
+        };
+
+        for codec in [Codec::Json, Codec::Bincode] {
+            let decoded: Signal = decode_with(codec, &encode_with(codec, &signal).unwrap()).unwrap();
+            assert_eq!(decoded.features[0], 0.5);
+            assert!(decoded.features[1].is_nan());
+            assert_eq!(decoded.features[2], -1.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod config_watch_tests {
+    use common::config::{SharedConfig, SystemConfig};
+    use std::time::Duration;
+
+    /// Repo config with inline credentials so loading doesn't need env vars
+    fn base_config() -> serde_json::Value {
+        let content = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../../config/system.json")).unwrap();
+        let mut config: serde_json::Value = serde_json::from_str(&content).unwrap();
+        config["execution"]["api_key"] = "test-key".into();
+        config["execution"]["api_secret"] = "test-secret".into();
+        config
+    }
+
+    #[tokio::test]
+    async fn test_watch_reloads_valid_and_ignores_invalid() {
+        let dir = std::env::temp_dir().join(format!("config-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("system.json");
+        let mut config = base_config();
+        std::fs::write(&path, config.to_string()).unwrap();
+
+        let (initial, mut updates) = SystemConfig::watch(path.to_str().unwrap()).unwrap();
+        let shared = SharedConfig::new(initial);
+
+        // An invalid limit is ignored, then the next valid edit comes through
+        config["risk"]["max_position_size"] = (-1.0).into();
+        std::fs::write(&path, config.to_string()).unwrap();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        config["risk"]["max_position_size"] = 1234.0.into();
+        std::fs::write(&path, config.to_string()).unwrap();
+
+        let reloaded = tokio::time::timeout(Duration::from_secs(5), updates.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reloaded.risk.max_position_size, 1234.0);
+
+        shared.replace(reloaded);
+        assert_eq!(shared.risk().max_position_size, 1234.0);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
+#[cfg(test)]
+mod config_env_tests {
+    use common::config::{apply_env_overrides, FeeSchedule, SystemConfig};
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn base_config() -> serde_json::Value {
+        let content = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../../config/system.json")).unwrap();
+        serde_json::from_str(&content).unwrap()
+    }
+
+    #[test]
+    fn test_env_overrides_take_precedence() {
+        let mut value = base_config();
+        apply_env_overrides(&mut value, vars(&[
+            ("APP_EXECUTION__RATE_LIMIT_PER_SECOND", "20"),
+            ("APP_RISK__ENABLE_CIRCUIT_BREAKER", "false"),
+            ("APP_METADATA__ENVIRONMENT", "staging"),
+            ("EXECUTION__RATE_LIMIT_PER_SECOND", "99"),
+        ])).unwrap();
+
+        let config: SystemConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.execution.rate_limit_per_second, 20);
+        assert!(!config.risk.enable_circuit_breaker);
+        assert_eq!(config.environment(), "staging");
+    }
+
+    #[test]
+    fn test_string_fields_stay_strings() {
+        let mut value = base_config();
+        apply_env_overrides(&mut value, vars(&[("APP_MARKET_DATA__EXCHANGE", "123")])).unwrap();
+        assert_eq!(value["market_data"]["exchange"], "123");
+    }
+
+    #[test]
+    fn test_fee_model_defaults_to_free_and_parses() {
+        let config: SystemConfig = serde_json::from_value(base_config()).unwrap();
+        assert_eq!(config.execution.fee_model, FeeSchedule::None);
+
+        let mut value = base_config();
+        value["execution"]["fee_model"] = serde_json::json!({"type": "per_share", "rate": 0.005, "minimum": 1.0});
+        let config: SystemConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.execution.fee_model, FeeSchedule::PerShare { rate: 0.005, minimum: 1.0 });
+
+        assert!(FeeSchedule::BasisPoints { bps: -1.0, minimum: 0.0 }.validate().is_err());
+        assert!(FeeSchedule::FlatPerOrder { fee: f64::NAN }.validate().is_err());
+    }
+
+    #[test]
+    fn test_instruments_parse_from_config() {
+        let config: SystemConfig = serde_json::from_value(base_config()).unwrap();
+        assert!(config.execution.instruments.is_empty());
+
+        let mut value = base_config();
+        value["execution"]["instruments"] = serde_json::json!({"AAPL": {"tick_size": 0.01, "lot_size": 1.0}});
+        let config: SystemConfig = serde_json::from_value(value).unwrap();
+        let spec = config.execution.instruments.get(&common::types::Symbol("AAPL".to_string())).unwrap();
+        assert_eq!((spec.tick_size, spec.lot_size, spec.min_qty), (0.01, 1.0, 0.0));
+        assert!(config.execution.validate().is_ok());
+
+        let mut execution = config.execution;
+        execution.instruments = execution
+            .instruments
+            .with_spec("MSFT", common::types::InstrumentSpec::new(0.01, 0.0));
+        assert!(execution.validate().is_err());
+    }
+
+    #[test]
+    fn test_market_data_session_validation() {
+        let config: SystemConfig = serde_json::from_value(base_config()).unwrap();
+        assert!(config.market_data.validate().is_ok());
+        assert!(config.market_data.bar_windows.contains(&"1d".to_string()));
+
+        let mut market_data = config.market_data.clone();
+        market_data.session.as_mut().unwrap().close = "09:00".to_string();
+        assert!(market_data.validate().is_err());
+
+        let mut market_data = config.market_data;
+        market_data.session.as_mut().unwrap().open = "9.30".to_string();
+        assert!(market_data.validate().is_err());
+    }
+
+    #[test]
+    fn test_override_through_scalar_is_rejected() {
+        let mut value = base_config();
+        let result = apply_env_overrides(&mut value, vars(&[("APP_RISK__MAX_POSITION_SIZE__X", "1")]));
+        assert!(result.is_err());
+    }
+}
+
+#[cfg(test)]
+mod error_tests {
+    use common::{ResultExt, TradingError};
+
+    #[test]
+    fn test_error_codes_are_stable() {
+        assert_eq!(TradingError::Risk("limit".to_string()).error_code(), "E_RISK_LIMIT");
+        assert_eq!(TradingError::Network("timeout".to_string()).error_code(), "E_NET");
+        assert_eq!(TradingError::Configuration("missing".to_string()).error_code(), "E_CONFIG");
+    }
+
+    #[test]
+    fn test_context_keeps_code_and_retryability() {
+        let result: common::Result<()> = Err(TradingError::Network("connection reset".to_string()));
+        let err = result.with_context(|| "order abc123 for AAPL").unwrap_err();
+
+        assert_eq!(err.to_string(), "order abc123 for AAPL: Network error: connection reset");
+        assert_eq!(err.error_code(), "E_NET");
+        assert!(err.is_retryable());
+        assert!(matches!(err.root(), TradingError::Network(_)));
+
+        let err = TradingError::Risk("too big".to_string()).with_context("AAPL");
+        assert!(!err.is_retryable());
+        assert_eq!(err.error_code(), "E_RISK_LIMIT");
+    }
 }
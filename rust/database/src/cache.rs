@@ -0,0 +1,154 @@
+//! Query-result cache for repeated reads
+//!
+//! Results are keyed by the generated SQL, which already encodes the
+//! filters and time window, and tagged with the table they read so inserts
+//! can invalidate them.
+
+use serde::Serialize;
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Query cache sizing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QueryCacheConfig {
+    /// Maximum cached results; the least recently used is evicted beyond this
+    pub capacity: usize,
+    /// How long a result is served before the query runs again
+    pub ttl: Duration,
+}
+
+impl Default for QueryCacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 256,
+            ttl: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Cache hit/miss counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Results currently cached
+    pub entries: usize,
+}
+
+impl CacheStats {
+    /// Fraction of lookups served from the cache
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+struct Entry {
+    table: &'static str,
+    value: Arc<dyn Any + Send + Sync>,
+    stored_at: Instant,
+    last_used: u64,
+}
+
+/// LRU cache of query results with a TTL
+pub(crate) struct QueryCache {
+    config: QueryCacheConfig,
+    entries: Mutex<HashMap<String, Entry>>,
+    /// Monotonic use counter for LRU ordering
+    tick: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl QueryCache {
+    pub(crate) fn new(config: QueryCacheConfig) -> Self {
+        Self {
+            config,
+            entries: Mutex::new(HashMap::new()),
+            tick: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Cached result for `sql`, if present, fresh and of type `T`
+    pub(crate) fn get<T: Clone + Send + Sync + 'static>(&self, sql: &str) -> Option<T> {
+        let mut entries = self.entries.lock().unwrap();
+        let fresh = entries
+            .get(sql)
+            .is_some_and(|entry| entry.stored_at.elapsed() < self.config.ttl);
+
+        let value = if fresh {
+            entries.get_mut(sql).and_then(|entry| {
+                entry.last_used = self.tick.fetch_add(1, Ordering::Relaxed);
+                entry.value.downcast_ref::<T>().cloned()
+            })
+        } else {
+            entries.remove(sql);
+            None
+        };
+
+        match value {
+            Some(_) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                metrics::counter!("database_query_cache_hits_total").increment(1);
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                metrics::counter!("database_query_cache_misses_total").increment(1);
+            }
+        }
+        value
+    }
+
+    /// Store a result read from `table`
+    pub(crate) fn insert<T: Clone + Send + Sync + 'static>(&self, table: &'static str, sql: String, value: &T) {
+        let mut entries = self.entries.lock().unwrap();
+        if !entries.contains_key(&sql) && entries.len() >= self.config.capacity.max(1) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+
+        entries.insert(
+            sql,
+            Entry {
+                table,
+                value: Arc::new(value.clone()),
+                stored_at: Instant::now(),
+                last_used: self.tick.fetch_add(1, Ordering::Relaxed),
+            },
+        );
+    }
+
+    /// Drop every result read from `table`
+    pub(crate) fn invalidate(&self, table: &str) {
+        self.entries
+            .lock()
+            .unwrap()
+            .retain(|_, entry| entry.table != table);
+    }
+
+    pub(crate) fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.entries.lock().unwrap().len(),
+        }
+    }
+}
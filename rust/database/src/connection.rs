@@ -1,5 +1,6 @@
 //! Database connection management with pooling
 
+use crate::cache::{CacheStats, QueryCache, QueryCacheConfig};
 use crate::error::{DatabaseError, Result};
 use crate::models::*;
 use crate::query::{QueryBuilder, TimeInterval};
@@ -8,9 +9,26 @@ use crate::schema::Schema;
 use chrono::{DateTime, Utc};
 use duckdb::{Config, Connection};
 use r2d2::{Pool, PooledConnection};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+/// Columns a candle CSV must provide
+const CANDLE_CSV_COLUMNS: [&str; 6] = ["timestamp", "open", "high", "low", "close", "volume"];
+
+/// Where an imported CSV row gets its symbol
+enum CsvSymbol<'a> {
+    /// Read from this column
+    Column(&'a str),
+    /// The same symbol for every row
+    Fixed(&'a str),
+}
+
+/// Quote a column name for use in generated SQL
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
 
 /// Type alias for connection pool
 pub type ConnectionPool = Pool<ConnectionManager>;
@@ -51,10 +69,33 @@ impl r2d2::ManageConnection for ConnectionManager {
     }
 }
 
+/// Connection pool and caching options for [`DatabaseManager::with_config`]
+#[derive(Debug, Clone)]
+pub struct PoolConfig {
+    /// Maximum pooled connections
+    pub max_size: u32,
+    /// How long a checkout waits for a free connection
+    pub connection_timeout: Duration,
+    /// Cache repeated reads for a short TTL; off by default so write-heavy
+    /// deployments don't pay for invalidation
+    pub query_cache: Option<QueryCacheConfig>,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_size: 10,
+            connection_timeout: Duration::from_secs(30),
+            query_cache: None,
+        }
+    }
+}
+
 /// High-level database manager with connection pooling
 pub struct DatabaseManager {
     pool: Arc<ConnectionPool>,
     path: PathBuf,
+    cache: Option<QueryCache>,
 }
 
 impl DatabaseManager {
@@ -71,20 +112,101 @@ impl DatabaseManager {
     /// # }
     /// ```
     pub async fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::build(path, PoolConfig::default())
+    }
+
+    /// Create a manager with explicit pool and cache settings
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use database::{DatabaseManager, PoolConfig, QueryCacheConfig};
+    /// use std::time::Duration;
+    ///
+    /// # async fn example() -> anyhow::Result<()> {
+    /// let config = PoolConfig {
+    ///     query_cache: Some(QueryCacheConfig { capacity: 128, ttl: Duration::from_secs(10) }),
+    ///     ..PoolConfig::default()
+    /// };
+    /// let db = DatabaseManager::with_config("metrics.duckdb", config).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn with_config<P: AsRef<Path>>(path: P, config: PoolConfig) -> Result<Self> {
+        Self::build(path, config)
+    }
+
+    /// Create a manager with an explicit pool size and checkout timeout
+    #[cfg(test)]
+    fn with_pool_config<P: AsRef<Path>>(
+        path: P,
+        max_size: u32,
+        connection_timeout: Duration,
+    ) -> Result<Self> {
+        Self::build(
+            path,
+            PoolConfig {
+                max_size,
+                connection_timeout,
+                ..PoolConfig::default()
+            },
+        )
+    }
+
+    fn build<P: AsRef<Path>>(path: P, config: PoolConfig) -> Result<Self> {
         let path = path.as_ref().to_path_buf();
         let manager = ConnectionManager::new(&path);
 
         let pool = Pool::builder()
-            .max_size(10) // Maximum 10 connections
-            .min_idle(Some(2)) // Keep at least 2 idle connections
+            .max_size(config.max_size)
+            .min_idle(Some(config.max_size.min(2))) // Keep at least 2 idle connections
+            .connection_timeout(config.connection_timeout)
             .build(manager)?;
 
         Ok(Self {
             pool: Arc::new(pool),
             path,
+            cache: config.query_cache.map(QueryCache::new),
         })
     }
 
+    /// Query cache hit/miss counts; all zero when caching is disabled
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache.as_ref().map(QueryCache::stats).unwrap_or_default()
+    }
+
+    /// Drop every cached query result
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Serve `sql` from the cache, or run it and cache the result
+    fn cached_query<T, F>(&self, table: &'static str, sql: String, run: F) -> Result<T>
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce(&str) -> Result<T>,
+    {
+        let Some(cache) = &self.cache else {
+            return run(&sql);
+        };
+        if let Some(hit) = cache.get::<T>(&sql) {
+            return Ok(hit);
+        }
+
+        let value = run(&sql)?;
+        cache.insert(table, sql, &value);
+        Ok(value)
+    }
+
+    /// Drop cached results read from a table that was just written
+    fn invalidate_cache(&self, table: &str) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate(table);
+        }
+    }
+
     /// Initialize database schema
     ///
     /// This creates all necessary tables and indexes if they don't exist.
@@ -102,9 +224,74 @@ impl DatabaseManager {
         Ok(())
     }
 
+    /// Apply pending schema migrations and return the resulting version
+    ///
+    /// Reads the current version from `schema_version`, then applies each
+    /// built-in migration with a higher version in ascending order. Every
+    /// migration runs in its own transaction together with its version
+    /// record, so a failing step is rolled back and the recorded version stays
+    /// at the last migration that succeeded. Already-applied migrations are
+    /// skipped, making repeated calls safe.
+    #[cfg(feature = "migration-tools")]
+    pub async fn migrate(&self) -> Result<u32> {
+        let mut conn = self.get_connection()?;
+        Schema::create_version_table(&conn)?;
+
+        let mut version = Schema::current_version(&conn)?;
+        tracing::info!("Current schema version: {}", version);
+
+        let mut pending = crate::migrations::get_builtin_migrations()
+            .into_iter()
+            .map(|m| m.version_number().map(|v| (v, m)))
+            .collect::<Result<Vec<_>>>()?;
+        pending.retain(|(v, _)| *v > version);
+        pending.sort_by_key(|(v, _)| *v);
+
+        for (target, migration) in pending {
+            tracing::info!("Applying migration {}: {}", target, migration.name);
+
+            let tx = conn.transaction()?;
+            tx.execute_batch(&migration.up_sql).map_err(|e| {
+                DatabaseError::migration(format!(
+                    "Migration {} ({}) failed: {}",
+                    target, migration.name, e
+                ))
+            })?;
+            tx.execute(
+                "INSERT INTO schema_version (version, name, applied_at) VALUES (?, ?, ?)",
+                duckdb::params![target, &migration.name, Utc::now().to_rfc3339()],
+            )?;
+            tx.commit()?;
+
+            version = target;
+            metrics::counter!("database_migrations_applied_total").increment(1);
+        }
+
+        tracing::info!("Schema at version {}", version);
+        Ok(version)
+    }
+
     /// Get a pooled connection
+    ///
+    /// Returns [`DatabaseError::PoolTimeout`] when every connection is checked
+    /// out for the whole connection timeout, and [`DatabaseError::Pool`] when
+    /// the pool had spare capacity but could not open a connection.
     pub fn get_connection(&self) -> Result<PooledConnection<ConnectionManager>> {
-        self.pool.get().map_err(DatabaseError::from)
+        self.pool.get().map_err(|e| {
+            // r2d2 reports every checkout failure as a timeout, so tell
+            // exhaustion apart from connection failures by the pool state
+            let state = self.pool.state();
+            if state.idle_connections == 0 && state.connections >= self.pool.max_size() {
+                metrics::counter!("database_pool_exhausted_total").increment(1);
+                tracing::warn!(
+                    "Connection pool exhausted ({} connections in use)",
+                    state.connections
+                );
+                DatabaseError::PoolTimeout(self.pool.connection_timeout())
+            } else {
+                DatabaseError::from(e)
+            }
+        })
     }
 
     /// Insert a single metric
@@ -146,6 +333,7 @@ impl DatabaseManager {
             ],
         )?;
 
+        self.invalidate_cache("trading_metrics");
         metrics::counter!("database_metrics_inserted_total").increment(1);
         Ok(())
     }
@@ -197,6 +385,7 @@ impl DatabaseManager {
         }
 
         tx.commit()?;
+        self.invalidate_cache("trading_metrics");
 
         let elapsed = start.elapsed();
         metrics::counter!("database_metrics_inserted_total").increment(metrics.len() as u64);
@@ -221,34 +410,67 @@ impl DatabaseManager {
         start_time: Option<DateTime<Utc>>,
         limit: i64,
     ) -> Result<Vec<MetricRecord>> {
-        let conn = self.get_connection()?;
         let query = QueryBuilder::new()
             .select_metrics(metric_name, symbol, start_time, limit);
 
-        let mut stmt = conn.prepare(&query)?;
-        let rows = stmt.query_map([], |row| {
-            let timestamp_str: String = row.get(0)?;
-            let timestamp = timestamp_str
-                .parse()
-                .map_err(|e| duckdb::Error::FromSqlConversionFailure(
-                    0,
-                    duckdb::types::Type::Text,
-                    Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Invalid timestamp format: {}", e)))
-                ))?;
-
-            Ok(MetricRecord {
-                timestamp,
-                metric_name: row.get(1)?,
-                value: row.get(2)?,
-                symbol: row.get(3)?,
-                labels: row
-                    .get::<_, Option<String>>(4)?
-                    .and_then(|s| serde_json::from_str(&s).ok()),
-            })
-        })?;
+        self.cached_query("trading_metrics", query, |query| {
+            let conn = self.get_connection()?;
+            let mut stmt = conn.prepare(query)?;
+            let rows = stmt.query_map([], metric_from_row)?;
 
-        rows.collect::<std::result::Result<Vec<_>, _>>()
-            .map_err(DatabaseError::from)
+            rows.collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(DatabaseError::from)
+        })
+    }
+
+    /// Get one page of metrics using keyset pagination
+    ///
+    /// Returns the page (newest first) together with the cursor for the next
+    /// page, which is the `(timestamp, symbol)` of the last row. Pass it back
+    /// as `cursor` to continue; `None` means there are no more pages. Unlike
+    /// an OFFSET scan, each page only touches the rows it returns, and a page
+    /// boundary that falls between rows sharing a timestamp (the same metric
+    /// across several symbols) neither skips nor repeats them.
+    ///
+    /// # Arguments
+    ///
+    /// * `metric_name` - Name of the metric to retrieve
+    /// * `symbol` - Optional symbol filter
+    /// * `start_time` - Optional start time filter
+    /// * `page_size` - Maximum number of records per page
+    /// * `cursor` - Cursor returned by the previous call, or `None` for the first page
+    pub async fn get_metrics_paged(
+        &self,
+        metric_name: &str,
+        symbol: Option<&str>,
+        start_time: Option<DateTime<Utc>>,
+        page_size: i64,
+        cursor: Option<&MetricCursor>,
+    ) -> Result<(Vec<MetricRecord>, Option<MetricCursor>)> {
+        if page_size <= 0 {
+            return Err(DatabaseError::invalid_param(format!(
+                "page_size must be positive, got {}",
+                page_size
+            )));
+        }
+
+        let conn = self.get_connection()?;
+        let query = QueryBuilder::new()
+            .select_metrics_page(metric_name, symbol, start_time, page_size, cursor);
+
+        let mut stmt = conn.prepare(&query)?;
+        let page = stmt
+            .query_map([], metric_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        // A short page means the result set is exhausted
+        let next_cursor = if page.len() as i64 == page_size {
+            page.last().map(MetricCursor::from)
+        } else {
+            None
+        };
+
+        Ok((page, next_cursor))
     }
 
     /// Insert a candle record
@@ -269,6 +491,7 @@ impl DatabaseManager {
             ],
         )?;
 
+        self.invalidate_cache("trading_candles");
         metrics::counter!("database_candles_inserted_total").increment(1);
         Ok(())
     }
@@ -281,37 +504,250 @@ impl DatabaseManager {
         start_time: Option<DateTime<Utc>>,
         limit: i64,
     ) -> Result<Vec<CandleRecord>> {
-        let conn = self.get_connection()?;
+        self.query_candles(symbol, interval, start_time, limit)
+    }
+
+    /// Get candles for several intervals at once
+    ///
+    /// Each interval is queried concurrently on its own pooled connection,
+    /// and its candles keep the order `get_candles` returns. Fails with the
+    /// first error if any query fails, rather than returning partial results.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use database::{DatabaseManager, TimeInterval};
+    ///
+    /// # async fn example(db: &DatabaseManager) -> anyhow::Result<()> {
+    /// let intervals = [TimeInterval::Minute, TimeInterval::FiveMinutes, TimeInterval::Hour];
+    /// let candles = db.get_candles_multi("BTC/USD", &intervals, None, 100).await?;
+    /// println!("{} hourly candles", candles[&TimeInterval::Hour].len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_candles_multi(
+        &self,
+        symbol: &str,
+        intervals: &[TimeInterval],
+        start_time: Option<DateTime<Utc>>,
+        limit: i64,
+    ) -> Result<HashMap<TimeInterval, Vec<CandleRecord>>> {
+        let mut unique: Vec<TimeInterval> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            if !unique.contains(interval) {
+                unique.push(*interval);
+            }
+        }
+
+        let results: Vec<Result<Vec<CandleRecord>>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = unique
+                .iter()
+                .map(|&interval| {
+                    scope.spawn(move || self.query_candles(symbol, interval, start_time, limit))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle.join().unwrap_or_else(|_| {
+                        Err(DatabaseError::Query("candle query thread panicked".to_string()))
+                    })
+                })
+                .collect()
+        });
+
+        unique
+            .into_iter()
+            .zip(results)
+            .map(|(interval, candles)| candles.map(|candles| (interval, candles)))
+            .collect()
+    }
+
+    fn query_candles(
+        &self,
+        symbol: &str,
+        interval: TimeInterval,
+        start_time: Option<DateTime<Utc>>,
+        limit: i64,
+    ) -> Result<Vec<CandleRecord>> {
         let query = QueryBuilder::new().select_candles(symbol, interval, start_time, limit);
 
-        let mut stmt = conn.prepare(&query)?;
-        let rows = stmt.query_map([], |row| {
-            let timestamp_str: String = row.get(0)?;
-            let timestamp = timestamp_str
-                .parse()
-                .map_err(|e| duckdb::Error::FromSqlConversionFailure(
-                    0,
-                    duckdb::types::Type::Text,
-                    Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Invalid timestamp format in candle: {}", e)))
-                ))?;
-
-            Ok(CandleRecord {
-                timestamp,
-                symbol: row.get(1)?,
-                open: row.get(2)?,
-                high: row.get(3)?,
-                low: row.get(4)?,
-                close: row.get(5)?,
-                volume: row.get(6)?,
-                trade_count: row.get(7)?,
-            })
-        })?;
+        self.cached_query("trading_candles", query, |query| {
+            let conn = self.get_connection()?;
+            let mut stmt = conn.prepare(query)?;
+            let rows = stmt.query_map([], |row| {
+                let timestamp_str: String = row.get(0)?;
+                let timestamp = timestamp_str
+                    .parse()
+                    .map_err(|e| duckdb::Error::FromSqlConversionFailure(
+                        0,
+                        duckdb::types::Type::Text,
+                        Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Invalid timestamp format in candle: {}", e)))
+                    ))?;
+
+                Ok(CandleRecord {
+                    timestamp,
+                    symbol: row.get(1)?,
+                    open: row.get(2)?,
+                    high: row.get(3)?,
+                    low: row.get(4)?,
+                    close: row.get(5)?,
+                    volume: row.get(6)?,
+                    trade_count: row.get(7)?,
+                })
+            })?;
+
+            rows.collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(DatabaseError::from)
+        })
+    }
 
-        rows.collect::<std::result::Result<Vec<_>, _>>()
-            .map_err(DatabaseError::from)
+    /// Bulk-load OHLCV bars from a CSV file into `trading_candles`
+    ///
+    /// The file is read with DuckDB's `read_csv_auto` and must have
+    /// `timestamp`, `open`, `high`, `low`, `close` and `volume` columns
+    /// (matched case-insensitively; `trade_count` is optional). The symbol is
+    /// read from `symbol_column`, or from a column named `symbol` when `None`.
+    /// Rows already present for the same timestamp and symbol are replaced.
+    /// Returns the number of rows imported.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use database::DatabaseManager;
+    /// use std::path::Path;
+    ///
+    /// # async fn example(db: &DatabaseManager) -> anyhow::Result<()> {
+    /// let rows = db.import_candles_csv(Path::new("history/bars.csv"), Some("ticker")).await?;
+    /// println!("imported {} bars", rows);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn import_candles_csv(&self, path: &Path, symbol_column: Option<&str>) -> Result<u64> {
+        self.import_candles(path, CsvSymbol::Column(symbol_column.unwrap_or("symbol")))
+    }
+
+    /// Bulk-load a single symbol's OHLCV bars from a CSV file without a
+    /// symbol column; see [`DatabaseManager::import_candles_csv`]
+    pub async fn import_candles_csv_for_symbol(&self, path: &Path, symbol: &str) -> Result<u64> {
+        self.import_candles(path, CsvSymbol::Fixed(symbol))
+    }
+
+    fn import_candles(&self, path: &Path, symbol: CsvSymbol<'_>) -> Result<u64> {
+        if !path.is_file() {
+            return Err(DatabaseError::not_found(format!("CSV file {}", path.display())));
+        }
+
+        let start = Instant::now();
+        let conn = self.get_connection()?;
+        let source = format!(
+            "read_csv_auto('{}', header = true)",
+            path.to_string_lossy().replace('\'', "''")
+        );
+
+        // Map the required columns onto the file's actual column names
+        let mut stmt = conn.prepare(&format!("DESCRIBE SELECT * FROM {}", source))?;
+        let columns: Vec<String> = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<std::result::Result<_, _>>()?;
+        let find = |name: &str| columns.iter().find(|c| c.eq_ignore_ascii_case(name)).cloned();
+
+        let missing: Vec<&str> = CANDLE_CSV_COLUMNS
+            .iter()
+            .copied()
+            .filter(|name| find(name).is_none())
+            .collect();
+        if !missing.is_empty() {
+            return Err(DatabaseError::schema(format!(
+                "CSV {} is missing required column(s) {} (found: {})",
+                path.display(),
+                missing.join(", "),
+                columns.join(", ")
+            )));
+        }
+        let column = |name: &str| quote_identifier(&find(name).expect("required column checked"));
+
+        let symbol_expr = match symbol {
+            CsvSymbol::Column(name) => match find(name) {
+                Some(actual) => format!("CAST({} AS VARCHAR)", quote_identifier(&actual)),
+                None => {
+                    return Err(DatabaseError::schema(format!(
+                        "CSV {} has no symbol column '{}' (found: {})",
+                        path.display(),
+                        name,
+                        columns.join(", ")
+                    )))
+                }
+            },
+            CsvSymbol::Fixed(symbol) => {
+                if symbol.is_empty() {
+                    return Err(DatabaseError::invalid_param("symbol must not be empty"));
+                }
+                format!("'{}'", symbol.replace('\'', "''"))
+            }
+        };
+        let trade_count_expr = find("trade_count")
+            .map(|actual| format!("CAST({} AS INTEGER)", quote_identifier(&actual)))
+            .unwrap_or_else(|| "NULL".to_string());
+
+        let select = format!(
+            "SELECT CAST({ts} AS TIMESTAMP) AS timestamp, {symbol} AS symbol, \
+             CAST({open} AS DOUBLE) AS open, CAST({high} AS DOUBLE) AS high, \
+             CAST({low} AS DOUBLE) AS low, CAST({close} AS DOUBLE) AS close, \
+             CAST({volume} AS BIGINT) AS volume, {trade_count} AS trade_count \
+             FROM {source}",
+            ts = column("timestamp"),
+            symbol = symbol_expr,
+            open = column("open"),
+            high = column("high"),
+            low = column("low"),
+            close = column("close"),
+            volume = column("volume"),
+            trade_count = trade_count_expr,
+            source = source,
+        );
+
+        // Reject the whole file rather than loading inconsistent bars
+        let invalid: i64 = conn
+            .query_row(
+                &format!(
+                    "SELECT COUNT(*) FROM ({}) WHERE timestamp IS NULL OR symbol IS NULL \
+                     OR open IS NULL OR high IS NULL OR low IS NULL OR close IS NULL OR volume IS NULL \
+                     OR high < low OR open NOT BETWEEN low AND high OR close NOT BETWEEN low AND high \
+                     OR low <= 0 OR volume < 0",
+                    select
+                ),
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| DatabaseError::schema(format!("CSV {} does not match the candle schema: {}", path.display(), e)))?;
+        if invalid > 0 {
+            return Err(DatabaseError::schema(format!(
+                "CSV {} has {} row(s) with missing values or inconsistent OHLC prices",
+                path.display(),
+                invalid
+            )));
+        }
+
+        let rows = conn.execute(
+            &format!(
+                "INSERT OR REPLACE INTO trading_candles (timestamp, symbol, open, high, low, close, volume, trade_count) {}",
+                select
+            ),
+            [],
+        )? as u64;
+
+        self.invalidate_cache("trading_candles");
+        metrics::counter!("database_candles_inserted_total").increment(rows);
+        tracing::info!("Imported {} candles from {} in {:?}", rows, path.display(), start.elapsed());
+        Ok(rows)
     }
 
     /// Get aggregated metrics
+    ///
+    /// `aggregation` accepts avg, sum, min, max, count, or a percentile such
+    /// as `p50`/`p95`/`p99`. Unknown values return
+    /// [`DatabaseError::InvalidParameter`].
     pub async fn get_aggregated_metrics(
         &self,
         metric_name: &str,
@@ -319,31 +755,174 @@ impl DatabaseManager {
         start_time: Option<DateTime<Utc>>,
         aggregation: &str,
     ) -> Result<Vec<AggregatedMetric>> {
+        let query = QueryBuilder::new().aggregate_metrics(metric_name, interval, start_time, aggregation)?;
+
+        self.cached_query("trading_metrics", query, |query| {
+            let conn = self.get_connection()?;
+            let mut stmt = conn.prepare(query)?;
+            let rows = stmt.query_map([], |row| {
+                let time_bucket_str: String = row.get(0)?;
+                let time_bucket = time_bucket_str
+                    .parse()
+                    .map_err(|e| duckdb::Error::FromSqlConversionFailure(
+                        0,
+                        duckdb::types::Type::Text,
+                        Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Invalid time_bucket format in aggregated metric: {}", e)))
+                    ))?;
+
+                Ok(AggregatedMetric {
+                    time_bucket,
+                    metric_name: row.get(1)?,
+                    symbol: row.get(2)?,
+                    value: row.get(3)?,
+                    count: row.get(4)?,
+                })
+            })?;
+
+            rows.collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(DatabaseError::from)
+        })
+    }
+
+    /// Insert a trade execution record
+    pub async fn insert_trade(&self, trade: &TradeRecord) -> Result<()> {
         let conn = self.get_connection()?;
-        let query = QueryBuilder::new().aggregate_metrics(metric_name, interval, start_time, aggregation);
 
-        let mut stmt = conn.prepare(&query)?;
-        let rows = stmt.query_map([], |row| {
-            let time_bucket_str: String = row.get(0)?;
-            let time_bucket = time_bucket_str
-                .parse()
-                .map_err(|e| duckdb::Error::FromSqlConversionFailure(
-                    0,
-                    duckdb::types::Type::Text,
-                    Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Invalid time_bucket format in aggregated metric: {}", e)))
-                ))?;
-
-            Ok(AggregatedMetric {
-                time_bucket,
-                metric_name: row.get(1)?,
-                symbol: row.get(2)?,
-                value: row.get(3)?,
-                count: row.get(4)?,
-            })
-        })?;
+        conn.execute(
+            "INSERT INTO trading_trades (trade_id, order_id, symbol, side, quantity, price, timestamp, commission, trade_value, liquidity, currency) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            duckdb::params![
+                &trade.trade_id,
+                &trade.order_id,
+                &trade.symbol,
+                &trade.side,
+                trade.quantity,
+                trade.price,
+                trade.timestamp.to_rfc3339(),
+                trade.commission,
+                trade.trade_value,
+                &trade.liquidity,
+                &trade.currency
+            ],
+        )?;
 
-        rows.collect::<std::result::Result<Vec<_>, _>>()
-            .map_err(DatabaseError::from)
+        self.invalidate_cache("trading_trades");
+        metrics::counter!("database_trades_inserted_total").increment(1);
+        Ok(())
+    }
+
+    /// Get volume-weighted average price per time bucket
+    ///
+    /// Buckets without traded volume are skipped rather than returned as NaN.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - Trading symbol
+    /// * `interval` - Time interval for bucketing
+    /// * `start_time` - Optional start time filter
+    pub async fn get_vwap(
+        &self,
+        symbol: &str,
+        interval: TimeInterval,
+        start_time: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(DateTime<Utc>, f64)>> {
+        let query = QueryBuilder::new().vwap(symbol, interval, start_time);
+
+        self.cached_query("trading_trades", query, |query| {
+            let conn = self.get_connection()?;
+            let mut stmt = conn.prepare(query)?;
+            let rows = stmt.query_map([], |row| {
+                let bucket_str: String = row.get(0)?;
+                let bucket = bucket_str
+                    .parse()
+                    .map_err(|e| duckdb::Error::FromSqlConversionFailure(
+                        0,
+                        duckdb::types::Type::Text,
+                        Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Invalid time_bucket format in vwap: {}", e)))
+                    ))?;
+
+                Ok((bucket, row.get::<_, f64>(1)?))
+            })?;
+
+            rows.collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(DatabaseError::from)
+        })
+    }
+
+    /// Trade blotter for end-of-day review
+    ///
+    /// Lists each fill between `start` and `end` (inclusive, oldest first)
+    /// with its parent order's totals, the running position and realized
+    /// P&L per symbol. Realized P&L uses average-cost accounting and is net
+    /// of commissions; fills before `start` still count towards the cost
+    /// basis and the running total.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use chrono::{Duration, Utc};
+    ///
+    /// # async fn example(db: &database::DatabaseManager) -> anyhow::Result<()> {
+    /// let today = Utc::now() - Duration::hours(24);
+    /// for row in db.trade_blotter(Some("AAPL"), Some(today), None).await? {
+    ///     println!("{} {} {} @ {} -> {:.2}", row.timestamp, row.side, row.quantity, row.price, row.cumulative_realized_pnl);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn trade_blotter(
+        &self,
+        symbol: Option<&str>,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<Vec<BlotterRow>> {
+        if let (Some(start), Some(end)) = (start, end) {
+            if start > end {
+                return Err(DatabaseError::invalid_param(format!(
+                    "blotter start {} is after end {}",
+                    start, end
+                )));
+            }
+        }
+
+        let conn = self.get_connection()?;
+        let query = QueryBuilder::new().trade_blotter(symbol, end);
+
+        let mut stmt = conn.prepare(&query)?;
+        let rows = stmt
+            .query_map([], |row| {
+                let timestamp_str: String = row.get(2)?;
+                let timestamp = timestamp_str
+                    .parse()
+                    .map_err(|e| duckdb::Error::FromSqlConversionFailure(
+                        2,
+                        duckdb::types::Type::Text,
+                        Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Invalid timestamp format in blotter: {}", e)))
+                    ))?;
+
+                Ok(BlotterRow {
+                    trade_id: row.get(0)?,
+                    order_id: row.get(1)?,
+                    timestamp,
+                    symbol: row.get(3)?,
+                    side: row.get(4)?,
+                    quantity: row.get(5)?,
+                    price: row.get(6)?,
+                    commission: row.get(7)?,
+                    order_fill_count: row.get(8)?,
+                    order_quantity: row.get(9)?,
+                    order_avg_price: row.get::<_, Option<f64>>(10)?.unwrap_or(0.0),
+                    position: row.get(11)?,
+                    realized_pnl: 0.0,
+                    cumulative_realized_pnl: 0.0,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut rows = apply_realized_pnl(rows);
+        if let Some(start) = start {
+            rows.retain(|row| row.timestamp >= start);
+        }
+        Ok(rows)
     }
 
     /// Log a system event
@@ -414,6 +993,76 @@ impl DatabaseManager {
     pub fn pool_stats(&self) -> r2d2::State {
         self.pool.state()
     }
+
+    /// Fraction of the pool's maximum size currently checked out (0.0 - 1.0)
+    pub fn pool_utilization(&self) -> f32 {
+        let state = self.pool.state();
+        let in_use = state.connections.saturating_sub(state.idle_connections);
+        in_use as f32 / self.pool.max_size() as f32
+    }
+}
+
+/// Map a `trading_metrics` row to a [`MetricRecord`]
+/// Fill in per-fill and running realized P&L with average-cost accounting.
+/// `rows` must be in execution order.
+fn apply_realized_pnl(mut rows: Vec<BlotterRow>) -> Vec<BlotterRow> {
+    // Per symbol: signed position, average entry price, running realized P&L
+    let mut books: HashMap<String, (f64, f64, f64)> = HashMap::new();
+
+    for row in &mut rows {
+        let (position, avg_price, cumulative) = books.entry(row.symbol.clone()).or_insert((0.0, 0.0, 0.0));
+        let direction = match row.side.to_ascii_lowercase().as_str() {
+            "buy" | "bid" => 1.0,
+            "sell" | "ask" => -1.0,
+            _ => 0.0,
+        };
+
+        let mut realized = -row.commission;
+        if direction != 0.0 && row.quantity > 0.0 {
+            if *position == 0.0 || position.signum() == direction {
+                // Opening or adding: blend the entry price
+                let held = position.abs();
+                *avg_price = (held * *avg_price + row.quantity * row.price) / (held + row.quantity);
+                *position += direction * row.quantity;
+            } else {
+                let closed = row.quantity.min(position.abs());
+                realized += closed * (row.price - *avg_price) * position.signum();
+                *position += direction * row.quantity;
+                if position.abs() < 1e-9 {
+                    *position = 0.0;
+                } else if position.signum() == direction {
+                    // Flipped through flat; the remainder opens at this price
+                    *avg_price = row.price;
+                }
+            }
+        }
+
+        *cumulative += realized;
+        row.realized_pnl = realized;
+        row.cumulative_realized_pnl = *cumulative;
+    }
+    rows
+}
+
+fn metric_from_row(row: &duckdb::Row<'_>) -> duckdb::Result<MetricRecord> {
+    let timestamp_str: String = row.get(0)?;
+    let timestamp = timestamp_str
+        .parse()
+        .map_err(|e| duckdb::Error::FromSqlConversionFailure(
+            0,
+            duckdb::types::Type::Text,
+            Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Invalid timestamp format: {}", e)))
+        ))?;
+
+    Ok(MetricRecord {
+        timestamp,
+        metric_name: row.get(1)?,
+        value: row.get(2)?,
+        symbol: row.get(3)?,
+        labels: row
+            .get::<_, Option<String>>(4)?
+            .and_then(|s| serde_json::from_str(&s).ok()),
+    })
 }
 
 #[cfg(test)]
@@ -453,4 +1102,27 @@ mod tests {
         let retrieved = db.get_metrics("test", None, None, 1000).await.unwrap();
         assert_eq!(retrieved.len(), 100);
     }
+
+    #[tokio::test]
+    async fn test_pool_exhaustion() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::with_pool_config(
+            temp_file.path(),
+            2,
+            Duration::from_millis(50),
+        )
+        .unwrap();
+        assert_eq!(db.pool_utilization(), 0.0);
+
+        let first = db.get_connection().unwrap();
+        assert_eq!(db.pool_utilization(), 0.5);
+        let _second = db.get_connection().unwrap();
+        assert_eq!(db.pool_utilization(), 1.0);
+
+        let err = db.get_connection().unwrap_err();
+        assert!(matches!(err, DatabaseError::PoolTimeout(_)));
+
+        drop(first);
+        assert!(db.get_connection().is_ok());
+    }
 }
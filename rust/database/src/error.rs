@@ -16,6 +16,10 @@ pub enum DatabaseError {
     #[error("Connection pool error: {0}")]
     Pool(#[from] r2d2::Error),
 
+    /// Every pooled connection stayed checked out for the whole timeout
+    #[error("Timed out after {0:?} waiting for a pooled connection")]
+    PoolTimeout(std::time::Duration),
+
     /// Serialization error
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
@@ -0,0 +1,26 @@
+//! Database dependency probe for service health checks
+
+use crate::connection::DatabaseManager;
+use common::health::DependencyStatus;
+use std::time::Instant;
+
+/// Check out a pooled connection and run a trivial query.
+///
+/// Pool exhaustion is reported as degraded (the database is up but busy);
+/// any other failure means the database is down.
+pub async fn check_database(db: &DatabaseManager) -> DependencyStatus {
+    let started = Instant::now();
+
+    let conn = match db.get_connection() {
+        Ok(conn) => conn,
+        Err(crate::DatabaseError::PoolTimeout(timeout)) => {
+            return DependencyStatus::degraded(format!("connection pool exhausted after {:?}", timeout));
+        }
+        Err(e) => return DependencyStatus::down(format!("connection failed: {}", e)),
+    };
+
+    match conn.query_row("SELECT 1", [], |row| row.get::<_, i32>(0)) {
+        Ok(_) => DependencyStatus::up(started.elapsed()),
+        Err(e) => DependencyStatus::down(format!("probe query failed: {}", e)),
+    }
+}
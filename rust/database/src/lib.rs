@@ -38,9 +38,12 @@
 //! # }
 //! ```
 
+pub mod cache;
 pub mod connection;
 pub mod error;
+pub mod health;
 pub mod models;
+pub mod monitor;
 pub mod query;
 pub mod schema;
 
@@ -48,9 +51,12 @@ pub mod schema;
 pub mod migrations;
 
 // Re-exports for convenience
-pub use connection::{ConnectionPool, DatabaseManager};
+pub use cache::{CacheStats, QueryCacheConfig};
+pub use connection::{ConnectionPool, DatabaseManager, PoolConfig};
 pub use error::{DatabaseError, Result};
+pub use health::check_database;
 pub use models::*;
+pub use monitor::{Anomaly, MetricMonitor, MonitorRule};
 pub use query::{QueryBuilder, TimeInterval};
 pub use schema::Schema;
 
@@ -19,6 +19,18 @@ pub struct Migration {
     pub down_sql: Option<String>,
 }
 
+impl Migration {
+    /// Parse the migration version as a number (e.g., "002" -> 2)
+    pub fn version_number(&self) -> Result<u32> {
+        self.version.parse().map_err(|_| {
+            DatabaseError::migration(format!(
+                "Migration version '{}' is not numeric",
+                self.version
+            ))
+        })
+    }
+}
+
 /// Migration manager
 pub struct MigrationManager {
     db: DatabaseManager,
@@ -271,6 +283,41 @@ pub fn get_builtin_migrations() -> Vec<Migration> {
                 DROP INDEX IF EXISTS idx_events_timestamp;
             "#.to_string()),
         },
+        Migration {
+            version: "003".to_string(),
+            name: "Add trades table".to_string(),
+            up_sql: r#"
+                CREATE TABLE IF NOT EXISTS trading_trades (
+                    trade_id VARCHAR PRIMARY KEY,
+                    order_id VARCHAR NOT NULL,
+                    symbol VARCHAR NOT NULL,
+                    side VARCHAR NOT NULL,
+                    quantity DOUBLE NOT NULL,
+                    price DOUBLE NOT NULL,
+                    timestamp TIMESTAMP NOT NULL,
+                    commission DOUBLE NOT NULL,
+                    trade_value DOUBLE NOT NULL,
+                    liquidity VARCHAR
+                );
+                CREATE INDEX IF NOT EXISTS idx_trades_symbol_time ON trading_trades(symbol, timestamp DESC);
+                CREATE INDEX IF NOT EXISTS idx_trades_order ON trading_trades(order_id);
+            "#.to_string(),
+            down_sql: Some(r#"
+                DROP INDEX IF EXISTS idx_trades_symbol_time;
+                DROP INDEX IF EXISTS idx_trades_order;
+                DROP TABLE IF EXISTS trading_trades CASCADE;
+            "#.to_string()),
+        },
+        Migration {
+            version: "004".to_string(),
+            name: "Add trade currency".to_string(),
+            up_sql: r#"
+                ALTER TABLE trading_trades ADD COLUMN IF NOT EXISTS currency VARCHAR DEFAULT 'USD';
+            "#.to_string(),
+            down_sql: Some(r#"
+                ALTER TABLE trading_trades DROP COLUMN IF EXISTS currency;
+            "#.to_string()),
+        },
     ]
 }
 
@@ -297,6 +344,35 @@ mod tests {
         assert_eq!(applied.len(), migrations.len());
     }
 
+    #[tokio::test]
+    async fn test_migrate_versioned() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(temp_file.path()).await.unwrap();
+
+        let latest = get_builtin_migrations()
+            .iter()
+            .map(|m| m.version_number().unwrap())
+            .max()
+            .unwrap();
+
+        assert_eq!(db.migrate().await.unwrap(), latest);
+
+        // Running again is a no-op
+        assert_eq!(db.migrate().await.unwrap(), latest);
+
+        let conn = db.get_connection().unwrap();
+        crate::schema::Schema::verify(&conn).unwrap();
+    }
+
+    #[test]
+    fn test_builtin_migration_versions_are_ordered() {
+        let versions: Vec<u32> = get_builtin_migrations()
+            .iter()
+            .map(|m| m.version_number().unwrap())
+            .collect();
+        assert!(versions.windows(2).all(|w| w[0] < w[1]));
+    }
+
     #[tokio::test]
     async fn test_rollback() {
         let temp_file = NamedTempFile::new().unwrap();
@@ -1,6 +1,7 @@
 //! Data models for database records
 
 use chrono::{DateTime, Utc};
+use common::types::{Currency, Money};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -19,6 +20,27 @@ pub struct MetricRecord {
     pub labels: Option<HashMap<String, String>>,
 }
 
+/// Position of the last row of a metrics page, for keyset pagination
+///
+/// Rows are unique on `(timestamp, symbol)` within a metric, so the cursor
+/// carries both and rows sharing the boundary timestamp are not lost.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetricCursor {
+    /// Timestamp of the last row returned
+    pub timestamp: DateTime<Utc>,
+    /// Symbol of the last row returned
+    pub symbol: Option<String>,
+}
+
+impl From<&MetricRecord> for MetricCursor {
+    fn from(metric: &MetricRecord) -> Self {
+        Self {
+            timestamp: metric.timestamp,
+            symbol: metric.symbol.clone(),
+        }
+    }
+}
+
 /// Candle/OHLCV record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CandleRecord {
@@ -63,6 +85,39 @@ pub struct TradeRecord {
     pub trade_value: f64,
     /// Liquidity (maker/taker)
     pub liquidity: Option<String>,
+    /// ISO currency code of price, commission and trade value
+    #[serde(default = "default_currency")]
+    pub currency: String,
+}
+
+fn default_currency() -> String {
+    Currency::default().code().to_string()
+}
+
+/// One fill in the trade blotter, with its parent order and running P&L
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlotterRow {
+    pub trade_id: String,
+    pub order_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub symbol: String,
+    pub side: String,
+    pub quantity: f64,
+    pub price: f64,
+    pub commission: f64,
+    /// Fills recorded for the parent order
+    pub order_fill_count: i64,
+    /// Total quantity filled across the parent order
+    pub order_quantity: f64,
+    /// Volume-weighted fill price of the parent order
+    pub order_avg_price: f64,
+    /// Signed position in the symbol after this fill (negative when short)
+    pub position: f64,
+    /// P&L this fill realized against the average cost, net of its commission
+    pub realized_pnl: f64,
+    /// Running realized P&L for the symbol, including fills before the
+    /// blotter's start time
+    pub cumulative_realized_pnl: f64,
 }
 
 /// System event record
@@ -195,6 +250,57 @@ impl CandleRecord {
     }
 }
 
+impl TradeRecord {
+    /// Create a USD trade record executed now, with no commission
+    pub fn new(
+        trade_id: impl Into<String>,
+        order_id: impl Into<String>,
+        symbol: impl Into<String>,
+        side: impl Into<String>,
+        quantity: f64,
+        price: f64,
+    ) -> Self {
+        Self {
+            trade_id: trade_id.into(),
+            order_id: order_id.into(),
+            symbol: symbol.into(),
+            side: side.into(),
+            quantity,
+            price,
+            timestamp: Utc::now(),
+            commission: 0.0,
+            trade_value: quantity * price,
+            liquidity: None,
+            currency: default_currency(),
+        }
+    }
+
+    /// Set commission
+    pub fn with_commission(mut self, commission: f64) -> Self {
+        self.commission = commission;
+        self
+    }
+
+    /// Set currency
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = currency.code().to_string();
+        self
+    }
+
+    /// Trade value and commission as `Money`; `None` if the currency code is unknown
+    pub fn trade_value_money(&self) -> Option<Money> {
+        self.parsed_currency().map(|c| Money::new(self.trade_value, c))
+    }
+
+    pub fn commission_money(&self) -> Option<Money> {
+        self.parsed_currency().map(|c| Money::new(self.commission, c))
+    }
+
+    fn parsed_currency(&self) -> Option<Currency> {
+        Currency::from_code(&self.currency)
+    }
+}
+
 impl SystemEvent {
     /// Create a new system event
     pub fn new(
@@ -254,6 +360,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_trade_record_currency() {
+        let trade = TradeRecord::new("t1", "o1", "AAPL", "buy", 10.0, 100.0).with_commission(1.0);
+        assert_eq!(trade.currency, "USD");
+        assert_eq!(trade.trade_value_money(), Some(Money::usd(1000.0)));
+
+        let trade = trade.with_currency(Currency::Eur);
+        assert_eq!(trade.commission_money(), Some(Money::new(1.0, Currency::Eur)));
+    }
+
     #[test]
     fn test_system_event_helpers() {
         let event = SystemEvent::info("Test message");
@@ -0,0 +1,246 @@
+//! Anomaly detection over the metrics stream
+
+use crate::connection::DatabaseManager;
+use crate::error::Result;
+use crate::models::{MetricRecord, SystemEvent};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Rows scanned per call to `MetricMonitor::scan` unless overridden
+const DEFAULT_LOOKBACK: i64 = 1000;
+
+/// Rule deciding whether a metric value is anomalous
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MonitorRule {
+    /// Value strictly above the threshold
+    StaticThreshold(f64),
+    /// Value more than `sigma` standard deviations from the mean of the
+    /// preceding `window` values. Needs a full window, and is skipped while
+    /// the window has no variance.
+    ZScore(usize, f64),
+    /// Absolute change from the previous value exceeds this many units per
+    /// second
+    RateOfChange(f64),
+}
+
+impl fmt::Display for MonitorRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MonitorRule::StaticThreshold(threshold) => write!(f, "above {}", threshold),
+            MonitorRule::ZScore(window, sigma) => write!(f, "{} sigma over {} values", sigma, window),
+            MonitorRule::RateOfChange(rate) => write!(f, "changing faster than {}/s", rate),
+        }
+    }
+}
+
+/// A metric value that broke a `MonitorRule`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Anomaly {
+    pub timestamp: DateTime<Utc>,
+    pub value: f64,
+    pub rule: MonitorRule,
+}
+
+/// Watches one metric for values breaking a rule.
+///
+/// # Example
+///
+/// ```no_run
+/// use database::{DatabaseManager, MetricMonitor, MonitorRule};
+///
+/// # async fn example(db: &DatabaseManager) -> anyhow::Result<()> {
+/// let monitor = MetricMonitor::new("order_latency_ms", MonitorRule::StaticThreshold(100.0))
+///     .with_event_logging(true);
+/// for anomaly in monitor.scan(db, None).await? {
+///     println!("{} at {}", anomaly.value, anomaly.timestamp);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MetricMonitor {
+    metric_name: String,
+    rule: MonitorRule,
+    symbol: Option<String>,
+    lookback: i64,
+    log_events: bool,
+}
+
+impl MetricMonitor {
+    pub fn new(metric_name: impl Into<String>, rule: MonitorRule) -> Self {
+        Self {
+            metric_name: metric_name.into(),
+            rule,
+            symbol: None,
+            lookback: DEFAULT_LOOKBACK,
+            log_events: false,
+        }
+    }
+
+    /// Only watch rows for this symbol
+    pub fn with_symbol(mut self, symbol: impl Into<String>) -> Self {
+        self.symbol = Some(symbol.into());
+        self
+    }
+
+    /// Most recent rows read by `scan`
+    pub fn with_lookback(mut self, rows: i64) -> Self {
+        self.lookback = rows;
+        self
+    }
+
+    /// Write a warning `SystemEvent` for each anomaly `scan` finds
+    pub fn with_event_logging(mut self, enabled: bool) -> Self {
+        self.log_events = enabled;
+        self
+    }
+
+    pub fn rule(&self) -> MonitorRule {
+        self.rule
+    }
+
+    /// Anomalies among `metrics`, which must be ordered oldest first
+    pub fn detect(&self, metrics: &[MetricRecord]) -> Vec<Anomaly> {
+        let anomaly = |metric: &MetricRecord| Anomaly {
+            timestamp: metric.timestamp,
+            value: metric.value,
+            rule: self.rule,
+        };
+
+        match self.rule {
+            MonitorRule::StaticThreshold(threshold) => metrics
+                .iter()
+                .filter(|m| m.value > threshold)
+                .map(anomaly)
+                .collect(),
+            MonitorRule::ZScore(window, sigma) => {
+                if window == 0 {
+                    return Vec::new();
+                }
+                metrics
+                    .windows(window + 1)
+                    .filter_map(|values| {
+                        let (current, history) = values.split_last()?;
+                        let n = history.len() as f64;
+                        let mean = history.iter().map(|m| m.value).sum::<f64>() / n;
+                        let variance = history.iter().map(|m| (m.value - mean).powi(2)).sum::<f64>() / n;
+                        let std_dev = variance.sqrt();
+                        (std_dev > 0.0 && ((current.value - mean) / std_dev).abs() > sigma)
+                            .then(|| anomaly(current))
+                    })
+                    .collect()
+            }
+            MonitorRule::RateOfChange(max_rate) => metrics
+                .windows(2)
+                .filter_map(|pair| {
+                    let elapsed = (pair[1].timestamp - pair[0].timestamp).num_milliseconds() as f64 / 1000.0;
+                    let change = (pair[1].value - pair[0].value).abs();
+                    // Simultaneous samples with different values change infinitely fast
+                    let rate = if elapsed > 0.0 { change / elapsed } else if change > 0.0 { f64::INFINITY } else { 0.0 };
+                    (rate > max_rate).then(|| anomaly(&pair[1]))
+                })
+                .collect(),
+        }
+    }
+
+    /// Read the most recent rows for the metric (on or after `since`, if
+    /// given) and return their anomalies, oldest first, logging each as a
+    /// `SystemEvent` when event logging is enabled
+    pub async fn scan(&self, db: &DatabaseManager, since: Option<DateTime<Utc>>) -> Result<Vec<Anomaly>> {
+        let mut metrics = db
+            .get_metrics(&self.metric_name, self.symbol.as_deref(), since, self.lookback)
+            .await?;
+        metrics.reverse();
+
+        let anomalies = self.detect(&metrics);
+        if !anomalies.is_empty() {
+            metrics::counter!("database_metric_anomalies_total").increment(anomalies.len() as u64);
+        }
+        if self.log_events {
+            for anomaly in &anomalies {
+                db.log_event(&self.event(anomaly)).await?;
+            }
+        }
+        Ok(anomalies)
+    }
+
+    /// Warning event describing `anomaly`
+    pub fn event(&self, anomaly: &Anomaly) -> SystemEvent {
+        let subject = match &self.symbol {
+            Some(symbol) => format!("{} for {}", self.metric_name, symbol),
+            None => self.metric_name.clone(),
+        };
+        let mut event = SystemEvent::new(
+            "monitor",
+            "warning",
+            format!("{} anomaly: {} ({})", subject, anomaly.value, anomaly.rule),
+        )
+        .with_details(serde_json::json!({
+            "metric_name": self.metric_name,
+            "symbol": self.symbol,
+            "value": anomaly.value,
+            "rule": anomaly.rule,
+        }));
+        event.timestamp = anomaly.timestamp;
+        event
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn series(values: &[f64]) -> Vec<MetricRecord> {
+        let start = Utc::now();
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let mut metric = MetricRecord::new("latency", *value);
+                metric.timestamp = start + Duration::seconds(i as i64);
+                metric
+            })
+            .collect()
+    }
+
+    fn anomalous_values(monitor: &MetricMonitor, values: &[f64]) -> Vec<f64> {
+        monitor.detect(&series(values)).iter().map(|a| a.value).collect()
+    }
+
+    #[test]
+    fn test_static_threshold() {
+        let monitor = MetricMonitor::new("latency", MonitorRule::StaticThreshold(100.0));
+        assert_eq!(anomalous_values(&monitor, &[50.0, 100.0, 150.0, 80.0]), vec![150.0]);
+    }
+
+    #[test]
+    fn test_zscore_needs_full_window() {
+        let monitor = MetricMonitor::new("latency", MonitorRule::ZScore(4, 3.0));
+        // The spike at index 2 has no full window yet; the one at index 8 does
+        let values = [10.0, 11.0, 90.0, 10.0, 11.0, 10.0, 11.0, 10.0, 90.0];
+        assert_eq!(anomalous_values(&monitor, &values), vec![90.0]);
+
+        // A flat window has no variance to score against
+        assert!(anomalous_values(&monitor, &[10.0, 10.0, 10.0, 10.0, 90.0]).is_empty());
+    }
+
+    #[test]
+    fn test_rate_of_change() {
+        // Samples are one second apart
+        let monitor = MetricMonitor::new("latency", MonitorRule::RateOfChange(20.0));
+        assert_eq!(anomalous_values(&monitor, &[10.0, 25.0, 60.0, 50.0]), vec![60.0]);
+    }
+
+    #[test]
+    fn test_event_describes_anomaly() {
+        let monitor = MetricMonitor::new("latency", MonitorRule::StaticThreshold(100.0)).with_symbol("AAPL");
+        let anomaly = monitor.detect(&series(&[150.0])).remove(0);
+
+        let event = monitor.event(&anomaly);
+        assert_eq!(event.severity, "warning");
+        assert_eq!(event.timestamp, anomaly.timestamp);
+        assert_eq!(event.message, "latency for AAPL anomaly: 150 (above 100)");
+    }
+}
@@ -1,14 +1,18 @@
 //! Type-safe query builder for DuckDB
 
+use crate::error::{DatabaseError, Result};
+use crate::models::MetricCursor;
 use chrono::{DateTime, Utc};
 
 /// Time interval for aggregation and bucketing
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TimeInterval {
     /// 1 second
     Second,
     /// 1 minute
     Minute,
+    /// 5 minutes
+    FiveMinutes,
     /// 1 hour
     Hour,
     /// 1 day
@@ -25,6 +29,7 @@ impl TimeInterval {
         match self {
             Self::Second => "1 second",
             Self::Minute => "1 minute",
+            Self::FiveMinutes => "5 minutes",
             Self::Hour => "1 hour",
             Self::Day => "1 day",
             Self::Week => "1 week",
@@ -37,6 +42,7 @@ impl TimeInterval {
         match self {
             Self::Second => "1s",
             Self::Minute => "1m",
+            Self::FiveMinutes => "5m",
             Self::Hour => "1h",
             Self::Day => "1d",
             Self::Week => "1w",
@@ -86,6 +92,56 @@ impl QueryBuilder {
         query
     }
 
+    /// Build a keyset-paginated SELECT query for metrics
+    ///
+    /// Rows are returned newest first, ties broken by symbol; `cursor`
+    /// restricts the page to rows ordered after the given position.
+    ///
+    /// # Arguments
+    ///
+    /// * `metric_name` - Name of the metric
+    /// * `symbol` - Optional symbol filter
+    /// * `start_time` - Optional start time filter
+    /// * `page_size` - Maximum number of records in the page
+    /// * `cursor` - Optional position of the last row of the previous page
+    pub fn select_metrics_page(
+        &self,
+        metric_name: &str,
+        symbol: Option<&str>,
+        start_time: Option<DateTime<Utc>>,
+        page_size: i64,
+        cursor: Option<&MetricCursor>,
+    ) -> String {
+        let mut query = format!(
+            "SELECT timestamp, metric_name, value, symbol, labels FROM trading_metrics WHERE metric_name = '{}'",
+            metric_name.replace('\'', "''")
+        );
+
+        if let Some(sym) = symbol {
+            query.push_str(&format!(" AND symbol = '{}'", sym.replace('\'', "''")));
+        }
+
+        if let Some(start) = start_time {
+            query.push_str(&format!(" AND timestamp >= '{}'", start.to_rfc3339()));
+        }
+
+        // NULL symbols sort as '' so the tie-break is total
+        if let Some(cursor) = cursor {
+            let ts = cursor.timestamp.to_rfc3339();
+            let sym = cursor.symbol.as_deref().unwrap_or("").replace('\'', "''");
+            query.push_str(&format!(
+                " AND (timestamp < '{}' OR (timestamp = '{}' AND COALESCE(symbol, '') < '{}'))",
+                ts, ts, sym
+            ));
+        }
+
+        query.push_str(&format!(
+            " ORDER BY timestamp DESC, COALESCE(symbol, '') DESC LIMIT {}",
+            page_size
+        ));
+        query
+    }
+
     /// Build SELECT query for candles with time bucketing
     ///
     /// # Arguments
@@ -135,22 +191,19 @@ impl QueryBuilder {
     /// * `metric_name` - Name of the metric
     /// * `interval` - Time interval for aggregation
     /// * `start_time` - Optional start time filter
-    /// * `aggregation` - Aggregation function (avg, sum, min, max, count)
+    /// * `aggregation` - Aggregation function (avg, sum, min, max, count, or a
+    ///   percentile such as p50, p95, p99, p99.9)
+    ///
+    /// Returns an error for unrecognized aggregation functions instead of
+    /// producing invalid SQL.
     pub fn aggregate_metrics(
         &self,
         metric_name: &str,
         interval: TimeInterval,
         start_time: Option<DateTime<Utc>>,
         aggregation: &str,
-    ) -> String {
-        let agg_fn = match aggregation.to_lowercase().as_str() {
-            "avg" | "average" => "AVG(value)",
-            "sum" | "total" => "SUM(value)",
-            "min" | "minimum" => "MIN(value)",
-            "max" | "maximum" => "MAX(value)",
-            "count" => "COUNT(*)",
-            _ => "AVG(value)", // Default to average
-        };
+    ) -> Result<String> {
+        let agg_fn = Self::aggregation_expr(aggregation)?;
 
         let mut query = format!(
             "SELECT \
@@ -171,6 +224,118 @@ impl QueryBuilder {
         }
 
         query.push_str(" GROUP BY bucket, metric_name, symbol ORDER BY bucket DESC");
+        Ok(query)
+    }
+
+    /// Translate an aggregation name into a SQL expression over `value`
+    ///
+    /// Percentiles (`p<N>`, with 0 < N <= 100) map to DuckDB's
+    /// `quantile_cont(value, N / 100)`.
+    fn aggregation_expr(aggregation: &str) -> Result<String> {
+        let normalized = aggregation.trim().to_lowercase();
+
+        let expr = match normalized.as_str() {
+            "avg" | "average" => "AVG(value)".to_string(),
+            "sum" | "total" => "SUM(value)".to_string(),
+            "min" | "minimum" => "MIN(value)".to_string(),
+            "max" | "maximum" => "MAX(value)".to_string(),
+            "count" => "COUNT(*)".to_string(),
+            "median" => "quantile_cont(value, 0.5)".to_string(),
+            other => {
+                let percentile = other
+                    .strip_prefix('p')
+                    .and_then(|n| n.parse::<f64>().ok())
+                    .filter(|n| n.is_finite() && *n > 0.0 && *n <= 100.0)
+                    .ok_or_else(|| {
+                        DatabaseError::invalid_param(format!(
+                            "Unknown aggregation function: '{}'",
+                            aggregation
+                        ))
+                    })?;
+
+                format!("quantile_cont(value, {})", percentile / 100.0)
+            }
+        };
+
+        Ok(expr)
+    }
+
+    /// Build volume-weighted average price query over trades
+    ///
+    /// Buckets with zero total volume are filtered out with `HAVING` so the
+    /// division never produces NaN.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - Trading symbol
+    /// * `interval` - Time interval for bucketing
+    /// * `start_time` - Optional start time filter
+    pub fn vwap(
+        &self,
+        symbol: &str,
+        interval: TimeInterval,
+        start_time: Option<DateTime<Utc>>,
+    ) -> String {
+        let mut query = format!(
+            "SELECT \
+                time_bucket(INTERVAL '{}', timestamp) AS bucket, \
+                SUM(price * quantity) / SUM(quantity) AS vwap \
+            FROM trading_trades \
+            WHERE symbol = '{}'",
+            interval.as_str(),
+            symbol.replace('\'', "''")
+        );
+
+        if let Some(start) = start_time {
+            query.push_str(&format!(" AND timestamp >= '{}'", start.to_rfc3339()));
+        }
+
+        query.push_str(" GROUP BY bucket HAVING SUM(quantity) > 0 ORDER BY bucket ASC");
+        query
+    }
+
+    /// Build the trade blotter query
+    ///
+    /// Returns every fill up to `end` (so positions opened earlier keep their
+    /// cost basis), oldest first, with its parent order's fill count,
+    /// quantity and average price and the running signed position per
+    /// symbol. Timestamps are formatted as RFC 3339.
+    pub fn trade_blotter(&self, symbol: Option<&str>, end: Option<DateTime<Utc>>) -> String {
+        let mut query = String::from(
+            "SELECT \
+                trade_id, \
+                order_id, \
+                strftime(timestamp, '%Y-%m-%dT%H:%M:%S.%fZ') AS ts, \
+                symbol, \
+                side, \
+                quantity, \
+                price, \
+                commission, \
+                COUNT(*) OVER parent AS order_fill_count, \
+                SUM(quantity) OVER parent AS order_quantity, \
+                SUM(quantity * price) OVER parent / NULLIF(SUM(quantity) OVER parent, 0) AS order_avg_price, \
+                SUM(CASE \
+                    WHEN lower(side) IN ('buy', 'bid') THEN quantity \
+                    WHEN lower(side) IN ('sell', 'ask') THEN -quantity \
+                    ELSE 0 END) OVER running AS position \
+            FROM trading_trades \
+            WHERE 1 = 1",
+        );
+
+        if let Some(sym) = symbol {
+            query.push_str(&format!(" AND symbol = '{}'", sym.replace('\'', "''")));
+        }
+
+        if let Some(end) = end {
+            query.push_str(&format!(" AND timestamp <= '{}'", end.to_rfc3339()));
+        }
+
+        query.push_str(
+            " WINDOW parent AS (PARTITION BY order_id), \
+              running AS (PARTITION BY symbol ORDER BY timestamp, trade_id \
+                          ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW) \
+            ORDER BY timestamp ASC, trade_id ASC",
+        );
         query
     }
 
@@ -275,15 +440,78 @@ mod tests {
         assert!(query.contains("LIMIT 50"));
     }
 
+    #[test]
+    fn test_select_metrics_page_cursor() {
+        let qb = QueryBuilder::new();
+        let first = qb.select_metrics_page("price", None, None, 500, None);
+        assert!(!first.contains("timestamp <"));
+        assert!(first.contains("LIMIT 500"));
+
+        let cursor = MetricCursor {
+            timestamp: Utc::now(),
+            symbol: Some("BTC/USD".to_string()),
+        };
+        let ts = cursor.timestamp.to_rfc3339();
+        let next = qb.select_metrics_page("price", None, None, 500, Some(&cursor));
+        assert!(next.contains(&format!("timestamp < '{}'", ts)));
+        assert!(next.contains(&format!("timestamp = '{}' AND COALESCE(symbol, '') < 'BTC/USD'", ts)));
+        assert!(next.contains("ORDER BY timestamp DESC, COALESCE(symbol, '') DESC"));
+    }
+
     #[test]
     fn test_aggregate_metrics() {
         let qb = QueryBuilder::new();
-        let query = qb.aggregate_metrics("price", TimeInterval::Hour, None, "avg");
+        let query = qb
+            .aggregate_metrics("price", TimeInterval::Hour, None, "avg")
+            .unwrap();
         assert!(query.contains("time_bucket"));
         assert!(query.contains("AVG(value)"));
         assert!(query.contains("GROUP BY"));
     }
 
+    #[test]
+    fn test_aggregate_metrics_percentiles() {
+        let qb = QueryBuilder::new();
+
+        let p95 = qb
+            .aggregate_metrics("latency", TimeInterval::Minute, None, "p95")
+            .unwrap();
+        assert!(p95.contains("quantile_cont(value, 0.95)"));
+
+        let p99 = qb
+            .aggregate_metrics("latency", TimeInterval::Minute, None, "P99")
+            .unwrap();
+        assert!(p99.contains("quantile_cont(value, 0.99)"));
+
+        let p50 = qb
+            .aggregate_metrics("latency", TimeInterval::Minute, None, "p50")
+            .unwrap();
+        assert!(p50.contains("quantile_cont(value, 0.5)"));
+    }
+
+    #[test]
+    fn test_aggregate_metrics_rejects_unknown() {
+        let qb = QueryBuilder::new();
+        for bad in ["stddev", "p0", "p101", "p", "pabc", "avg; DROP TABLE x"] {
+            let result = qb.aggregate_metrics("latency", TimeInterval::Minute, None, bad);
+            assert!(
+                matches!(result, Err(DatabaseError::InvalidParameter(_))),
+                "expected rejection for {}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_vwap_query() {
+        let qb = QueryBuilder::new();
+        let query = qb.vwap("AAPL", TimeInterval::Minute, None);
+        assert!(query.contains("SUM(price * quantity) / SUM(quantity)"));
+        assert!(query.contains("FROM trading_trades"));
+        assert!(query.contains("symbol = 'AAPL'"));
+        assert!(query.contains("HAVING SUM(quantity) > 0"));
+    }
+
     #[test]
     fn test_time_interval_strings() {
         assert_eq!(TimeInterval::Minute.as_str(), "1 minute");
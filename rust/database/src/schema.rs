@@ -12,6 +12,7 @@ impl Schema {
         Self::create_metrics_table(conn)?;
         Self::create_candles_table(conn)?;
         Self::create_events_table(conn)?;
+        Self::create_trades_table(conn)?;
         Self::create_indexes(conn)?;
         Ok(())
     }
@@ -77,6 +78,30 @@ impl Schema {
         Ok(())
     }
 
+    /// Create trading_trades table
+    ///
+    /// Stores individual trade executions (fills).
+    fn create_trades_table(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS trading_trades (
+                trade_id VARCHAR PRIMARY KEY,
+                order_id VARCHAR NOT NULL,
+                symbol VARCHAR NOT NULL,
+                side VARCHAR NOT NULL,
+                quantity DOUBLE NOT NULL,
+                price DOUBLE NOT NULL,
+                timestamp TIMESTAMP NOT NULL,
+                commission DOUBLE NOT NULL,
+                trade_value DOUBLE NOT NULL,
+                liquidity VARCHAR,
+                currency VARCHAR NOT NULL DEFAULT 'USD'
+            )",
+        )?;
+
+        tracing::debug!("Created trading_trades table");
+        Ok(())
+    }
+
     /// Create indexes for performance optimization
     fn create_indexes(conn: &Connection) -> Result<()> {
         // Metrics indexes
@@ -99,10 +124,44 @@ impl Schema {
             CREATE INDEX IF NOT EXISTS idx_events_type ON system_events(event_type);",
         )?;
 
+        // Trades indexes
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_trades_symbol_time ON trading_trades(symbol, timestamp DESC);
+            CREATE INDEX IF NOT EXISTS idx_trades_order ON trading_trades(order_id);",
+        )?;
+
         tracing::debug!("Created database indexes");
         Ok(())
     }
 
+    /// Create schema_version table
+    ///
+    /// Records each numbered migration applied by `DatabaseManager::migrate`.
+    pub fn create_version_table(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS schema_version (
+                version INTEGER PRIMARY KEY,
+                name VARCHAR NOT NULL,
+                applied_at TIMESTAMP NOT NULL
+            )",
+        )?;
+
+        tracing::debug!("Created schema_version table");
+        Ok(())
+    }
+
+    /// Get the highest recorded migration version (0 if none applied)
+    pub fn current_version(conn: &Connection) -> Result<u32> {
+        let version: i64 = conn.query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM schema_version",
+            [],
+            |row| row.get(0),
+        )?;
+
+        u32::try_from(version)
+            .map_err(|_| DatabaseError::schema(format!("Invalid schema version: {}", version)))
+    }
+
     /// Drop all tables (use with caution!)
     #[allow(dead_code)]
     pub fn drop_all(conn: &Connection) -> Result<()> {
@@ -110,6 +169,8 @@ impl Schema {
             "DROP TABLE IF EXISTS trading_metrics CASCADE;
             DROP TABLE IF EXISTS trading_candles CASCADE;
             DROP TABLE IF EXISTS system_events CASCADE;
+            DROP TABLE IF EXISTS trading_trades CASCADE;
+            DROP TABLE IF EXISTS schema_version CASCADE;
             DROP SEQUENCE IF EXISTS system_events_seq CASCADE;",
         )?;
 
@@ -120,7 +181,12 @@ impl Schema {
     /// Verify schema integrity
     pub fn verify(conn: &Connection) -> Result<()> {
         // Check if all tables exist
-        let tables = vec!["trading_metrics", "trading_candles", "system_events"];
+        let tables = vec![
+            "trading_metrics",
+            "trading_candles",
+            "system_events",
+            "trading_trades",
+        ];
 
         for table in tables {
             let mut stmt = conn.prepare(&format!(
@@ -177,6 +243,13 @@ mod tests {
         assert!(Schema::verify(&conn).is_ok());
     }
 
+    #[test]
+    fn test_current_version_starts_at_zero() {
+        let conn = Connection::open_in_memory().unwrap();
+        Schema::create_version_table(&conn).unwrap();
+        assert_eq!(Schema::current_version(&conn).unwrap(), 0);
+    }
+
     #[test]
     fn test_schema_version() {
         assert_eq!(Schema::version(), "1.0.0");
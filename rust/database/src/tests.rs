@@ -33,6 +33,16 @@ mod integration_tests {
         assert!(retrieved.iter().all(|m| m.symbol == Some("BTC/USD".to_string())));
     }
 
+    #[tokio::test]
+    async fn test_check_database() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(temp_file.path()).await.unwrap();
+
+        let status = check_database(&db).await;
+        assert!(status.status.is_healthy(), "{:?}", status.message);
+        assert!(status.latency_ms.is_some());
+    }
+
     #[tokio::test]
     async fn test_candle_operations() {
         let temp_file = NamedTempFile::new().unwrap();
@@ -82,6 +92,42 @@ mod integration_tests {
         assert!(id > 0);
     }
 
+    #[tokio::test]
+    async fn test_metric_monitor_logs_anomalies() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(temp_file.path()).await.unwrap();
+        db.initialize().await.unwrap();
+
+        let start = Utc::now() - Duration::minutes(10);
+        for (i, latency) in [20.0, 25.0, 180.0, 22.0, 240.0].into_iter().enumerate() {
+            let mut metric = MetricRecord::new("order_latency_ms", latency).with_symbol("AAPL");
+            metric.timestamp = start + Duration::seconds(i as i64);
+            db.insert_metric(&metric).await.unwrap();
+        }
+
+        let monitor = MetricMonitor::new("order_latency_ms", MonitorRule::StaticThreshold(100.0))
+            .with_symbol("AAPL")
+            .with_event_logging(true);
+        let anomalies = monitor.scan(&db, None).await.unwrap();
+        let values: Vec<f64> = anomalies.iter().map(|a| a.value).collect();
+        assert_eq!(values, vec![180.0, 240.0]);
+
+        let logged: i64 = db
+            .get_connection()
+            .unwrap()
+            .query_row(
+                "SELECT COUNT(*) FROM system_events WHERE event_type = 'monitor' AND severity = 'warning'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(logged, 2);
+
+        // Nothing for another symbol
+        let other = MetricMonitor::new("order_latency_ms", MonitorRule::StaticThreshold(100.0)).with_symbol("MSFT");
+        assert!(other.scan(&db, None).await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_aggregated_metrics() {
         let temp_file = NamedTempFile::new().unwrap();
@@ -106,6 +152,41 @@ mod integration_tests {
         assert!(!aggregated.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_vwap() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(temp_file.path()).await.unwrap();
+        db.initialize().await.unwrap();
+
+        let now = Utc::now();
+        let fills = [(100.0, 10.0), (102.0, 30.0)];
+        for (i, (price, quantity)) in fills.iter().enumerate() {
+            let trade = TradeRecord {
+                trade_id: format!("t{}", i),
+                order_id: "o1".to_string(),
+                symbol: "AAPL".to_string(),
+                side: "buy".to_string(),
+                quantity: *quantity,
+                price: *price,
+                timestamp: now,
+                commission: 0.0,
+                trade_value: price * quantity,
+                liquidity: None,
+                currency: "USD".to_string(),
+            };
+            db.insert_trade(&trade).await.unwrap();
+        }
+
+        let vwap = db.get_vwap("AAPL", TimeInterval::Hour, None).await.unwrap();
+        assert_eq!(vwap.len(), 1);
+        // (100*10 + 102*30) / 40 = 101.5
+        assert!((vwap[0].1 - 101.5).abs() < 1e-9);
+
+        // No trades for the symbol -> no buckets, not NaN
+        let empty = db.get_vwap("MSFT", TimeInterval::Hour, None).await.unwrap();
+        assert!(empty.is_empty());
+    }
+
     #[tokio::test]
     async fn test_table_statistics() {
         let temp_file = NamedTempFile::new().unwrap();
@@ -165,6 +246,82 @@ mod integration_tests {
         assert!(db.optimize().await.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_paged_metrics() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(temp_file.path()).await.unwrap();
+        db.initialize().await.unwrap();
+
+        let now = Utc::now();
+        let metrics: Vec<MetricRecord> = (0..25)
+            .map(|i| {
+                let mut metric = MetricRecord::new("paged", i as f64);
+                metric.timestamp = now - Duration::seconds(i);
+                metric
+            })
+            .collect();
+        db.insert_metrics(&metrics).await.unwrap();
+
+        let mut cursor = None;
+        let mut seen = Vec::new();
+        let mut pages = 0;
+        loop {
+            let (page, next) = db
+                .get_metrics_paged("paged", None, None, 10, cursor.as_ref())
+                .await
+                .unwrap();
+            pages += 1;
+            seen.extend(page);
+            match next {
+                Some(c) => cursor = Some(c),
+                None => break,
+            }
+        }
+
+        assert_eq!(pages, 3);
+        assert_eq!(seen.len(), 25);
+        assert!(seen.windows(2).all(|w| w[0].timestamp > w[1].timestamp));
+    }
+
+    #[tokio::test]
+    async fn test_paged_metrics_split_on_shared_timestamp() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(temp_file.path()).await.unwrap();
+        db.initialize().await.unwrap();
+
+        // Three timestamps with four symbols each; pages of 5 split every group
+        let now = Utc::now();
+        let metrics: Vec<MetricRecord> = (0..3)
+            .flat_map(|i| {
+                ["AAPL", "GOOGL", "MSFT", "TSLA"].into_iter().map(move |symbol| {
+                    let mut metric = MetricRecord::new("shared", i as f64).with_symbol(symbol);
+                    metric.timestamp = now - Duration::seconds(i);
+                    metric
+                })
+            })
+            .collect();
+        db.insert_metrics(&metrics).await.unwrap();
+
+        let mut cursor = None;
+        let mut seen = Vec::new();
+        loop {
+            let (page, next) = db
+                .get_metrics_paged("shared", None, None, 5, cursor.as_ref())
+                .await
+                .unwrap();
+            seen.extend(page);
+            match next {
+                Some(c) => cursor = Some(c),
+                None => break,
+            }
+        }
+
+        assert_eq!(seen.len(), 12);
+        let mut keys: Vec<_> = seen.iter().map(|m| (m.timestamp, m.symbol.clone())).collect();
+        keys.dedup();
+        assert_eq!(keys.len(), 12);
+    }
+
     #[tokio::test]
     async fn test_time_range_queries() {
         let temp_file = NamedTempFile::new().unwrap();
@@ -190,4 +347,229 @@ mod integration_tests {
         assert!(recent.len() <= 60); // Should be approximately 60 minutes
         assert!(recent.iter().all(|m| m.timestamp >= hour_ago));
     }
+
+    #[tokio::test]
+    async fn test_candles_multi_interval() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(temp_file.path()).await.unwrap();
+        db.initialize().await.unwrap();
+
+        let start = Utc::now() - Duration::hours(3);
+        for i in 0..120 {
+            let close = 100.0 + i as f64;
+            let candle = CandleRecord::new(start + Duration::minutes(i), "BTC/USD", close, close, close, close, 10);
+            db.insert_candle(&candle).await.unwrap();
+        }
+
+        // Duplicates are queried once
+        let intervals = [TimeInterval::Minute, TimeInterval::FiveMinutes, TimeInterval::Hour, TimeInterval::Minute];
+        let candles = db.get_candles_multi("BTC/USD", &intervals, None, 1000).await.unwrap();
+        assert_eq!(candles.len(), 3);
+
+        for interval in [TimeInterval::Minute, TimeInterval::FiveMinutes, TimeInterval::Hour] {
+            let single = db.get_candles("BTC/USD", interval, None, 1000).await.unwrap();
+            let multi = &candles[&interval];
+            assert_eq!(multi.len(), single.len());
+            assert!(multi.iter().zip(&single).all(|(a, b)| a.timestamp == b.timestamp && a.close == b.close));
+        }
+        assert_eq!(candles[&TimeInterval::Minute].len(), 120);
+        assert!(candles[&TimeInterval::FiveMinutes].len() >= 24);
+        assert_eq!(candles[&TimeInterval::FiveMinutes].iter().map(|c| c.volume).sum::<i64>(), 1200);
+    }
+
+    #[tokio::test]
+    async fn test_candles_multi_fails_whole_request() {
+        // Schema never created, so every query fails
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(temp_file.path()).await.unwrap();
+
+        let result = db
+            .get_candles_multi("BTC/USD", &[TimeInterval::Minute, TimeInterval::Hour], None, 10)
+            .await;
+        assert!(result.is_err());
+    }
+
+    fn write_csv(contents: &str) -> NamedTempFile {
+        use std::io::Write;
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        file
+    }
+
+    fn candle_count(db: &DatabaseManager, symbol: &str) -> i64 {
+        db.get_connection()
+            .unwrap()
+            .query_row(
+                "SELECT COUNT(*) FROM trading_candles WHERE symbol = ?",
+                [symbol],
+                |row| row.get(0),
+            )
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_import_candles_csv() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(temp_file.path()).await.unwrap();
+        db.initialize().await.unwrap();
+
+        // Symbol column, mixed-case headers, no trade_count
+        let csv = write_csv(
+            "Ticker,Timestamp,Open,High,Low,Close,Volume\n\
+             AAPL,2024-01-02 14:30:00,185.0,186.0,184.5,185.5,1200\n\
+             AAPL,2024-01-02 14:31:00,185.5,186.2,185.1,186.0,900\n\
+             MSFT,2024-01-02 14:30:00,370.0,371.0,369.5,370.5,800\n",
+        );
+        let rows = db.import_candles_csv(csv.path(), Some("ticker")).await.unwrap();
+        assert_eq!(rows, 3);
+        assert_eq!(candle_count(&db, "AAPL"), 2);
+        assert_eq!(candle_count(&db, "MSFT"), 1);
+
+        // Per-file symbol; re-importing the same bars replaces them
+        let csv = write_csv(
+            "timestamp,open,high,low,close,volume,trade_count\n\
+             2024-01-02 14:30:00,42000.0,42100.0,41900.0,42050.0,5,120\n",
+        );
+        for _ in 0..2 {
+            let rows = db.import_candles_csv_for_symbol(csv.path(), "BTC/USD").await.unwrap();
+            assert_eq!(rows, 1);
+        }
+        assert_eq!(candle_count(&db, "BTC/USD"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_import_candles_csv_rejects_bad_files() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(temp_file.path()).await.unwrap();
+        db.initialize().await.unwrap();
+
+        // Missing the close column
+        let csv = write_csv("symbol,timestamp,open,high,low,volume\nAAPL,2024-01-02 14:30:00,1,2,0.5,10\n");
+        let err = db.import_candles_csv(csv.path(), None).await.unwrap_err();
+        assert!(matches!(err, DatabaseError::Schema(_)));
+        assert!(err.to_string().contains("close"), "{}", err);
+
+        // High below low
+        let csv = write_csv("symbol,timestamp,open,high,low,close,volume\nAAPL,2024-01-02 14:30:00,1,0.5,2,1,10\n");
+        let err = db.import_candles_csv(csv.path(), None).await.unwrap_err();
+        assert!(err.to_string().contains("inconsistent"), "{}", err);
+        assert_eq!(candle_count(&db, "AAPL"), 0);
+
+        // Per-file import still needs a real file
+        let missing = std::path::Path::new("/nonexistent/bars.csv");
+        assert!(matches!(
+            db.import_candles_csv_for_symbol(missing, "AAPL").await,
+            Err(DatabaseError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_query_cache() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let config = PoolConfig {
+            query_cache: Some(QueryCacheConfig {
+                capacity: 2,
+                ttl: std::time::Duration::from_secs(60),
+            }),
+            ..PoolConfig::default()
+        };
+        let db = DatabaseManager::with_config(temp_file.path(), config).await.unwrap();
+        db.initialize().await.unwrap();
+
+        db.insert_metric(&MetricRecord::new("latency", 10.0)).await.unwrap();
+        assert_eq!(db.get_metrics("latency", None, None, 100).await.unwrap().len(), 1);
+        assert_eq!(db.get_metrics("latency", None, None, 100).await.unwrap().len(), 1);
+        assert_eq!(db.cache_stats().hits, 1);
+        assert_eq!(db.cache_stats().misses, 1);
+
+        // An insert into the table invalidates its cached reads
+        db.insert_metric(&MetricRecord::new("latency", 20.0)).await.unwrap();
+        assert_eq!(db.get_metrics("latency", None, None, 100).await.unwrap().len(), 2);
+        assert_eq!(db.cache_stats().misses, 2);
+
+        // Writes to other tables leave it alone
+        db.insert_candle(&CandleRecord::new(Utc::now(), "ETH/USD", 1.0, 1.0, 1.0, 1.0, 1))
+            .await
+            .unwrap();
+        db.get_metrics("latency", None, None, 100).await.unwrap();
+        assert_eq!(db.cache_stats().hits, 2);
+
+        // Capacity bounds the entries
+        db.get_metrics("latency", None, None, 1).await.unwrap();
+        db.get_metrics("latency", None, None, 2).await.unwrap();
+        assert_eq!(db.cache_stats().entries, 2);
+
+        db.clear_cache();
+        assert_eq!(db.cache_stats().entries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_query_cache_disabled_by_default() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(temp_file.path()).await.unwrap();
+        db.initialize().await.unwrap();
+
+        db.get_metrics("latency", None, None, 100).await.unwrap();
+        db.get_metrics("latency", None, None, 100).await.unwrap();
+        assert_eq!(db.cache_stats(), CacheStats::default());
+    }
+
+    #[tokio::test]
+    async fn test_trade_blotter() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(temp_file.path()).await.unwrap();
+        db.initialize().await.unwrap();
+
+        let open = Utc::now() - Duration::hours(3);
+        let fill = |id: &str, order: &str, symbol: &str, side: &str, qty: f64, price: f64, minutes: i64| {
+            let mut trade = TradeRecord::new(id, order, symbol, side, qty, price);
+            trade.timestamp = open + Duration::minutes(minutes);
+            trade.commission = 1.0;
+            trade
+        };
+        let trades = [
+            // One buy order filled in two parts
+            fill("t1", "o1", "AAPL", "buy", 50.0, 100.0, 0),
+            fill("t2", "o1", "AAPL", "buy", 50.0, 102.0, 1),
+            fill("t3", "o2", "MSFT", "buy", 10.0, 400.0, 2),
+            fill("t4", "o3", "AAPL", "sell", 40.0, 111.0, 60),
+            fill("t5", "o4", "AAPL", "sell", 60.0, 96.0, 120),
+        ];
+        for trade in &trades {
+            db.insert_trade(trade).await.unwrap();
+        }
+
+        let all = db.trade_blotter(None, None, None).await.unwrap();
+        let ids: Vec<&str> = all.iter().map(|r| r.trade_id.as_str()).collect();
+        assert_eq!(ids, ["t1", "t2", "t3", "t4", "t5"]);
+
+        assert_eq!(all[0].order_fill_count, 2);
+        assert_eq!(all[0].order_quantity, 100.0);
+        assert!((all[0].order_avg_price - 101.0).abs() < 1e-9);
+        assert_eq!(all[1].position, 100.0);
+
+        // Average cost 101: +400 on the first sale, -300 on the second,
+        // each net of a 1.0 commission
+        assert!((all[3].realized_pnl - 399.0).abs() < 1e-9);
+        assert!((all[4].realized_pnl + 301.0).abs() < 1e-9);
+        assert_eq!(all[4].position, 0.0);
+        let aapl_total = all[4].cumulative_realized_pnl;
+        assert!((aapl_total - (-1.0 - 1.0 + 399.0 - 301.0)).abs() < 1e-9);
+
+        // A late start keeps the cost basis and running total from earlier fills
+        let late = db
+            .trade_blotter(Some("AAPL"), Some(open + Duration::minutes(30)), None)
+            .await
+            .unwrap();
+        assert_eq!(late.len(), 2);
+        assert!((late[1].cumulative_realized_pnl - aapl_total).abs() < 1e-9);
+
+        let early = db
+            .trade_blotter(Some("AAPL"), None, Some(open + Duration::minutes(30)))
+            .await
+            .unwrap();
+        assert_eq!(early.len(), 2);
+
+        assert!(db.trade_blotter(None, Some(Utc::now()), Some(open)).await.is_err());
+    }
 }
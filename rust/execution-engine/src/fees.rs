@@ -0,0 +1,153 @@
+use common::config::FeeSchedule;
+use common::types::Order;
+
+/// Commission charged on a fill
+pub trait FeeModel: Send + Sync {
+    /// Commission for filling `fill_qty` of `order` at `fill_price`
+    fn commission(&self, order: &Order, fill_price: f64, fill_qty: f64) -> f64;
+}
+
+/// Build the fee model for a configured schedule
+pub fn fee_model_for(schedule: &FeeSchedule) -> Box<dyn FeeModel> {
+    match *schedule {
+        FeeSchedule::None => Box::new(NoFees),
+        FeeSchedule::FlatPerOrder { fee } => Box::new(FlatPerOrder::new(fee)),
+        FeeSchedule::PerShare { rate, minimum } => Box::new(PerShare::new(rate).with_minimum(minimum)),
+        FeeSchedule::BasisPoints { bps, minimum } => Box::new(BasisPoints::new(bps).with_minimum(minimum)),
+    }
+}
+
+/// Commission-free trading
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoFees;
+
+impl FeeModel for NoFees {
+    fn commission(&self, _order: &Order, _fill_price: f64, _fill_qty: f64) -> f64 {
+        0.0
+    }
+}
+
+/// Fixed fee per order.
+///
+/// Partial fills are charged their share of the fee, so the fills of one
+/// order add up to exactly `fee`.
+#[derive(Debug, Clone, Copy)]
+pub struct FlatPerOrder {
+    fee: f64,
+}
+
+impl FlatPerOrder {
+    pub fn new(fee: f64) -> Self {
+        Self { fee }
+    }
+}
+
+impl FeeModel for FlatPerOrder {
+    fn commission(&self, order: &Order, _fill_price: f64, fill_qty: f64) -> f64 {
+        if fill_qty <= 0.0 {
+            return 0.0;
+        }
+        if order.quantity.0 <= 0.0 {
+            return self.fee;
+        }
+        self.fee * (fill_qty / order.quantity.0).min(1.0)
+    }
+}
+
+/// Fee per share (unit) filled, with a per-fill minimum
+#[derive(Debug, Clone, Copy)]
+pub struct PerShare {
+    rate: f64,
+    minimum: f64,
+}
+
+impl PerShare {
+    pub fn new(rate: f64) -> Self {
+        Self { rate, minimum: 0.0 }
+    }
+
+    pub fn with_minimum(mut self, minimum: f64) -> Self {
+        self.minimum = minimum;
+        self
+    }
+}
+
+impl FeeModel for PerShare {
+    fn commission(&self, _order: &Order, _fill_price: f64, fill_qty: f64) -> f64 {
+        if fill_qty <= 0.0 {
+            return 0.0;
+        }
+        (self.rate * fill_qty).max(self.minimum)
+    }
+}
+
+/// Fee in basis points of fill notional, with a per-fill minimum
+#[derive(Debug, Clone, Copy)]
+pub struct BasisPoints {
+    bps: f64,
+    minimum: f64,
+}
+
+impl BasisPoints {
+    pub fn new(bps: f64) -> Self {
+        Self { bps, minimum: 0.0 }
+    }
+
+    pub fn with_minimum(mut self, minimum: f64) -> Self {
+        self.minimum = minimum;
+        self
+    }
+}
+
+impl FeeModel for BasisPoints {
+    fn commission(&self, _order: &Order, fill_price: f64, fill_qty: f64) -> f64 {
+        if fill_qty <= 0.0 {
+            return 0.0;
+        }
+        (fill_price.abs() * fill_qty * self.bps / 10_000.0).max(self.minimum)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::OrderBuilder;
+
+    #[test]
+    fn test_flat_fee_is_prorated_over_fills() {
+        let order = OrderBuilder::new().limit(150.0).quantity(100.0).build();
+        let model = FlatPerOrder::new(1.0);
+
+        assert!((model.commission(&order, 150.0, 100.0) - 1.0).abs() < 1e-9);
+        assert!((model.commission(&order, 150.0, 25.0) - 0.25).abs() < 1e-9);
+        assert_eq!(model.commission(&order, 150.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_per_share_applies_minimum() {
+        let order = OrderBuilder::new().limit(150.0).quantity(1000.0).build();
+        let model = PerShare::new(0.005).with_minimum(1.0);
+
+        assert!((model.commission(&order, 150.0, 1000.0) - 5.0).abs() < 1e-9);
+        assert!((model.commission(&order, 150.0, 10.0) - 1.0).abs() < 1e-9);
+        assert_eq!(model.commission(&order, 150.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_basis_points_of_notional() {
+        let order = OrderBuilder::new().limit(150.0).quantity(100.0).build();
+        let model = BasisPoints::new(10.0);
+
+        // 100 * 150 = 15,000 notional; 10 bps = 15
+        assert!((model.commission(&order, 150.0, 100.0) - 15.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fee_model_for_schedule() {
+        let order = OrderBuilder::new().limit(150.0).quantity(100.0).build();
+
+        assert_eq!(fee_model_for(&FeeSchedule::None).commission(&order, 150.0, 100.0), 0.0);
+        let per_share = fee_model_for(&FeeSchedule::PerShare { rate: 0.01, minimum: 0.0 });
+        assert!((per_share.commission(&order, 150.0, 100.0) - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,316 @@
+use common::{Result, TradingError, types::{Level, OrderBook}};
+use market_data::orderbook::FastOrderBook;
+use std::collections::HashMap;
+use std::time::Duration;
+use crate::router::AlpacaOrderRequest;
+
+/// Quantity below which a simulated order is considered completely filled
+const FILL_EPSILON: f64 = 1e-9;
+
+/// Paper-trading fill simulation parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FillSimConfig {
+    /// Simulated exchange round-trip latency
+    pub latency_ms: u64,
+    /// Probability that a marketable order only partially fills
+    pub partial_fill_prob: f64,
+    /// Probability that the simulated exchange rejects an order
+    pub reject_prob: f64,
+}
+
+impl Default for FillSimConfig {
+    fn default() -> Self {
+        Self {
+            latency_ms: 0,
+            partial_fill_prob: 0.0,
+            reject_prob: 0.0,
+        }
+    }
+}
+
+impl FillSimConfig {
+    pub fn validate(&self) -> Result<()> {
+        for (name, prob) in [("partial_fill_prob", self.partial_fill_prob), ("reject_prob", self.reject_prob)] {
+            if !(0.0..=1.0).contains(&prob) {
+                return Err(TradingError::Configuration(format!(
+                    "{} must be between 0 and 1, got {}",
+                    name, prob
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of simulating an order against the book
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedFill {
+    /// Alpaca-style order status
+    pub status: &'static str,
+    pub filled_qty: f64,
+    pub avg_price: Option<f64>,
+    /// Best opposite price when the order arrived, which a fill walking
+    /// deeper into the book slips away from
+    pub expected_price: Option<f64>,
+}
+
+/// Simulates exchange fills for paper trading by walking order book snapshots
+pub struct FillSimulator {
+    config: FillSimConfig,
+    books: HashMap<String, OrderBook>,
+}
+
+impl FillSimulator {
+    pub fn new(config: FillSimConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            books: HashMap::new(),
+        })
+    }
+
+    pub fn latency(&self) -> Duration {
+        Duration::from_millis(self.config.latency_ms)
+    }
+
+    /// Replace the book used to fill orders for the book's symbol
+    pub fn update_book(&mut self, book: &FastOrderBook) {
+        let snapshot = book.to_snapshot(usize::MAX);
+        self.books.insert(snapshot.symbol.0.clone(), snapshot);
+    }
+
+    /// Simulate an order.
+    ///
+    /// Market orders walk the opposite side of the book, and whatever it
+    /// cannot fill is cancelled with the filled part kept. Limit orders only
+    /// fill when marketable, and then only against levels at or better than
+    /// the limit; the rest rests as an open order. Stop orders are accepted
+    /// without filling. A random roll against `partial_fill_prob` fills only
+    /// a fraction of the order, and one against `reject_prob` rejects it.
+    /// Immediate orders never rest: IOC cancels whatever does not fill at
+    /// once, and FOK is cancelled unfilled unless it fills completely.
+    /// Returns `None` when there is no book for the symbol.
+    pub fn simulate(&self, order: &AlpacaOrderRequest) -> Option<Result<SimulatedFill>> {
+        let book = self.books.get(&order.symbol)?;
+
+        if rand::random::<f64>() < self.config.reject_prob {
+            return Some(Err(TradingError::OrderValidation(format!(
+                "Simulated rejection of {} order for {}",
+                order.side, order.symbol
+            ))));
+        }
+
+        let is_buy = order.side == "buy";
+        let levels = if is_buy { &book.asks } else { &book.bids };
+        let expected_price = levels.first().map(|level| level.price.0);
+
+        let limit = match order.r#type.as_str() {
+            "market" => None,
+            "limit" => order.limit_price,
+            _ => {
+                return Some(Ok(SimulatedFill {
+                    status: "new",
+                    filled_qty: 0.0,
+                    avg_price: None,
+                    expected_price,
+                }))
+            }
+        };
+
+        let mut target = order.qty;
+        if rand::random::<f64>() < self.config.partial_fill_prob {
+            target *= rand::random::<f64>();
+        }
+
+        let (filled_qty, avg_price) = walk_levels(levels, target, limit, is_buy);
+
+        let complete = filled_qty >= order.qty - FILL_EPSILON;
+        if order.time_in_force == "fok" && !complete {
+            return Some(Ok(SimulatedFill {
+                status: "canceled",
+                filled_qty: 0.0,
+                avg_price: None,
+                expected_price,
+            }));
+        }
+
+        let status = if complete {
+            "filled"
+        } else if order.time_in_force == "ioc" || limit.is_none() {
+            // Market orders never rest: what the book can't fill is cancelled
+            "canceled"
+        } else if filled_qty > 0.0 {
+            "partially_filled"
+        } else {
+            "new"
+        };
+
+        Some(Ok(SimulatedFill {
+            status,
+            filled_qty,
+            avg_price,
+            expected_price,
+        }))
+    }
+
+    /// Fill what a resting limit order can take from the current book.
+    ///
+    /// Walks levels at or better than the limit for the `remaining`
+    /// quantity. Unlike `simulate` there are no reject or partial-fill
+    /// rolls, since the order is already working. The status is `filled`
+    /// once `remaining` is taken, else `partially_filled`. Returns `None`
+    /// when there is no book for the symbol or nothing is marketable.
+    pub fn fill_resting(&self, order: &AlpacaOrderRequest, remaining: f64) -> Option<SimulatedFill> {
+        let book = self.books.get(&order.symbol)?;
+        let is_buy = order.side == "buy";
+        let levels = if is_buy { &book.asks } else { &book.bids };
+        let expected_price = levels.first().map(|level| level.price.0);
+
+        let (filled_qty, avg_price) = walk_levels(levels, remaining, order.limit_price, is_buy);
+        let avg_price = avg_price?;
+
+        let status = if filled_qty >= remaining - FILL_EPSILON {
+            "filled"
+        } else {
+            "partially_filled"
+        };
+        Some(SimulatedFill {
+            status,
+            filled_qty,
+            avg_price: Some(avg_price),
+            expected_price,
+        })
+    }
+}
+
+/// Walk levels best-first, stopping at `limit` if given.
+/// Returns the filled quantity and its volume-weighted average price.
+fn walk_levels(levels: &[Level], target: f64, limit: Option<f64>, is_buy: bool) -> (f64, Option<f64>) {
+    let mut remaining = target;
+    let mut filled = 0.0;
+    let mut cost = 0.0;
+
+    for level in levels {
+        if remaining <= FILL_EPSILON {
+            break;
+        }
+        if let Some(limit) = limit {
+            let marketable = if is_buy { level.price.0 <= limit } else { level.price.0 >= limit };
+            if !marketable {
+                break;
+            }
+        }
+
+        let qty = remaining.min(level.quantity.0);
+        filled += qty;
+        cost += qty * level.price.0;
+        remaining -= qty;
+    }
+
+    let avg_price = (filled > 0.0).then_some(cost / filled);
+    (filled, avg_price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::types::{Price, Quantity, Symbol};
+
+    fn create_simulator(config: FillSimConfig) -> FillSimulator {
+        let mut book = FastOrderBook::new(Symbol("AAPL".to_string()));
+        book.update_bid(Price(99.9), Quantity(100.0));
+        book.update_ask(Price(100.1), Quantity(50.0));
+        book.update_ask(Price(100.3), Quantity(100.0));
+
+        let mut simulator = FillSimulator::new(config).unwrap();
+        simulator.update_book(&book);
+        simulator
+    }
+
+    fn create_request(r#type: &str, side: &str, qty: f64, limit_price: Option<f64>) -> AlpacaOrderRequest {
+        AlpacaOrderRequest {
+            client_order_id: "client".to_string(),
+            symbol: "AAPL".to_string(),
+            qty,
+            side: side.to_string(),
+            r#type: r#type.to_string(),
+            time_in_force: "gtc".to_string(),
+            limit_price,
+            stop_price: None,
+        }
+    }
+
+    #[test]
+    fn test_market_order_walks_book() {
+        let simulator = create_simulator(FillSimConfig::default());
+        let fill = simulator.simulate(&create_request("market", "buy", 100.0, None)).unwrap().unwrap();
+
+        assert_eq!(fill.status, "filled");
+        assert_eq!(fill.filled_qty, 100.0);
+        assert!((fill.avg_price.unwrap() - 100.2).abs() < 1e-6);
+        assert_eq!(fill.expected_price, Some(100.1));
+
+        // Deeper than the book: the unfilled rest is cancelled, not left open
+        let fill = simulator.simulate(&create_request("market", "buy", 200.0, None)).unwrap().unwrap();
+        assert_eq!(fill.status, "canceled");
+        assert_eq!(fill.filled_qty, 150.0);
+    }
+
+    #[test]
+    fn test_limit_order_fills_only_when_marketable() {
+        let simulator = create_simulator(FillSimConfig::default());
+
+        let resting = simulator.simulate(&create_request("limit", "buy", 10.0, Some(100.0))).unwrap().unwrap();
+        assert_eq!(resting.status, "new");
+        assert_eq!(resting.filled_qty, 0.0);
+
+        // Marketable, but only the first ask level is within the limit
+        let partial = simulator.simulate(&create_request("limit", "buy", 80.0, Some(100.2))).unwrap().unwrap();
+        assert_eq!(partial.status, "partially_filled");
+        assert_eq!(partial.filled_qty, 50.0);
+        assert!((partial.avg_price.unwrap() - 100.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_immediate_orders_never_rest() {
+        let simulator = create_simulator(FillSimConfig::default());
+
+        // Only 50 is offered within the limit: IOC keeps it, FOK takes none
+        let mut ioc = create_request("limit", "buy", 80.0, Some(100.2));
+        ioc.time_in_force = "ioc".to_string();
+        let fill = simulator.simulate(&ioc).unwrap().unwrap();
+        assert_eq!(fill.status, "canceled");
+        assert_eq!(fill.filled_qty, 50.0);
+
+        let mut fok = create_request("limit", "buy", 80.0, Some(100.2));
+        fok.time_in_force = "fok".to_string();
+        let fill = simulator.simulate(&fok).unwrap().unwrap();
+        assert_eq!(fill.status, "canceled");
+        assert_eq!(fill.filled_qty, 0.0);
+        assert!(fill.avg_price.is_none());
+
+        // A FOK the book can absorb fills in full
+        let mut fok = create_request("market", "buy", 100.0, None);
+        fok.time_in_force = "fok".to_string();
+        assert_eq!(simulator.simulate(&fok).unwrap().unwrap().status, "filled");
+    }
+
+    #[test]
+    fn test_reject_probability() {
+        let simulator = create_simulator(FillSimConfig { reject_prob: 1.0, ..FillSimConfig::default() });
+        assert!(simulator.simulate(&create_request("market", "sell", 10.0, None)).unwrap().is_err());
+    }
+
+    #[test]
+    fn test_unknown_symbol_not_simulated() {
+        let simulator = create_simulator(FillSimConfig::default());
+        let mut request = create_request("market", "buy", 10.0, None);
+        request.symbol = "MSFT".to_string();
+        assert!(simulator.simulate(&request).is_none());
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert!(FillSimulator::new(FillSimConfig { partial_fill_prob: 1.5, ..FillSimConfig::default() }).is_err());
+    }
+}
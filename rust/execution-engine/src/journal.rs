@@ -0,0 +1,213 @@
+use common::{Result, types::{Order, OrderStatus}};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::warn;
+
+/// Append-only journal of order submissions and status transitions.
+///
+/// Each record is the full order as of the transition, written as one JSON
+/// line and synced to disk before the call returns, so a crash loses at most
+/// the record being written. Replaying the file rebuilds open-order state.
+pub struct OrderJournal {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl OrderJournal {
+    /// Open (or create) the journal at `path`, appending to existing records.
+    ///
+    /// A torn final record left by a crash mid-write is truncated away first,
+    /// so the next record starts on a line of its own.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).read(true).append(true).open(&path)?;
+        truncate_torn_record(&file, &path)?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Journal whose appends always fail (the disk is full) and which
+    /// replays as empty
+    #[cfg(all(test, target_os = "linux"))]
+    pub(crate) fn failing() -> Self {
+        let file = OpenOptions::new().append(true).open("/dev/full").unwrap();
+        Self {
+            path: PathBuf::from("/dev/null"),
+            file: Mutex::new(file),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append the order's current state
+    pub fn append(&self, order: &Order) -> Result<()> {
+        let mut line = serde_json::to_vec(order)?;
+        line.push(b'\n');
+
+        let mut file = self.file.lock().unwrap();
+        file.write_all(&line)?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    /// Rebuild open orders from the journal.
+    ///
+    /// Records are matched by client order id, falling back to the order id
+    /// when there is none, so a submission journaled before the exchange
+    /// assigned an id is superseded by the later records. The last record
+    /// for each order wins; orders whose last state is Filled, Cancelled or
+    /// Rejected are skipped. A torn final line from a crash mid-write is
+    /// ignored.
+    pub fn replay(&self) -> Result<Vec<Order>> {
+        let reader = BufReader::new(File::open(&self.path)?);
+        let mut latest: HashMap<String, Order> = HashMap::new();
+
+        for (line_number, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<Order>(&line) {
+                Ok(order) => {
+                    let key = if order.client_order_id.is_empty() {
+                        order.order_id.clone()
+                    } else {
+                        order.client_order_id.clone()
+                    };
+                    latest.insert(key, order);
+                }
+                Err(e) => warn!(
+                    "Skipping unreadable journal record {}:{}: {}",
+                    self.path.display(),
+                    line_number + 1,
+                    e
+                ),
+            }
+        }
+
+        let mut open: Vec<Order> = latest
+            .into_values()
+            .filter(|order| matches!(order.status, OrderStatus::Pending | OrderStatus::PartiallyFilled))
+            .collect();
+        open.sort_by_key(|order| order.created_at);
+        Ok(open)
+    }
+}
+
+/// Cut `file` back to just after its last newline
+fn truncate_torn_record(mut file: &File, path: &Path) -> Result<()> {
+    let len = file.metadata()?.len();
+    if len == 0 {
+        return Ok(());
+    }
+
+    let mut end = len;
+    let mut buf = [0u8; 4096];
+    while end > 0 {
+        let start = end.saturating_sub(buf.len() as u64);
+        let chunk = &mut buf[..(end - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(chunk)?;
+        if let Some(newline) = chunk.iter().rposition(|b| *b == b'\n') {
+            end = start + newline as u64 + 1;
+            break;
+        }
+        end = start;
+    }
+
+    if end < len {
+        warn!(
+            "Truncating torn journal record at {}:{} ({} bytes)",
+            path.display(),
+            end,
+            len - end
+        );
+        file.set_len(end)?;
+        file.sync_data()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::types::Quantity;
+    use common::OrderBuilder;
+
+    fn journal_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("order-journal-{}-{}.jsonl", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn create_test_order(order_id: &str, status: OrderStatus) -> Order {
+        OrderBuilder::new()
+            .order_id(order_id)
+            .client_order_id(&format!("client-{}", order_id))
+            .limit(150.0)
+            .status(status)
+            .build()
+    }
+
+    #[test]
+    fn test_replay_keeps_latest_open_state() {
+        let path = journal_path("replay");
+        {
+            let journal = OrderJournal::open(&path).unwrap();
+            journal.append(&create_test_order("1", OrderStatus::Pending)).unwrap();
+            journal.append(&create_test_order("2", OrderStatus::Pending)).unwrap();
+            journal.append(&create_test_order("3", OrderStatus::Pending)).unwrap();
+
+            let mut partial = create_test_order("1", OrderStatus::PartiallyFilled);
+            partial.filled_quantity = Quantity(40.0);
+            journal.append(&partial).unwrap();
+            journal.append(&create_test_order("2", OrderStatus::Filled)).unwrap();
+            journal.append(&create_test_order("3", OrderStatus::Cancelled)).unwrap();
+        }
+
+        // Reopening appends rather than truncating
+        let journal = OrderJournal::open(&path).unwrap();
+        let open = journal.replay().unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].order_id, "1");
+        assert_eq!(open[0].status, OrderStatus::PartiallyFilled);
+        assert_eq!(open[0].filled_quantity, Quantity(40.0));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_replay_ignores_torn_record() {
+        let path = journal_path("torn");
+        let journal = OrderJournal::open(&path).unwrap();
+        journal.append(&create_test_order("1", OrderStatus::Pending)).unwrap();
+        {
+            let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+            file.write_all(b"{\"order_id\":\"2\",\"sta").unwrap();
+        }
+
+        let open = journal.replay().unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].order_id, "1");
+
+        // Records written after a restart are not glued onto the torn line
+        drop(journal);
+        let journal = OrderJournal::open(&path).unwrap();
+        journal.append(&create_test_order("3", OrderStatus::Pending)).unwrap();
+        let open = journal.replay().unwrap();
+        assert_eq!(open.len(), 2);
+        assert_eq!(open[1].order_id, "3");
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -2,21 +2,45 @@
 ///
 /// Handles order routing, smart order execution, and slippage minimization.
 
+pub mod fees;
+pub mod fill_sim;
+pub mod journal;
+pub mod rate_limit;
 pub mod router;
 pub mod retry;
 pub mod slippage;
+pub mod smart_router;
 pub mod stop_loss_executor;
+pub mod tracker;
 
+pub use fees::{BasisPoints, FeeModel, FlatPerOrder, NoFees, PerShare};
+pub use fill_sim::{FillSimConfig, FillSimulator};
+pub use journal::OrderJournal;
+pub use rate_limit::RateLimiter;
 pub use router::OrderRouter;
-pub use retry::RetryPolicy;
-pub use slippage::SlippageEstimator;
+pub use retry::{Jitter, RetryPolicy};
+pub use slippage::{realized_slippage_bps, SlippageEstimator};
+pub use smart_router::{RoutePlan, SmartRouter, VenueId};
 pub use stop_loss_executor::StopLossExecutor;
+pub use tracker::{OrderEvent, OrderTracker};
 
-use common::{Result, types::Order};
+use chrono::Utc;
+use common::types::{Order, OrderStatus, OrderType, Quantity, Side, Signal, SignalAction, TimeInForce};
+use common::{EventReceiver, Result, ResultExt, Shutdown, SystemSignal};
+use market_data::{MarketDataSubscriber, MarketMessage};
+use tracing::{info, warn};
+
+/// Slippage tolerance (percent) for stop orders placed as limits
+const STOP_SLIPPAGE_TOLERANCE_PCT: f64 = 0.5;
 
 pub struct ExecutionEngineService {
     router: OrderRouter,
     slippage_estimator: SlippageEstimator,
+    stop_executor: StopLossExecutor,
+    /// Size of the market order placed for each buy or sell signal; signals
+    /// are only logged when unset
+    signal_quantity: Option<Quantity>,
+    shutdown: Shutdown,
 }
 
 impl ExecutionEngineService {
@@ -24,16 +48,258 @@ impl ExecutionEngineService {
         Ok(Self {
             router: OrderRouter::new(config)?,
             slippage_estimator: SlippageEstimator::new(),
+            stop_executor: StopLossExecutor::new(true, STOP_SLIPPAGE_TOLERANCE_PCT),
+            signal_quantity: None,
+            shutdown: Shutdown::default(),
         })
     }
 
+    /// Share a shutdown coordinator; routes in progress are tracked so
+    /// `Shutdown::drain` waits for them
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Trade buy and sell signals with market orders of `quantity`
+    pub fn with_signal_orders(mut self, quantity: Quantity) -> Self {
+        self.signal_quantity = Some(quantity);
+        self
+    }
+
+    /// Journal order state to `path` and restore the open orders it holds
+    pub fn with_journal(mut self, path: impl AsRef<std::path::Path>) -> Result<Self> {
+        self.router = self.router.with_journal(OrderJournal::open(path)?)?;
+        Ok(self)
+    }
+
+    /// Lifecycle tracker for every order this service routes
+    pub fn tracker(&self) -> std::sync::Arc<OrderTracker> {
+        self.router.tracker()
+    }
+
+    pub fn shutdown(&self) -> Shutdown {
+        self.shutdown.clone()
+    }
+
+    /// Route an order. Refused once shutdown has begun.
     pub async fn submit_order(&self, order: Order) -> Result<()> {
         // Estimate slippage
         let _estimated_slippage = self.slippage_estimator.estimate(&order);
 
         // Route order (current market price would come from market data feed in production)
-        self.router.route(order, None).await?;
+        let context = format!("order {} for {}", order.client_order_id, order.symbol.0);
+        self.shutdown
+            .track(self.router.route(order, None))
+            .await
+            .and_then(|routed| routed)
+            .with_context(|| context)?;
 
         Ok(())
     }
+
+    /// Act on a signal from the event bus: stop triggers become closing
+    /// market orders, trading signals are routed when `with_signal_orders`
+    /// is set, and everything else is logged
+    pub async fn handle_signal(&self, signal: SystemSignal) -> Result<()> {
+        match signal {
+            SystemSignal::StopTriggered(stop) => {
+                info!("Closing {} {} on stop: {}", stop.symbol.0, stop.quantity.0, stop.reason);
+                let order = self.stop_executor.create_stop_loss_order(
+                    stop.symbol,
+                    stop.close_side,
+                    stop.quantity,
+                    stop.current_price,
+                    stop.trigger_price,
+                )?;
+                self.submit_order(order).await
+            }
+            SystemSignal::CircuitBreakerTripped { reason } => {
+                warn!("Circuit breaker tripped upstream: {}", reason);
+                Ok(())
+            }
+            SystemSignal::SignalGenerated(signal) => {
+                info!("Signal for {}: {:?}", signal.symbol.0, signal.action);
+                match self.signal_order(&signal) {
+                    Some(order) => self.submit_order(order).await,
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+
+    /// Market order for a buy or sell signal, if signal orders are enabled
+    fn signal_order(&self, signal: &Signal) -> Option<Order> {
+        let quantity = self.signal_quantity?;
+        let side = match signal.action {
+            SignalAction::Buy => Side::Bid,
+            SignalAction::Sell => Side::Ask,
+            SignalAction::Hold => return None,
+        };
+
+        let now = Utc::now();
+        let nanos = now.timestamp_nanos_opt().unwrap_or(0);
+        Some(Order {
+            order_id: format!("signal-{}-{}", signal.symbol.0, now.timestamp_millis()),
+            client_order_id: format!("signal-{}-{}", signal.symbol.0, nanos),
+            symbol: signal.symbol.clone(),
+            side,
+            order_type: OrderType::Market,
+            quantity,
+            price: None,
+            stop_price: None,
+            time_in_force: TimeInForce::Gtc,
+            status: OrderStatus::Pending,
+            filled_quantity: Quantity(0.0),
+            average_price: None,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Handle signals published by the signal bridge until shutdown begins
+    /// or the subscription closes. Subscribe `subscriber` to the `signal`
+    /// topic; other messages are ignored.
+    pub async fn run_signals(&self, mut subscriber: MarketDataSubscriber) {
+        loop {
+            let message = tokio::select! {
+                _ = self.shutdown.cancelled() => break,
+                message = subscriber.recv() => message,
+            };
+
+            let signal = match message {
+                Ok(MarketMessage::Signal(signal)) => signal,
+                Ok(_) => continue,
+                Err(_) if subscriber.is_closed() => break,
+                Err(e) => {
+                    warn!("Failed to receive signal: {}", e);
+                    continue;
+                }
+            };
+
+            if let Err(e) = self.handle_signal(SystemSignal::SignalGenerated(signal)).await {
+                warn!("Failed to handle signal event: {}", e);
+            }
+        }
+    }
+
+    /// Handle signals until the bus closes or shutdown begins. A failed
+    /// order is logged and does not stop the loop.
+    pub async fn run_events(&self, mut events: EventReceiver) {
+        loop {
+            let signal = tokio::select! {
+                _ = self.shutdown.cancelled() => break,
+                signal = events.recv() => match signal {
+                    Some(signal) => signal,
+                    None => break,
+                },
+            };
+
+            let kind = signal.kind();
+            if let Err(e) = self.handle_signal(signal).await {
+                warn!("Failed to handle {} event: {}", kind, e);
+            }
+        }
+        if events.missed() > 0 {
+            warn!("Event loop stopped after missing {} signals", events.missed());
+        }
+    }
+
+    /// Cancel expired DAY, GTD and over-age orders every `interval` until
+    /// shutdown begins
+    pub async fn run_expiry(&self, interval: std::time::Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            tokio::select! {
+                _ = self.shutdown.cancelled() => break,
+                _ = ticker.tick() => {}
+            }
+            self.router.cancel_expired(Utc::now()).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::config::ExecutionConfig;
+    use common::types::{Price, Quantity, Side, Symbol};
+    use common::{EventBus, StopTriggered};
+    use std::collections::HashMap;
+
+    fn paper_config() -> ExecutionConfig {
+        ExecutionConfig {
+            exchange_api_url: "https://paper-api.alpaca.markets".to_string(),
+            api_key: None,
+            api_secret: None,
+            rate_limit_per_second: 100,
+            retry_attempts: 1,
+            retry_delay_ms: 100,
+            paper_trading: true,
+            max_slippage_bps: 50.0,
+            symbol_max_slippage_bps: HashMap::new(),
+            fee_model: Default::default(),
+            instruments: Default::default(),
+            session: None,
+            max_order_age_secs: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stop_events_route_closing_orders() {
+        let service = ExecutionEngineService::new(paper_config()).await.unwrap();
+        let mut orders = service.tracker().subscribe();
+        let bus = EventBus::default();
+        let events = bus.subscribe();
+
+        bus.publish(SystemSignal::StopTriggered(StopTriggered {
+            symbol: Symbol("AAPL".to_string()),
+            close_side: Side::Ask,
+            quantity: Quantity(10.0),
+            trigger_price: Price(95.0),
+            current_price: Price(94.5),
+            reason: "Static stop triggered".to_string(),
+        }));
+        bus.publish(SystemSignal::CircuitBreakerTripped {
+            reason: "session loss".to_string(),
+        });
+        drop(bus);
+
+        // Returns once the bus is closed and drained
+        service.run_events(events).await;
+
+        let order = orders.try_recv().unwrap().order().clone();
+        assert_eq!(order.symbol, Symbol("AAPL".to_string()));
+        assert_eq!(order.side, Side::Ask);
+        assert_eq!(order.quantity, Quantity(10.0));
+    }
+    fn signal(action: SignalAction) -> SystemSignal {
+        SystemSignal::SignalGenerated(Signal {
+            symbol: Symbol("MSFT".to_string()),
+            action,
+            confidence: 0.8,
+            features: vec![0.01],
+            timestamp: Utc::now(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_signals_route_orders_only_when_enabled() {
+        let service = ExecutionEngineService::new(paper_config()).await.unwrap();
+        let mut orders = service.tracker().subscribe();
+        service.handle_signal(signal(SignalAction::Buy)).await.unwrap();
+        assert!(orders.try_recv().is_err());
+
+        let service = service.with_signal_orders(Quantity(5.0));
+        let mut orders = service.tracker().subscribe();
+        service.handle_signal(signal(SignalAction::Hold)).await.unwrap();
+        assert!(orders.try_recv().is_err());
+
+        service.handle_signal(signal(SignalAction::Sell)).await.unwrap();
+        let order = orders.try_recv().unwrap().order().clone();
+        assert_eq!(order.symbol, Symbol("MSFT".to_string()));
+        assert_eq!(order.side, Side::Ask);
+        assert_eq!(order.order_type, OrderType::Market);
+        assert_eq!(order.quantity, Quantity(5.0));
+    }
 }
@@ -1,11 +1,19 @@
 use execution_engine::ExecutionEngineService;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 use common::config::SystemConfig;
-use common::health::HealthCheck;
+use common::health::{check_exchange, HealthCheck};
+use common::Shutdown;
 use common::metrics::{MetricsConfig, start_metrics_server};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
+/// Order journal replayed on startup to restore open orders
+const ORDER_JOURNAL_PATH: &str = "data/orders.journal";
+
+/// How often resting orders are checked for expiry
+const ORDER_EXPIRY_INTERVAL: Duration = Duration::from_secs(1);
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Initialize tracing
@@ -17,7 +25,7 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("Execution Engine Service starting...");
 
     // Load configuration with validation
-    let config = match SystemConfig::from_file("config/system.json") {
+    let config = match SystemConfig::from_file_with_env("config/system.json") {
         Ok(cfg) => {
             tracing::info!(
                 "Configuration loaded successfully - Environment: {}, Paper Trading: {}",
@@ -61,12 +69,14 @@ async fn main() -> anyhow::Result<()> {
     // Store values before move
     let is_paper_trading = config.is_paper_trading();
     let environment = config.environment();
+    let exchange_api_url = config.execution.exchange_api_url.clone();
 
     // Initialize service
-    let _service = match ExecutionEngineService::new(config.execution).await {
+    let shutdown = Shutdown::default();
+    let service = match ExecutionEngineService::new(config.execution).await {
         Ok(svc) => {
             tracing::info!("✓ Execution Engine initialized successfully");
-            svc
+            svc.with_shutdown(shutdown.clone())
         }
         Err(e) => {
             tracing::error!("Failed to initialize service: {}", e);
@@ -76,21 +86,51 @@ async fn main() -> anyhow::Result<()> {
         }
     };
 
-    // Update health status
+    let service = match service.with_journal(ORDER_JOURNAL_PATH) {
+        Ok(svc) => {
+            tracing::info!("✓ Order journal at {}", ORDER_JOURNAL_PATH);
+            svc
+        }
+        Err(e) => {
+            tracing::error!("Failed to open order journal: {}", e);
+            let mut h = health.write().await;
+            *h = HealthCheck::unhealthy("execution-engine", format!("Order journal unavailable: {}", e));
+            return Err(anyhow::anyhow!("Order journal error: {}", e));
+        }
+    };
+
+    // Update health status, probing the exchange API
     {
+        let exchange = check_exchange(&exchange_api_url).await;
+        if !exchange.status.is_healthy() {
+            tracing::warn!("Exchange API check failed: {:?}", exchange.message);
+        }
+
         let mut h = health.write().await;
         *h = HealthCheck::healthy("execution-engine")
             .with_metric("status", "ready")
             .with_metric("paper_trading", is_paper_trading.to_string())
-            .with_metric("environment", &environment);
+            .with_metric("environment", &environment)
+            .with_dependency("exchange", exchange);
     }
 
+    // Cancel DAY, GTD and over-age orders as they expire
+    let service = Arc::new(service);
+    let expiry_task = tokio::spawn({
+        let service = service.clone();
+        async move { service.run_expiry(ORDER_EXPIRY_INTERVAL).await }
+    });
+
     tracing::info!("🚀 Execution Engine is ready");
 
     // Keep service running
     tokio::signal::ctrl_c().await?;
     tracing::info!("Shutdown signal received, stopping Execution Engine...");
 
+    // Refuse new orders and let in-flight routes finish
+    shutdown.drain().await;
+    let _ = expiry_task.await;
+
     // Stop metrics server
     if let Some(handle) = metrics_handle {
         handle.abort();
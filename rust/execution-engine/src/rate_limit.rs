@@ -0,0 +1,139 @@
+use common::{Result, TradingError};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Async token bucket shared by every request an `OrderRouter` makes.
+///
+/// Tokens refill continuously at `rate_per_second` up to `burst`. Each
+/// `acquire` reserves a token immediately, letting the balance go negative,
+/// and sleeps until its reservation is covered, so concurrent callers are
+/// served in arrival order and the combined rate never exceeds the limit.
+pub struct RateLimiter {
+    rate_per_second: f64,
+    burst: f64,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Limiter refilling `rate_per_second` tokens per second, holding at most `burst`
+    pub fn new(rate_per_second: u32, burst: u32) -> Result<Self> {
+        if rate_per_second == 0 {
+            return Err(TradingError::Configuration(
+                "rate_limit_per_second must be greater than 0".to_string()
+            ));
+        }
+        if burst == 0 {
+            return Err(TradingError::Configuration(
+                "rate limit burst must be greater than 0".to_string()
+            ));
+        }
+
+        Ok(Self {
+            rate_per_second: rate_per_second as f64,
+            burst: burst as f64,
+            bucket: Mutex::new(Bucket {
+                tokens: burst as f64,
+                last_refill: Instant::now(),
+            }),
+        })
+    }
+
+    /// Wait for a token.
+    ///
+    /// The token is reserved when this is first polled; dropping the future
+    /// while it waits forfeits the token.
+    pub async fn acquire(&self) {
+        let wait = self.reserve();
+        if !wait.is_zero() {
+            metrics::counter!("execution_rate_limited_total").increment(1);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Tokens available right now; negative while callers are queued
+    pub fn available(&self) -> f64 {
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket);
+        bucket.tokens
+    }
+
+    /// Take a token, returning how long until it is actually available
+    fn reserve(&self) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket);
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / self.rate_per_second)
+        }
+    }
+
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate_per_second).min(self.burst);
+        bucket.last_refill = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_rejects_zero_rate_or_burst() {
+        assert!(RateLimiter::new(0, 1).is_err());
+        assert!(RateLimiter::new(1, 0).is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_burst_then_steady_rate() {
+        let limiter = RateLimiter::new(10, 5).unwrap();
+        let start = Instant::now();
+
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        // The next 10 tokens arrive at 10 per second
+        for _ in 0..10 {
+            limiter.acquire().await;
+        }
+        let elapsed = start.elapsed().as_secs_f64();
+        assert!((elapsed - 1.0).abs() < 1e-3, "elapsed {}", elapsed);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_concurrent_callers_share_bucket() {
+        let limiter = Arc::new(RateLimiter::new(4, 1).unwrap());
+        let start = Instant::now();
+
+        let handles: Vec<_> = (0..9)
+            .map(|_| {
+                let limiter = limiter.clone();
+                tokio::spawn(async move {
+                    limiter.acquire().await;
+                    start.elapsed()
+                })
+            })
+            .collect();
+
+        let mut latest = Duration::ZERO;
+        for handle in handles {
+            latest = latest.max(handle.await.unwrap());
+        }
+
+        // One token up front, then 8 more at 4 per second
+        assert!((latest.as_secs_f64() - 2.0).abs() < 1e-3, "latest {:?}", latest);
+        assert!(limiter.available() < 1e-6);
+    }
+}
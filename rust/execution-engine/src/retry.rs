@@ -1,12 +1,24 @@
 use std::future::Future;
 use tokio::time::{sleep, Duration};
 
+/// How much randomness is applied to each backoff delay
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Jitter {
+    /// Use the computed delay as-is
+    None,
+    /// Scale the delay by a random factor in 85-115%
+    Proportional,
+    /// Pick a random delay between zero and the computed delay
+    Full,
+}
+
 #[derive(Clone)]
 pub struct RetryPolicy {
     max_attempts: u32,
     initial_delay_ms: u64,
     max_delay_ms: u64,
     backoff_multiplier: f64,
+    jitter: Jitter,
 }
 
 impl RetryPolicy {
@@ -16,9 +28,26 @@ impl RetryPolicy {
             initial_delay_ms,
             max_delay_ms: 30000, // 30 seconds max
             backoff_multiplier: 2.0,
+            jitter: Jitter::Proportional,
+        }
+    }
+
+    /// Retry with a constant delay between attempts and no jitter
+    pub fn fixed(delay_ms: u64) -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay_ms: delay_ms,
+            max_delay_ms: delay_ms,
+            backoff_multiplier: 1.0,
+            jitter: Jitter::None,
         }
     }
 
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
     pub fn with_max_delay(mut self, max_delay_ms: u64) -> Self {
         self.max_delay_ms = max_delay_ms;
         self
@@ -29,45 +58,38 @@ impl RetryPolicy {
         self
     }
 
+    pub fn with_jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Delay to wait after the given failed attempt (0-based) before retrying.
+    ///
+    /// The base delay grows by `backoff_multiplier` per attempt and is capped
+    /// at `max_delay_ms` before jitter is applied.
+    pub fn next_delay(&self, attempt: u32) -> Duration {
+        let exp = self.backoff_multiplier.powi(attempt.min(i32::MAX as u32) as i32);
+        let ceiling = (self.initial_delay_ms as f64 * exp).min(self.max_delay_ms as f64);
+
+        let delay = match self.jitter {
+            Jitter::None => ceiling,
+            Jitter::Proportional => {
+                (ceiling * ((rand::random::<f64>() * 0.3) + 0.85)).min(self.max_delay_ms as f64)
+            }
+            Jitter::Full => ceiling * rand::random::<f64>(),
+        };
+
+        Duration::from_millis(delay as u64)
+    }
+
     /// Execute with exponential backoff retry
-    pub async fn execute<F, Fut, T, E>(&self, mut f: F) -> Result<T, E>
+    pub async fn execute<F, Fut, T, E>(&self, f: F) -> Result<T, E>
     where
         F: FnMut() -> Fut,
         Fut: Future<Output = Result<T, E>>,
         E: std::fmt::Debug,
     {
-        let mut attempts = 0;
-        let mut delay = self.initial_delay_ms;
-
-        loop {
-            match f().await {
-                Ok(result) => return Ok(result),
-                Err(e) => {
-                    attempts += 1;
-                    if attempts >= self.max_attempts {
-                        return Err(e);
-                    }
-
-                    // Exponential backoff with jitter
-                    let jitter = (rand::random::<f64>() * 0.3) + 0.85; // 85-115% of delay
-                    let backoff_delay = (delay as f64 * jitter) as u64;
-                    let capped_delay = backoff_delay.min(self.max_delay_ms);
-
-                    tracing::warn!(
-                        "Retry attempt {}/{} after {:?}, error: {:?}",
-                        attempts,
-                        self.max_attempts,
-                        Duration::from_millis(capped_delay),
-                        e
-                    );
-
-                    sleep(Duration::from_millis(capped_delay)).await;
-
-                    // Increase delay for next attempt
-                    delay = (delay as f64 * self.backoff_multiplier) as u64;
-                }
-            }
-        }
+        self.execute_with_condition(f, |_| true).await
     }
 
     /// Execute with custom retry condition
@@ -83,7 +105,6 @@ impl RetryPolicy {
         E: std::fmt::Debug,
     {
         let mut attempts = 0;
-        let mut delay = self.initial_delay_ms;
 
         loop {
             match f().await {
@@ -93,26 +114,21 @@ impl RetryPolicy {
                         return Err(e);
                     }
 
+                    let delay = self.next_delay(attempts);
                     attempts += 1;
                     if attempts >= self.max_attempts {
                         return Err(e);
                     }
 
-                    let jitter = (rand::random::<f64>() * 0.3) + 0.85;
-                    let backoff_delay = (delay as f64 * jitter) as u64;
-                    let capped_delay = backoff_delay.min(self.max_delay_ms);
-
                     tracing::warn!(
                         "Retry attempt {}/{} after {:?}, error: {:?}",
                         attempts,
                         self.max_attempts,
-                        Duration::from_millis(capped_delay),
+                        delay,
                         e
                     );
 
-                    sleep(Duration::from_millis(capped_delay)).await;
-
-                    delay = (delay as f64 * self.backoff_multiplier) as u64;
+                    sleep(delay).await;
                 }
             }
         }
@@ -150,6 +166,56 @@ mod tests {
         assert_eq!(attempts.load(Ordering::Relaxed), 2);
     }
 
+    #[test]
+    fn test_next_delay_exponential_and_capped() {
+        let policy = RetryPolicy::new(5, 100)
+            .with_max_delay(1000)
+            .with_jitter(Jitter::None);
+
+        assert_eq!(policy.next_delay(0), Duration::from_millis(100));
+        assert_eq!(policy.next_delay(1), Duration::from_millis(200));
+        assert_eq!(policy.next_delay(3), Duration::from_millis(800));
+        assert_eq!(policy.next_delay(10), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn test_next_delay_full_jitter_bounded() {
+        let policy = RetryPolicy::new(5, 100).with_jitter(Jitter::Full);
+        for _ in 0..100 {
+            assert!(policy.next_delay(2) <= Duration::from_millis(400));
+        }
+    }
+
+    #[test]
+    fn test_fixed_delay() {
+        let policy = RetryPolicy::fixed(250);
+        assert_eq!(policy.next_delay(0), Duration::from_millis(250));
+        assert_eq!(policy.next_delay(5), Duration::from_millis(250));
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_short_circuits() {
+        let policy = RetryPolicy::new(3, 10);
+        let attempts = Arc::new(AtomicU32::new(0));
+        let attempts_clone = attempts.clone();
+
+        let result = policy
+            .execute_with_condition(
+                || {
+                    let attempts = attempts_clone.clone();
+                    async move {
+                        attempts.fetch_add(1, Ordering::Relaxed);
+                        Err::<i32, _>(common::TradingError::OrderValidation("bad qty".to_string()))
+                    }
+                },
+                common::TradingError::is_retryable,
+            )
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::Relaxed), 1);
+    }
+
     #[tokio::test]
     async fn test_retry_max_attempts() {
         let policy = RetryPolicy::new(3, 10);
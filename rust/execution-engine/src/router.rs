@@ -1,13 +1,33 @@
-use common::{Result, TradingError, types::Order, config::ExecutionConfig};
-use crate::retry::RetryPolicy;
-use governor::{Quota, RateLimiter, clock::DefaultClock, state::{InMemoryState, NotKeyed}};
+use common::{Result, TradingError, config::ExecutionConfig, messaging::OrderResponse};
+use common::types::{Order, OrderStatus, OrderType, Price, Quantity, Side, Symbol, TimeInForce};
+use crate::fees::{FeeModel, fee_model_for};
+use crate::fill_sim::{FillSimConfig, FillSimulator};
+use crate::journal::OrderJournal;
+use crate::rate_limit::RateLimiter;
+use crate::retry::{Jitter, RetryPolicy};
+use crate::slippage::realized_slippage_bps;
+use crate::tracker::OrderTracker;
+use chrono::{DateTime, Utc};
+use market_data::orderbook::FastOrderBook;
+use market_data::SessionCalendar;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::num::NonZeroU32;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How often a resting iceberg tranche is polled for fills in live trading
+const ICEBERG_POLL_INTERVAL_MS: u64 = 250;
+
+/// Residual quantity below which an iceberg order is considered complete
+const ICEBERG_QTY_EPSILON: f64 = 1e-9;
+
+/// Polls of a working iceberg tranche before it is cancelled (one minute)
+const ICEBERG_MAX_POLLS: u32 = 240;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AlpacaOrderRequest {
+    pub client_order_id: String,
     pub symbol: String,
     pub qty: f64,
     pub side: String,
@@ -19,21 +39,67 @@ pub struct AlpacaOrderRequest {
     pub stop_price: Option<f64>,
 }
 
-#[derive(Debug, Deserialize)]
+/// Body of an Alpaca order replace (PATCH) request
+#[derive(Debug, Serialize)]
+pub struct AlpacaReplaceRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qty: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit_price: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct AlpacaOrderResponse {
     pub id: String,
+    #[serde(default)]
+    pub client_order_id: String,
     pub status: String,
     pub symbol: String,
     pub qty: String,
     pub filled_qty: String,
     pub side: String,
+    #[serde(default)]
+    pub filled_avg_price: Option<String>,
+    /// Commission on the filled quantity; set by the paper exchange only
+    #[serde(default)]
+    pub commission: Option<f64>,
+    /// Realized slippage versus the expected price in basis points (positive
+    /// is adverse); set by the paper exchange only
+    #[serde(default)]
+    pub slippage_bps: Option<f64>,
+}
+
+impl AlpacaOrderResponse {
+    /// Average fill price, if the order has filled at all
+    pub fn avg_price(&self) -> Option<f64> {
+        self.filled_avg_price.as_deref().and_then(|p| p.parse().ok())
+    }
 }
 
 pub struct OrderRouter {
     config: ExecutionConfig,
     retry_policy: RetryPolicy,
-    rate_limiter: Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>,
+    /// Token bucket shared by every exchange request this router makes
+    rate_limiter: Arc<RateLimiter>,
     http_client: Client,
+    /// Client order ids already sent to the exchange, used as an idempotency guard
+    submitted: Mutex<HashSet<String>>,
+    /// Orders accepted by the simulated exchange in paper trading, keyed by client order id
+    paper_orders: Mutex<HashMap<String, AlpacaOrderResponse>>,
+    /// Paper limit orders still working, keyed by client order id, which
+    /// fill as later books make them marketable
+    paper_resting: Mutex<HashMap<String, AlpacaOrderRequest>>,
+    /// Book-driven fill simulation for paper trading; canned fills when unset
+    fill_simulator: Mutex<Option<FillSimulator>>,
+    /// Commission charged on paper fills
+    fee_model: Box<dyn FeeModel>,
+    /// Lifecycle tracking for every routed order
+    tracker: Arc<OrderTracker>,
+    /// Session whose close expires DAY orders
+    session: SessionCalendar,
+    /// Number of accepted paper orders whose response should be lost in transit
+    #[cfg(test)]
+    drop_responses: std::sync::atomic::AtomicU32,
 }
 
 impl OrderRouter {
@@ -44,19 +110,18 @@ impl OrderRouter {
         // Validate credentials are present for live trading
         config.validate_credentials()?;
 
+        // Full jitter keeps concurrent retries from synchronizing
         let retry_policy = RetryPolicy::new(
             config.retry_attempts,
             config.retry_delay_ms,
-        );
+        )
+        .with_jitter(Jitter::Full);
 
-        // Create rate limiter with proper error handling
-        let quota = Quota::per_second(
-            NonZeroU32::new(config.rate_limit_per_second)
-                .ok_or_else(|| TradingError::Configuration(
-                    "rate_limit_per_second must be greater than 0".to_string()
-                ))?
-        );
-        let rate_limiter = Arc::new(RateLimiter::direct(quota));
+        // One bucket for the whole router, bursting up to one second's worth
+        let rate_limiter = Arc::new(RateLimiter::new(
+            config.rate_limit_per_second,
+            config.rate_limit_per_second,
+        )?);
 
         // Configure HTTP client with TLS requirements
         let http_client = Client::builder()
@@ -66,16 +131,70 @@ impl OrderRouter {
             .build()
             .map_err(|e| TradingError::Network(format!("HTTP client error: {}", e)))?;
 
+        let fee_model = fee_model_for(&config.fee_model);
+        let session = match &config.session {
+            Some(session) => SessionCalendar::from_config(session)?,
+            None => SessionCalendar::us_equities(),
+        };
+
         Ok(Self {
             config,
             retry_policy,
             rate_limiter,
             http_client,
+            submitted: Mutex::new(HashSet::new()),
+            paper_orders: Mutex::new(HashMap::new()),
+            paper_resting: Mutex::new(HashMap::new()),
+            fill_simulator: Mutex::new(None),
+            fee_model,
+            tracker: Arc::new(OrderTracker::new()),
+            session,
+            #[cfg(test)]
+            drop_responses: std::sync::atomic::AtomicU32::new(0),
         })
     }
 
     /// Route and execute order with retry logic
+    ///
+    /// The order's `client_order_id` is used as an idempotency key: if it has
+    /// already been sent (for example the exchange accepted it but the response
+    /// was lost and the retry policy kicked in), the exchange is asked for the
+    /// existing order and it is returned instead of submitting a duplicate.
     pub async fn route(&self, order: Order, current_market_price: Option<f64>) -> Result<AlpacaOrderResponse> {
+        self.route_with_limits(order, current_market_price, None).await
+    }
+
+    /// Route an order with an optional per-order slippage limit in basis points.
+    ///
+    /// The effective limit is the per-order override if given, otherwise the
+    /// symbol's entry in `symbol_max_slippage_bps`, otherwise `max_slippage_bps`.
+    pub async fn route_with_limits(
+        &self,
+        order: Order,
+        current_market_price: Option<f64>,
+        max_slippage_bps: Option<f64>,
+    ) -> Result<AlpacaOrderResponse> {
+        order.validate()?;
+        self.config.instruments.check_order(&order)?;
+
+        if let Some(expiry) = self.expires_at(&order) {
+            if expiry <= Utc::now() {
+                return Err(TradingError::OrderValidation(format!(
+                    "Order {} expired at {}",
+                    order.client_order_id, expiry
+                )));
+            }
+        }
+
+        if let Some(bps) = max_slippage_bps {
+            if bps <= 0.0 || !bps.is_finite() {
+                return Err(TradingError::OrderValidation(format!(
+                    "Invalid slippage limit override: {} bps (must be positive)",
+                    bps
+                )));
+            }
+        }
+
         // Check slippage for limit orders
         if let Some(limit_price) = order.price {
             if let Some(market_price) = current_market_price {
@@ -105,10 +224,11 @@ impl OrderRouter {
                     )));
                 }
 
-                if slippage_bps > self.config.max_slippage_bps {
+                let (max_bps, source) = self.slippage_limit(&order.symbol.0, max_slippage_bps);
+                if slippage_bps > max_bps {
                     return Err(TradingError::Risk(format!(
-                        "Slippage too high: {:.2} bps (limit={}, market={}, max={})",
-                        slippage_bps, limit_price.0, market_price, self.config.max_slippage_bps
+                        "Slippage too high: {:.2} bps exceeds {} bps limit from {} (limit={}, market={})",
+                        slippage_bps, max_bps, source, limit_price.0, market_price
                     )));
                 }
             }
@@ -120,18 +240,154 @@ impl OrderRouter {
         let http_client = self.http_client.clone();
         let config = self.config.clone();
 
-        retry_policy
-            .execute(|| async {
+        let result = retry_policy
+            .execute_with_condition(|| async {
+                // Skip re-submission if the exchange already has this order
+                if let Some(existing) = self.find_submitted(&order.client_order_id).await? {
+                    tracing::info!(
+                        "Order {} already accepted as {}, skipping re-submission",
+                        order.client_order_id,
+                        existing.id
+                    );
+                    return Ok(existing);
+                }
+
                 // Wait for rate limiter
-                rate_limiter.until_ready().await;
+                rate_limiter.acquire().await;
+
+                // Write ahead of the first send, so a crash before the
+                // response still leaves a record of the order. An order that
+                // can't be journaled is not sent.
+                if !order.client_order_id.is_empty()
+                    && self.submitted.lock().unwrap().insert(order.client_order_id.clone())
+                {
+                    if let Err(e) = self.tracker.record_submission(&order) {
+                        self.submitted.lock().unwrap().remove(&order.client_order_id);
+                        return Err(e.with_context("journaling order before submission"));
+                    }
+                }
 
                 // Build request
                 let alpaca_order = self.build_alpaca_request(&order)?;
 
                 // Send to exchange
-                self.send_to_exchange(&http_client, &config, alpaca_order).await
-            })
-            .await
+                self.send_to_exchange(&http_client, &config, &order, alpaca_order, current_market_price)
+                    .await
+            }, TradingError::is_retryable)
+            .await;
+
+        match &result {
+            Ok(response) => {
+                let mut tracked = order;
+                tracked.order_id = response.id.clone();
+                tracked.status = OrderStatus::Pending;
+                self.tracker.register(tracked);
+                self.sync_tracker(response);
+            }
+            Err(e) if !e.is_retryable() => {
+                // Rejected outright; track it under the client order id
+                let mut tracked = order;
+                if tracked.order_id.is_empty() {
+                    tracked.order_id = tracked.client_order_id.clone();
+                }
+                tracked.status = OrderStatus::Rejected;
+                self.tracker.register(tracked);
+            }
+            Err(_) => {}
+        }
+
+        result
+    }
+
+    /// Journal order state for crash recovery. Open orders already in the
+    /// journal are restored into the tracker and their client order ids
+    /// marked as submitted, so a restart never resends them. Orders sent
+    /// without a response before the crash are restored under their client
+    /// order id until routing them again finds the exchange's copy.
+    pub fn with_journal(mut self, journal: OrderJournal) -> Result<Self> {
+        let tracker = OrderTracker::with_journal(journal)?;
+        self.submitted
+            .get_mut()
+            .unwrap()
+            .extend(tracker.open_orders().into_iter().map(|o| o.client_order_id));
+        self.tracker = Arc::new(tracker);
+        Ok(self)
+    }
+
+    /// Order lifecycle tracker fed by this router
+    pub fn tracker(&self) -> Arc<OrderTracker> {
+        Arc::clone(&self.tracker)
+    }
+
+    /// When a resting order is cancelled: the session close for DAY orders,
+    /// the expiry for GTD orders, or `max_order_age_secs` after creation if
+    /// that comes first. `None` for orders that never expire, and for IOC
+    /// and FOK orders, which never rest.
+    pub fn expires_at(&self, order: &Order) -> Option<DateTime<Utc>> {
+        let time_in_force = match order.time_in_force {
+            TimeInForce::Day => self.session.next_close(order.created_at),
+            TimeInForce::Gtd(expiry) => Some(expiry),
+            TimeInForce::Gtc => None,
+            TimeInForce::Ioc | TimeInForce::Fok => return None,
+        };
+        let max_age = self
+            .config
+            .max_order_age_secs
+            .map(|secs| order.created_at + chrono::Duration::seconds(secs as i64));
+
+        match (time_in_force, max_age) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Cancel every tracked open order that has expired by `now`, returning
+    /// the cancelled orders. Call periodically; an order that fails to
+    /// cancel, for example because it filled meanwhile, is logged and
+    /// retried on the next call if it is still open.
+    pub async fn cancel_expired(&self, now: DateTime<Utc>) -> Vec<Order> {
+        let expired: Vec<_> = self
+            .tracker
+            .open_orders()
+            .into_iter()
+            .filter(|order| self.expires_at(order).is_some_and(|expiry| expiry <= now))
+            .collect();
+
+        let mut cancelled = Vec::with_capacity(expired.len());
+        for order in expired {
+            match self.cancel(&order.order_id).await {
+                Ok(()) => {
+                    tracing::info!("Cancelled expired {:?} order {}", order.time_in_force, order.order_id);
+                    if let Some(order) = self.tracker.get_order(&order.order_id) {
+                        cancelled.push(order);
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to cancel expired order {}: {}", order.order_id, e),
+            }
+        }
+        cancelled
+    }
+
+    /// Bring the tracked order in line with an exchange response
+    fn sync_tracker(&self, response: &AlpacaOrderResponse) {
+        let status = order_status_from_alpaca(&response.status);
+        let filled = Quantity(response.filled_qty.parse().unwrap_or(0.0));
+        let avg_price = response.avg_price().map(Price);
+
+        if let Err(e) = self.tracker.update_status(&response.id, status, filled, avg_price) {
+            tracing::debug!("Tracker not updated for {}: {}", response.id, e);
+        }
+    }
+
+    /// Effective slippage limit for a symbol and where it came from
+    fn slippage_limit(&self, symbol: &str, order_override: Option<f64>) -> (f64, &'static str) {
+        if let Some(bps) = order_override {
+            return (bps, "order override");
+        }
+        match self.config.symbol_max_slippage_bps.get(symbol) {
+            Some(bps) => (*bps, "symbol config"),
+            None => (self.config.max_slippage_bps, "default config"),
+        }
     }
 
     fn build_alpaca_request(&self, order: &Order) -> Result<AlpacaOrderRequest> {
@@ -147,12 +403,22 @@ impl OrderRouter {
             common::types::OrderType::StopLimit => "stop_limit",
         };
 
+        // Alpaca has no good-til-date; those rest as GTC until
+        // `cancel_expired` removes them
+        let time_in_force = match order.time_in_force {
+            TimeInForce::Day => "day",
+            TimeInForce::Gtc | TimeInForce::Gtd(_) => "gtc",
+            TimeInForce::Ioc => "ioc",
+            TimeInForce::Fok => "fok",
+        };
+
         Ok(AlpacaOrderRequest {
+            client_order_id: order.client_order_id.clone(),
             symbol: order.symbol.0.clone(),
             qty: order.quantity.0,
             side: side.to_string(),
             r#type: order_type.to_string(),
-            time_in_force: "gtc".to_string(),
+            time_in_force: time_in_force.to_string(),
             limit_price: order.price.map(|p| p.0),
             stop_price: order.stop_price.map(|p| p.0),
         })
@@ -162,18 +428,78 @@ impl OrderRouter {
         &self,
         client: &Client,
         config: &ExecutionConfig,
+        source: &Order,
         order: AlpacaOrderRequest,
+        expected_price: Option<f64>,
     ) -> Result<AlpacaOrderResponse> {
         if config.paper_trading {
             // Paper trading mode - simulate response
-            return Ok(AlpacaOrderResponse {
+            let simulated = {
+                let simulator = self.fill_simulator.lock().unwrap();
+                simulator
+                    .as_ref()
+                    .and_then(|sim| sim.simulate(&order).map(|fill| (sim.latency(), fill)))
+            };
+
+            // Slippage is measured against the caller's market price, else
+            // the touch the simulated fill started from
+            let (status, filled_qty, filled_avg_price, expected_price) = match simulated {
+                Some((latency, fill)) => {
+                    tokio::time::sleep(latency).await;
+                    let fill = fill?;
+                    let expected = expected_price.or(fill.expected_price);
+                    (fill.status.to_string(), fill.filled_qty, fill.avg_price, expected)
+                }
+                None => ("filled".to_string(), order.qty, order.limit_price, expected_price),
+            };
+            let slippage_bps = expected_price
+                .zip(filled_avg_price)
+                .map(|(expected, avg)| realized_slippage_bps(source.side, expected, avg));
+            if let Some(bps) = slippage_bps {
+                common::metrics::execution::record_slippage(&order.symbol, bps);
+            }
+            let commission = self
+                .fee_model
+                .commission(source, filled_avg_price.unwrap_or(0.0), filled_qty);
+
+            let response = AlpacaOrderResponse {
                 id: uuid::Uuid::new_v4().to_string(),
-                status: "filled".to_string(),
+                client_order_id: order.client_order_id.clone(),
+                status,
                 symbol: order.symbol.clone(),
                 qty: order.qty.to_string(),
-                filled_qty: order.qty.to_string(),
+                filled_qty: filled_qty.to_string(),
                 side: order.side.clone(),
-            });
+                filled_avg_price: filled_avg_price.map(|p| p.to_string()),
+                commission: Some(commission),
+                slippage_bps,
+            };
+            if !order.client_order_id.is_empty() {
+                self.paper_orders
+                    .lock()
+                    .unwrap()
+                    .insert(order.client_order_id.clone(), response.clone());
+                if order.r#type == "limit" && matches!(response.status.as_str(), "new" | "partially_filled") {
+                    self.paper_resting
+                        .lock()
+                        .unwrap()
+                        .insert(order.client_order_id.clone(), order);
+                }
+            }
+
+            #[cfg(test)]
+            {
+                use std::sync::atomic::Ordering;
+                let lost = self
+                    .drop_responses
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                    .is_ok();
+                if lost {
+                    return Err(TradingError::Network("simulated response loss".to_string()));
+                }
+            }
+
+            return Ok(response);
         }
 
         // Validate URL uses HTTPS before sending credentials
@@ -209,18 +535,152 @@ impl OrderRouter {
             let status = response.status();
             let text = response.text().await
                 .unwrap_or_else(|_| "<failed to read response body>".to_string());
+            let message = format!("Order rejected: {} - {}", status, text);
+
+            // Client errors (other than rate limiting) will fail the same way on retry
+            if status.is_client_error() && status != reqwest::StatusCode::TOO_MANY_REQUESTS {
+                return Err(TradingError::OrderValidation(message));
+            }
+            return Err(TradingError::Exchange(message));
+        }
+
+        response
+            .json::<AlpacaOrderResponse>()
+            .await
+            .map_err(|e| TradingError::Parse(format!("Response parse error: {}", e)))
+    }
+
+    /// Look up a previously submitted order on the exchange.
+    ///
+    /// Only consults the exchange when `client_order_id` is in the local
+    /// submitted set; ids never sent from this router short-circuit to `None`.
+    async fn find_submitted(&self, client_order_id: &str) -> Result<Option<AlpacaOrderResponse>> {
+        if !self.submitted.lock().unwrap().contains(client_order_id) {
+            return Ok(None);
+        }
+        self.get_order_by_client_id(client_order_id).await
+    }
+
+    /// Get an order by its client order id, or `None` if the exchange does not know it
+    pub async fn get_order_by_client_id(&self, client_order_id: &str) -> Result<Option<AlpacaOrderResponse>> {
+        if self.config.paper_trading {
+            return Ok(self.paper_orders.lock().unwrap().get(client_order_id).cloned());
+        }
+
+        self.rate_limiter.acquire().await;
+
+        // Validate HTTPS before sending credentials
+        if !self.config.exchange_api_url.starts_with("https://") {
+            return Err(TradingError::Configuration(
+                "Cannot send API credentials over non-HTTPS connection".to_string()
+            ));
+        }
+
+        let url = format!("{}/v2/orders:by_client_order_id", self.config.exchange_api_url);
+
+        // Get credentials with proper error handling
+        let api_key = self.config.api_key.as_ref()
+            .ok_or_else(|| TradingError::Configuration(
+                "API key not configured".to_string()
+            ))?;
+
+        let api_secret = self.config.api_secret.as_ref()
+            .ok_or_else(|| TradingError::Configuration(
+                "API secret not configured".to_string()
+            ))?;
+
+        let response = self
+            .http_client
+            .get(&url)
+            .query(&[("client_order_id", client_order_id)])
+            .header("APCA-API-KEY-ID", api_key)
+            .header("APCA-API-SECRET-KEY", api_secret)
+            .send()
+            .await
+            .map_err(|e| TradingError::Network(format!("Request failed: {}", e)))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            let status = response.status();
             return Err(TradingError::Exchange(format!(
-                "Order rejected: {} - {}",
-                status, text
+                "Order lookup failed: {}",
+                status
             )));
         }
 
         response
             .json::<AlpacaOrderResponse>()
             .await
+            .map(Some)
             .map_err(|e| TradingError::Parse(format!("Response parse error: {}", e)))
     }
 
+    /// Simulate paper-trading fills against order books instead of filling
+    /// every order in full. Has no effect in live trading.
+    pub fn set_fill_simulator(&self, config: FillSimConfig) -> Result<()> {
+        *self.fill_simulator.lock().unwrap() = Some(FillSimulator::new(config)?);
+        Ok(())
+    }
+
+    /// Update the book the fill simulator uses for this book's symbol, and
+    /// fill resting paper limit orders that the new book makes marketable
+    pub fn update_paper_book(&self, book: &FastOrderBook) {
+        let mut simulator = self.fill_simulator.lock().unwrap();
+        if let Some(simulator) = simulator.as_mut() {
+            simulator.update_book(book);
+            self.fill_resting_paper(simulator, &book.symbol().0);
+        }
+    }
+
+    /// Re-run the working paper limit orders for `symbol` against the
+    /// simulator's current book, accumulating fills and commission
+    fn fill_resting_paper(&self, simulator: &FillSimulator, symbol: &str) {
+        let mut filled = Vec::new();
+        {
+            let mut resting = self.paper_resting.lock().unwrap();
+            let mut paper_orders = self.paper_orders.lock().unwrap();
+            resting.retain(|client_order_id, request| {
+                if request.symbol != symbol {
+                    return true;
+                }
+                // Drop orders cancelled or replaced since they rested
+                let Some(response) = paper_orders.get_mut(client_order_id) else {
+                    return false;
+                };
+                if !matches!(response.status.as_str(), "new" | "partially_filled") {
+                    return false;
+                }
+
+                let prior_qty: f64 = response.filled_qty.parse().unwrap_or(0.0);
+                let Some(fill) = simulator.fill_resting(request, request.qty - prior_qty) else {
+                    return true;
+                };
+                let price = fill.avg_price.unwrap_or(0.0);
+                let total_qty = prior_qty + fill.filled_qty;
+                let prior_cost = prior_qty * response.avg_price().unwrap_or(0.0);
+                let commission = self
+                    .tracker
+                    .get_order(&response.id)
+                    .map(|source| self.fee_model.commission(&source, price, fill.filled_qty))
+                    .unwrap_or(0.0);
+
+                response.status = fill.status.to_string();
+                response.filled_qty = total_qty.to_string();
+                response.filled_avg_price = Some(((prior_cost + fill.filled_qty * price) / total_qty).to_string());
+                response.commission = Some(response.commission.unwrap_or(0.0) + commission);
+                filled.push(response.clone());
+                fill.status != "filled"
+            });
+        }
+
+        for response in &filled {
+            self.sync_tracker(response);
+        }
+    }
+
     /// Fragment large order into smaller pieces (TWAP-style)
     pub async fn execute_twap(
         &self,
@@ -233,7 +693,7 @@ impl OrderRouter {
 
         for i in 0..num_slices {
             let mut slice_order = order.clone();
-            slice_order.quantity = common::types::Quantity(slice_qty);
+            slice_order.quantity = Quantity(slice_qty);
             slice_order.client_order_id = format!("{}_slice_{}", order.client_order_id, i);
 
             let response = self.route(slice_order, None).await?;
@@ -247,9 +707,313 @@ impl OrderRouter {
         Ok(responses)
     }
 
+    /// Split a parent order into `slices` child orders spread evenly over `over`.
+    ///
+    /// Each child goes through [`route`](Self::route), so the rate limiter and
+    /// slippage check apply per slice. A failed slice is recorded with
+    /// `success: false` and the schedule carries on with the remaining slices,
+    /// so the returned responses always describe every slice in order.
+    pub async fn route_twap(
+        &self,
+        parent: Order,
+        slices: usize,
+        over: Duration,
+        current_price: Option<f64>,
+    ) -> Result<Vec<OrderResponse>> {
+        if slices == 0 {
+            return Err(TradingError::OrderValidation(
+                "TWAP requires at least one slice".to_string()
+            ));
+        }
+
+        let quantities = self.align_to_lots(&parent.symbol, vec![parent.quantity.0 / slices as f64; slices]);
+        self.route_schedule(parent, &quantities, over, current_price, "twap").await
+    }
+
+    /// Split a parent order according to an intraday volume profile.
+    ///
+    /// `profile` is a normalized volume curve: bucket `i` receives
+    /// `parent.quantity * profile[i]` and buckets are spaced evenly over `over`.
+    /// Empty buckets are skipped but still take up their slot in the schedule.
+    /// Failures are reported per slice exactly as in [`route_twap`](Self::route_twap).
+    pub async fn route_vwap(
+        &self,
+        parent: Order,
+        profile: &[f64],
+        over: Duration,
+    ) -> Result<Vec<OrderResponse>> {
+        validate_volume_profile(profile)?;
+
+        let quantities = self.align_to_lots(&parent.symbol, profile.iter().map(|w| parent.quantity.0 * w).collect());
+        self.route_schedule(parent, &quantities, over, None, "vwap").await
+    }
+
+    /// Work a large limit order by only showing `display` at a time.
+    ///
+    /// Each tranche is a `display`-sized limit order at `price` (the last one
+    /// may be smaller), and the next tranche is only submitted once the
+    /// previous one is completely filled. If a tranche is rejected, cancelled,
+    /// still working after `ICEBERG_MAX_POLLS` polls (it is then cancelled)
+    /// or its status cannot be read, it is reported with `success: false` and
+    /// no further tranches are sent. Returns one response per tranche sent.
+    pub async fn route_iceberg(
+        &self,
+        total: Quantity,
+        display: Quantity,
+        price: Price,
+        side: Side,
+        symbol: Symbol,
+    ) -> Result<Vec<OrderResponse>> {
+        if total.0 <= 0.0 || !total.0.is_finite() {
+            return Err(TradingError::OrderValidation(format!(
+                "Iceberg total quantity must be positive, got {}",
+                total.0
+            )));
+        }
+        if display.0 <= 0.0 || !display.0.is_finite() {
+            return Err(TradingError::OrderValidation(format!(
+                "Iceberg display quantity must be positive, got {}",
+                display.0
+            )));
+        }
+
+        let parent_id = uuid::Uuid::new_v4().to_string();
+        let mut remaining = total.0;
+        let mut responses = Vec::new();
+
+        while remaining > ICEBERG_QTY_EPSILON {
+            let qty = remaining.min(display.0);
+            let now = chrono::Utc::now();
+            let tranche = Order {
+                order_id: String::new(),
+                client_order_id: format!("iceberg_{}_{}", parent_id, responses.len()),
+                symbol: symbol.clone(),
+                side,
+                order_type: OrderType::Limit,
+                quantity: Quantity(qty),
+                price: Some(price),
+                stop_price: None,
+                time_in_force: TimeInForce::Gtc,
+                status: OrderStatus::Pending,
+                filled_quantity: Quantity(0.0),
+                average_price: None,
+                created_at: now,
+                updated_at: now,
+            };
+            let client_order_id = tranche.client_order_id.clone();
+
+            let response = match self.route(tranche, None).await {
+                Ok(response) => self.await_terminal(response).await,
+                Err(e) => Err(e),
+            };
+
+            match response {
+                Ok(response) if response.status == "filled" => {
+                    remaining -= qty;
+                    let filled_avg_price = response.avg_price();
+                    responses.push(OrderResponse {
+                        order_id: response.id,
+                        client_order_id,
+                        success: true,
+                        error: None,
+                        filled_quantity: response.filled_qty.parse().unwrap_or(qty),
+                        filled_avg_price,
+                        commission: response.commission.unwrap_or(0.0),
+                        slippage_bps: response.slippage_bps,
+                    });
+                }
+                Ok(response) => {
+                    let filled_avg_price = response.avg_price();
+                    tracing::warn!(
+                        "Iceberg tranche {} ended as {}, aborting with {} of {} remaining",
+                        client_order_id, response.status, remaining, total.0
+                    );
+                    responses.push(OrderResponse {
+                        order_id: response.id,
+                        client_order_id,
+                        success: false,
+                        error: Some(format!("tranche ended with status {}", response.status)),
+                        filled_quantity: response.filled_qty.parse().unwrap_or(0.0),
+                        filled_avg_price,
+                        commission: response.commission.unwrap_or(0.0),
+                        slippage_bps: response.slippage_bps,
+                    });
+                    break;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Iceberg tranche {} failed, aborting with {} of {} remaining: {}",
+                        client_order_id, remaining, total.0, e
+                    );
+                    responses.push(OrderResponse {
+                        order_id: String::new(),
+                        client_order_id,
+                        success: false,
+                        error: Some(e.to_string()),
+                        filled_quantity: 0.0,
+                        filled_avg_price: None,
+                        commission: 0.0,
+                        slippage_bps: None,
+                    });
+                    break;
+                }
+            }
+        }
+
+        Ok(responses)
+    }
+
+    /// Poll an order until it reaches a terminal status.
+    ///
+    /// Gives up after `ICEBERG_MAX_POLLS` polls and cancels the order, so a
+    /// tranche that never fills neither stalls the iceberg nor keeps resting
+    /// once abandoned. The status is read again after the cancel, since the
+    /// order may have filled meanwhile.
+    async fn await_terminal(&self, mut response: AlpacaOrderResponse) -> Result<AlpacaOrderResponse> {
+        let is_terminal = |status: &str| {
+            matches!(status, "filled" | "canceled" | "expired" | "rejected" | "done_for_day")
+        };
+
+        for _ in 0..ICEBERG_MAX_POLLS {
+            if is_terminal(&response.status) {
+                return Ok(response);
+            }
+            tokio::time::sleep(Duration::from_millis(ICEBERG_POLL_INTERVAL_MS)).await;
+            response = self.get_order_status(&response.id).await?;
+        }
+        if is_terminal(&response.status) {
+            return Ok(response);
+        }
+
+        tracing::warn!(
+            "Order {} still {} after {} polls, cancelling",
+            response.id, response.status, ICEBERG_MAX_POLLS
+        );
+        if let Err(e) = self.cancel(&response.id).await {
+            tracing::warn!("Failed to cancel stalled order {}: {}", response.id, e);
+        }
+        self.get_order_status(&response.id).await
+    }
+
+    /// Round schedule slices to whole lots of the symbol's `InstrumentSpec`.
+    ///
+    /// Each slice sends whatever brings the cumulative total up to the
+    /// rounded-down cumulative target, so rounding remainders roll into later
+    /// slices instead of being lost. Unchanged for symbols without a spec.
+    fn align_to_lots(&self, symbol: &Symbol, quantities: Vec<f64>) -> Vec<f64> {
+        if self.config.instruments.get(symbol).is_none() {
+            return quantities;
+        }
+
+        let mut target = 0.0;
+        let mut sent = 0.0;
+        quantities
+            .into_iter()
+            .map(|qty| {
+                target += qty;
+                let through = self.config.instruments.round_qty(symbol, Quantity(target)).0;
+                let child = through - sent;
+                sent = through;
+                child
+            })
+            .collect()
+    }
+
+    /// Submit one child per entry in `quantities`, evenly spaced over `over`.
+    async fn route_schedule(
+        &self,
+        parent: Order,
+        quantities: &[f64],
+        over: Duration,
+        current_price: Option<f64>,
+        algo: &str,
+    ) -> Result<Vec<OrderResponse>> {
+        let slices = quantities.len();
+        let interval = over / slices as u32;
+        let mut responses = Vec::with_capacity(slices);
+        let mut filled_qty = 0.0;
+
+        for (i, &qty) in quantities.iter().enumerate() {
+            if qty > 0.0 {
+                let mut child = parent.clone();
+                child.quantity = Quantity(qty);
+                child.client_order_id = format!("{}_{}_{}", parent.client_order_id, algo, i);
+                let client_order_id = child.client_order_id.clone();
+
+                match self.route(child, current_price).await {
+                    Ok(response) => {
+                        let filled = response.filled_qty.parse::<f64>().unwrap_or(0.0);
+                        filled_qty += filled;
+                        let filled_avg_price = response.avg_price();
+                        responses.push(OrderResponse {
+                            order_id: response.id,
+                            client_order_id,
+                            success: true,
+                            error: None,
+                            filled_quantity: filled,
+                            filled_avg_price,
+                            commission: response.commission.unwrap_or(0.0),
+                            slippage_bps: response.slippage_bps,
+                        });
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            "{} slice {}/{} for {} failed: {}",
+                            algo.to_uppercase(),
+                            i + 1,
+                            slices,
+                            parent.client_order_id,
+                            e
+                        );
+                        metrics::counter!("execution_schedule_slice_failures_total").increment(1);
+                        responses.push(OrderResponse {
+                            order_id: String::new(),
+                            client_order_id,
+                            success: false,
+                            error: Some(e.to_string()),
+                            filled_quantity: 0.0,
+                            filled_avg_price: None,
+                            commission: 0.0,
+                            slippage_bps: None,
+                        });
+                    }
+                }
+            }
+
+            if i < slices - 1 {
+                tokio::time::sleep(interval).await;
+            }
+        }
+
+        let succeeded = responses.iter().filter(|r| r.success).count();
+        tracing::info!(
+            "{} {} complete: {}/{} slices succeeded, filled {} of {}",
+            algo.to_uppercase(),
+            parent.client_order_id,
+            succeeded,
+            responses.len(),
+            filled_qty,
+            parent.quantity.0
+        );
+
+        Ok(responses)
+    }
+
     /// Get order status
     pub async fn get_order_status(&self, order_id: &str) -> Result<AlpacaOrderResponse> {
-        self.rate_limiter.until_ready().await;
+        if self.config.paper_trading {
+            let response = self.paper_orders
+                .lock()
+                .unwrap()
+                .values()
+                .find(|o| o.id == order_id)
+                .cloned()
+                .ok_or_else(|| TradingError::Exchange(format!("Order not found: {}", order_id)))?;
+            self.sync_tracker(&response);
+            return Ok(response);
+        }
+
+        self.rate_limiter.acquire().await;
 
         // Validate HTTPS before sending credentials
         if !self.config.paper_trading && !self.config.exchange_api_url.starts_with("https://") {
@@ -280,44 +1044,940 @@ impl OrderRouter {
             .await
             .map_err(|e| TradingError::Network(format!("Request failed: {}", e)))?;
 
-        response
+        let response = response
             .json::<AlpacaOrderResponse>()
             .await
-            .map_err(|e| TradingError::Parse(format!("Response parse error: {}", e)))
+            .map_err(|e| TradingError::Parse(format!("Response parse error: {}", e)))?;
+        self.sync_tracker(&response);
+
+        Ok(response)
     }
 
     /// Cancel order
     pub async fn cancel_order(&self, order_id: &str) -> Result<()> {
-        self.rate_limiter.until_ready().await;
+        self.cancel(order_id).await
+    }
 
-        // Validate HTTPS before sending credentials
-        if !self.config.paper_trading && !self.config.exchange_api_url.starts_with("https://") {
-            return Err(TradingError::Configuration(
-                "Cannot send API credentials over non-HTTPS connection".to_string()
+    /// Cancel a resting order.
+    ///
+    /// Goes through the rate limiter and retry policy, retrying only
+    /// transport failures. Cancelling an order that is already filled,
+    /// cancelled or rejected fails with `TradingError::Exchange`.
+    pub async fn cancel(&self, order_id: &str) -> Result<()> {
+        if let Some(order) = self.tracker.get_order(order_id) {
+            ensure_open(order_id, order.status)?;
+        }
+
+        if self.config.paper_trading {
+            self.rate_limiter.acquire().await;
+            self.cancel_paper(order_id)?;
+        } else {
+            self.retry_policy
+                .execute_with_condition(|| async {
+                    self.rate_limiter.acquire().await;
+                    self.send_cancel(order_id).await
+                }, is_transport_error)
+                .await?;
+        }
+
+        self.mark_cancelled(order_id);
+        Ok(())
+    }
+
+    /// Amend the quantity and/or limit price of a resting order.
+    ///
+    /// The exchange replaces the order with a new one, so the returned
+    /// response carries the new order id; the original is tracked as
+    /// cancelled. Fails with `TradingError::Exchange` if the order is no
+    /// longer open.
+    pub async fn replace(
+        &self,
+        order_id: &str,
+        new_qty: Option<Quantity>,
+        new_price: Option<Price>,
+    ) -> Result<OrderResponse> {
+        if new_qty.is_none() && new_price.is_none() {
+            return Err(TradingError::OrderValidation(
+                "Replace requires a new quantity or price".to_string()
             ));
         }
+        if let Some(qty) = new_qty {
+            if qty.0 <= 0.0 || !qty.0.is_finite() {
+                return Err(TradingError::OrderValidation(format!(
+                    "Invalid replacement quantity: {} (must be positive)",
+                    qty.0
+                )));
+            }
+        }
+        if let Some(price) = new_price {
+            if price.0 <= 0.0 || !price.0.is_finite() {
+                return Err(TradingError::OrderValidation(format!(
+                    "Invalid replacement price: {} (must be positive)",
+                    price.0
+                )));
+            }
+        }
+
+        let original = self.tracker.get_order(order_id);
+        if let Some(order) = &original {
+            ensure_open(order_id, order.status)?;
+        }
+
+        if self.config.paper_trading {
+            let original = original.ok_or_else(|| {
+                TradingError::Exchange(format!("Order not found: {}", order_id))
+            })?;
 
+            // Re-submit so the fill simulator sees the amended order
+            let now = chrono::Utc::now();
+            let mut amended = original;
+            amended.order_id = String::new();
+            amended.client_order_id = uuid::Uuid::new_v4().to_string();
+            amended.quantity = new_qty.unwrap_or(amended.quantity);
+            amended.price = new_price.or(amended.price);
+            amended.status = OrderStatus::Pending;
+            amended.filled_quantity = Quantity(0.0);
+            amended.average_price = None;
+            amended.created_at = now;
+            amended.updated_at = now;
+
+            // Reject an inconsistent amendment before the original is cancelled
+            amended.validate()?;
+            self.cancel_paper(order_id)?;
+            self.mark_cancelled(order_id);
+
+            let response = self.route(amended, None).await?;
+            return Ok(order_response(response));
+        }
+
+        let request = AlpacaReplaceRequest {
+            qty: new_qty.map(|q| q.0),
+            limit_price: new_price.map(|p| p.0),
+        };
+        let response = self.retry_policy
+            .execute_with_condition(|| async {
+                self.rate_limiter.acquire().await;
+                self.send_replace(order_id, &request).await
+            }, is_transport_error)
+            .await?;
+
+        self.mark_cancelled(order_id);
+        if let Some(mut amended) = original {
+            amended.order_id = response.id.clone();
+            amended.client_order_id = response.client_order_id.clone();
+            amended.quantity = new_qty.unwrap_or(amended.quantity);
+            amended.price = new_price.or(amended.price);
+            amended.status = OrderStatus::Pending;
+            amended.filled_quantity = Quantity(0.0);
+            amended.average_price = None;
+            self.tracker.register(amended);
+        }
+        self.sync_tracker(&response);
+
+        Ok(order_response(response))
+    }
+
+    /// Cancel an order on the simulated exchange
+    fn cancel_paper(&self, order_id: &str) -> Result<()> {
+        let mut paper_orders = self.paper_orders.lock().unwrap();
+        let order = paper_orders
+            .values_mut()
+            .find(|o| o.id == order_id)
+            .ok_or_else(|| TradingError::Exchange(format!("Order not found: {}", order_id)))?;
+
+        ensure_open(order_id, order_status_from_alpaca(&order.status))?;
+        order.status = "canceled".to_string();
+        Ok(())
+    }
+
+    fn mark_cancelled(&self, order_id: &str) {
+        if let Some(order) = self.tracker.get_order(order_id) {
+            if let Err(e) = self.tracker.update_status(
+                order_id,
+                OrderStatus::Cancelled,
+                order.filled_quantity,
+                order.average_price,
+            ) {
+                tracing::debug!("Tracker not updated for cancelled {}: {}", order_id, e);
+            }
+        }
+    }
+
+    async fn send_cancel(&self, order_id: &str) -> Result<()> {
+        let (api_key, api_secret) = self.live_credentials()?;
         let url = format!("{}/v2/orders/{}", self.config.exchange_api_url, order_id);
 
-        // Get credentials with proper error handling
-        let api_key = self.config.api_key.as_ref()
+        let response = self.http_client
+            .delete(&url)
+            .header("APCA-API-KEY-ID", api_key)
+            .header("APCA-API-SECRET-KEY", api_secret)
+            .send()
+            .await
+            .map_err(|e| TradingError::Network(format!("Cancel failed: {}", e)))?;
+
+        match response.status() {
+            status if status.is_success() => Ok(()),
+            reqwest::StatusCode::NOT_FOUND => {
+                Err(TradingError::Exchange(format!("Order not found: {}", order_id)))
+            }
+            reqwest::StatusCode::UNPROCESSABLE_ENTITY => Err(TradingError::Exchange(format!(
+                "Order {} is no longer cancelable (already filled or closed)",
+                order_id
+            ))),
+            status => Err(TradingError::Exchange(format!(
+                "Cancel of {} failed: {}",
+                order_id, status
+            ))),
+        }
+    }
+
+    async fn send_replace(&self, order_id: &str, request: &AlpacaReplaceRequest) -> Result<AlpacaOrderResponse> {
+        let (api_key, api_secret) = self.live_credentials()?;
+        let url = format!("{}/v2/orders/{}", self.config.exchange_api_url, order_id);
+
+        let response = self.http_client
+            .patch(&url)
+            .header("APCA-API-KEY-ID", api_key)
+            .header("APCA-API-SECRET-KEY", api_secret)
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| TradingError::Network(format!("Replace failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await
+                .unwrap_or_else(|_| "<failed to read response body>".to_string());
+            return Err(TradingError::Exchange(format!(
+                "Replace of {} rejected: {} - {}",
+                order_id, status, text
+            )));
+        }
+
+        response
+            .json::<AlpacaOrderResponse>()
+            .await
+            .map_err(|e| TradingError::Parse(format!("Response parse error: {}", e)))
+    }
+
+    /// API credentials for live requests, refusing to send them over plain HTTP
+    fn live_credentials(&self) -> Result<(&str, &str)> {
+        if !self.config.exchange_api_url.starts_with("https://") {
+            return Err(TradingError::Configuration(
+                "Cannot send API credentials over non-HTTPS connection".to_string()
+            ));
+        }
+
+        let api_key = self.config.api_key.as_deref()
             .ok_or_else(|| TradingError::Configuration(
                 "API key not configured".to_string()
             ))?;
 
-        let api_secret = self.config.api_secret.as_ref()
+        let api_secret = self.config.api_secret.as_deref()
             .ok_or_else(|| TradingError::Configuration(
                 "API secret not configured".to_string()
             ))?;
 
-        self.http_client
-            .delete(&url)
-            .header("APCA-API-KEY-ID", api_key)
-            .header("APCA-API-SECRET-KEY", api_secret)
-            .send()
+        Ok((api_key, api_secret))
+    }
+}
+
+/// Fail with an exchange error unless the order can still be amended or cancelled
+fn ensure_open(order_id: &str, status: OrderStatus) -> Result<()> {
+    match status {
+        OrderStatus::Pending | OrderStatus::PartiallyFilled => Ok(()),
+        OrderStatus::Filled => Err(TradingError::Exchange(format!(
+            "Order {} is already filled",
+            order_id
+        ))),
+        OrderStatus::Cancelled => Err(TradingError::Exchange(format!(
+            "Order {} is already cancelled",
+            order_id
+        ))),
+        OrderStatus::Rejected => Err(TradingError::Exchange(format!(
+            "Order {} was rejected",
+            order_id
+        ))),
+    }
+}
+
+/// Cancel and replace only retry failures that never reached the exchange
+fn is_transport_error(error: &TradingError) -> bool {
+    matches!(error, TradingError::Network(_))
+}
+
+fn order_response(response: AlpacaOrderResponse) -> OrderResponse {
+    let filled_quantity = response.filled_qty.parse().unwrap_or(0.0);
+    let filled_avg_price = response.avg_price();
+    OrderResponse {
+        order_id: response.id,
+        client_order_id: response.client_order_id,
+        success: true,
+        error: None,
+        filled_quantity,
+        filled_avg_price,
+        commission: response.commission.unwrap_or(0.0),
+        slippage_bps: response.slippage_bps,
+    }
+}
+
+/// Map an Alpaca order status string onto the order lifecycle
+fn order_status_from_alpaca(status: &str) -> OrderStatus {
+    match status {
+        "filled" => OrderStatus::Filled,
+        "partially_filled" => OrderStatus::PartiallyFilled,
+        "canceled" | "expired" | "done_for_day" => OrderStatus::Cancelled,
+        "rejected" => OrderStatus::Rejected,
+        _ => OrderStatus::Pending,
+    }
+}
+
+/// Tolerance when checking that a volume profile sums to 1.0
+const PROFILE_SUM_TOLERANCE: f64 = 1e-6;
+
+/// Check that a volume profile is non-empty, non-negative and sums to ~1.0
+fn validate_volume_profile(profile: &[f64]) -> Result<()> {
+    if profile.is_empty() {
+        return Err(TradingError::OrderValidation(
+            "VWAP profile must have at least one bucket".to_string()
+        ));
+    }
+
+    if let Some(bad) = profile.iter().find(|w| **w < 0.0 || !w.is_finite()) {
+        return Err(TradingError::OrderValidation(format!(
+            "VWAP profile buckets must be finite and non-negative, got {}",
+            bad
+        )));
+    }
+
+    let sum: f64 = profile.iter().sum();
+    if (sum - 1.0).abs() > PROFILE_SUM_TOLERANCE {
+        return Err(TradingError::OrderValidation(format!(
+            "VWAP profile must sum to 1.0, got {}",
+            sum
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::types::{InstrumentSpec, InstrumentSpecs};
+    use common::OrderBuilder;
+
+    fn paper_config() -> ExecutionConfig {
+        ExecutionConfig {
+            exchange_api_url: "https://paper-api.alpaca.markets".to_string(),
+            api_key: None,
+            api_secret: None,
+            rate_limit_per_second: 100,
+            retry_attempts: 1,
+            retry_delay_ms: 100,
+            paper_trading: true,
+            max_slippage_bps: 50.0,
+            symbol_max_slippage_bps: HashMap::new(),
+            fee_model: Default::default(),
+            instruments: Default::default(),
+            session: None,
+            max_order_age_secs: None,
+        }
+    }
+
+    fn create_test_order(qty: f64, price: Option<f64>) -> Order {
+        let builder = OrderBuilder::new().order_id("test").client_order_id("parent").quantity(qty);
+        match price {
+            Some(price) => builder.limit(price).build(),
+            None => builder.build(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_route_rejects_inconsistent_order() {
+        let router = OrderRouter::new(paper_config()).unwrap();
+
+        let mut stop_limit = create_test_order(10.0, Some(100.0));
+        stop_limit.order_type = OrderType::StopLimit;
+        let err = router.route(stop_limit, None).await.unwrap_err();
+        assert!(matches!(err, TradingError::Validation(_)));
+
+        // Rejected before submission, so nothing is tracked
+        assert!(router.tracker().open_orders().is_empty());
+        assert!(router.find_submitted("parent").await.unwrap().is_none());
+    }
+
+    fn lot_config() -> ExecutionConfig {
+        ExecutionConfig {
+            instruments: InstrumentSpecs::new()
+                .with_spec("AAPL", InstrumentSpec::new(0.01, 1.0).with_min_qty(5.0)),
+            ..paper_config()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_route_enforces_instrument_spec() {
+        let router = OrderRouter::new(lot_config()).unwrap();
+
+        for order in [
+            create_test_order(10.0, Some(150.005)),
+            create_test_order(10.5, Some(150.0)),
+            create_test_order(3.0, Some(150.0)),
+        ] {
+            let err = router.route(order, None).await.unwrap_err();
+            assert!(matches!(err, TradingError::Validation(_)), "{:?}", err);
+        }
+        assert!(router.tracker().open_orders().is_empty());
+
+        // Rounded with the spec, the same order is accepted
+        let specs = &router.config.instruments;
+        let symbol = Symbol("AAPL".to_string());
+        let mut order = create_test_order(10.5, Some(150.005));
+        order.quantity = specs.round_qty(&symbol, order.quantity);
+        order.price = order.price.map(|p| specs.round_price(&symbol, order.side, p));
+        assert_eq!(order.price, Some(Price(150.0)));
+        router.route(order, None).await.unwrap();
+
+        // Symbols without a spec are not checked
+        let mut other = create_test_order(0.5, Some(99.999));
+        other.symbol = Symbol("MSFT".to_string());
+        router.route(other, None).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_route_twap_slices_whole_lots() {
+        let router = OrderRouter::new(lot_config()).unwrap();
+        let parent = create_test_order(20.0, None);
+
+        let responses = router.route_twap(parent, 3, Duration::from_millis(0), None).await.unwrap();
+        let quantities: Vec<f64> = responses.iter().map(|r| r.filled_quantity).collect();
+        assert!(responses.iter().all(|r| r.success));
+        assert_eq!(quantities, vec![6.0, 7.0, 7.0]);
+    }
+
+    #[tokio::test]
+    async fn test_route_twap_slices_parent() {
+        let router = OrderRouter::new(paper_config()).unwrap();
+        let responses = router
+            .route_twap(create_test_order(100.0, None), 4, Duration::from_millis(40), None)
             .await
-            .map_err(|e| TradingError::Network(format!("Cancel failed: {}", e)))?;
+            .unwrap();
 
-        Ok(())
+        assert_eq!(responses.len(), 4);
+        assert!(responses.iter().all(|r| r.success));
+        assert_eq!(responses[0].client_order_id, "parent_twap_0");
+        assert_eq!(responses[3].client_order_id, "parent_twap_3");
+    }
+
+    #[tokio::test]
+    async fn test_route_twap_reports_failed_slices() {
+        let router = OrderRouter::new(paper_config()).unwrap();
+        // Limit 110 against market 100 is 1000 bps, well past the 50 bps cap
+        let responses = router
+            .route_twap(create_test_order(10.0, Some(110.0)), 3, Duration::ZERO, Some(100.0))
+            .await
+            .unwrap();
+
+        assert_eq!(responses.len(), 3);
+        assert!(responses.iter().all(|r| !r.success && r.error.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_route_vwap_weights_slices() {
+        let router = OrderRouter::new(paper_config()).unwrap();
+        let responses = router
+            .route_vwap(create_test_order(100.0, None), &[0.5, 0.0, 0.3, 0.2], Duration::ZERO)
+            .await
+            .unwrap();
+
+        // The empty bucket is skipped
+        assert_eq!(responses.len(), 3);
+        assert!(responses.iter().all(|r| r.success));
+        assert_eq!(responses[1].client_order_id, "parent_vwap_2");
+    }
+
+    #[tokio::test]
+    async fn test_route_iceberg_tranches() {
+        let router = OrderRouter::new(paper_config()).unwrap();
+        let responses = router
+            .route_iceberg(
+                Quantity(250.0),
+                Quantity(100.0),
+                Price(150.0),
+                Side::Bid,
+                Symbol("AAPL".to_string()),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(responses.len(), 3);
+        assert!(responses.iter().all(|r| r.success));
+        assert_eq!(responses[0].filled_quantity, 100.0);
+        assert_eq!(responses[2].filled_quantity, 50.0);
+    }
+
+    #[tokio::test]
+    async fn test_route_iceberg_rejects_zero_display() {
+        let router = OrderRouter::new(paper_config()).unwrap();
+        let result = router
+            .route_iceberg(
+                Quantity(100.0),
+                Quantity(0.0),
+                Price(150.0),
+                Side::Bid,
+                Symbol("AAPL".to_string()),
+            )
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_route_iceberg_fills_when_book_moves() {
+        let router = resting_router(paper_config());
+        let mut crossing = FastOrderBook::new(Symbol("AAPL".to_string()));
+        crossing.update_ask(Price(100.0), Quantity(50.0));
+
+        // The first tranche rests at 100.0 until the offer comes down to it
+        let (responses, ()) = tokio::join!(
+            router.route_iceberg(Quantity(25.0), Quantity(10.0), Price(100.0), Side::Bid, Symbol("AAPL".to_string())),
+            async {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                router.update_paper_book(&crossing);
+            }
+        );
+        let responses = responses.unwrap();
+
+        assert_eq!(responses.len(), 3);
+        assert!(responses.iter().all(|r| r.success));
+        assert_eq!(responses[0].filled_avg_price, Some(100.0));
+        assert_eq!(responses[2].filled_quantity, 5.0);
+        let first = router.tracker().get_order(&responses[0].order_id).unwrap();
+        assert_eq!(first.status, OrderStatus::Filled);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_route_iceberg_cancels_stalled_tranche() {
+        let router = resting_router(paper_config());
+        let responses = router
+            .route_iceberg(Quantity(25.0), Quantity(10.0), Price(100.0), Side::Bid, Symbol("AAPL".to_string()))
+            .await
+            .unwrap();
+
+        // Never marketable: the first tranche is cancelled and the rest not sent
+        assert_eq!(responses.len(), 1);
+        assert!(!responses[0].success);
+        assert_eq!(responses[0].filled_quantity, 0.0);
+        assert!(responses[0].error.as_deref().unwrap().contains("canceled"));
+        assert!(router.tracker().open_orders().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_no_duplicate_after_lost_response() {
+        let mut config = paper_config();
+        config.retry_attempts = 3;
+        let router = OrderRouter::new(config).unwrap();
+
+        // The exchange accepts the order but the response never arrives
+        router.drop_responses.store(1, std::sync::atomic::Ordering::SeqCst);
+
+        let response = router.route(create_test_order(10.0, None), None).await.unwrap();
+        assert_eq!(response.client_order_id, "parent");
+        assert_eq!(router.paper_orders.lock().unwrap().len(), 1);
+
+        // Routing the same order again returns the accepted one
+        let again = router.route(create_test_order(10.0, None), None).await.unwrap();
+        assert_eq!(again.id, response.id);
+        assert_eq!(router.paper_orders.lock().unwrap().len(), 1);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_unjournaled_order_not_sent() {
+        let router = OrderRouter::new(paper_config())
+            .unwrap()
+            .with_journal(OrderJournal::failing())
+            .unwrap();
+
+        assert!(router.route(create_test_order(10.0, None), None).await.is_err());
+        assert!(router.paper_orders.lock().unwrap().is_empty());
+        assert!(!router.submitted.lock().unwrap().contains("parent"));
+        assert!(router.tracker().open_orders().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_journal_restores_order_sent_before_crash() {
+        let path = std::env::temp_dir().join(format!("router-journal-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        {
+            let router = OrderRouter::new(paper_config())
+                .unwrap()
+                .with_journal(OrderJournal::open(&path).unwrap())
+                .unwrap();
+
+            // The process dies between sending the order and reading the response
+            router.drop_responses.store(1, std::sync::atomic::Ordering::SeqCst);
+            assert!(router.route(create_test_order(10.0, Some(100.0)), None).await.is_err());
+        }
+
+        let router = OrderRouter::new(paper_config())
+            .unwrap()
+            .with_journal(OrderJournal::open(&path).unwrap())
+            .unwrap();
+        let open = router.tracker().open_orders();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].order_id, "parent");
+        assert_eq!(open[0].status, OrderStatus::Pending);
+        assert!(router.submitted.lock().unwrap().contains("parent"));
+
+        // Routing it again records the exchange id in place of the client id
+        let response = router.route(create_test_order(10.0, Some(100.0)), None).await.unwrap();
+        assert!(router.tracker().get_order("parent").is_none());
+        assert_eq!(router.tracker().get_order(&response.id).unwrap().client_order_id, "parent");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_fill_simulator_prices_from_book() {
+        let router = OrderRouter::new(paper_config()).unwrap();
+        router
+            .set_fill_simulator(FillSimConfig { latency_ms: 5, ..FillSimConfig::default() })
+            .unwrap();
+
+        let mut book = FastOrderBook::new(Symbol("AAPL".to_string()));
+        book.update_ask(Price(100.1), Quantity(50.0));
+        book.update_ask(Price(100.3), Quantity(100.0));
+        router.update_paper_book(&book);
+
+        let response = router.route(create_test_order(100.0, None), None).await.unwrap();
+        assert_eq!(response.status, "filled");
+        assert!((response.avg_price().unwrap() - 100.2).abs() < 1e-6);
+
+        let status = router.get_order_status(&response.id).await.unwrap();
+        assert_eq!(status.client_order_id, "parent");
+    }
+
+    #[tokio::test]
+    async fn test_thin_book_market_order_not_left_open() {
+        let router = OrderRouter::new(paper_config()).unwrap();
+        router.set_fill_simulator(FillSimConfig::default()).unwrap();
+
+        let mut book = FastOrderBook::new(Symbol("AAPL".to_string()));
+        book.update_ask(Price(100.1), Quantity(30.0));
+        router.update_paper_book(&book);
+
+        let response = router.route(create_test_order(100.0, None), None).await.unwrap();
+        assert_eq!(response.status, "canceled");
+
+        // The filled part is kept and nothing is left working
+        let tracked = router.tracker().get_order(&response.id).unwrap();
+        assert_eq!(tracked.status, OrderStatus::Cancelled);
+        assert_eq!(tracked.filled_quantity, Quantity(30.0));
+        assert!(router.tracker().open_orders().is_empty());
+        assert!(router.paper_resting.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_paper_fill_reports_realized_slippage() {
+        let router = OrderRouter::new(paper_config()).unwrap();
+        router.set_fill_simulator(FillSimConfig::default()).unwrap();
+
+        let mut book = FastOrderBook::new(Symbol("AAPL".to_string()));
+        book.update_bid(Price(99.9), Quantity(50.0));
+        book.update_bid(Price(99.7), Quantity(100.0));
+        book.update_ask(Price(100.1), Quantity(50.0));
+        book.update_ask(Price(100.3), Quantity(100.0));
+        router.update_paper_book(&book);
+
+        // Walking two ask levels fills at 100.2 against a 100.1 touch
+        let buy = router.route(create_test_order(100.0, None), None).await.unwrap();
+        let expected = (100.2 - 100.1) / 100.1 * 10000.0;
+        assert!((buy.slippage_bps.unwrap() - expected).abs() < 1e-6);
+        let status = router.get_order_status(&buy.id).await.unwrap();
+        assert!((order_response(status).slippage_bps.unwrap() - expected).abs() < 1e-6);
+
+        // The caller's market price takes precedence over the touch
+        let mut order = create_test_order(100.0, None);
+        order.client_order_id = "priced".to_string();
+        let priced = router.route(order, Some(100.0)).await.unwrap();
+        assert!((priced.slippage_bps.unwrap() - 20.0).abs() < 1e-6);
+
+        // A sell walking down the bids slips below the touch
+        let mut order = create_test_order(100.0, None);
+        order.client_order_id = "sell".to_string();
+        order.side = Side::Ask;
+        let sell = router.route(order, None).await.unwrap();
+        assert!((sell.avg_price().unwrap() - 99.8).abs() < 1e-6);
+        let expected = (99.9 - 99.8) / 99.9 * 10000.0;
+        assert!((sell.slippage_bps.unwrap() - expected).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_paper_fill_charges_commission() {
+        let mut config = paper_config();
+        config.fee_model = common::config::FeeSchedule::BasisPoints { bps: 10.0, minimum: 0.0 };
+        let router = OrderRouter::new(config).unwrap();
+
+        // Canned fill at the limit: 100 * 150 = 15,000 notional, 10 bps = 15
+        let response = router.route(create_test_order(100.0, Some(150.0)), None).await.unwrap();
+        assert!((response.commission.unwrap() - 15.0).abs() < 1e-9);
+
+        let status = router.get_order_status(&response.id).await.unwrap();
+        assert!((order_response(status).commission - 15.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_is_shared_across_concurrent_routes() {
+        let mut config = paper_config();
+        config.rate_limit_per_second = 20;
+        let router = Arc::new(OrderRouter::new(config).unwrap());
+        let start = std::time::Instant::now();
+
+        // 20 go through on the initial burst, the other 20 at 20 per second
+        let handles: Vec<_> = (0..40)
+            .map(|i| {
+                let router = router.clone();
+                let mut order = create_test_order(1.0, None);
+                order.client_order_id = format!("concurrent-{}", i);
+                tokio::spawn(async move { router.route(order, None).await })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+
+        let elapsed = start.elapsed().as_secs_f64();
+        assert!((0.9..1.5).contains(&elapsed), "40 orders at 20/s took {:.3}s", elapsed);
+    }
+
+    #[tokio::test]
+    async fn test_routed_orders_are_tracked() {
+        let router = OrderRouter::new(paper_config()).unwrap();
+        let mut events = router.tracker().subscribe();
+
+        let response = router.route(create_test_order(10.0, None), None).await.unwrap();
+        let tracked = router.tracker().get_order(&response.id).unwrap();
+        assert_eq!(tracked.status, OrderStatus::Filled);
+        assert_eq!(tracked.filled_quantity, Quantity(10.0));
+
+        assert!(matches!(events.try_recv().unwrap(), crate::tracker::OrderEvent::Accepted(_)));
+        assert!(matches!(events.try_recv().unwrap(), crate::tracker::OrderEvent::Fill(_)));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_resting_order() {
+        let router = OrderRouter::new(paper_config()).unwrap();
+        router.set_fill_simulator(FillSimConfig::default()).unwrap();
+        let mut book = FastOrderBook::new(Symbol("AAPL".to_string()));
+        book.update_ask(Price(100.1), Quantity(50.0));
+        router.update_paper_book(&book);
+
+        // Bid below the ask rests unfilled
+        let response = router.route(create_test_order(10.0, Some(100.0)), None).await.unwrap();
+        assert_eq!(response.status, "new");
+
+        router.cancel(&response.id).await.unwrap();
+        let tracked = router.tracker().get_order(&response.id).unwrap();
+        assert_eq!(tracked.status, OrderStatus::Cancelled);
+
+        let err = router.cancel(&response.id).await.unwrap_err();
+        assert!(matches!(err, TradingError::Exchange(_)));
+    }
+
+    /// Paper router whose book rests bids below 100.1 and offers 50 there
+    fn resting_router(config: ExecutionConfig) -> OrderRouter {
+        let router = OrderRouter::new(config).unwrap();
+        router.set_fill_simulator(FillSimConfig::default()).unwrap();
+        let mut book = FastOrderBook::new(Symbol("AAPL".to_string()));
+        book.update_ask(Price(100.1), Quantity(50.0));
+        router.update_paper_book(&book);
+        router
+    }
+
+    fn create_tif_order(client_order_id: &str, qty: f64, price: f64, time_in_force: TimeInForce) -> Order {
+        OrderBuilder::new()
+            .client_order_id(client_order_id)
+            .quantity(qty)
+            .limit(price)
+            .time_in_force(time_in_force)
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_day_and_gtd_orders_expire() {
+        let router = resting_router(paper_config());
+        let now = Utc::now();
+
+        let day = router.route(create_tif_order("day", 10.0, 100.0, TimeInForce::Day), None).await.unwrap();
+        let tracker = router.tracker();
+        let close = router.expires_at(&tracker.get_order(&day.id).unwrap()).unwrap();
+        assert!(close > now);
+
+        // Expire the GTD order halfway to the close, whenever the test runs
+        let gtd_expiry = now + (close - now) / 2;
+        let gtd = router
+            .route(create_tif_order("gtd", 10.0, 100.0, TimeInForce::Gtd(gtd_expiry)), None)
+            .await
+            .unwrap();
+        let gtc = router.route(create_tif_order("gtc", 10.0, 100.0, TimeInForce::Gtc), None).await.unwrap();
+
+        assert_eq!(router.expires_at(&tracker.get_order(&gtc.id).unwrap()), None);
+
+        // Nothing has expired yet
+        assert!(router.cancel_expired(now).await.is_empty());
+        assert_eq!(tracker.open_orders().len(), 3);
+
+        let cancelled = router.cancel_expired(gtd_expiry).await;
+        assert_eq!(cancelled.len(), 1);
+        assert_eq!(cancelled[0].order_id, gtd.id);
+        assert_eq!(cancelled[0].status, OrderStatus::Cancelled);
+
+        // By the session close only the GTC order still rests
+        router.cancel_expired(close).await;
+        assert_eq!(tracker.get_order(&day.id).unwrap().status, OrderStatus::Cancelled);
+        let open = tracker.open_orders();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].order_id, gtc.id);
+    }
+
+    #[tokio::test]
+    async fn test_max_order_age_cancels_gtc() {
+        let mut config = paper_config();
+        config.max_order_age_secs = Some(60);
+        let router = resting_router(config);
+
+        let response = router.route(create_tif_order("old", 10.0, 100.0, TimeInForce::Gtc), None).await.unwrap();
+        let order = router.tracker().get_order(&response.id).unwrap();
+        let expiry = router.expires_at(&order).unwrap();
+        assert_eq!(expiry, order.created_at + chrono::Duration::seconds(60));
+
+        assert!(router.cancel_expired(expiry - chrono::Duration::seconds(1)).await.is_empty());
+        assert_eq!(router.cancel_expired(expiry).await.len(), 1);
+        assert!(router.tracker().open_orders().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_route_rejects_expired_gtd() {
+        let router = resting_router(paper_config());
+        let mut order = create_tif_order("late", 10.0, 100.0, TimeInForce::Gtd(Utc::now() - chrono::Duration::hours(1)));
+        order.created_at = Utc::now() - chrono::Duration::hours(2);
+
+        let err = router.route(order, None).await.unwrap_err();
+        assert!(matches!(err, TradingError::OrderValidation(ref m) if m.contains("expired")));
+    }
+
+    #[tokio::test]
+    async fn test_immediate_orders_do_not_rest() {
+        let router = resting_router(paper_config());
+        let tracker = router.tracker();
+
+        // IOC keeps the 50 available within its limit and cancels the rest
+        let ioc = router.route(create_tif_order("ioc", 80.0, 100.2, TimeInForce::Ioc), None).await.unwrap();
+        assert_eq!(ioc.status, "canceled");
+        let order = tracker.get_order(&ioc.id).unwrap();
+        assert_eq!(order.status, OrderStatus::Cancelled);
+        assert_eq!(order.filled_quantity, Quantity(50.0));
+
+        // FOK cannot fill 80 at once, so nothing fills
+        let fok = router.route(create_tif_order("fok", 80.0, 100.2, TimeInForce::Fok), None).await.unwrap();
+        assert_eq!(fok.status, "canceled");
+        let order = tracker.get_order(&fok.id).unwrap();
+        assert_eq!(order.status, OrderStatus::Cancelled);
+        assert_eq!(order.filled_quantity, Quantity(0.0));
+
+        assert!(tracker.open_orders().is_empty());
+        assert!(router.expires_at(&order).is_none());
+    }
+
+    #[test]
+    fn test_time_in_force_sent_to_exchange() {
+        let router = OrderRouter::new(paper_config()).unwrap();
+        let request = |time_in_force| {
+            router
+                .build_alpaca_request(&create_tif_order("tif", 10.0, 100.0, time_in_force))
+                .unwrap()
+                .time_in_force
+        };
+
+        assert_eq!(request(TimeInForce::Day), "day");
+        assert_eq!(request(TimeInForce::Ioc), "ioc");
+        assert_eq!(request(TimeInForce::Fok), "fok");
+        // Good-til-date is enforced locally by `cancel_expired`
+        assert_eq!(request(TimeInForce::Gtd(Utc::now() + chrono::Duration::hours(1))), "gtc");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_filled_order_fails() {
+        let router = OrderRouter::new(paper_config()).unwrap();
+        let response = router.route(create_test_order(10.0, None), None).await.unwrap();
+
+        let err = router.cancel(&response.id).await.unwrap_err();
+        assert!(matches!(err, TradingError::Exchange(ref m) if m.contains("already filled")));
+    }
+
+    #[tokio::test]
+    async fn test_replace_resting_order() {
+        let router = OrderRouter::new(paper_config()).unwrap();
+        router.set_fill_simulator(FillSimConfig::default()).unwrap();
+        let mut book = FastOrderBook::new(Symbol("AAPL".to_string()));
+        book.update_ask(Price(100.1), Quantity(50.0));
+        router.update_paper_book(&book);
+
+        let resting = router.route(create_test_order(10.0, Some(100.0)), None).await.unwrap();
+
+        // Raising the bid through the ask fills the replacement
+        let replaced = router
+            .replace(&resting.id, Some(Quantity(20.0)), Some(Price(100.2)))
+            .await
+            .unwrap();
+        assert_ne!(replaced.order_id, resting.id);
+        assert_eq!(replaced.filled_quantity, 20.0);
+
+        let tracker = router.tracker();
+        assert_eq!(tracker.get_order(&resting.id).unwrap().status, OrderStatus::Cancelled);
+        assert_eq!(tracker.get_order(&replaced.order_id).unwrap().status, OrderStatus::Filled);
+    }
+
+    #[tokio::test]
+    async fn test_get_order_by_client_id_unknown() {
+        let router = OrderRouter::new(paper_config()).unwrap();
+        assert!(router.get_order_by_client_id("missing").await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_validate_volume_profile() {
+        assert!(validate_volume_profile(&[0.25, 0.25, 0.5]).is_ok());
+        assert!(validate_volume_profile(&[]).is_err());
+        assert!(validate_volume_profile(&[0.5, 0.4]).is_err());
+        assert!(validate_volume_profile(&[1.2, -0.2]).is_err());
+        assert!(validate_volume_profile(&[f64::NAN, 1.0]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_slippage_limit_precedence() {
+        let mut config = paper_config();
+        config.symbol_max_slippage_bps.insert("AAPL".to_string(), 200.0);
+        let router = OrderRouter::new(config).unwrap();
+
+        // 100 bps passes the 200 bps symbol limit despite the 50 bps default
+        assert!(router.route(create_test_order(10.0, Some(101.0)), Some(100.0)).await.is_ok());
+
+        // A per-order override takes precedence over the symbol limit
+        let err = router
+            .route_with_limits(create_test_order(10.0, Some(101.0)), Some(100.0), Some(20.0))
+            .await
+            .unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("100.00 bps"));
+        assert!(message.contains("20 bps limit from order override"));
+    }
+
+    #[tokio::test]
+    async fn test_route_twap_rejects_zero_slices() {
+        let router = OrderRouter::new(paper_config()).unwrap();
+        let result = router
+            .route_twap(create_test_order(10.0, None), 0, Duration::ZERO, None)
+            .await;
+        assert!(result.is_err());
     }
 }
@@ -1,4 +1,5 @@
 use common::types::{Order, OrderType, Side};
+use market_data::orderbook::FastOrderBook;
 
 /// CRITICAL BUG FIX: SlippageEstimator now implements proper market impact calculation
 ///
@@ -14,6 +15,10 @@ pub struct SlippageEstimator {
     base_slippage_bps: f64,
     /// Volatility multiplier for market conditions
     volatility_multiplier: f64,
+    /// Impact coefficient `k` in the square-root model
+    impact_coefficient: f64,
+    /// Daily volatility `sigma` in basis points
+    daily_volatility_bps: f64,
 }
 
 impl SlippageEstimator {
@@ -21,6 +26,8 @@ impl SlippageEstimator {
         Self {
             base_slippage_bps: 1.0,  // 1 basis point base slippage
             volatility_multiplier: 1.0,
+            impact_coefficient: 1.0,
+            daily_volatility_bps: 200.0, // 2% daily volatility
         }
     }
 
@@ -29,9 +36,17 @@ impl SlippageEstimator {
         Self {
             base_slippage_bps,
             volatility_multiplier,
+            ..Self::new()
         }
     }
 
+    /// Configure the square-root impact model used by `estimate_with_book`
+    pub fn with_impact_model(mut self, impact_coefficient: f64, daily_volatility_bps: f64) -> Self {
+        self.impact_coefficient = impact_coefficient;
+        self.daily_volatility_bps = daily_volatility_bps;
+        self
+    }
+
     /// Estimate slippage in basis points
     ///
     /// ALGORITHM:
@@ -135,6 +150,44 @@ impl SlippageEstimator {
         self.estimate(order)
     }
 
+    /// Estimate slippage in basis points against a live order book
+    ///
+    /// Assumes the order executes aggressively and combines:
+    /// 1. Spread crossing: half the quoted spread, paid to reach the touch
+    /// 2. Depth: extra cost of walking past the touch into deeper levels
+    /// 3. Impact: `k * sigma * sqrt(order_qty / adv)`, scaled by the volatility multiplier
+    ///
+    /// Quantity beyond the visible depth is covered by the impact term only.
+    /// Falls back to `estimate` when the book has no usable mid price or
+    /// `adv` is not positive.
+    pub fn estimate_with_book(&self, order: &Order, book: &FastOrderBook, adv: f64) -> f64 {
+        let mid = match book.mid_price() {
+            Some(mid) if mid.0 > 0.0 => mid.0,
+            _ => return self.estimate(order),
+        };
+        if adv <= 0.0 || !adv.is_finite() {
+            return self.estimate(order);
+        }
+
+        let order_qty = order.quantity.0;
+        let half_spread_bps = book.spread_bps().unwrap_or(0.0) / 2.0;
+
+        let (avg_price, filled, _unfilled) = book.walk_book(order.side, order_qty);
+        let walk_cost_bps = if filled > 0.0 {
+            ((avg_price - mid).abs() / mid) * 10000.0
+        } else {
+            half_spread_bps
+        };
+        let depth_cost_bps = (walk_cost_bps - half_spread_bps).max(0.0);
+
+        let impact_bps = self.impact_coefficient
+            * self.daily_volatility_bps
+            * (order_qty / adv).sqrt()
+            * self.volatility_multiplier;
+
+        half_spread_bps + depth_cost_bps + impact_bps
+    }
+
     /// Update volatility multiplier based on market conditions
     pub fn update_volatility(&mut self, new_multiplier: f64) {
         self.volatility_multiplier = new_multiplier;
@@ -147,27 +200,32 @@ impl Default for SlippageEstimator {
     }
 }
 
+/// Realized slippage of a fill in basis points versus the price expected
+/// when the order was sent. Positive is adverse: a buy filled above, or a
+/// sell filled below, the expected price. Zero if `expected_price` is not
+/// positive.
+pub fn realized_slippage_bps(side: Side, expected_price: f64, filled_avg_price: f64) -> f64 {
+    if expected_price <= 0.0 || !expected_price.is_finite() {
+        return 0.0;
+    }
+    let diff = match side {
+        Side::Bid => filled_avg_price - expected_price,
+        Side::Ask => expected_price - filled_avg_price,
+    };
+    diff / expected_price * 10000.0
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use common::types::{OrderStatus, Price, Quantity, Symbol};
-    use chrono::Utc;
+    use common::types::{Price, Quantity, Symbol};
+    use common::OrderBuilder;
 
     fn create_test_order(qty: f64, price: Option<f64>, order_type: OrderType) -> Order {
-        Order {
-            order_id: "test".to_string(),
-            client_order_id: "client".to_string(),
-            symbol: Symbol("AAPL".to_string()),
-            side: Side::Bid,
-            order_type,
-            quantity: Quantity(qty),
-            price: price.map(Price),
-            stop_price: None,
-            status: OrderStatus::Pending,
-            filled_quantity: Quantity(0.0),
-            average_price: None,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
+        let builder = OrderBuilder::new().client_order_id("client").quantity(qty);
+        match (order_type, price) {
+            (OrderType::Limit, Some(price)) => builder.limit(price).build(),
+            _ => builder.build(),
         }
     }
 
@@ -227,4 +285,55 @@ mod tests {
             "High volatility should increase slippage: {} vs {}",
             high_vol_slippage, low_vol_slippage);
     }
+
+    fn create_test_book() -> FastOrderBook {
+        let mut book = FastOrderBook::new(Symbol("AAPL".to_string()));
+        book.update_bid(Price(99.9), Quantity(100.0));
+        book.update_ask(Price(100.1), Quantity(50.0));
+        book.update_ask(Price(100.3), Quantity(100.0));
+        book
+    }
+
+    #[test]
+    fn test_estimate_with_book_spread_only() {
+        let estimator = SlippageEstimator::new();
+        let book = create_test_book();
+        let order = create_test_order(10.0, None, OrderType::Market);
+
+        // Half of the 20bp spread plus a small impact term
+        let slippage = estimator.estimate_with_book(&order, &book, 1_000_000.0);
+        assert!(slippage > 10.0 && slippage < 11.0);
+    }
+
+    #[test]
+    fn test_estimate_with_book_walks_levels() {
+        let estimator = SlippageEstimator::new();
+        let book = create_test_book();
+
+        let small = estimator.estimate_with_book(
+            &create_test_order(50.0, None, OrderType::Market), &book, 1_000_000.0);
+        let large = estimator.estimate_with_book(
+            &create_test_order(150.0, None, OrderType::Market), &book, 1_000_000.0);
+
+        assert!(large > small + 5.0);
+    }
+
+    #[test]
+    fn test_estimate_with_book_falls_back() {
+        let estimator = SlippageEstimator::new();
+        let empty = FastOrderBook::new(Symbol("AAPL".to_string()));
+        let order = create_test_order(100.0, None, OrderType::Market);
+
+        assert_eq!(estimator.estimate_with_book(&order, &empty, 1_000_000.0), estimator.estimate(&order));
+        assert_eq!(estimator.estimate_with_book(&order, &create_test_book(), 0.0), estimator.estimate(&order));
+    }
+
+    #[test]
+    fn test_realized_slippage_sign() {
+        assert!((realized_slippage_bps(Side::Bid, 100.0, 100.1) - 10.0).abs() < 1e-9);
+        assert!((realized_slippage_bps(Side::Ask, 100.0, 99.9) - 10.0).abs() < 1e-9);
+        // Price improvement is negative slippage
+        assert!((realized_slippage_bps(Side::Bid, 100.0, 99.95) + 5.0).abs() < 1e-9);
+        assert_eq!(realized_slippage_bps(Side::Bid, 0.0, 100.0), 0.0);
+    }
 }
@@ -0,0 +1,257 @@
+use common::{Result, TradingError, types::{Order, OrderType, Quantity, Side}};
+use market_data::orderbook::FastOrderBook;
+use std::collections::HashMap;
+use crate::router::{AlpacaOrderResponse, OrderRouter};
+
+/// Identifier of an execution venue
+pub type VenueId = String;
+
+/// Split of a parent order across venues
+#[derive(Debug, Clone)]
+pub struct RoutePlan {
+    /// Child orders in the order they should be sent
+    pub children: Vec<(VenueId, Order)>,
+    /// Weighted average price of the visible liquidity the plan consumes
+    pub expected_avg_price: Option<f64>,
+}
+
+/// Routes orders across several venues, taking the cheapest liquidity first
+pub struct SmartRouter {
+    venues: Vec<(VenueId, OrderRouter)>,
+}
+
+impl SmartRouter {
+    pub fn new() -> Self {
+        Self { venues: Vec::new() }
+    }
+
+    /// Add a venue; each venue keeps its own rate limiter and retry policy
+    pub fn add_venue(&mut self, venue: impl Into<VenueId>, router: OrderRouter) {
+        self.venues.push((venue.into(), router));
+    }
+
+    pub fn venue(&self, venue: &str) -> Option<&OrderRouter> {
+        self.venues.iter().find(|(id, _)| id == venue).map(|(_, router)| router)
+    }
+
+    /// Split an order across venues by walking their combined books.
+    ///
+    /// Levels from every venue are merged best price first and consumed up to
+    /// the order size (and, for limit orders, up to the limit price). Any
+    /// quantity the visible books cannot absorb goes to the venue with the
+    /// best touch. With a single venue the order is passed through unchanged.
+    pub fn plan(&self, order: &Order, books: &HashMap<VenueId, FastOrderBook>) -> Result<RoutePlan> {
+        if self.venues.is_empty() {
+            return Err(TradingError::Configuration(
+                "SmartRouter has no venues configured".to_string()
+            ));
+        }
+
+        if self.venues.len() == 1 {
+            let (venue, _) = &self.venues[0];
+            let expected_avg_price = books
+                .get(venue)
+                .map(|book| book.walk_book(order.side, order.quantity.0))
+                .and_then(|(avg, filled, _)| (filled > 0.0).then_some(avg));
+            return Ok(RoutePlan {
+                children: vec![(venue.clone(), order.clone())],
+                expected_avg_price,
+            });
+        }
+
+        let is_buy = order.side == Side::Bid;
+        let limit = match order.order_type {
+            OrderType::Limit | OrderType::StopLimit => order.price.map(|p| p.0),
+            OrderType::Market | OrderType::StopMarket => None,
+        };
+
+        // (price, quantity, venue index) across all venues
+        let mut levels: Vec<(f64, f64, usize)> = Vec::new();
+        for (idx, (venue, _)) in self.venues.iter().enumerate() {
+            if let Some(book) = books.get(venue) {
+                let snapshot = book.to_snapshot(usize::MAX);
+                let side = if is_buy { snapshot.asks } else { snapshot.bids };
+                levels.extend(side.iter().map(|l| (l.price.0, l.quantity.0, idx)));
+            }
+        }
+        levels.sort_by(|a, b| {
+            let ord = a.0.total_cmp(&b.0);
+            if is_buy { ord } else { ord.reverse() }
+        });
+
+        let mut allocated = vec![0.0; self.venues.len()];
+        let mut remaining = order.quantity.0;
+        let mut cost = 0.0;
+        let mut filled = 0.0;
+
+        for (price, qty, idx) in &levels {
+            if remaining <= 0.0 {
+                break;
+            }
+            if let Some(limit) = limit {
+                if (is_buy && *price > limit) || (!is_buy && *price < limit) {
+                    break;
+                }
+            }
+            let take = remaining.min(*qty);
+            allocated[*idx] += take;
+            cost += take * price;
+            filled += take;
+            remaining -= take;
+        }
+
+        if remaining > 0.0 {
+            // Rest goes to the best touch, or the first venue if no book has liquidity
+            let best = levels.first().map(|(_, _, idx)| *idx).unwrap_or(0);
+            allocated[best] += remaining;
+        }
+
+        let children = self
+            .venues
+            .iter()
+            .zip(allocated)
+            .filter(|(_, qty)| *qty > 0.0)
+            .map(|((venue, _), qty)| {
+                let mut child = order.clone();
+                child.quantity = Quantity(qty);
+                child.client_order_id = format!("{}_{}", order.client_order_id, venue);
+                (venue.clone(), child)
+            })
+            .collect();
+
+        Ok(RoutePlan {
+            children,
+            expected_avg_price: (filled > 0.0).then_some(cost / filled),
+        })
+    }
+
+    /// Plan and send an order, returning each child's outcome by venue.
+    ///
+    /// Every child goes through its venue's `OrderRouter::route`, so slippage
+    /// checks, rate limits and retries apply per venue.
+    pub async fn route(
+        &self,
+        order: Order,
+        books: &HashMap<VenueId, FastOrderBook>,
+        current_market_price: Option<f64>,
+    ) -> Result<Vec<(VenueId, Result<AlpacaOrderResponse>)>> {
+        let plan = self.plan(&order, books)?;
+        let mut results = Vec::with_capacity(plan.children.len());
+
+        for (venue, child) in plan.children {
+            let router = self.venue(&venue).ok_or_else(|| {
+                TradingError::Configuration(format!("Unknown venue: {}", venue))
+            })?;
+            let result = router.route(child, current_market_price).await;
+            if let Err(e) = &result {
+                tracing::warn!("Smart route of {} to {} failed: {}", order.client_order_id, venue, e);
+            }
+            results.push((venue, result));
+        }
+
+        Ok(results)
+    }
+}
+
+impl Default for SmartRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::config::ExecutionConfig;
+    use common::types::{Price, Symbol};
+    use common::OrderBuilder;
+
+    fn paper_router() -> OrderRouter {
+        OrderRouter::new(ExecutionConfig {
+            exchange_api_url: "https://paper-api.alpaca.markets".to_string(),
+            api_key: None,
+            api_secret: None,
+            rate_limit_per_second: 100,
+            retry_attempts: 1,
+            retry_delay_ms: 100,
+            paper_trading: true,
+            max_slippage_bps: 50.0,
+            symbol_max_slippage_bps: HashMap::new(),
+            fee_model: Default::default(),
+            instruments: Default::default(),
+            session: None,
+            max_order_age_secs: None,
+        })
+        .unwrap()
+    }
+
+    fn create_test_order(qty: f64) -> Order {
+        OrderBuilder::new().client_order_id("parent").quantity(qty).build()
+    }
+
+    fn book_with_ask(price: f64, qty: f64) -> FastOrderBook {
+        let mut book = FastOrderBook::new(Symbol("AAPL".to_string()));
+        book.update_ask(Price(price), Quantity(qty));
+        book
+    }
+
+    #[test]
+    fn test_split_prefers_better_venue() {
+        let mut router = SmartRouter::new();
+        router.add_venue("alpha", paper_router());
+        router.add_venue("beta", paper_router());
+
+        let mut books = HashMap::new();
+        books.insert("alpha".to_string(), book_with_ask(100.2, 100.0));
+        books.insert("beta".to_string(), book_with_ask(100.1, 60.0));
+
+        let plan = router.plan(&create_test_order(100.0), &books).unwrap();
+        let split: HashMap<_, _> = plan
+            .children
+            .iter()
+            .map(|(venue, child)| (venue.as_str(), child.quantity.0))
+            .collect();
+
+        assert_eq!(split["beta"], 60.0);
+        assert_eq!(split["alpha"], 40.0);
+        let expected = (60.0 * 100.1 + 40.0 * 100.2) / 100.0;
+        assert!((plan.expected_avg_price.unwrap() - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_overflow_goes_to_best_venue() {
+        let mut router = SmartRouter::new();
+        router.add_venue("alpha", paper_router());
+        router.add_venue("beta", paper_router());
+
+        let mut books = HashMap::new();
+        books.insert("alpha".to_string(), book_with_ask(100.2, 10.0));
+        books.insert("beta".to_string(), book_with_ask(100.1, 10.0));
+
+        let plan = router.plan(&create_test_order(50.0), &books).unwrap();
+        let beta = plan.children.iter().find(|(venue, _)| venue == "beta").unwrap();
+        assert_eq!(beta.1.quantity.0, 40.0);
+    }
+
+    #[tokio::test]
+    async fn test_single_venue_passes_order_through() {
+        let mut router = SmartRouter::new();
+        router.add_venue("alpha", paper_router());
+
+        let results = router
+            .route(create_test_order(25.0), &HashMap::new(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        let response = results[0].1.as_ref().unwrap();
+        assert_eq!(response.client_order_id, "parent");
+        assert_eq!(response.filled_qty, "25");
+    }
+
+    #[test]
+    fn test_no_venues() {
+        let router = SmartRouter::new();
+        assert!(router.plan(&create_test_order(10.0), &HashMap::new()).is_err());
+    }
+}
@@ -1,5 +1,5 @@
 use common::{
-    types::{Order, OrderStatus, OrderType, Price, Quantity, Side, Symbol},
+    types::{Order, OrderStatus, OrderType, Price, Quantity, Side, Symbol, TimeInForce},
     Result, TradingError,
 };
 use chrono::Utc;
@@ -60,6 +60,7 @@ impl StopLossExecutor {
             quantity,
             price,
             stop_price,
+            time_in_force: TimeInForce::Gtc,
             status: OrderStatus::Pending,
             filled_quantity: Quantity(0.0),
             average_price: None,
@@ -0,0 +1,326 @@
+use crate::journal::OrderJournal;
+use common::{Result, TradingError, types::{Order, OrderStatus, Price, Quantity}};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use tokio::sync::broadcast;
+use tracing::{error, info};
+
+/// Capacity of the order event channel; slow subscribers lag rather than block
+const ORDER_EVENT_CAPACITY: usize = 1024;
+
+/// Order lifecycle event, carrying the order as of the transition
+#[derive(Debug, Clone)]
+pub enum OrderEvent {
+    Accepted(Order),
+    PartialFill(Order),
+    Fill(Order),
+    Cancel(Order),
+    Reject(Order),
+}
+
+impl OrderEvent {
+    pub fn order(&self) -> &Order {
+        match self {
+            OrderEvent::Accepted(order)
+            | OrderEvent::PartialFill(order)
+            | OrderEvent::Fill(order)
+            | OrderEvent::Cancel(order)
+            | OrderEvent::Reject(order) => order,
+        }
+    }
+
+    fn for_status(order: Order) -> Self {
+        match order.status {
+            OrderStatus::Pending => OrderEvent::Accepted(order),
+            OrderStatus::PartiallyFilled => OrderEvent::PartialFill(order),
+            OrderStatus::Filled => OrderEvent::Fill(order),
+            OrderStatus::Cancelled => OrderEvent::Cancel(order),
+            OrderStatus::Rejected => OrderEvent::Reject(order),
+        }
+    }
+}
+
+/// Tracks routed orders by `order_id` and publishes their lifecycle events
+pub struct OrderTracker {
+    orders: RwLock<HashMap<String, Order>>,
+    events: broadcast::Sender<OrderEvent>,
+    /// Durable record of every registration and transition, if configured
+    journal: Option<OrderJournal>,
+}
+
+impl OrderTracker {
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(ORDER_EVENT_CAPACITY);
+        Self {
+            orders: RwLock::new(HashMap::new()),
+            events,
+            journal: None,
+        }
+    }
+
+    /// Create a tracker that journals every order and transition, restoring
+    /// the open orders already in the journal. Restored orders emit no events.
+    pub fn with_journal(journal: OrderJournal) -> Result<Self> {
+        let open = journal.replay()?;
+        info!(
+            "Restored {} open orders from journal {}",
+            open.len(),
+            journal.path().display()
+        );
+
+        let mut tracker = Self::new();
+        tracker.orders = RwLock::new(
+            open.into_iter()
+                .map(|order| (order.order_id.clone(), order))
+                .collect(),
+        );
+        tracker.journal = Some(journal);
+        Ok(tracker)
+    }
+
+    /// Subscribe to order events emitted after this call
+    pub fn subscribe(&self) -> broadcast::Receiver<OrderEvent> {
+        self.events.subscribe()
+    }
+
+    /// Journal an order that is about to be sent, before the exchange has
+    /// assigned it an id.
+    ///
+    /// The record is keyed by client order id and nothing is tracked or
+    /// emitted until `register`. If the process dies before the exchange
+    /// responds, a restart restores the order as open under its client
+    /// order id instead of forgetting it was sent. Unlike later transitions
+    /// a failed write is returned, since the order must not be sent without
+    /// its record.
+    pub fn record_submission(&self, order: &Order) -> Result<()> {
+        let Some(journal) = &self.journal else {
+            return Ok(());
+        };
+        let mut pending = order.clone();
+        pending.order_id = order.client_order_id.clone();
+        pending.status = OrderStatus::Pending;
+
+        let _orders = self.orders.write().unwrap();
+        journal.append(&pending)
+    }
+
+    /// Start tracking an order, emitting the event for its current status.
+    ///
+    /// An order restored from a `record_submission` record is replaced by
+    /// the registered one. Returns false (and emits nothing) if the order id
+    /// is already tracked.
+    pub fn register(&self, order: Order) -> bool {
+        {
+            let mut orders = self.orders.write().unwrap();
+            if orders.contains_key(&order.order_id) {
+                return false;
+            }
+            if !order.client_order_id.is_empty() && order.client_order_id != order.order_id {
+                orders.remove(&order.client_order_id);
+            }
+            orders.insert(order.order_id.clone(), order.clone());
+            self.journal(&order);
+        }
+        self.emit(order);
+        true
+    }
+
+    /// Record a status change for a tracked order.
+    ///
+    /// Updates that don't change status or filled quantity are ignored.
+    /// Fails if the order is unknown or already in a terminal state.
+    pub fn update_status(
+        &self,
+        order_id: &str,
+        status: OrderStatus,
+        filled_qty: Quantity,
+        avg_price: Option<Price>,
+    ) -> Result<()> {
+        let updated = {
+            let mut orders = self.orders.write().unwrap();
+            let order = orders.get_mut(order_id).ok_or_else(|| {
+                TradingError::Execution(format!("Unknown order: {}", order_id))
+            })?;
+
+            if order.status == status && order.filled_quantity == filled_qty {
+                return Ok(());
+            }
+            if !is_open(order.status) {
+                return Err(TradingError::Execution(format!(
+                    "Order {} is already {:?}",
+                    order_id, order.status
+                )));
+            }
+
+            order.status = status;
+            order.filled_quantity = filled_qty;
+            if avg_price.is_some() {
+                order.average_price = avg_price;
+            }
+            order.updated_at = chrono::Utc::now();
+            self.journal(order);
+            order.clone()
+        };
+
+        self.emit(updated);
+        Ok(())
+    }
+
+    pub fn get_order(&self, order_id: &str) -> Option<Order> {
+        self.orders.read().unwrap().get(order_id).cloned()
+    }
+
+    /// Orders that are still working (pending or partially filled)
+    pub fn open_orders(&self) -> Vec<Order> {
+        self.orders
+            .read()
+            .unwrap()
+            .values()
+            .filter(|o| is_open(o.status))
+            .cloned()
+            .collect()
+    }
+
+    /// Journal writes happen under the orders lock so records for one order
+    /// land in transition order. A failed write is logged rather than
+    /// failing the transition, which has already happened at the exchange.
+    fn journal(&self, order: &Order) {
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(order) {
+                error!("Failed to journal order {}: {}", order.order_id, e);
+            }
+        }
+    }
+
+    fn emit(&self, order: Order) {
+        // No subscribers is not an error
+        let _ = self.events.send(OrderEvent::for_status(order));
+    }
+}
+
+impl Default for OrderTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_open(status: OrderStatus) -> bool {
+    matches!(status, OrderStatus::Pending | OrderStatus::PartiallyFilled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::OrderBuilder;
+
+    fn create_test_order(order_id: &str) -> Order {
+        OrderBuilder::new()
+            .order_id(order_id)
+            .client_order_id(&format!("client-{}", order_id))
+            .limit(150.0)
+            .build()
+    }
+
+    #[test]
+    fn test_lifecycle_events() {
+        let tracker = OrderTracker::new();
+        let mut events = tracker.subscribe();
+
+        assert!(tracker.register(create_test_order("1")));
+        tracker
+            .update_status("1", OrderStatus::PartiallyFilled, Quantity(40.0), Some(Price(150.0)))
+            .unwrap();
+        tracker
+            .update_status("1", OrderStatus::Filled, Quantity(100.0), Some(Price(149.9)))
+            .unwrap();
+
+        assert!(matches!(events.try_recv().unwrap(), OrderEvent::Accepted(_)));
+        assert!(matches!(events.try_recv().unwrap(), OrderEvent::PartialFill(o) if o.filled_quantity == Quantity(40.0)));
+        assert!(matches!(events.try_recv().unwrap(), OrderEvent::Fill(_)));
+        assert!(tracker.open_orders().is_empty());
+        assert_eq!(tracker.get_order("1").unwrap().average_price, Some(Price(149.9)));
+    }
+
+    #[test]
+    fn test_terminal_orders_are_final() {
+        let tracker = OrderTracker::new();
+        tracker.register(create_test_order("1"));
+        tracker.update_status("1", OrderStatus::Cancelled, Quantity(0.0), None).unwrap();
+
+        assert!(tracker.update_status("1", OrderStatus::Filled, Quantity(100.0), None).is_err());
+        assert!(tracker.update_status("missing", OrderStatus::Filled, Quantity(100.0), None).is_err());
+    }
+
+    #[test]
+    fn test_open_orders_and_duplicate_register() {
+        let tracker = OrderTracker::new();
+        assert!(tracker.register(create_test_order("1")));
+        assert!(!tracker.register(create_test_order("1")));
+        tracker.register(create_test_order("2"));
+        tracker.update_status("2", OrderStatus::Rejected, Quantity(0.0), None).unwrap();
+
+        let open = tracker.open_orders();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].order_id, "1");
+    }
+
+    #[test]
+    fn test_journal_restores_open_orders() {
+        let path = std::env::temp_dir().join(format!("tracker-journal-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        {
+            let tracker = OrderTracker::with_journal(OrderJournal::open(&path).unwrap()).unwrap();
+            tracker.register(create_test_order("1"));
+            tracker.register(create_test_order("2"));
+            tracker
+                .update_status("1", OrderStatus::PartiallyFilled, Quantity(40.0), Some(Price(150.0)))
+                .unwrap();
+            tracker.update_status("2", OrderStatus::Filled, Quantity(100.0), None).unwrap();
+        }
+
+        // Simulated restart
+        let tracker = OrderTracker::with_journal(OrderJournal::open(&path).unwrap()).unwrap();
+        let open = tracker.open_orders();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].order_id, "1");
+        assert_eq!(open[0].filled_quantity, Quantity(40.0));
+        assert!(tracker.get_order("2").is_none());
+
+        // Restored orders keep transitioning and journaling
+        tracker.update_status("1", OrderStatus::Filled, Quantity(100.0), None).unwrap();
+        let tracker = OrderTracker::with_journal(OrderJournal::open(&path).unwrap()).unwrap();
+        assert!(tracker.open_orders().is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_submission_superseded_by_exchange_id() {
+        let path = std::env::temp_dir().join(format!("tracker-submission-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        {
+            let tracker = OrderTracker::with_journal(OrderJournal::open(&path).unwrap()).unwrap();
+            tracker.record_submission(&create_test_order("1")).unwrap();
+            // Sent but never registered
+            assert!(tracker.open_orders().is_empty());
+        }
+
+        // Crash before the response: the order is restored under its client id
+        let tracker = OrderTracker::with_journal(OrderJournal::open(&path).unwrap()).unwrap();
+        let open = tracker.open_orders();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].order_id, "client-1");
+
+        // The exchange id replaces the client id entry, in memory and on disk
+        let mut accepted = create_test_order("exchange-1");
+        accepted.client_order_id = "client-1".to_string();
+        assert!(tracker.register(accepted));
+        assert!(tracker.get_order("client-1").is_none());
+        let tracker = OrderTracker::with_journal(OrderJournal::open(&path).unwrap()).unwrap();
+        let open = tracker.open_orders();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].order_id, "exchange-1");
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
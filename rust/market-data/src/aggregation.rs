@@ -1,6 +1,11 @@
+use common::config::SessionConfig;
 use common::types::{Bar, Price, Quantity, Symbol, Trade};
-use chrono::{DateTime, Duration, Utc};
+use common::{Result, TradingError};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
 use std::collections::HashMap;
+use std::str::FromStr;
+use tracing::warn;
 
 /// Time window for bar aggregation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -35,21 +40,259 @@ impl TimeWindow {
         }
     }
 
+    pub const ALL: [TimeWindow; 11] = [
+        TimeWindow::Seconds1,
+        TimeWindow::Seconds5,
+        TimeWindow::Seconds15,
+        TimeWindow::Seconds30,
+        TimeWindow::Minutes1,
+        TimeWindow::Minutes5,
+        TimeWindow::Minutes15,
+        TimeWindow::Minutes30,
+        TimeWindow::Hours1,
+        TimeWindow::Hours4,
+        TimeWindow::Days1,
+    ];
+
+    /// Name used in configuration, e.g. "30s", "1h", "1d"
+    pub fn label(&self) -> &'static str {
+        match self {
+            TimeWindow::Seconds1 => "1s",
+            TimeWindow::Seconds5 => "5s",
+            TimeWindow::Seconds15 => "15s",
+            TimeWindow::Seconds30 => "30s",
+            TimeWindow::Minutes1 => "1m",
+            TimeWindow::Minutes5 => "5m",
+            TimeWindow::Minutes15 => "15m",
+            TimeWindow::Minutes30 => "30m",
+            TimeWindow::Hours1 => "1h",
+            TimeWindow::Hours4 => "4h",
+            TimeWindow::Days1 => "1d",
+        }
+    }
+
+    /// Start of the window containing `timestamp`, aligned to the Unix epoch
     pub fn floor_timestamp(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
-        let duration = self.duration();
-        let seconds = duration.num_seconds();
-        let ts_seconds = timestamp.timestamp();
-        let floored = (ts_seconds / seconds) * seconds;
-        DateTime::from_timestamp(floored, 0).unwrap_or(timestamp)
+        self.align(timestamp, DateTime::UNIX_EPOCH)
+    }
+
+    /// Start of the window containing `timestamp`, with windows laid end to
+    /// end from `anchor`
+    pub fn align(&self, timestamp: DateTime<Utc>, anchor: DateTime<Utc>) -> DateTime<Utc> {
+        let window_ms = self.duration().num_milliseconds();
+        let offset_ms = (timestamp - anchor).num_milliseconds().div_euclid(window_ms) * window_ms;
+        anchor + Duration::milliseconds(offset_ms)
+    }
+}
+
+impl FromStr for TimeWindow {
+    type Err = TradingError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        TimeWindow::ALL
+            .into_iter()
+            .find(|window| window.label() == s.trim())
+            .ok_or_else(|| {
+                let labels: Vec<&str> = TimeWindow::ALL.iter().map(TimeWindow::label).collect();
+                TradingError::Configuration(format!(
+                    "unknown bar window {:?}, expected one of {}",
+                    s,
+                    labels.join(", ")
+                ))
+            })
+    }
+}
+
+/// Days searched for the next or previous session; covers any weekend
+const MAX_SESSION_SCAN_DAYS: i64 = 7;
+
+/// Regular trading hours of an exchange, Monday to Friday
+///
+/// Time bars aligned to a calendar start at the session open rather than
+/// UTC midnight, and are cut short at the close so no bar spans the
+/// overnight gap. Trades outside the session form their own bars, aligned
+/// to the previous close and cut short at the next open. Holidays are not
+/// modelled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SessionCalendar {
+    timezone: Tz,
+    open: NaiveTime,
+    close: NaiveTime,
+}
+
+impl SessionCalendar {
+    pub fn new(timezone: Tz, open: NaiveTime, close: NaiveTime) -> Result<Self> {
+        if open >= close {
+            return Err(TradingError::Configuration(format!(
+                "session open {} must be before close {}",
+                open, close
+            )));
+        }
+        Ok(Self { timezone, open, close })
+    }
+
+    /// NYSE/Nasdaq regular hours, 09:30 to 16:00 New York time
+    pub fn us_equities() -> Self {
+        Self {
+            timezone: chrono_tz::America::New_York,
+            open: NaiveTime::from_hms_opt(9, 30, 0).expect("valid time"),
+            close: NaiveTime::from_hms_opt(16, 0, 0).expect("valid time"),
+        }
+    }
+
+    pub fn from_config(config: &SessionConfig) -> Result<Self> {
+        let timezone: Tz = config.timezone.parse().map_err(|e| {
+            TradingError::Configuration(format!("unknown session timezone {:?}: {}", config.timezone, e))
+        })?;
+        let (open, close) = config.hours()?;
+        Self::new(timezone, open, close)
+    }
+
+    pub fn is_trading_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+    }
+
+    /// Open and close of the session on the exchange-local `date`, if it trades
+    pub fn session_on(&self, date: NaiveDate) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        if !self.is_trading_day(date) {
+            return None;
+        }
+        Some((self.local_to_utc(date, self.open)?, self.local_to_utc(date, self.close)?))
+    }
+
+    pub fn is_open(&self, timestamp: DateTime<Utc>) -> bool {
+        self.session_on(self.local_date(timestamp))
+            .is_some_and(|(open, close)| timestamp >= open && timestamp < close)
+    }
+
+    /// The session, or the gap between sessions, containing `timestamp`
+    pub fn segment(&self, timestamp: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        let date = self.local_date(timestamp);
+        if let Some((open, close)) = self.session_on(date) {
+            if timestamp >= open && timestamp < close {
+                return (open, close);
+            }
+        }
+
+        let previous_close = (0..=MAX_SESSION_SCAN_DAYS)
+            .filter_map(|days| self.session_on(date - Duration::days(days)))
+            .map(|(_, close)| close)
+            .find(|close| *close <= timestamp);
+        let next_open = (0..=MAX_SESSION_SCAN_DAYS)
+            .filter_map(|days| self.session_on(date + Duration::days(days)))
+            .map(|(open, _)| open)
+            .find(|open| *open > timestamp);
+
+        (
+            previous_close.unwrap_or(timestamp),
+            next_open.unwrap_or(timestamp + Duration::days(MAX_SESSION_SCAN_DAYS)),
+        )
+    }
+
+    /// Close of the session in progress at `timestamp`, or of the next
+    /// session when the market is closed
+    pub fn next_close(&self, timestamp: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let date = self.local_date(timestamp);
+        (0..=MAX_SESSION_SCAN_DAYS)
+            .filter_map(|days| self.session_on(date + Duration::days(days)))
+            .map(|(_, close)| close)
+            .find(|close| *close > timestamp)
+    }
+
+    fn local_date(&self, timestamp: DateTime<Utc>) -> NaiveDate {
+        timestamp.with_timezone(&self.timezone).date_naive()
+    }
+
+    fn local_to_utc(&self, date: NaiveDate, time: NaiveTime) -> Option<DateTime<Utc>> {
+        self.timezone
+            .from_local_datetime(&date.and_time(time))
+            .earliest()
+            .map(|local| local.with_timezone(&Utc))
+    }
+}
+
+/// Start and end of the bar for `window` containing `timestamp`
+fn bar_bounds(
+    window: TimeWindow,
+    timestamp: DateTime<Utc>,
+    session: Option<&SessionCalendar>,
+) -> (DateTime<Utc>, DateTime<Utc>) {
+    match session {
+        Some(calendar) => {
+            let (segment_start, segment_end) = calendar.segment(timestamp);
+            let start = window.align(timestamp, segment_start);
+            (start, (start + window.duration()).min(segment_end))
+        }
+        None => {
+            let start = window.floor_timestamp(timestamp);
+            (start, start + window.duration())
+        }
+    }
+}
+
+/// Most empty windows reported for one gap; longer gaps are truncated
+const MAX_GAP_BARS: usize = 10_000;
+
+/// A time window that saw no trades
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingBar {
+    pub symbol: Symbol,
+    pub window: TimeWindow,
+    /// Start of the empty window
+    pub timestamp: DateTime<Utc>,
+    /// Whether a synthetic bar was emitted for it
+    pub forward_filled: bool,
+}
+
+/// Output of `BarAggregator::process_trade_events`
+#[derive(Debug, Clone, PartialEq)]
+pub enum BarEvent {
+    /// A completed bar, including synthetic forward-filled bars
+    Bar(Bar),
+    /// An empty window between two trades
+    Missing(MissingBar),
+}
+
+/// Starts of the windows in `[from, until)`, skipping time outside the session
+fn missing_windows(
+    window: TimeWindow,
+    from: DateTime<Utc>,
+    until: DateTime<Utc>,
+    session: Option<&SessionCalendar>,
+) -> Vec<DateTime<Utc>> {
+    let mut starts = Vec::new();
+    let mut next = from;
+
+    while next < until {
+        if let Some(calendar) = session {
+            if !calendar.is_open(next) {
+                next = calendar.segment(next).1;
+                continue;
+            }
+        }
+        if starts.len() == MAX_GAP_BARS {
+            warn!(
+                "{} gap from {} to {} exceeds {} bars, reporting the first {} only",
+                window.label(), from, until, MAX_GAP_BARS, MAX_GAP_BARS
+            );
+            break;
+        }
+
+        let (start, end) = bar_bounds(window, next, session);
+        starts.push(start);
+        next = end;
     }
+
+    starts
 }
 
 /// Accumulator for building bars from trades
 #[derive(Debug, Clone)]
 struct BarAccumulator {
     symbol: Symbol,
-    window: TimeWindow,
     window_start: DateTime<Utc>,
+    /// Exclusive; earlier than `window_start + duration` at a session boundary
+    window_end: DateTime<Utc>,
     open: Option<Price>,
     high: Price,
     low: Price,
@@ -59,13 +302,18 @@ struct BarAccumulator {
 }
 
 impl BarAccumulator {
-    fn new(symbol: Symbol, window: TimeWindow, timestamp: DateTime<Utc>) -> Self {
-        let window_start = window.floor_timestamp(timestamp);
+    fn new(
+        symbol: Symbol,
+        window: TimeWindow,
+        timestamp: DateTime<Utc>,
+        session: Option<&SessionCalendar>,
+    ) -> Self {
+        let (window_start, window_end) = bar_bounds(window, timestamp, session);
 
         Self {
             symbol,
-            window,
             window_start,
+            window_end,
             open: None,
             high: Price(0.0),
             low: Price(f64::MAX),
@@ -104,8 +352,143 @@ impl BarAccumulator {
     }
 
     fn is_in_window(&self, timestamp: DateTime<Utc>) -> bool {
-        let window_end = self.window_start + self.window.duration();
-        timestamp >= self.window_start && timestamp < window_end
+        timestamp >= self.window_start && timestamp < self.window_end
+    }
+}
+
+/// Tolerance for deciding an activity bar has reached its threshold
+const ACTIVITY_EPSILON: f64 = 1e-9;
+
+/// Information-driven bar types, closed by trading activity instead of the clock
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BarType {
+    /// Close a bar once cumulative traded quantity reaches the threshold
+    VolumeBar(Quantity),
+    /// Close a bar once cumulative price * quantity reaches the threshold
+    DollarBar(f64),
+    /// Close a bar every N trades
+    TickBar(u64),
+    /// Emit a brick each time price moves `brick_size` from the last brick close
+    ///
+    /// Bricks have synthetic open/close on brick boundaries, and high/low equal
+    /// to the brick's own range (`max`/`min` of open and close) rather than the
+    /// traded extremes. A move spanning several bricks emits one brick per
+    /// `brick_size`; the volume traded since the previous brick is attributed
+    /// to the first of them.
+    Renko(f64),
+}
+
+impl BarType {
+    fn is_valid(&self) -> bool {
+        let threshold = match self {
+            BarType::VolumeBar(quantity) => quantity.0,
+            BarType::DollarBar(notional) => *notional,
+            BarType::TickBar(count) => *count as f64,
+            BarType::Renko(brick_size) => *brick_size,
+        };
+        threshold.is_finite() && threshold > 0.0
+    }
+}
+
+/// Accumulator for activity-driven bars
+#[derive(Debug, Clone)]
+struct ActivityAccumulator {
+    symbol: Symbol,
+    start: DateTime<Utc>,
+    open: Option<Price>,
+    high: Price,
+    low: Price,
+    close: Price,
+    volume: Quantity,
+    dollar_volume: f64,
+    trade_count: u64,
+}
+
+impl ActivityAccumulator {
+    fn new(symbol: Symbol, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            symbol,
+            start: timestamp,
+            open: None,
+            high: Price(0.0),
+            low: Price(f64::MAX),
+            close: Price(0.0),
+            volume: Quantity(0.0),
+            dollar_volume: 0.0,
+            trade_count: 0,
+        }
+    }
+
+    fn add(&mut self, price: Price, quantity: f64, timestamp: DateTime<Utc>) {
+        if self.open.is_none() {
+            self.open = Some(price);
+            self.start = timestamp;
+        }
+
+        self.high = Price(self.high.0.max(price.0));
+        self.low = Price(self.low.0.min(price.0));
+        self.close = price;
+
+        self.volume = Quantity(self.volume.0 + quantity);
+        self.dollar_volume += price.0 * quantity;
+    }
+
+    /// Quantity at `price` still needed to reach a volume or dollar threshold
+    fn capacity(&self, bar_type: BarType, price: Price) -> f64 {
+        match bar_type {
+            BarType::VolumeBar(threshold) => threshold.0 - self.volume.0,
+            BarType::DollarBar(threshold) => (threshold - self.dollar_volume) / price.0,
+            BarType::TickBar(_) | BarType::Renko(_) => f64::INFINITY,
+        }
+    }
+
+    fn to_bar(&self) -> Option<Bar> {
+        self.open.map(|open| Bar {
+            symbol: self.symbol.clone(),
+            open,
+            high: self.high,
+            low: self.low,
+            close: self.close,
+            volume: self.volume,
+            timestamp: self.start,
+        })
+    }
+}
+
+/// Renko brick state for one symbol
+#[derive(Debug, Clone)]
+struct RenkoState {
+    symbol: Symbol,
+    last_close: Price,
+    volume: Quantity,
+}
+
+impl RenkoState {
+    /// Emit every brick completed by a move to `trade.price`
+    fn update(&mut self, brick_size: f64, trade: &Trade) -> Vec<Bar> {
+        self.volume = Quantity(self.volume.0 + trade.quantity.0);
+
+        let moved = trade.price.0 - self.last_close.0;
+        let bricks = (moved.abs() / brick_size + ACTIVITY_EPSILON).floor() as u64;
+        let step = brick_size * moved.signum();
+
+        let mut completed = Vec::with_capacity(bricks as usize);
+        for _ in 0..bricks {
+            let open = self.last_close;
+            let close = Price(open.0 + step);
+            completed.push(Bar {
+                symbol: self.symbol.clone(),
+                open,
+                high: Price(open.0.max(close.0)),
+                low: Price(open.0.min(close.0)),
+                close,
+                volume: std::mem::replace(&mut self.volume, Quantity(0.0)),
+                timestamp: trade.timestamp,
+            });
+            self.last_close = close;
+        }
+
+        completed
     }
 }
 
@@ -113,6 +496,12 @@ impl BarAccumulator {
 pub struct BarAggregator {
     accumulators: HashMap<(String, TimeWindow), BarAccumulator>,
     windows: Vec<TimeWindow>,
+    session: Option<SessionCalendar>,
+    /// Emit a flat zero-volume bar for each empty time window
+    forward_fill: bool,
+    activity_accumulators: HashMap<(String, usize), ActivityAccumulator>,
+    renko_states: HashMap<(String, usize), RenkoState>,
+    bar_types: Vec<BarType>,
 }
 
 impl BarAggregator {
@@ -120,12 +509,54 @@ impl BarAggregator {
         Self {
             accumulators: HashMap::new(),
             windows,
+            session: None,
+            forward_fill: false,
+            activity_accumulators: HashMap::new(),
+            renko_states: HashMap::new(),
+            bar_types: Vec::new(),
         }
     }
 
+    /// Align time bars to an exchange session instead of UTC midnight
+    pub fn with_session(mut self, session: SessionCalendar) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// Fill empty time windows with a bar whose OHLC is the previous close
+    /// and whose volume is zero, so consumers see evenly spaced bars.
+    ///
+    /// With a session calendar only windows inside the session are filled.
+    pub fn with_forward_fill(mut self, forward_fill: bool) -> Self {
+        self.forward_fill = forward_fill;
+        self
+    }
+
+    /// Also build information-driven bars from the same trade stream
+    ///
+    /// Bar types with a non-positive or non-finite threshold are ignored.
+    pub fn with_bar_types(mut self, bar_types: Vec<BarType>) -> Self {
+        self.bar_types = bar_types.into_iter().filter(BarType::is_valid).collect();
+        self
+    }
+
     /// Process a trade and emit completed bars
     pub fn process_trade(&mut self, trade: &Trade) -> Vec<Bar> {
-        let mut completed_bars = Vec::new();
+        self.process_trade_events(trade)
+            .into_iter()
+            .filter_map(|event| match event {
+                BarEvent::Bar(bar) => Some(bar),
+                BarEvent::Missing(_) => None,
+            })
+            .collect()
+    }
+
+    /// Process a trade, emitting completed bars and any empty windows the
+    /// trade reveals since the symbol's previous trade
+    pub fn process_trade_events(&mut self, trade: &Trade) -> Vec<BarEvent> {
+        let mut events = Vec::new();
+        let session = self.session.as_ref();
+        let forward_fill = self.forward_fill;
 
         for &window in &self.windows {
             let key = (trade.symbol.0.clone(), window);
@@ -135,25 +566,135 @@ impl BarAggregator {
                 .accumulators
                 .entry(key.clone())
                 .or_insert_with(|| {
-                    BarAccumulator::new(trade.symbol.clone(), window, trade.timestamp)
+                    BarAccumulator::new(trade.symbol.clone(), window, trade.timestamp, session)
                 });
 
             // Check if trade is in current window
             if !accumulator.is_in_window(trade.timestamp) {
-                // Complete the current bar
-                if let Some(bar) = accumulator.to_bar() {
-                    completed_bars.push(bar);
-                }
+                let previous = accumulator.to_bar();
+                let gap_start = accumulator.window_end;
 
                 // Start new accumulator for new window
-                *accumulator = BarAccumulator::new(trade.symbol.clone(), window, trade.timestamp);
+                *accumulator =
+                    BarAccumulator::new(trade.symbol.clone(), window, trade.timestamp, session);
+
+                // Complete the current bar, then report the windows skipped since
+                if let Some(bar) = previous {
+                    let last_close = bar.close;
+                    events.push(BarEvent::Bar(bar));
+
+                    for start in missing_windows(window, gap_start, accumulator.window_start, session) {
+                        metrics::counter!("market_data_missing_bars_total", "window" => window.label())
+                            .increment(1);
+                        events.push(BarEvent::Missing(MissingBar {
+                            symbol: trade.symbol.clone(),
+                            window,
+                            timestamp: start,
+                            forward_filled: forward_fill,
+                        }));
+                        if forward_fill {
+                            events.push(BarEvent::Bar(Bar {
+                                symbol: trade.symbol.clone(),
+                                open: last_close,
+                                high: last_close,
+                                low: last_close,
+                                close: last_close,
+                                volume: Quantity(0.0),
+                                timestamp: start,
+                            }));
+                        }
+                    }
+                }
             }
 
             // Update accumulator
             accumulator.update(trade);
         }
 
-        completed_bars
+        // Activity bars split trades by size, so a trade without a positive,
+        // finite price and quantity could never fill one
+        let valid = trade.price.0.is_finite()
+            && trade.price.0 > 0.0
+            && trade.quantity.0.is_finite()
+            && trade.quantity.0 > 0.0;
+        if !valid {
+            if !self.bar_types.is_empty() {
+                warn!(
+                    "Skipping {} trade with price {} and quantity {} for activity bars",
+                    trade.symbol.0, trade.price.0, trade.quantity.0
+                );
+            }
+            return events;
+        }
+
+        let mut activity_bars = Vec::new();
+        for index in 0..self.bar_types.len() {
+            self.process_activity_trade(index, trade, &mut activity_bars);
+        }
+        events.extend(activity_bars.into_iter().map(BarEvent::Bar));
+
+        events
+    }
+
+    /// Feed a trade into an activity bar, splitting it across bars as needed
+    ///
+    /// The part of a trade that overshoots the threshold is carried into the
+    /// next bar, so a single trade larger than the threshold closes one or more
+    /// bars immediately.
+    fn process_activity_trade(&mut self, index: usize, trade: &Trade, completed_bars: &mut Vec<Bar>) {
+        let bar_type = self.bar_types[index];
+        let key = (trade.symbol.0.clone(), index);
+
+        if let BarType::Renko(brick_size) = bar_type {
+            match self.renko_states.get_mut(&key) {
+                Some(state) => completed_bars.extend(state.update(brick_size, trade)),
+                None => {
+                    // First trade anchors the brick grid
+                    self.renko_states.insert(
+                        key,
+                        RenkoState {
+                            symbol: trade.symbol.clone(),
+                            last_close: trade.price,
+                            volume: trade.quantity,
+                        },
+                    );
+                }
+            }
+            return;
+        }
+
+        let accumulator = self
+            .activity_accumulators
+            .entry(key)
+            .or_insert_with(|| ActivityAccumulator::new(trade.symbol.clone(), trade.timestamp));
+
+        if let BarType::TickBar(count) = bar_type {
+            accumulator.add(trade.price, trade.quantity.0, trade.timestamp);
+            accumulator.trade_count += 1;
+            if accumulator.trade_count >= count {
+                if let Some(bar) = accumulator.to_bar() {
+                    completed_bars.push(bar);
+                }
+                *accumulator = ActivityAccumulator::new(trade.symbol.clone(), trade.timestamp);
+            }
+            return;
+        }
+
+        let mut remaining = trade.quantity.0;
+        while remaining > ACTIVITY_EPSILON {
+            let capacity = accumulator.capacity(bar_type, trade.price);
+            let fill = remaining.min(capacity);
+
+            accumulator.add(trade.price, fill, trade.timestamp);
+            remaining -= fill;
+
+            if capacity - fill <= ACTIVITY_EPSILON {
+                if let Some(bar) = accumulator.to_bar() {
+                    completed_bars.push(bar);
+                }
+                *accumulator = ActivityAccumulator::new(trade.symbol.clone(), trade.timestamp);
+            }
+        }
     }
 
     /// Get current (incomplete) bar for a symbol and window
@@ -162,7 +703,18 @@ impl BarAggregator {
         self.accumulators.get(&key).and_then(|acc| acc.to_bar())
     }
 
+    /// Get current (incomplete) activity bar for a symbol and bar type
+    pub fn get_current_activity_bar(&self, symbol: &str, bar_type: BarType) -> Option<Bar> {
+        let index = self.bar_types.iter().position(|bt| *bt == bar_type)?;
+        self.activity_accumulators
+            .get(&(symbol.to_string(), index))
+            .and_then(|acc| acc.to_bar())
+    }
+
     /// Force completion of all current bars
+    ///
+    /// Partially formed Renko bricks are discarded and the brick grid is
+    /// re-anchored on the next trade.
     pub fn flush(&mut self) -> Vec<Bar> {
         let mut completed_bars = Vec::new();
 
@@ -172,7 +724,15 @@ impl BarAggregator {
             }
         }
 
+        for accumulator in self.activity_accumulators.values() {
+            if let Some(bar) = accumulator.to_bar() {
+                completed_bars.push(bar);
+            }
+        }
+
         self.accumulators.clear();
+        self.activity_accumulators.clear();
+        self.renko_states.clear();
 
         completed_bars
     }
@@ -260,3 +820,334 @@ impl MicrostructureFeatures {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::types::Side;
+
+    fn trade(price: f64, quantity: f64, seconds: i64) -> Trade {
+        Trade {
+            symbol: Symbol("AAPL".to_string()),
+            price: Price(price),
+            quantity: Quantity(quantity),
+            side: Side::Bid,
+            timestamp: DateTime::from_timestamp(1_700_000_000 + seconds, 0).unwrap(),
+            trade_id: format!("t{}", seconds),
+        }
+    }
+
+    fn trade_at(price: f64, timestamp: DateTime<Utc>) -> Trade {
+        Trade {
+            timestamp,
+            ..trade(price, 10.0, 0)
+        }
+    }
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_forward_fill_spans_multi_window_gap() {
+        let mut aggregator = BarAggregator::new(vec![TimeWindow::Minutes1]).with_forward_fill(true);
+
+        assert!(aggregator.process_trade(&trade_at(100.0, utc(2024, 3, 4, 10, 0))).is_empty());
+        let half_past = utc(2024, 3, 4, 10, 0) + Duration::seconds(30);
+        assert!(aggregator.process_trade(&trade_at(101.0, half_past)).is_empty());
+
+        // Nothing trades from 10:01 to 10:03
+        let events = aggregator.process_trade_events(&trade_at(105.0, utc(2024, 3, 4, 10, 4)));
+        let missing: Vec<&MissingBar> = events
+            .iter()
+            .filter_map(|event| match event {
+                BarEvent::Missing(missing) => Some(missing),
+                BarEvent::Bar(_) => None,
+            })
+            .collect();
+        assert_eq!(missing.len(), 3);
+        assert!(missing.iter().all(|missing| missing.forward_filled));
+        assert_eq!(missing[0].timestamp, utc(2024, 3, 4, 10, 1));
+
+        let bars: Vec<&Bar> = events
+            .iter()
+            .filter_map(|event| match event {
+                BarEvent::Bar(bar) => Some(bar),
+                BarEvent::Missing(_) => None,
+            })
+            .collect();
+        let timestamps: Vec<DateTime<Utc>> = bars.iter().map(|bar| bar.timestamp).collect();
+        assert_eq!(
+            timestamps,
+            vec![
+                utc(2024, 3, 4, 10, 0),
+                utc(2024, 3, 4, 10, 1),
+                utc(2024, 3, 4, 10, 2),
+                utc(2024, 3, 4, 10, 3),
+            ]
+        );
+        for filled in &bars[1..] {
+            assert_eq!(filled.open, Price(101.0));
+            assert_eq!(filled.high, Price(101.0));
+            assert_eq!(filled.low, Price(101.0));
+            assert_eq!(filled.close, Price(101.0));
+            assert_eq!(filled.volume, Quantity(0.0));
+        }
+    }
+
+    #[test]
+    fn test_gaps_reported_without_fill_by_default() {
+        let mut aggregator = BarAggregator::new(vec![TimeWindow::Minutes1]);
+
+        aggregator.process_trade(&trade_at(100.0, utc(2024, 3, 4, 10, 0)));
+        let events = aggregator.process_trade_events(&trade_at(105.0, utc(2024, 3, 4, 10, 4)));
+        assert_eq!(events.len(), 4);
+        assert!(matches!(&events[0], BarEvent::Bar(bar) if bar.timestamp == utc(2024, 3, 4, 10, 0)));
+        assert!(events[1..]
+            .iter()
+            .all(|event| matches!(event, BarEvent::Missing(missing) if !missing.forward_filled)));
+    }
+
+    #[test]
+    fn test_forward_fill_skips_overnight_gap() {
+        let mut aggregator = BarAggregator::new(vec![TimeWindow::Hours1])
+            .with_session(SessionCalendar::us_equities())
+            .with_forward_fill(true);
+
+        aggregator.process_trade(&trade_at(100.0, utc(2024, 3, 4, 20, 45)));
+
+        // Monday 15:30 bar, then straight to Tuesday's open
+        let bars = aggregator.process_trade(&trade_at(101.0, utc(2024, 3, 5, 14, 40)));
+        assert_eq!(bars.len(), 1);
+
+        // Tuesday 10:30 ET passes without trades
+        let bars = aggregator.process_trade(&trade_at(102.0, utc(2024, 3, 5, 16, 40)));
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[1].timestamp, utc(2024, 3, 5, 15, 30));
+        assert_eq!(bars[1].close, Price(101.0));
+    }
+
+    #[test]
+    fn test_time_window_labels_round_trip() {
+        for window in TimeWindow::ALL {
+            assert_eq!(window.label().parse::<TimeWindow>().unwrap(), window);
+        }
+        assert!("2m".parse::<TimeWindow>().is_err());
+    }
+
+    #[test]
+    fn test_session_follows_daylight_saving() {
+        let calendar = SessionCalendar::us_equities();
+
+        // 09:30 EST is 14:30 UTC in March, 09:30 EDT is 13:30 UTC in July
+        let (open, close) = calendar.session_on(NaiveDate::from_ymd_opt(2024, 3, 4).unwrap()).unwrap();
+        assert_eq!(open, utc(2024, 3, 4, 14, 30));
+        assert_eq!(close, utc(2024, 3, 4, 21, 0));
+        let (open, _) = calendar.session_on(NaiveDate::from_ymd_opt(2024, 7, 1).unwrap()).unwrap();
+        assert_eq!(open, utc(2024, 7, 1, 13, 30));
+
+        assert!(calendar.session_on(NaiveDate::from_ymd_opt(2024, 3, 9).unwrap()).is_none());
+
+        // Friday's close to Monday's open is one gap
+        let (gap_start, gap_end) = calendar.segment(utc(2024, 3, 9, 12, 0));
+        assert_eq!(gap_start, utc(2024, 3, 8, 21, 0));
+        assert_eq!(gap_end, utc(2024, 3, 11, 13, 30));
+    }
+
+    #[test]
+    fn test_next_close() {
+        let calendar = SessionCalendar::us_equities();
+
+        // During the session, after the close, and over the weekend
+        assert_eq!(calendar.next_close(utc(2024, 3, 4, 15, 0)), Some(utc(2024, 3, 4, 21, 0)));
+        assert_eq!(calendar.next_close(utc(2024, 3, 4, 21, 0)), Some(utc(2024, 3, 5, 21, 0)));
+        assert_eq!(calendar.next_close(utc(2024, 3, 9, 12, 0)), Some(utc(2024, 3, 11, 20, 0)));
+    }
+
+    #[test]
+    fn test_hourly_bars_align_to_session_open() {
+        let mut aggregator =
+            BarAggregator::new(vec![TimeWindow::Hours1]).with_session(SessionCalendar::us_equities());
+
+        assert!(aggregator.process_trade(&trade_at(100.0, utc(2024, 3, 4, 14, 31))).is_empty());
+        assert!(aggregator.process_trade(&trade_at(101.0, utc(2024, 3, 4, 15, 29))).is_empty());
+
+        let bars = aggregator.process_trade(&trade_at(102.0, utc(2024, 3, 4, 15, 30)));
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].timestamp, utc(2024, 3, 4, 14, 30));
+        assert_eq!(bars[0].close, Price(101.0));
+    }
+
+    #[test]
+    fn test_bar_closes_at_session_boundary() {
+        let mut aggregator =
+            BarAggregator::new(vec![TimeWindow::Hours1]).with_session(SessionCalendar::us_equities());
+
+        // The 15:30 bar would run to 16:30 but the session closes at 16:00
+        assert!(aggregator.process_trade(&trade_at(100.0, utc(2024, 3, 4, 20, 45))).is_empty());
+        let bars = aggregator.process_trade(&trade_at(101.0, utc(2024, 3, 4, 21, 10)));
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].timestamp, utc(2024, 3, 4, 20, 30));
+
+        // After-hours trades start their own bar at the close
+        let current = aggregator.get_current_bar("AAPL", TimeWindow::Hours1).unwrap();
+        assert_eq!(current.timestamp, utc(2024, 3, 4, 21, 0));
+    }
+
+    #[test]
+    fn test_daily_bars_cover_one_session() {
+        let mut aggregator =
+            BarAggregator::new(vec![TimeWindow::Days1]).with_session(SessionCalendar::us_equities());
+
+        assert!(aggregator.process_trade(&trade_at(100.0, utc(2024, 3, 4, 14, 30))).is_empty());
+        let bars = aggregator.process_trade(&trade_at(101.0, utc(2024, 3, 4, 20, 59)));
+        assert!(bars.is_empty());
+
+        let bars = aggregator.process_trade(&trade_at(102.0, utc(2024, 3, 5, 14, 35)));
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].timestamp, utc(2024, 3, 4, 14, 30));
+        assert_eq!(bars[0].close, Price(101.0));
+        assert_eq!(
+            aggregator.get_current_bar("AAPL", TimeWindow::Days1).unwrap().timestamp,
+            utc(2024, 3, 5, 14, 30)
+        );
+    }
+
+    #[test]
+    fn test_volume_bars_carry_remainder() {
+        let mut aggregator =
+            BarAggregator::new(vec![]).with_bar_types(vec![BarType::VolumeBar(Quantity(100.0))]);
+
+        assert!(aggregator.process_trade(&trade(10.0, 60.0, 0)).is_empty());
+
+        let bars = aggregator.process_trade(&trade(11.0, 70.0, 1));
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].volume, Quantity(100.0));
+        assert_eq!(bars[0].open, Price(10.0));
+        assert_eq!(bars[0].close, Price(11.0));
+
+        // The 30 shares over the threshold start the next bar
+        let current = aggregator
+            .get_current_activity_bar("AAPL", BarType::VolumeBar(Quantity(100.0)))
+            .unwrap();
+        assert!((current.volume.0 - 30.0).abs() < 1e-9);
+        assert_eq!(current.open, Price(11.0));
+    }
+
+    #[test]
+    fn test_oversized_trade_closes_bars_immediately() {
+        let mut aggregator =
+            BarAggregator::new(vec![]).with_bar_types(vec![BarType::VolumeBar(Quantity(100.0))]);
+
+        let bars = aggregator.process_trade(&trade(10.0, 250.0, 0));
+        assert_eq!(bars.len(), 2);
+        assert!(bars.iter().all(|bar| bar.volume == Quantity(100.0)));
+
+        let flushed = aggregator.flush();
+        assert_eq!(flushed.len(), 1);
+        assert!((flushed[0].volume.0 - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dollar_bars() {
+        let mut aggregator =
+            BarAggregator::new(vec![]).with_bar_types(vec![BarType::DollarBar(1_000.0)]);
+
+        assert!(aggregator.process_trade(&trade(10.0, 50.0, 0)).is_empty());
+
+        // $500 + $1000: closes one bar at $1000 and carries $500
+        let bars = aggregator.process_trade(&trade(20.0, 50.0, 1));
+        assert_eq!(bars.len(), 1);
+        assert!((bars[0].volume.0 - 75.0).abs() < 1e-9);
+
+        let current = aggregator
+            .get_current_activity_bar("AAPL", BarType::DollarBar(1_000.0))
+            .unwrap();
+        assert!((current.volume.0 - 25.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_activity_bars_skip_invalid_trades() {
+        let mut aggregator = BarAggregator::new(vec![]).with_bar_types(vec![
+            BarType::DollarBar(1_000.0),
+            BarType::VolumeBar(Quantity(100.0)),
+        ]);
+
+        // Would never reduce the remaining quantity and loop forever
+        assert!(aggregator.process_trade(&trade(0.0, 50.0, 0)).is_empty());
+        assert!(aggregator.process_trade(&trade(-10.0, 50.0, 1)).is_empty());
+        assert!(aggregator.process_trade(&trade(10.0, -50.0, 2)).is_empty());
+        assert!(aggregator.process_trade(&trade(f64::NAN, 50.0, 3)).is_empty());
+        assert!(aggregator
+            .get_current_activity_bar("AAPL", BarType::DollarBar(1_000.0))
+            .is_none());
+
+        // Valid trades still build bars afterwards
+        let bars = aggregator.process_trade(&trade(10.0, 100.0, 4));
+        assert_eq!(bars.len(), 2);
+    }
+
+    #[test]
+    fn test_tick_bars() {
+        let mut aggregator =
+            BarAggregator::new(vec![]).with_bar_types(vec![BarType::TickBar(3)]);
+
+        assert!(aggregator.process_trade(&trade(10.0, 5.0, 0)).is_empty());
+        assert!(aggregator.process_trade(&trade(12.0, 5.0, 1)).is_empty());
+
+        let bars = aggregator.process_trade(&trade(9.0, 5.0, 2));
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].open, Price(10.0));
+        assert_eq!(bars[0].high, Price(12.0));
+        assert_eq!(bars[0].low, Price(9.0));
+        assert_eq!(bars[0].close, Price(9.0));
+        assert_eq!(bars[0].volume, Quantity(15.0));
+
+        assert!(aggregator
+            .get_current_activity_bar("AAPL", BarType::TickBar(3))
+            .is_none());
+    }
+
+    #[test]
+    fn test_renko_gap_spans_three_bricks() {
+        let mut aggregator =
+            BarAggregator::new(vec![]).with_bar_types(vec![BarType::Renko(1.0)]);
+
+        assert!(aggregator.process_trade(&trade(100.0, 10.0, 0)).is_empty());
+        assert!(aggregator.process_trade(&trade(100.6, 10.0, 1)).is_empty());
+
+        // Gap from 100 to 103.4 completes exactly three up bricks
+        let bricks = aggregator.process_trade(&trade(103.4, 10.0, 2));
+        assert_eq!(bricks.len(), 3);
+        let closes: Vec<f64> = bricks.iter().map(|b| b.close.0).collect();
+        assert_eq!(closes, vec![101.0, 102.0, 103.0]);
+        assert_eq!(bricks[1].open, Price(101.0));
+        assert_eq!(bricks[1].high, Price(102.0));
+        assert_eq!(bricks[1].low, Price(101.0));
+
+        // Volume since the previous brick lands on the first brick of the move
+        assert_eq!(bricks[0].volume, Quantity(30.0));
+        assert_eq!(bricks[2].volume, Quantity(0.0));
+
+        // Down move measured from the last brick close (103)
+        let bricks = aggregator.process_trade(&trade(101.0, 10.0, 3));
+        assert_eq!(bricks.len(), 2);
+        assert_eq!(bricks[0].open, Price(103.0));
+        assert_eq!(bricks[0].close, Price(102.0));
+        assert_eq!(bricks[0].high, Price(103.0));
+        assert_eq!(bricks[1].close, Price(101.0));
+    }
+
+    #[test]
+    fn test_invalid_bar_types_ignored() {
+        let mut aggregator = BarAggregator::new(vec![]).with_bar_types(vec![
+            BarType::VolumeBar(Quantity(0.0)),
+            BarType::DollarBar(f64::NAN),
+            BarType::TickBar(0),
+            BarType::Renko(-1.0),
+        ]);
+
+        assert!(aggregator.process_trade(&trade(10.0, 50.0, 0)).is_empty());
+        assert!(aggregator.flush().is_empty());
+    }
+}
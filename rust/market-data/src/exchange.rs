@@ -0,0 +1,307 @@
+use crate::subscriber::MarketMessage;
+use chrono::{DateTime, Utc};
+use common::types::{Bar, Level, OrderBook, Price, Quantity, Side, Symbol, Trade};
+use common::{Result, TradingError};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::{debug, warn};
+
+const ALPACA_WSS_URL: &str = "wss://stream.data.alpaca.markets/v2/iex";
+
+/// Exchange-specific wire protocol for `WebSocketClient`
+///
+/// An adapter owns everything that differs between venues: the endpoint, the
+/// authentication and subscription frames, and how text frames map onto
+/// exchange-neutral `MarketMessage`s. Top-of-book quotes are reported as
+/// one-level `OrderBook` snapshots.
+pub trait ExchangeAdapter: Send + Sync {
+    /// Exchange name as used in `MarketDataConfig.exchange`
+    fn name(&self) -> &'static str;
+
+    /// WebSocket endpoint
+    fn url(&self) -> &str;
+
+    /// Authentication frame sent right after connecting, if the venue needs one
+    fn auth(&self, api_key: &str, api_secret: &str) -> Option<String>;
+
+    /// Subscription frame for trades, quotes and bars on `symbols`
+    fn subscribe_message(&self, symbols: &[String]) -> String;
+
+    /// Decode a text frame; control messages yield nothing
+    ///
+    /// Returns a `Vec` because venues such as Alpaca batch several events
+    /// into one frame.
+    fn parse_message(&self, raw: &str) -> Vec<MarketMessage>;
+
+    /// Whether parsed trades carry the real aggressor side. When false the
+    /// service infers it from the book.
+    fn reports_trade_side(&self) -> bool {
+        false
+    }
+}
+
+/// Select the adapter for a configured exchange name
+pub fn adapter_for(exchange: &str) -> Result<Box<dyn ExchangeAdapter>> {
+    match exchange.to_ascii_lowercase().as_str() {
+        "alpaca" => Ok(Box::new(AlpacaAdapter::default())),
+        other => Err(TradingError::Configuration(format!(
+            "Unsupported market data exchange: {}",
+            other
+        ))),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "T")]
+pub enum AlpacaMessage {
+    #[serde(rename = "t")]
+    Trade {
+        #[serde(rename = "S")]
+        symbol: String,
+        #[serde(rename = "p")]
+        price: f64,
+        #[serde(rename = "s")]
+        size: f64,
+        #[serde(rename = "t")]
+        timestamp: String,
+        #[serde(rename = "i")]
+        id: u64,
+    },
+    #[serde(rename = "q")]
+    Quote {
+        #[serde(rename = "S")]
+        symbol: String,
+        #[serde(rename = "bp")]
+        bid_price: f64,
+        #[serde(rename = "bs")]
+        bid_size: f64,
+        #[serde(rename = "ap")]
+        ask_price: f64,
+        #[serde(rename = "as")]
+        ask_size: f64,
+        #[serde(rename = "t")]
+        timestamp: String,
+    },
+    #[serde(rename = "b")]
+    Bar {
+        #[serde(rename = "S")]
+        symbol: String,
+        #[serde(rename = "o")]
+        open: f64,
+        #[serde(rename = "h")]
+        high: f64,
+        #[serde(rename = "l")]
+        low: f64,
+        #[serde(rename = "c")]
+        close: f64,
+        #[serde(rename = "v")]
+        volume: f64,
+        #[serde(rename = "t")]
+        timestamp: String,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+impl AlpacaMessage {
+    pub fn symbol(&self) -> Option<&str> {
+        match self {
+            AlpacaMessage::Trade { symbol, .. }
+            | AlpacaMessage::Quote { symbol, .. }
+            | AlpacaMessage::Bar { symbol, .. } => Some(symbol),
+            AlpacaMessage::Unknown => None,
+        }
+    }
+}
+
+/// Alpaca market data v2 stream
+pub struct AlpacaAdapter {
+    url: String,
+}
+
+impl AlpacaAdapter {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+
+    /// Parse a text frame into Alpaca messages, skipping control messages
+    pub fn parse_alpaca(text: &str) -> Vec<AlpacaMessage> {
+        // Try to parse as array of messages
+        if let Ok(messages) = serde_json::from_str::<Vec<AlpacaMessage>>(text) {
+            messages
+                .into_iter()
+                .filter(|msg| match msg {
+                    AlpacaMessage::Unknown => {
+                        debug!("Unknown message type: {}", text);
+                        false
+                    }
+                    _ => true,
+                })
+                .collect()
+        } else {
+            if let Ok(value) = serde_json::from_str::<Value>(text) {
+                // Handle control messages (auth confirmation, subscription confirmation, etc.)
+                debug!("Control message: {:?}", value);
+            } else {
+                warn!("Failed to parse message: {}", text);
+            }
+            Vec::new()
+        }
+    }
+}
+
+impl Default for AlpacaAdapter {
+    fn default() -> Self {
+        Self::new(ALPACA_WSS_URL)
+    }
+}
+
+impl ExchangeAdapter for AlpacaAdapter {
+    fn name(&self) -> &'static str {
+        "alpaca"
+    }
+
+    fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn auth(&self, api_key: &str, api_secret: &str) -> Option<String> {
+        let auth_msg = json!({
+            "action": "auth",
+            "key": api_key,
+            "secret": api_secret
+        });
+        Some(auth_msg.to_string())
+    }
+
+    fn subscribe_message(&self, symbols: &[String]) -> String {
+        let subscribe_msg = json!({
+            "action": "subscribe",
+            "trades": symbols,
+            "quotes": symbols,
+            "bars": symbols
+        });
+        subscribe_msg.to_string()
+    }
+
+    fn parse_message(&self, raw: &str) -> Vec<MarketMessage> {
+        Self::parse_alpaca(raw)
+            .into_iter()
+            .filter_map(|msg| match msg {
+                // Alpaca stock trades don't report the aggressor side
+                AlpacaMessage::Trade { symbol, price, size, timestamp, id } => {
+                    Some(MarketMessage::Trade(Trade {
+                        symbol: Symbol(symbol),
+                        price: Price(price),
+                        quantity: Quantity(size),
+                        side: Side::Bid,
+                        timestamp: parse_timestamp(&timestamp),
+                        trade_id: id.to_string(),
+                    }))
+                }
+                AlpacaMessage::Quote {
+                    symbol,
+                    bid_price,
+                    bid_size,
+                    ask_price,
+                    ask_size,
+                    timestamp,
+                } => {
+                    let timestamp = parse_timestamp(&timestamp);
+                    let level = |price: f64, quantity: f64| Level {
+                        price: Price(price),
+                        quantity: Quantity(quantity),
+                        timestamp,
+                    };
+                    Some(MarketMessage::OrderBook(OrderBook {
+                        symbol: Symbol(symbol),
+                        bids: vec![level(bid_price, bid_size)],
+                        asks: vec![level(ask_price, ask_size)],
+                        timestamp,
+                        sequence: 0,
+                    }))
+                }
+                AlpacaMessage::Bar { symbol, open, high, low, close, volume, timestamp } => {
+                    Some(MarketMessage::Bar(Bar {
+                        symbol: Symbol(symbol),
+                        open: Price(open),
+                        high: Price(high),
+                        low: Price(low),
+                        close: Price(close),
+                        volume: Quantity(volume),
+                        timestamp: parse_timestamp(&timestamp),
+                    }))
+                }
+                AlpacaMessage::Unknown => None,
+            })
+            .collect()
+    }
+}
+
+/// Parse an RFC 3339 feed timestamp, falling back to the local clock
+fn parse_timestamp(timestamp: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|ts| ts.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_text_skips_control_messages() {
+        let control = r#"[{"T":"success","msg":"authenticated"}]"#;
+        assert!(AlpacaAdapter::parse_alpaca(control).is_empty());
+
+        let mixed = r#"[{"T":"success","msg":"connected"},{"T":"t","S":"AAPL","p":150.25,"s":100,"t":"2024-01-01T10:00:00Z","i":1}]"#;
+        let messages = AlpacaAdapter::parse_alpaca(mixed);
+        assert_eq!(messages.len(), 1);
+        assert!(matches!(messages[0], AlpacaMessage::Trade { .. }));
+    }
+
+    #[test]
+    fn test_alpaca_adapter_maps_to_market_messages() {
+        let adapter = AlpacaAdapter::default();
+        let raw = r#"[{"T":"t","S":"AAPL","p":150.25,"s":100,"t":"2024-01-01T10:00:00Z","i":7},{"T":"q","S":"AAPL","bp":150.00,"bs":10,"ap":150.05,"as":5,"t":"2024-01-01T10:00:00Z"}]"#;
+
+        let messages = adapter.parse_message(raw);
+        assert_eq!(messages.len(), 2);
+
+        match &messages[0] {
+            MarketMessage::Trade(trade) => {
+                assert_eq!(trade.price, Price(150.25));
+                assert_eq!(trade.trade_id, "7");
+                assert_eq!(trade.timestamp.to_rfc3339(), "2024-01-01T10:00:00+00:00");
+            }
+            other => panic!("expected trade, got {:?}", other),
+        }
+        match &messages[1] {
+            MarketMessage::OrderBook(book) => {
+                assert_eq!(book.bids[0].price, Price(150.0));
+                assert_eq!(book.asks[0].quantity, Quantity(5.0));
+            }
+            other => panic!("expected book, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_alpaca_handshake_frames() {
+        let adapter = AlpacaAdapter::default();
+        let auth: Value = serde_json::from_str(&adapter.auth("k", "s").unwrap()).unwrap();
+        assert_eq!(auth, json!({"action": "auth", "key": "k", "secret": "s"}));
+
+        let symbols = vec!["AAPL".to_string()];
+        let subscribe: Value = serde_json::from_str(&adapter.subscribe_message(&symbols)).unwrap();
+        assert_eq!(subscribe["trades"], json!(["AAPL"]));
+        assert_eq!(subscribe["quotes"], json!(["AAPL"]));
+        assert_eq!(subscribe["bars"], json!(["AAPL"]));
+    }
+
+    #[test]
+    fn test_adapter_for() {
+        assert_eq!(adapter_for("alpaca").unwrap().name(), "alpaca");
+        assert_eq!(adapter_for("Alpaca").unwrap().name(), "alpaca");
+        assert!(matches!(adapter_for("binance"), Err(TradingError::Configuration(_))));
+    }
+}
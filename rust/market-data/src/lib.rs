@@ -4,17 +4,31 @@
 /// and tick-to-bar aggregation. Publishes market data via ZMQ.
 
 pub mod websocket;
+pub mod exchange;
 pub mod orderbook;
 pub mod aggregation;
 pub mod publisher;
+pub mod subscriber;
+pub mod replay;
 
 pub use websocket::WebSocketClient;
-pub use orderbook::OrderBookManager;
-pub use aggregation::{BarAggregator, TimeWindow};
-pub use publisher::MarketDataPublisher;
+pub use exchange::{adapter_for, AlpacaAdapter, AlpacaMessage, ExchangeAdapter};
+pub use orderbook::{BookFeatures, L3OrderBook, LevelChange, OrderBookError, OrderBookManager, TopOfBookEvent};
+pub use aggregation::{BarAggregator, BarEvent, BarType, MissingBar, SessionCalendar, TimeWindow};
+pub use publisher::{Compression, MarketDataPublisher};
+pub use subscriber::{MarketDataSubscriber, MarketMessage};
+pub use replay::{OrderBookReplay, ReplayEvent};
 
-use common::{Result, TradingError};
-use tracing::{info, error};
+use common::messaging::Message;
+use common::types::Side;
+use common::{HealthCheck, Readiness, Result, Shutdown, TradingError};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{debug, info, error, warn};
+
+/// Number of levels per side published in book snapshots
+const SNAPSHOT_LEVELS: usize = 10;
 
 /// Main market data service
 pub struct MarketDataService {
@@ -22,6 +36,11 @@ pub struct MarketDataService {
     orderbook_manager: OrderBookManager,
     bar_aggregator: BarAggregator,
     publisher: MarketDataPublisher,
+    health: Arc<RwLock<HealthCheck>>,
+    readiness: Readiness,
+    shutdown: Shutdown,
+    stale_after: Duration,
+    feed_stale: bool,
 }
 
 impl MarketDataService {
@@ -40,7 +59,9 @@ impl MarketDataService {
             ))?;
 
         // Create WebSocket client with proper parameters
-        let ws_client = WebSocketClient::new(
+        let adapter = adapter_for(&config.exchange)?;
+        let ws_client = WebSocketClient::with_adapter(
+            adapter,
             api_key,
             api_secret,
             config.symbols.clone(),
@@ -48,13 +69,15 @@ impl MarketDataService {
 
         let orderbook_manager = OrderBookManager::new();
 
-        // Create BarAggregator with default time windows
-        let time_windows = vec![
-            TimeWindow::Minutes1,
-            TimeWindow::Minutes5,
-            TimeWindow::Minutes15,
-        ];
-        let bar_aggregator = BarAggregator::new(time_windows);
+        let time_windows = config
+            .bar_windows
+            .iter()
+            .map(|window| window.parse())
+            .collect::<Result<Vec<TimeWindow>>>()?;
+        let mut bar_aggregator = BarAggregator::new(time_windows).with_forward_fill(config.forward_fill_bars);
+        if let Some(session) = &config.session {
+            bar_aggregator = bar_aggregator.with_session(SessionCalendar::from_config(session)?);
+        }
 
         let publisher = MarketDataPublisher::new(&config.zmq_publish_address)?;
 
@@ -63,21 +86,177 @@ impl MarketDataService {
             orderbook_manager,
             bar_aggregator,
             publisher,
+            health: Arc::new(RwLock::new(HealthCheck::healthy("market-data"))),
+            readiness: Readiness::new(),
+            shutdown: Shutdown::default(),
+            stale_after: Duration::from_millis(config.stale_after_ms),
+            feed_stale: false,
         })
     }
 
+    /// Clock used by the staleness watchdog
+    pub fn with_clock(mut self, clock: common::SharedClock) -> Self {
+        self.ws_client = self.ws_client.with_clock(clock);
+        self
+    }
+
+    /// Share a health status that the staleness watchdog updates
+    pub fn with_health(mut self, health: Arc<RwLock<HealthCheck>>) -> Self {
+        self.health = health;
+        self
+    }
+
+    pub fn health(&self) -> Arc<RwLock<HealthCheck>> {
+        Arc::clone(&self.health)
+    }
+
+    /// Share a readiness flag, set once `run` has connected to the feed and
+    /// cleared when it stops
+    pub fn with_readiness(mut self, readiness: Readiness) -> Self {
+        self.readiness = readiness;
+        self
+    }
+
+    pub fn readiness(&self) -> Readiness {
+        self.readiness.clone()
+    }
+
+    /// Share a shutdown coordinator; `run` returns once shutdown begins,
+    /// after finishing the message it is processing
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Whether `symbol` has gone without data for longer than `stale_after_ms`
+    pub fn is_stale(&self, symbol: &str) -> bool {
+        self.ws_client.is_stale(symbol, self.stale_after)
+    }
+
     pub async fn run(&mut self) -> Result<()> {
         info!("Starting Market Data Service");
 
+        let shutdown = self.shutdown.token();
+
         // Main processing loop
         loop {
-            // TODO: Implement event processing
-            // - Receive WebSocket messages
-            // - Update order book
-            // - Aggregate bars
-            // - Publish updates
+            if !self.ws_client.is_connected() {
+                let connected = tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    result = self.ws_client.connect_with_retry() => result,
+                };
+                if let Err(e) = connected {
+                    error!("Market data feed unavailable: {}", e);
+                    self.readiness.mark_not_ready();
+                    return Err(e);
+                }
+                self.readiness.mark_ready();
+            }
+
+            let next = tokio::select! {
+                _ = shutdown.cancelled() => break,
+                next = tokio::time::timeout(self.stale_after, self.ws_client.next_message()) => next,
+            };
+            match next {
+                Ok(Some(msg)) => {
+                    if self.feed_stale {
+                        self.mark_feed_fresh().await;
+                    }
+                    if let Err(e) = self.handle_message(msg) {
+                        warn!("Failed to process market data message: {}", e);
+                    }
+                }
+                Ok(None) => {
+                    warn!("WebSocket disconnected, reconnecting");
+                }
+                Err(_) => {
+                    self.mark_feed_stale().await;
+                    self.ws_client.disconnect();
+                }
+            }
+        }
+
+        info!("Shutdown requested, stopping Market Data Service");
+        self.readiness.mark_not_ready();
+        self.ws_client.disconnect();
+        Ok(())
+    }
+
+    /// Watchdog: no data within `stale_after_ms`, so report unhealthy and
+    /// force a reconnect
+    async fn mark_feed_stale(&mut self) {
+        let age = self.ws_client.last_message_age().unwrap_or(self.stale_after);
+        warn!("No market data for {:?}, forcing reconnect", age);
+        metrics::counter!("market_data_stale_total").increment(1);
+
+        self.feed_stale = true;
+        let mut health = self.health.write().await;
+        *health = HealthCheck::unhealthy(
+            "market-data",
+            format!("Market data stale: no messages for {}ms", age.as_millis()),
+        );
+    }
+
+    async fn mark_feed_fresh(&mut self) {
+        info!("Market data feed recovered");
+
+        self.feed_stale = false;
+        let mut health = self.health.write().await;
+        *health = HealthCheck::healthy("market-data").with_metric("status", "running");
+    }
+
+    /// Route one feed message through the book, the aggregator and the publisher
+    fn handle_message(&mut self, msg: MarketMessage) -> Result<()> {
+        match msg {
+            MarketMessage::Trade(mut trade) => {
+                if !self.ws_client.adapter().reports_trade_side() {
+                    trade.side = self.aggressor_side(&trade.symbol.0, trade.price.0);
+                }
+
+                for event in self.bar_aggregator.process_trade_events(&trade) {
+                    match event {
+                        BarEvent::Bar(bar) => self.publisher.publish(Message::BarUpdate(bar))?,
+                        BarEvent::Missing(missing) => debug!(
+                            "No {} trades for {} in window starting {}{}",
+                            missing.window.label(),
+                            missing.symbol.0,
+                            missing.timestamp,
+                            if missing.forward_filled { ", forward-filled" } else { "" }
+                        ),
+                    }
+                }
+                self.publisher.publish(Message::TradeUpdate(trade))?;
+            }
+            MarketMessage::OrderBook(mut book) => {
+                // Quotes are top-of-book only, so each one replaces the book
+                let symbol = book.symbol.0.clone();
+                book.sequence = self
+                    .orderbook_manager
+                    .get(&symbol)
+                    .map_or(1, |existing| existing.sequence() + 1);
+                self.orderbook_manager.apply_snapshot(&symbol, book);
+
+                if let Some(snapshot) = self.orderbook_manager.get_snapshot(&symbol, SNAPSHOT_LEVELS) {
+                    self.publisher.publish(Message::OrderBookUpdate(snapshot))?;
+                }
+            }
+            MarketMessage::Bar(bar) => {
+                self.publisher.publish(Message::BarUpdate(bar))?;
+            }
+            // Exchange feeds never carry signals
+            MarketMessage::Signal(_) => {}
+        }
+
+        Ok(())
+    }
 
-            tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
+    /// Classify a trade with the quote rule: at or above the mid is
+    /// buyer-initiated, below is seller-initiated. Trades with no book yet
+    /// default to buyer-initiated.
+    fn aggressor_side(&self, symbol: &str, price: f64) -> Side {
+        match self.orderbook_manager.get(symbol).and_then(|book| book.mid_price()) {
+            Some(mid) if price < mid.0 => Side::Ask,
+            _ => Side::Bid,
         }
     }
 }
@@ -2,6 +2,7 @@ use market_data::MarketDataService;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 use common::config::SystemConfig;
 use common::health::HealthCheck;
+use common::Shutdown;
 use common::metrics::{MetricsConfig, start_metrics_server};
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -17,7 +18,7 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("Market Data Service starting...");
 
     // Load configuration with validation
-    let config = match SystemConfig::from_file("config/system.json") {
+    let config = match SystemConfig::from_file_with_env("config/system.json") {
         Ok(cfg) => {
             tracing::info!(
                 "Configuration loaded successfully - Environment: {}, Paper Trading: {}",
@@ -56,11 +57,23 @@ async fn main() -> anyhow::Result<()> {
     // Store values before move
     let symbols_count = config.market_data.symbols.len();
 
+    // Stop the run loop on Ctrl-C
+    let shutdown = Shutdown::default();
+    {
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                tracing::info!("Shutdown signal received, stopping Market Data Service...");
+                shutdown.begin();
+            }
+        });
+    }
+
     // Initialize service
     let mut service = match MarketDataService::new(config.market_data).await {
         Ok(svc) => {
             tracing::info!("✓ Market Data Service initialized successfully");
-            svc
+            svc.with_health(Arc::clone(&health)).with_shutdown(shutdown.clone())
         }
         Err(e) => {
             tracing::error!("Failed to initialize service: {}", e);
@@ -94,6 +107,8 @@ async fn main() -> anyhow::Result<()> {
         }
     };
 
+    shutdown.drain().await;
+
     // Stop metrics server
     if let Some(handle) = metrics_handle {
         handle.abort();
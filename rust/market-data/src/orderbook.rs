@@ -1,6 +1,148 @@
 use common::types::{Level, OrderBook, Price, Quantity, Side, Symbol};
+use common::TradingError;
 use chrono::Utc;
-use std::collections::{BTreeMap, HashMap};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Mutex;
+use thiserror::Error;
+use tokio::sync::mpsc;
+
+/// Buffered top-of-book events per subscriber before new events are dropped
+const TOP_OF_BOOK_CHANNEL_CAPACITY: usize = 1024;
+
+/// Errors raised while maintaining a book from an exchange feed
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum OrderBookError {
+    /// A delta arrived out of order. `got < expected` is a duplicate or stale
+    /// message; `got > expected` means messages were lost and the book must be
+    /// rebuilt from a fresh snapshot.
+    #[error("Sequence gap: expected {expected}, got {got}")]
+    SequenceGap { expected: u64, got: u64 },
+
+    #[error("No snapshot applied for symbol {0}")]
+    MissingSnapshot(String),
+}
+
+impl From<OrderBookError> for TradingError {
+    fn from(err: OrderBookError) -> Self {
+        TradingError::MarketData(err.to_string())
+    }
+}
+
+/// Fixed-point scale for price keys (8 decimal places)
+const PRICE_SCALE: f64 = 100000000.0;
+
+/// Rounded rather than truncated, so `price_key(key_price(k)) == k` and a
+/// price like 100.07 (stored as 100.06999...) keys to the intended level
+#[inline]
+fn price_key(price: Price) -> u64 {
+    (price.0 * PRICE_SCALE).round() as u64
+}
+
+#[inline]
+fn key_price(price_key: u64) -> Price {
+    Price(price_key as f64 / PRICE_SCALE)
+}
+
+/// Change to one price level between two book states
+///
+/// A zero `new_qty` removes the level; otherwise the level is added or its
+/// quantity replaced.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LevelChange {
+    pub side: Side,
+    pub price: Price,
+    pub new_qty: Quantity,
+}
+
+/// Changes turning the levels in `from` into those in `to`, in price order
+fn diff_levels(
+    side: Side,
+    from: &BTreeMap<u64, Quantity>,
+    to: &BTreeMap<u64, Quantity>,
+    changes: &mut Vec<LevelChange>,
+) {
+    let mut from_iter = from.iter().peekable();
+    let mut to_iter = to.iter().peekable();
+
+    loop {
+        let change = match (from_iter.peek(), to_iter.peek()) {
+            (None, None) => break,
+            (Some((&key, _)), None) => {
+                from_iter.next();
+                Some((key, Quantity(0.0)))
+            }
+            (None, Some((&key, &qty))) => {
+                to_iter.next();
+                Some((key, qty))
+            }
+            (Some((&from_key, &from_qty)), Some((&to_key, &to_qty))) => {
+                if from_key < to_key {
+                    from_iter.next();
+                    Some((from_key, Quantity(0.0)))
+                } else if to_key < from_key {
+                    to_iter.next();
+                    Some((to_key, to_qty))
+                } else {
+                    from_iter.next();
+                    to_iter.next();
+                    (from_qty != to_qty).then_some((to_key, to_qty))
+                }
+            }
+        };
+
+        if let Some((key, new_qty)) = change {
+            changes.push(LevelChange { side, price: key_price(key), new_qty });
+        }
+    }
+}
+
+/// Microstructure features computed from a book at one point in time
+///
+/// Price-derived fields are `None` when either side of the book is empty.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookFeatures {
+    pub mid: Option<f64>,
+    pub microprice: Option<f64>,
+    pub spread_bps: Option<f64>,
+    /// Depth imbalance over `levels` levels per side
+    pub imbalance: f64,
+    pub levels: usize,
+    pub best_bid_size: f64,
+    pub best_ask_size: f64,
+}
+
+impl BookFeatures {
+    /// One `MetricRecord` per available feature, ready for
+    /// `DatabaseManager::insert_metrics`
+    #[cfg(feature = "database")]
+    pub fn to_metrics(
+        &self,
+        symbol: &str,
+        timestamp: chrono::DateTime<Utc>,
+    ) -> Vec<database::MetricRecord> {
+        let features = [
+            ("book_mid", self.mid),
+            ("book_microprice", self.microprice),
+            ("book_spread_bps", self.spread_bps),
+            ("book_imbalance", Some(self.imbalance)),
+            ("book_best_bid_size", Some(self.best_bid_size)),
+            ("book_best_ask_size", Some(self.best_ask_size)),
+        ];
+
+        features
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|v| (name, v)))
+            .map(|(name, value)| {
+                let mut record = database::MetricRecord::new(name, value)
+                    .with_symbol(symbol)
+                    .add_label("levels", self.levels.to_string());
+                record.timestamp = timestamp;
+                record
+            })
+            .collect()
+    }
+}
 
 /// High-performance order book using BTreeMap (optimized from BinaryHeap)
 /// OPTIMIZATION: BTreeMap provides O(log n) insert/remove with sorted iteration
@@ -12,6 +154,9 @@ pub struct FastOrderBook {
     asks: BTreeMap<u64, Quantity>,  // price_key -> quantity (sorted)
     sequence: u64,
     last_update_ns: i64,
+    last_update_side: Option<Side>,
+    /// Maximum levels kept per side; unbounded when `None`
+    max_depth: Option<usize>,
 }
 
 impl FastOrderBook {
@@ -22,6 +167,38 @@ impl FastOrderBook {
             asks: BTreeMap::new(),
             sequence: 0,
             last_update_ns: 0,
+            last_update_side: None,
+            max_depth: None,
+        }
+    }
+
+    /// Book keeping at most `max_depth` (at least 1) levels per side.
+    ///
+    /// When a side grows past the cap its worst-priced level is evicted, so
+    /// only the most competitive levels are retained and a feed spraying
+    /// far-away prices cannot grow the book without bound.
+    pub fn with_depth(symbol: Symbol, max_depth: usize) -> Self {
+        Self {
+            max_depth: Some(max_depth.max(1)),
+            ..Self::new(symbol)
+        }
+    }
+
+    /// Maximum levels kept per side, if bounded
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    /// Drop the lowest bids and highest asks beyond `max_depth`
+    fn evict_excess(&mut self) {
+        let Some(max_depth) = self.max_depth else {
+            return;
+        };
+        while self.bids.len() > max_depth {
+            self.bids.pop_first();
+        }
+        while self.asks.len() > max_depth {
+            self.asks.pop_last();
         }
     }
 
@@ -31,14 +208,16 @@ impl FastOrderBook {
     pub fn update_bid(&mut self, price: Price, quantity: Quantity) {
         let start = std::time::Instant::now();
 
-        let price_key = (price.0 * 100000000.0) as u64;
+        let price_key = price_key(price);
 
         if quantity.0 == 0.0 {
             self.bids.remove(&price_key);
         } else {
             self.bids.insert(price_key, quantity);
+            self.evict_excess();
         }
 
+        self.last_update_side = Some(Side::Bid);
         self.sequence += 1;
         self.last_update_ns = start.elapsed().as_nanos() as i64;
     }
@@ -49,14 +228,16 @@ impl FastOrderBook {
     pub fn update_ask(&mut self, price: Price, quantity: Quantity) {
         let start = std::time::Instant::now();
 
-        let price_key = (price.0 * 100000000.0) as u64;
+        let price_key = price_key(price);
 
         if quantity.0 == 0.0 {
             self.asks.remove(&price_key);
         } else {
             self.asks.insert(price_key, quantity);
+            self.evict_excess();
         }
 
+        self.last_update_side = Some(Side::Ask);
         self.sequence += 1;
         self.last_update_ns = start.elapsed().as_nanos() as i64;
     }
@@ -65,23 +246,88 @@ impl FastOrderBook {
     /// BTreeMap keeps entries sorted, just get the last (highest) key
     #[inline]
     pub fn best_bid(&self) -> Option<Price> {
-        self.bids.iter().next_back().map(|(price_key, _)| {
-            Price(*price_key as f64 / 100000000.0)
-        })
+        self.bids.iter().next_back().map(|(price_key, _)| key_price(*price_key))
     }
 
     /// Get best ask price (lowest ask) - OPTIMIZED
     /// BTreeMap keeps entries sorted, just get the first (lowest) key
     #[inline]
     pub fn best_ask(&self) -> Option<Price> {
-        self.asks.iter().next().map(|(price_key, _)| {
-            Price(*price_key as f64 / 100000000.0)
-        })
+        self.asks.iter().next().map(|(price_key, _)| key_price(*price_key))
+    }
+
+    /// Best bid strictly above best ask
+    #[inline]
+    pub fn is_crossed(&self) -> bool {
+        match (self.bids.keys().next_back(), self.asks.keys().next()) {
+            (Some(bid), Some(ask)) => bid > ask,
+            _ => false,
+        }
+    }
+
+    /// Best bid equal to best ask
+    #[inline]
+    pub fn is_locked(&self) -> bool {
+        match (self.bids.keys().next_back(), self.asks.keys().next()) {
+            (Some(bid), Some(ask)) => bid == ask,
+            _ => false,
+        }
+    }
+
+    /// Remove levels that cross the side updated most recently
+    ///
+    /// The latest update is treated as authoritative, so stale levels on the
+    /// opposite side at or through its touch are dropped. With no update yet
+    /// recorded (e.g. straight after a snapshot) the bids are trimmed.
+    /// Returns the number of levels removed.
+    pub fn sanitize(&mut self) -> usize {
+        if !self.is_crossed() {
+            return 0;
+        }
+
+        let removed = match self.last_update_side {
+            // A crossed book has both sides, so the keys are present
+            Some(Side::Bid) => {
+                let best_bid = *self.bids.keys().next_back().unwrap_or(&0);
+                let kept = self.asks.split_off(&best_bid.saturating_add(1));
+                std::mem::replace(&mut self.asks, kept).len()
+            }
+            _ => {
+                let best_ask = *self.asks.keys().next().unwrap_or(&u64::MAX);
+                self.bids.split_off(&best_ask).len()
+            }
+        };
+
+        self.sequence += 1;
+        removed
+    }
+
+    /// Best bid price and size
+    #[inline]
+    pub fn best_bid_level(&self) -> Option<(Price, Quantity)> {
+        self.bids
+            .iter()
+            .next_back()
+            .map(|(price_key, qty)| (key_price(*price_key), *qty))
+    }
+
+    /// Best ask price and size
+    #[inline]
+    pub fn best_ask_level(&self) -> Option<(Price, Quantity)> {
+        self.asks
+            .iter()
+            .next()
+            .map(|(price_key, qty)| (key_price(*price_key), *qty))
     }
 
     /// Get mid price
+    ///
+    /// `None` if either side is empty or the book is crossed.
     #[inline]
     pub fn mid_price(&self) -> Option<Price> {
+        if self.is_crossed() {
+            return None;
+        }
         match (self.best_bid(), self.best_ask()) {
             (Some(bid), Some(ask)) => Some(Price((bid.0 + ask.0) / 2.0)),
             _ => None,
@@ -89,8 +335,13 @@ impl FastOrderBook {
     }
 
     /// Get spread in basis points
+    ///
+    /// `None` if either side is empty or the book is crossed.
     #[inline]
     pub fn spread_bps(&self) -> Option<f64> {
+        if self.is_crossed() {
+            return None;
+        }
         match (self.best_bid(), self.best_ask()) {
             (Some(bid), Some(ask)) => {
                 let mid = (bid.0 + ask.0) / 2.0;
@@ -144,7 +395,7 @@ impl FastOrderBook {
                 break;
             }
 
-            let price = *price_key as f64 / 100000000.0;
+            let price = key_price(*price_key).0;
             let available = quantity.0;
             let fill_qty = remaining.min(available);
 
@@ -174,6 +425,82 @@ impl FastOrderBook {
         }
     }
 
+    /// Depth imbalance with level i on each side weighted by `decay^i`
+    ///
+    /// Level 0 is the touch, so a `decay` below 1 makes near-touch liquidity
+    /// count more. Returns 0.0 when both sides are empty.
+    pub fn weighted_imbalance(&self, num_levels: usize, decay: f64) -> f64 {
+        let weighted = |levels: &mut dyn Iterator<Item = &Quantity>| -> f64 {
+            levels
+                .take(num_levels)
+                .zip(std::iter::successors(Some(1.0), |w| Some(w * decay)))
+                .map(|(qty, weight)| qty.0 * weight)
+                .sum()
+        };
+
+        let bid_depth = weighted(&mut self.bids.values().rev());
+        let ask_depth = weighted(&mut self.asks.values());
+        let total = bid_depth + ask_depth;
+
+        if total > 0.0 {
+            (bid_depth - ask_depth) / total
+        } else {
+            0.0
+        }
+    }
+
+    /// Size-weighted microprice from the top of book
+    ///
+    /// `(bid * ask_size + ask * bid_size) / (bid_size + ask_size)`, which leans
+    /// toward the side with less resting size. `None` on a crossed book.
+    pub fn microprice(&self) -> Option<Price> {
+        if self.is_crossed() {
+            return None;
+        }
+        let (bid_key, bid_qty) = self.bids.iter().next_back()?;
+        let (ask_key, ask_qty) = self.asks.iter().next()?;
+
+        let bid = key_price(*bid_key).0;
+        let ask = key_price(*ask_key).0;
+        let total = bid_qty.0 + ask_qty.0;
+
+        Some(Price((bid * ask_qty.0 + ask * bid_qty.0) / total))
+    }
+
+    /// Mid of the volume-weighted bid and ask prices over the top N levels
+    pub fn weighted_mid(&self, num_levels: usize) -> Option<Price> {
+        if self.is_crossed() {
+            return None;
+        }
+        let vwap = |levels: &mut dyn Iterator<Item = (&u64, &Quantity)>| {
+            let (notional, quantity) = levels.take(num_levels).fold(
+                (0.0, 0.0),
+                |(notional, quantity), (price_key, qty)| {
+                    (notional + key_price(*price_key).0 * qty.0, quantity + qty.0)
+                },
+            );
+            (quantity > 0.0).then_some(notional / quantity)
+        };
+
+        let bid = vwap(&mut self.bids.iter().rev())?;
+        let ask = vwap(&mut self.asks.iter())?;
+
+        Some(Price((bid + ask) / 2.0))
+    }
+
+    /// Snapshot of microstructure features, with imbalance over `num_levels`
+    pub fn features(&self, num_levels: usize) -> BookFeatures {
+        BookFeatures {
+            mid: self.mid_price().map(|p| p.0),
+            microprice: self.microprice().map(|p| p.0),
+            spread_bps: self.spread_bps(),
+            imbalance: self.imbalance(num_levels),
+            levels: num_levels,
+            best_bid_size: self.bids.values().next_back().map_or(0.0, |q| q.0),
+            best_ask_size: self.asks.values().next().map_or(0.0, |q| q.0),
+        }
+    }
+
     /// Convert to snapshot - OPTIMIZED
     pub fn to_snapshot(&self, max_levels: usize) -> OrderBook {
         let bids: Vec<Level> = self
@@ -182,7 +509,7 @@ impl FastOrderBook {
             .rev()  // Reverse to get highest bids first
             .take(max_levels)
             .map(|(price_key, quantity)| Level {
-                price: Price(*price_key as f64 / 100000000.0),
+                price: key_price(*price_key),
                 quantity: *quantity,
                 timestamp: Utc::now(),
             })
@@ -193,7 +520,7 @@ impl FastOrderBook {
             .iter()
             .take(max_levels)
             .map(|(price_key, quantity)| Level {
-                price: Price(*price_key as f64 / 100000000.0),
+                price: key_price(*price_key),
                 quantity: *quantity,
                 timestamp: Utc::now(),
             })
@@ -212,20 +539,400 @@ impl FastOrderBook {
     pub fn last_update_latency_ns(&self) -> i64 {
         self.last_update_ns
     }
+
+    /// Symbol this book is for
+    pub fn symbol(&self) -> &Symbol {
+        &self.symbol
+    }
+
+    /// Current book sequence number
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Replace the book contents with an exchange snapshot
+    ///
+    /// The book adopts the snapshot's sequence number, so subsequent deltas
+    /// must start at `snapshot.sequence + 1`.
+    pub fn apply_snapshot(&mut self, snapshot: &OrderBook) {
+        self.bids.clear();
+        self.asks.clear();
+
+        for level in &snapshot.bids {
+            if level.quantity.0 > 0.0 {
+                self.bids.insert(price_key(level.price), level.quantity);
+            }
+        }
+        for level in &snapshot.asks {
+            if level.quantity.0 > 0.0 {
+                self.asks.insert(price_key(level.price), level.quantity);
+            }
+        }
+        self.evict_excess();
+
+        self.sequence = snapshot.sequence;
+        self.last_update_side = None;
+    }
+
+    /// Apply an incremental update if it directly follows the current sequence
+    ///
+    /// A zero quantity removes the level. On any sequence mismatch the book is
+    /// left untouched.
+    pub fn apply_delta(
+        &mut self,
+        sequence: u64,
+        deltas: &[(Side, Price, Quantity)],
+    ) -> Result<(), OrderBookError> {
+        let expected = self.sequence + 1;
+        if sequence != expected {
+            return Err(OrderBookError::SequenceGap { expected, got: sequence });
+        }
+
+        for (side, price, quantity) in deltas {
+            match side {
+                Side::Bid => self.update_bid(*price, *quantity),
+                Side::Ask => self.update_ask(*price, *quantity),
+            }
+        }
+
+        // update_bid/update_ask bump the local counter; the feed sequence wins
+        self.sequence = sequence;
+        Ok(())
+    }
+
+    /// CRC32 checksum over the top `levels` of each side
+    ///
+    /// The checksum input follows the Kraken convention: the top asks
+    /// (lowest first) followed by the top bids (highest first), each level
+    /// contributing its price string then its quantity string. Every string
+    /// is the value's shortest decimal representation with the decimal point
+    /// removed and leading zeros stripped, so `0.05` becomes `5` and `150.25`
+    /// becomes `15025`. The strings are concatenated with no separators.
+    pub fn checksum(&self, levels: usize) -> u32 {
+        let mut payload = String::new();
+
+        for (price_key, quantity) in self.asks.iter().take(levels) {
+            payload.push_str(&checksum_field(key_price(*price_key).0));
+            payload.push_str(&checksum_field(quantity.0));
+        }
+
+        for (price_key, quantity) in self.bids.iter().rev().take(levels) {
+            payload.push_str(&checksum_field(key_price(*price_key).0));
+            payload.push_str(&checksum_field(quantity.0));
+        }
+
+        crc32fast::hash(payload.as_bytes())
+    }
+
+    /// Compare against an exchange-supplied checksum
+    ///
+    /// A mismatch means the local book has diverged and should be resynced
+    /// from a fresh snapshot.
+    pub fn verify_checksum(&self, expected: u32, levels: usize) -> bool {
+        self.checksum(levels) == expected
+    }
+
+    /// Minimal level changes that turn this book into `other`
+    ///
+    /// Bids come before asks, each in ascending price order. Applying the
+    /// result to this book with `apply_diff` reproduces `other`'s levels.
+    pub fn diff(&self, other: &FastOrderBook) -> Vec<LevelChange> {
+        let mut changes = Vec::new();
+        diff_levels(Side::Bid, &self.bids, &other.bids, &mut changes);
+        diff_levels(Side::Ask, &self.asks, &other.asks, &mut changes);
+        changes
+    }
+
+    /// Apply changes produced by `diff`
+    ///
+    /// Unlike `apply_delta` there is no sequence check; the local sequence
+    /// advances once per change.
+    pub fn apply_diff(&mut self, changes: &[LevelChange]) {
+        for change in changes {
+            match change.side {
+                Side::Bid => self.update_bid(change.price, change.new_qty),
+                Side::Ask => self.update_ask(change.price, change.new_qty),
+            }
+        }
+    }
 }
 
+/// Format a price or quantity for checksum input (see `FastOrderBook::checksum`)
+fn checksum_field(value: f64) -> String {
+    let digits: String = value.to_string().chars().filter(|c| *c != '.').collect();
+    let trimmed = digits.trim_start_matches('0');
+    if trimmed.is_empty() {
+        "0".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Resting order in an L3 book
+#[derive(Debug, Clone)]
+struct L3Order {
+    side: Side,
+    price_key: u64,
+    quantity: Quantity,
+}
+
+/// Order-by-order (L3) book with FIFO queues at each price level
+///
+/// Orders are tracked by exchange order id so queue position can be modeled.
+/// Reducing an order's size keeps its place in the queue; increasing it
+/// sends it to the back, as most matching engines do.
+pub struct L3OrderBook {
+    symbol: Symbol,
+    orders: HashMap<u64, L3Order>,
+    bids: BTreeMap<u64, VecDeque<u64>>, // price_key -> order ids in arrival order
+    asks: BTreeMap<u64, VecDeque<u64>>, // price_key -> order ids in arrival order
+    sequence: u64,
+}
+
+impl L3OrderBook {
+    pub fn new(symbol: Symbol) -> Self {
+        Self {
+            symbol,
+            orders: HashMap::new(),
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            sequence: 0,
+        }
+    }
+
+    pub fn symbol(&self) -> &Symbol {
+        &self.symbol
+    }
+
+    /// Add an order at the back of its price level
+    ///
+    /// Returns false (and leaves the book unchanged) if the id already exists
+    /// or the quantity is not positive.
+    pub fn add_order(&mut self, id: u64, side: Side, price: Price, quantity: Quantity) -> bool {
+        if self.orders.contains_key(&id) || quantity.0 <= 0.0 {
+            return false;
+        }
+
+        let key = price_key(price);
+        self.levels_mut(side).entry(key).or_default().push_back(id);
+        self.orders.insert(id, L3Order { side, price_key: key, quantity });
+        self.sequence += 1;
+        true
+    }
+
+    /// Remove an order; unknown ids are ignored
+    pub fn cancel_order(&mut self, id: u64) -> bool {
+        let Some(order) = self.orders.remove(&id) else {
+            return false;
+        };
+
+        let levels = self.levels_mut(order.side);
+        if let Some(queue) = levels.get_mut(&order.price_key) {
+            queue.retain(|&queued| queued != id);
+            if queue.is_empty() {
+                levels.remove(&order.price_key);
+            }
+        }
+        self.sequence += 1;
+        true
+    }
+
+    /// Change an order's size
+    ///
+    /// A decrease keeps queue priority, an increase moves the order to the
+    /// back of its level, and zero cancels it.
+    pub fn modify_order(&mut self, id: u64, new_quantity: Quantity) -> bool {
+        if new_quantity.0 <= 0.0 {
+            return self.cancel_order(id);
+        }
+
+        let Some(order) = self.orders.get_mut(&id) else {
+            return false;
+        };
+
+        let lose_priority = new_quantity.0 > order.quantity.0;
+        order.quantity = new_quantity;
+        let (side, key) = (order.side, order.price_key);
+
+        if lose_priority {
+            if let Some(queue) = self.levels_mut(side).get_mut(&key) {
+                queue.retain(|&queued| queued != id);
+                queue.push_back(id);
+            }
+        }
+        self.sequence += 1;
+        true
+    }
+
+    /// Apply a fill against an order, removing it once fully executed
+    pub fn execute(&mut self, id: u64, filled_quantity: Quantity) -> bool {
+        let Some(order) = self.orders.get_mut(&id) else {
+            return false;
+        };
+
+        let remaining = order.quantity.0 - filled_quantity.0;
+        if remaining <= 0.0 {
+            return self.cancel_order(id);
+        }
+
+        order.quantity = Quantity(remaining);
+        self.sequence += 1;
+        true
+    }
+
+    /// Volume resting ahead of an order at its price level
+    pub fn queue_ahead(&self, id: u64) -> Option<Quantity> {
+        let order = self.orders.get(&id)?;
+        let queue = self.levels(order.side).get(&order.price_key)?;
+
+        let ahead = queue
+            .iter()
+            .take_while(|&&queued| queued != id)
+            .filter_map(|queued| self.orders.get(queued))
+            .map(|o| o.quantity.0)
+            .sum();
+
+        Some(Quantity(ahead))
+    }
+
+    /// Total quantity resting at a price level
+    pub fn level_quantity(&self, side: Side, price: Price) -> Quantity {
+        let total = self
+            .levels(side)
+            .get(&price_key(price))
+            .map(|queue| {
+                queue
+                    .iter()
+                    .filter_map(|id| self.orders.get(id))
+                    .map(|o| o.quantity.0)
+                    .sum()
+            })
+            .unwrap_or(0.0);
+        Quantity(total)
+    }
+
+    /// Remaining quantity of an order
+    pub fn order_quantity(&self, id: u64) -> Option<Quantity> {
+        self.orders.get(&id).map(|o| o.quantity)
+    }
+
+    /// Number of resting orders
+    pub fn order_count(&self) -> usize {
+        self.orders.len()
+    }
+
+    /// Get best bid price (highest bid)
+    #[inline]
+    pub fn best_bid(&self) -> Option<Price> {
+        self.bids.keys().next_back().map(|&key| key_price(key))
+    }
+
+    /// Get best ask price (lowest ask)
+    #[inline]
+    pub fn best_ask(&self) -> Option<Price> {
+        self.asks.keys().next().map(|&key| key_price(key))
+    }
+
+    /// Get mid price
+    #[inline]
+    pub fn mid_price(&self) -> Option<Price> {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) => Some(Price((bid.0 + ask.0) / 2.0)),
+            _ => None,
+        }
+    }
+
+    fn levels(&self, side: Side) -> &BTreeMap<u64, VecDeque<u64>> {
+        match side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
+        }
+    }
+
+    fn levels_mut(&mut self, side: Side) -> &mut BTreeMap<u64, VecDeque<u64>> {
+        match side {
+            Side::Bid => &mut self.bids,
+            Side::Ask => &mut self.asks,
+        }
+    }
+}
+
+/// Change in best bid or ask (price or size) for one symbol
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopOfBookEvent {
+    pub symbol: Symbol,
+    pub bid: Option<(Price, Quantity)>,
+    pub ask: Option<(Price, Quantity)>,
+    pub timestamp: chrono::DateTime<Utc>,
+}
+
+/// Best bid and ask as of the last emitted event
+type Touch = (Option<(Price, Quantity)>, Option<(Price, Quantity)>);
+
 /// Manager for multiple order books
 pub struct OrderBookManager {
     books: HashMap<String, FastOrderBook>,
+    touches: HashMap<String, Touch>,
+    top_of_book_subscribers: Mutex<Vec<mpsc::Sender<TopOfBookEvent>>>,
 }
 
 impl OrderBookManager {
     pub fn new() -> Self {
         Self {
             books: HashMap::new(),
+            touches: HashMap::new(),
+            top_of_book_subscribers: Mutex::new(Vec::new()),
         }
     }
 
+    /// Receive an event whenever a symbol's best bid or ask changes
+    ///
+    /// Events fire after `update_bid`, `update_ask`, `apply_snapshot` and
+    /// `apply_delta`; a size-only change at the touch counts as a change.
+    /// Changes made directly through `get_or_create` are not observed. A slow
+    /// subscriber whose buffer is full misses events rather than blocking
+    /// the feed.
+    pub fn subscribe_top_of_book(&self) -> mpsc::Receiver<TopOfBookEvent> {
+        let (sender, receiver) = mpsc::channel(TOP_OF_BOOK_CHANNEL_CAPACITY);
+        self.top_of_book_subscribers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(sender);
+        receiver
+    }
+
+    fn emit_top_of_book(&mut self, symbol: &str) {
+        let Some(book) = self.books.get(symbol) else {
+            return;
+        };
+        let touch = (book.best_bid_level(), book.best_ask_level());
+
+        if self.touches.get(symbol) == Some(&touch) {
+            return;
+        }
+        self.touches.insert(symbol.to_string(), touch);
+
+        let event = TopOfBookEvent {
+            symbol: Symbol(symbol.to_string()),
+            bid: touch.0,
+            ask: touch.1,
+            timestamp: Utc::now(),
+        };
+
+        let subscribers = self
+            .top_of_book_subscribers
+            .get_mut()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        subscribers.retain(|sender| match sender.try_send(event.clone()) {
+            Ok(()) => true,
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                metrics::counter!("orderbook_top_of_book_dropped_total").increment(1);
+                true
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => false,
+        });
+    }
+
     pub fn get_or_create(&mut self, symbol: &str) -> &mut FastOrderBook {
         self.books
             .entry(symbol.to_string())
@@ -238,15 +945,41 @@ impl OrderBookManager {
 
     pub fn update_bid(&mut self, symbol: &str, price: Price, quantity: Quantity) {
         self.get_or_create(symbol).update_bid(price, quantity);
+        self.emit_top_of_book(symbol);
     }
 
     pub fn update_ask(&mut self, symbol: &str, price: Price, quantity: Quantity) {
         self.get_or_create(symbol).update_ask(price, quantity);
+        self.emit_top_of_book(symbol);
     }
 
     pub fn get_snapshot(&self, symbol: &str, max_levels: usize) -> Option<OrderBook> {
         self.books.get(symbol).map(|book| book.to_snapshot(max_levels))
     }
+
+    /// Rebuild a symbol's book from an exchange snapshot
+    pub fn apply_snapshot(&mut self, symbol: &str, snapshot: OrderBook) {
+        self.get_or_create(symbol).apply_snapshot(&snapshot);
+        self.emit_top_of_book(symbol);
+    }
+
+    /// Apply a sequenced delta to a symbol's book
+    ///
+    /// Fails with `SequenceGap` when `sequence` is not exactly one past the
+    /// last applied sequence; the caller should re-request a snapshot.
+    pub fn apply_delta(
+        &mut self,
+        symbol: &str,
+        sequence: u64,
+        deltas: &[(Side, Price, Quantity)],
+    ) -> Result<(), OrderBookError> {
+        self.books
+            .get_mut(symbol)
+            .ok_or_else(|| OrderBookError::MissingSnapshot(symbol.to_string()))?
+            .apply_delta(sequence, deltas)?;
+        self.emit_top_of_book(symbol);
+        Ok(())
+    }
 }
 
 impl Default for OrderBookManager {
@@ -320,4 +1053,447 @@ mod tests {
         // Should be well under 50μs per update
         assert!(elapsed.as_micros() < 50000);
     }
+
+    #[test]
+    fn test_top_of_book_events() {
+        let mut manager = OrderBookManager::new();
+        let mut events = manager.subscribe_top_of_book();
+
+        manager.update_bid("AAPL", Price(150.0), Quantity(100.0));
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.symbol, Symbol("AAPL".to_string()));
+        assert_eq!(event.bid, Some((Price(150.0), Quantity(100.0))));
+        assert!(event.ask.is_none());
+
+        // Deeper level: touch unchanged, no event
+        manager.update_bid("AAPL", Price(149.0), Quantity(500.0));
+        assert!(events.try_recv().is_err());
+
+        // Size-only change at the touch still emits
+        manager.update_bid("AAPL", Price(150.0), Quantity(80.0));
+        assert_eq!(events.try_recv().unwrap().bid, Some((Price(150.0), Quantity(80.0))));
+
+        manager.update_ask("AAPL", Price(150.5), Quantity(10.0));
+        assert_eq!(events.try_recv().unwrap().ask, Some((Price(150.5), Quantity(10.0))));
+
+        // Dropped receivers are pruned without affecting updates
+        drop(events);
+        manager.update_ask("AAPL", Price(150.25), Quantity(10.0));
+        assert!(manager.top_of_book_subscribers.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_crossed_book_guard() {
+        let mut book = FastOrderBook::new(Symbol("AAPL".to_string()));
+        book.update_bid(Price(100.0), Quantity(100.0));
+        book.update_ask(Price(100.5), Quantity(100.0));
+        book.update_ask(Price(101.0), Quantity(100.0));
+        assert!(!book.is_crossed() && !book.is_locked());
+
+        // Locked: still usable, zero spread
+        book.update_bid(Price(100.5), Quantity(50.0));
+        assert!(book.is_locked() && !book.is_crossed());
+        assert_eq!(book.spread_bps(), Some(0.0));
+
+        // Crossed: price-derived accessors refuse to answer
+        book.update_bid(Price(100.75), Quantity(50.0));
+        assert!(book.is_crossed());
+        assert!(book.mid_price().is_none());
+        assert!(book.spread_bps().is_none());
+        assert!(book.microprice().is_none());
+        assert!(book.weighted_mid(3).is_none());
+
+        // The bid update is newest, so the stale 100.5 ask goes
+        assert_eq!(book.sanitize(), 1);
+        assert!(!book.is_crossed());
+        assert_eq!(book.best_ask(), Some(Price(101.0)));
+        assert_eq!(book.best_bid(), Some(Price(100.75)));
+        assert_eq!(book.sanitize(), 0);
+
+        // An ask through the bids removes the stale bids instead
+        book.update_ask(Price(100.25), Quantity(10.0));
+        assert_eq!(book.sanitize(), 2);
+        assert_eq!(book.best_bid(), Some(Price(100.0)));
+    }
+
+    #[test]
+    fn test_book_features() {
+        let mut book = FastOrderBook::new(Symbol("AAPL".to_string()));
+
+        let empty = book.features(5);
+        assert!(empty.mid.is_none() && empty.microprice.is_none() && empty.spread_bps.is_none());
+        assert_eq!(empty.imbalance, 0.0);
+        assert_eq!(empty.best_bid_size, 0.0);
+
+        book.update_bid(Price(100.0), Quantity(300.0));
+        book.update_ask(Price(101.0), Quantity(100.0));
+
+        let features = book.features(1);
+        assert_eq!(features.mid, Some(100.5));
+        assert_eq!(features.microprice, book.microprice().map(|p| p.0));
+        assert_eq!(features.imbalance, 0.5);
+        assert_eq!(features.best_bid_size, 300.0);
+        assert_eq!(features.best_ask_size, 100.0);
+
+        let json = serde_json::to_string(&features).unwrap();
+        assert_eq!(serde_json::from_str::<BookFeatures>(&json).unwrap(), features);
+    }
+
+    #[cfg(feature = "database")]
+    #[test]
+    fn test_book_features_to_metrics() {
+        let mut book = FastOrderBook::new(Symbol("AAPL".to_string()));
+        book.update_bid(Price(100.0), Quantity(300.0));
+
+        // One-sided book: price features are skipped
+        let timestamp = Utc::now();
+        let metrics = book.features(5).to_metrics("AAPL", timestamp);
+        assert_eq!(metrics.len(), 3);
+        assert!(metrics.iter().all(|m| m.symbol.as_deref() == Some("AAPL") && m.timestamp == timestamp));
+        assert!(metrics.iter().any(|m| m.metric_name == "book_best_bid_size" && m.value == 300.0));
+    }
+
+    #[test]
+    fn test_weighted_imbalance() {
+        let mut book = FastOrderBook::new(Symbol("AAPL".to_string()));
+        assert_eq!(book.weighted_imbalance(5, 0.5), 0.0);
+
+        book.update_bid(Price(150.0), Quantity(100.0));
+        book.update_bid(Price(149.0), Quantity(400.0));
+        book.update_ask(Price(151.0), Quantity(300.0));
+        book.update_ask(Price(152.0), Quantity(100.0));
+
+        // decay 1.0 matches the unweighted imbalance
+        assert!((book.weighted_imbalance(2, 1.0) - book.imbalance(2)).abs() < 1e-12);
+
+        // bid 100 + 400*0.5 = 300, ask 300 + 100*0.5 = 350
+        let imbalance = book.weighted_imbalance(2, 0.5);
+        assert!((imbalance - (300.0 - 350.0) / 650.0).abs() < 1e-12);
+        assert!(book.imbalance(2) > 0.0 && imbalance < 0.0);
+    }
+
+    #[test]
+    fn test_microprice_and_weighted_mid() {
+        let mut book = FastOrderBook::new(Symbol("AAPL".to_string()));
+        assert!(book.microprice().is_none());
+
+        book.update_bid(Price(100.0), Quantity(300.0));
+        assert!(book.microprice().is_none());
+        assert!(book.weighted_mid(2).is_none());
+
+        book.update_ask(Price(101.0), Quantity(100.0));
+        book.update_bid(Price(99.0), Quantity(100.0));
+        book.update_ask(Price(102.0), Quantity(300.0));
+
+        // (100 * 100 + 101 * 300) / 400 = 100.75, pulled toward the thin ask
+        let micro = book.microprice().unwrap();
+        assert!((micro.0 - 100.75).abs() < 1e-9);
+
+        // bid vwap 99.75, ask vwap 101.75
+        let mid = book.weighted_mid(2).unwrap();
+        assert!((mid.0 - 100.75).abs() < 1e-9);
+
+        // One level degenerates to the plain mid
+        assert_eq!(book.weighted_mid(1), book.mid_price());
+    }
+
+    #[test]
+    fn test_checksum_format() {
+        assert_eq!(checksum_field(150.25), "15025");
+        assert_eq!(checksum_field(0.05), "5");
+        assert_eq!(checksum_field(100.0), "100");
+
+        let mut book = FastOrderBook::new(Symbol("XBT/USD".to_string()));
+        book.update_bid(Price(150.0), Quantity(1.5));
+        book.update_bid(Price(149.5), Quantity(2.0));
+        book.update_ask(Price(150.5), Quantity(0.25));
+        book.update_ask(Price(151.0), Quantity(3.0));
+
+        // asks low->high, then bids high->low
+        let expected = crc32fast::hash(b"15052515131501514952");
+        assert_eq!(book.checksum(10), expected);
+        assert!(book.verify_checksum(expected, 10));
+
+        // Any change in the top levels must change the checksum
+        book.update_bid(Price(150.0), Quantity(1.0));
+        assert!(!book.verify_checksum(expected, 10));
+    }
+
+    type Levels = Vec<(u64, Quantity)>;
+
+    fn levels(book: &FastOrderBook) -> (Levels, Levels) {
+        (
+            book.bids.iter().map(|(k, q)| (*k, *q)).collect(),
+            book.asks.iter().map(|(k, q)| (*k, *q)).collect(),
+        )
+    }
+
+    #[test]
+    fn test_max_depth_evicts_worst_levels() {
+        let mut book = FastOrderBook::with_depth(Symbol("AAPL".to_string()), 3);
+        assert_eq!(book.max_depth(), Some(3));
+
+        for i in 0..5 {
+            book.update_bid(Price(100.0 - i as f64), Quantity(10.0));
+            book.update_ask(Price(101.0 + i as f64), Quantity(10.0));
+        }
+        // The farthest levels (97, 96 and 104, 105) were evicted in turn
+        let bids: Vec<Price> = book.to_snapshot(10).bids.iter().map(|l| l.price).collect();
+        let asks: Vec<Price> = book.to_snapshot(10).asks.iter().map(|l| l.price).collect();
+        assert_eq!(bids, vec![Price(100.0), Price(99.0), Price(98.0)]);
+        assert_eq!(asks, vec![Price(101.0), Price(102.0), Price(103.0)]);
+
+        // A more competitive level displaces the current worst
+        book.update_bid(Price(100.5), Quantity(20.0));
+        book.update_ask(Price(100.75), Quantity(20.0));
+        assert_eq!(book.best_bid(), Some(Price(100.5)));
+        assert_eq!(book.best_ask(), Some(Price(100.75)));
+        let bids: Vec<Price> = book.to_snapshot(10).bids.iter().map(|l| l.price).collect();
+        let asks: Vec<Price> = book.to_snapshot(10).asks.iter().map(|l| l.price).collect();
+        assert_eq!(bids, vec![Price(100.5), Price(100.0), Price(99.0)]);
+        assert_eq!(asks, vec![Price(100.75), Price(101.0), Price(102.0)]);
+
+        // Imbalance is computed over the retained levels only: 40 vs 40
+        assert!(book.imbalance(10).abs() < 1e-9);
+
+        // A far-away level is dropped immediately
+        book.update_bid(Price(50.0), Quantity(1000.0));
+        assert_eq!(book.to_snapshot(10).bids.len(), 3);
+        assert_eq!(book.to_snapshot(10).bids[2].price, Price(99.0));
+    }
+
+    #[test]
+    fn test_unbounded_by_default() {
+        let mut book = FastOrderBook::new(Symbol("AAPL".to_string()));
+        assert_eq!(book.max_depth(), None);
+        for i in 0..100 {
+            book.update_bid(Price(100.0 - i as f64 * 0.5), Quantity(1.0));
+        }
+        assert_eq!(book.to_snapshot(1000).bids.len(), 100);
+    }
+
+    #[test]
+    fn test_diff_is_minimal() {
+        let mut a = FastOrderBook::new(Symbol("AAPL".to_string()));
+        a.update_bid(Price(150.0), Quantity(100.0));
+        a.update_bid(Price(149.5), Quantity(200.0));
+        a.update_ask(Price(150.5), Quantity(150.0));
+
+        let mut b = FastOrderBook::new(Symbol("AAPL".to_string()));
+        b.update_bid(Price(150.0), Quantity(100.0));
+        b.update_bid(Price(149.75), Quantity(50.0));
+        b.update_ask(Price(150.5), Quantity(75.0));
+
+        assert_eq!(
+            a.diff(&b),
+            vec![
+                LevelChange { side: Side::Bid, price: Price(149.5), new_qty: Quantity(0.0) },
+                LevelChange { side: Side::Bid, price: Price(149.75), new_qty: Quantity(50.0) },
+                LevelChange { side: Side::Ask, price: Price(150.5), new_qty: Quantity(75.0) },
+            ]
+        );
+        assert!(a.diff(&a).is_empty());
+    }
+
+    /// Check that `a.apply_diff(&a.diff(&b))` reproduces `b` for random
+    /// books whose level prices are drawn by `price`
+    fn assert_diff_round_trips(price: impl Fn(&mut dyn FnMut(u64) -> u64) -> Price) {
+        let mut rng_state = 0x2545_f491_u64;
+        let mut next = move |bound: u64| {
+            rng_state ^= rng_state << 13;
+            rng_state ^= rng_state >> 7;
+            rng_state ^= rng_state << 17;
+            rng_state % bound
+        };
+        let random_book = |next: &mut dyn FnMut(u64) -> u64| {
+            let mut book = FastOrderBook::new(Symbol("AAPL".to_string()));
+            for _ in 0..next(20) {
+                let price = price(&mut *next);
+                let quantity = Quantity(next(5) as f64 * 10.0);
+                if next(2) == 0 {
+                    book.update_bid(price, quantity);
+                } else {
+                    book.update_ask(price, quantity);
+                }
+            }
+            book
+        };
+
+        for _ in 0..200 {
+            let mut a = random_book(&mut next);
+            let b = random_book(&mut next);
+            a.apply_diff(&a.diff(&b));
+            assert_eq!(levels(&a), levels(&b));
+            assert!(a.diff(&b).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_apply_diff_round_trip() {
+        assert_diff_round_trips(|next| Price(100.0 + next(40) as f64 * 0.25));
+    }
+
+    #[test]
+    fn test_apply_diff_round_trip_inexact_prices() {
+        // Cent and 8-decimal prices have no exact binary representation
+        assert_diff_round_trips(|next| Price(next(1_000_000) as f64 * 0.01));
+        assert_diff_round_trips(|next| Price(next(100_000_000_000) as f64 / PRICE_SCALE));
+    }
+
+    fn snapshot(sequence: u64) -> OrderBook {
+        let level = |price: f64, quantity: f64| Level {
+            price: Price(price),
+            quantity: Quantity(quantity),
+            timestamp: Utc::now(),
+        };
+        OrderBook {
+            symbol: Symbol("AAPL".to_string()),
+            bids: vec![level(150.0, 100.0), level(149.5, 200.0)],
+            asks: vec![level(150.5, 150.0)],
+            timestamp: Utc::now(),
+            sequence,
+        }
+    }
+
+    #[test]
+    fn test_apply_snapshot_and_delta() {
+        let mut manager = OrderBookManager::new();
+        manager.apply_snapshot("AAPL", snapshot(10));
+
+        let book = manager.get("AAPL").unwrap();
+        assert_eq!(book.sequence(), 10);
+        assert_eq!(book.best_bid(), Some(Price(150.0)));
+
+        manager
+            .apply_delta(
+                "AAPL",
+                11,
+                &[
+                    (Side::Bid, Price(150.0), Quantity(0.0)),
+                    (Side::Ask, Price(150.25), Quantity(50.0)),
+                ],
+            )
+            .unwrap();
+
+        let book = manager.get("AAPL").unwrap();
+        assert_eq!(book.sequence(), 11);
+        assert_eq!(book.best_bid(), Some(Price(149.5)));
+        assert_eq!(book.best_ask(), Some(Price(150.25)));
+    }
+
+    #[test]
+    fn test_delta_updates_inexact_snapshot_level() {
+        let level = |price: f64, quantity: f64| Level {
+            price: Price(price),
+            quantity: Quantity(quantity),
+            timestamp: Utc::now(),
+        };
+        let mut book = FastOrderBook::new(Symbol("F".to_string()));
+        book.apply_snapshot(&OrderBook {
+            symbol: Symbol("F".to_string()),
+            bids: vec![level(4.35, 100.0), level(4.34, 200.0)],
+            asks: vec![level(4.36, 150.0)],
+            timestamp: Utc::now(),
+            sequence: 1,
+        });
+
+        // 4.35 * 1e8 is 434999999.99...; both paths must key it the same
+        book.apply_delta(2, &[(Side::Bid, Price(4.35), Quantity(0.0))]).unwrap();
+        assert_eq!(book.best_bid(), Some(Price(4.34)));
+        assert_eq!(book.to_snapshot(10).bids.len(), 1);
+
+        book.apply_delta(3, &[(Side::Ask, Price(4.36), Quantity(75.0))]).unwrap();
+        assert_eq!(book.to_snapshot(10).asks.len(), 1);
+        assert_eq!(book.to_snapshot(10).asks[0].quantity, Quantity(75.0));
+    }
+
+    #[test]
+    fn test_delta_sequence_gap() {
+        let mut manager = OrderBookManager::new();
+        manager.apply_snapshot("AAPL", snapshot(10));
+        let delta = [(Side::Bid, Price(151.0), Quantity(10.0))];
+
+        // Out of order: message 11 was lost
+        assert_eq!(
+            manager.apply_delta("AAPL", 12, &delta),
+            Err(OrderBookError::SequenceGap { expected: 11, got: 12 })
+        );
+
+        manager.apply_delta("AAPL", 11, &delta).unwrap();
+
+        // Duplicate of an already applied message
+        assert_eq!(
+            manager.apply_delta("AAPL", 11, &delta),
+            Err(OrderBookError::SequenceGap { expected: 12, got: 11 })
+        );
+
+        // Rejected deltas leave the book untouched
+        assert_eq!(manager.get("AAPL").unwrap().sequence(), 11);
+
+        assert_eq!(
+            manager.apply_delta("MSFT", 1, &delta),
+            Err(OrderBookError::MissingSnapshot("MSFT".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_l3_fifo_queue_ahead() {
+        let mut book = L3OrderBook::new(Symbol("AAPL".to_string()));
+
+        assert!(book.add_order(1, Side::Bid, Price(150.0), Quantity(100.0)));
+        assert!(book.add_order(2, Side::Bid, Price(150.0), Quantity(50.0)));
+        assert!(book.add_order(3, Side::Bid, Price(150.0), Quantity(25.0)));
+        assert!(book.add_order(4, Side::Ask, Price(150.5), Quantity(10.0)));
+        assert!(!book.add_order(1, Side::Bid, Price(149.0), Quantity(1.0))); // duplicate id
+
+        assert_eq!(book.queue_ahead(1), Some(Quantity(0.0)));
+        assert_eq!(book.queue_ahead(3), Some(Quantity(150.0)));
+        assert_eq!(book.level_quantity(Side::Bid, Price(150.0)), Quantity(175.0));
+
+        // Partial fill at the front reduces the queue ahead of later orders
+        assert!(book.execute(1, Quantity(60.0)));
+        assert_eq!(book.queue_ahead(3), Some(Quantity(90.0)));
+
+        // Full fill removes the order
+        assert!(book.execute(1, Quantity(40.0)));
+        assert_eq!(book.order_quantity(1), None);
+        assert_eq!(book.queue_ahead(3), Some(Quantity(50.0)));
+
+        assert_eq!(book.best_bid(), Some(Price(150.0)));
+        assert_eq!(book.best_ask(), Some(Price(150.5)));
+        assert_eq!(book.mid_price(), Some(Price(150.25)));
+    }
+
+    #[test]
+    fn test_l3_modify_priority() {
+        let mut book = L3OrderBook::new(Symbol("AAPL".to_string()));
+        book.add_order(1, Side::Ask, Price(151.0), Quantity(100.0));
+        book.add_order(2, Side::Ask, Price(151.0), Quantity(50.0));
+
+        // Size down keeps priority
+        assert!(book.modify_order(1, Quantity(80.0)));
+        assert_eq!(book.queue_ahead(2), Some(Quantity(80.0)));
+
+        // Size up loses priority
+        assert!(book.modify_order(1, Quantity(120.0)));
+        assert_eq!(book.queue_ahead(1), Some(Quantity(50.0)));
+        assert_eq!(book.queue_ahead(2), Some(Quantity(0.0)));
+    }
+
+    #[test]
+    fn test_l3_cancel() {
+        let mut book = L3OrderBook::new(Symbol("AAPL".to_string()));
+        book.add_order(1, Side::Bid, Price(150.0), Quantity(100.0));
+
+        // Unknown ids are a no-op
+        assert!(!book.cancel_order(42));
+        assert!(!book.modify_order(42, Quantity(1.0)));
+        assert!(!book.execute(42, Quantity(1.0)));
+        assert_eq!(book.order_count(), 1);
+
+        assert!(book.cancel_order(1));
+        assert!(book.best_bid().is_none());
+        assert!(book.mid_price().is_none());
+        assert_eq!(book.queue_ahead(1), None);
+    }
 }
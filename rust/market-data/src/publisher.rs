@@ -1,18 +1,223 @@
-use common::{Result, TradingError, messaging::Message};
+use common::{Result, TradingError, messaging::{Codec, Envelope, Message, topics}};
+use tracing::info;
 
+/// zstd level used for published payloads; favours speed over ratio
+const ZSTD_LEVEL: i32 = 1;
+
+/// Payload compression applied by `MarketDataPublisher`
+///
+/// The first byte of every published payload is the compression actually
+/// used for that message, so subscribers can decode without knowing the
+/// publisher's configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None = 0,
+    Lz4 = 1,
+    Zstd = 2,
+}
+
+impl Compression {
+    fn from_header(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Compression::None),
+            1 => Some(Compression::Lz4),
+            2 => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// Prefix `raw` with a compression header, compressing when it helps
+///
+/// If the compressed form is not smaller than the raw bytes (typical for a
+/// single trade), the payload is sent uncompressed with a `None` header.
+pub fn encode_payload(compression: Compression, raw: &[u8]) -> Result<Vec<u8>> {
+    let compressed = match compression {
+        Compression::None => None,
+        Compression::Lz4 => Some(lz4::block::compress(raw, None, true)?),
+        Compression::Zstd => Some(zstd::bulk::compress(raw, ZSTD_LEVEL)?),
+    };
+
+    let mut payload = Vec::with_capacity(raw.len() + 1);
+    match compressed {
+        Some(bytes) if bytes.len() < raw.len() => {
+            payload.push(compression as u8);
+            payload.extend_from_slice(&bytes);
+        }
+        _ => {
+            payload.push(Compression::None as u8);
+            payload.extend_from_slice(raw);
+        }
+    }
+
+    Ok(payload)
+}
+
+/// Strip the compression header and decompress a published payload
+pub fn decode_payload(payload: &[u8]) -> Result<Vec<u8>> {
+    let (&header, body) = payload
+        .split_first()
+        .ok_or_else(|| TradingError::Messaging("Empty market data payload".to_string()))?;
+
+    match Compression::from_header(header) {
+        Some(Compression::None) => Ok(body.to_vec()),
+        Some(Compression::Lz4) => Ok(lz4::block::decompress(body, None)?),
+        Some(Compression::Zstd) => Ok(zstd::stream::decode_all(body)?),
+        None => Err(TradingError::Messaging(format!(
+            "Unknown compression header: {}",
+            header
+        ))),
+    }
+}
+
+/// ZMQ PUB socket broadcasting market data as JSON
+///
+/// Each message is sent as two frames: a topic of the form
+/// `market.<kind>.<symbol>` (e.g. `market.bar.AAPL`) so subscribers can
+/// prefix-filter, followed by a one-byte `Compression` header and the
+/// (possibly compressed) `Envelope<Message>`, JSON-encoded unless another
+/// `Codec` is chosen with `with_codec`.
 pub struct MarketDataPublisher {
     address: String,
+    socket: zmq::Socket,
+    compression: Compression,
+    codec: Codec,
 }
 
 impl MarketDataPublisher {
     pub fn new(address: &str) -> Result<Self> {
+        Self::with_compression(address, Compression::None)
+    }
+
+    pub fn with_compression(address: &str, compression: Compression) -> Result<Self> {
+        let context = zmq::Context::new();
+        let socket = context
+            .socket(zmq::PUB)
+            .map_err(|e| TradingError::Messaging(format!("Failed to create PUB socket: {}", e)))?;
+
+        socket
+            .bind(address)
+            .map_err(|e| TradingError::Messaging(format!("Failed to bind {}: {}", address, e)))?;
+
+        // Resolve a wildcard port such as tcp://127.0.0.1:* to the one bound
+        let address = socket
+            .get_last_endpoint()
+            .ok()
+            .and_then(|endpoint| endpoint.ok())
+            .unwrap_or_else(|| address.to_string());
+
+        info!("Market data publisher bound to {} ({:?} compression)", address, compression);
+
         Ok(Self {
-            address: address.to_string(),
+            address,
+            socket,
+            compression,
+            codec: Codec::default(),
         })
     }
 
+    /// Bound endpoint, with any wildcard port resolved
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    /// Encode envelopes with `codec`; subscribers detect it from the header
+    pub fn with_codec(mut self, codec: Codec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    pub fn codec(&self) -> Codec {
+        self.codec
+    }
+
     pub fn publish(&self, message: Message) -> Result<()> {
-        // TODO: Implement ZMQ publishing
+        let topic = Self::topic(&message);
+        let payload = encode_payload(self.compression, &Envelope::wrap(message).encode_with(self.codec)?)?;
+
+        self.socket
+            .send(topic.as_bytes(), zmq::SNDMORE)
+            .and_then(|_| self.socket.send(payload, 0))
+            .map_err(|e| TradingError::Messaging(format!("Publish on {} failed: {}", topic, e)))?;
+
+        metrics::counter!("market_data_messages_published_total").increment(1);
         Ok(())
     }
+
+    /// Topic frame for a message
+    pub fn topic(message: &Message) -> String {
+        match message {
+            Message::OrderBookUpdate(book) => format!("{}.book.{}", topics::MARKET_DATA, book.symbol.0),
+            Message::TradeUpdate(trade) => format!("{}.trade.{}", topics::MARKET_DATA, trade.symbol.0),
+            Message::BarUpdate(bar) => format!("{}.bar.{}", topics::MARKET_DATA, bar.symbol.0),
+            Message::SignalGenerated(signal) => format!("{}.{}", topics::SIGNALS, signal.symbol.0),
+            Message::Heartbeat(_) | Message::Shutdown => topics::SYSTEM.to_string(),
+            _ => topics::MARKET_DATA.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use common::types::{Level, OrderBook, Price, Quantity, Side, Symbol, Trade};
+
+    fn book_snapshot() -> Vec<u8> {
+        let levels: Vec<Level> = (0..50)
+            .map(|i| Level {
+                price: Price(150.0 + i as f64 * 0.01),
+                quantity: Quantity(100.0),
+                timestamp: Utc::now(),
+            })
+            .collect();
+        let book = OrderBook {
+            symbol: Symbol("AAPL".to_string()),
+            bids: levels.clone(),
+            asks: levels,
+            timestamp: Utc::now(),
+            sequence: 1,
+        };
+        serde_json::to_vec(&Message::OrderBookUpdate(book)).unwrap()
+    }
+
+    #[test]
+    fn test_compression_round_trip() {
+        let raw = book_snapshot();
+
+        for compression in [Compression::None, Compression::Lz4, Compression::Zstd] {
+            let payload = encode_payload(compression, &raw).unwrap();
+            assert_eq!(payload[0], compression as u8);
+            assert_eq!(decode_payload(&payload).unwrap(), raw);
+        }
+    }
+
+    #[test]
+    fn test_small_payload_not_inflated() {
+        let trade = Message::TradeUpdate(Trade {
+            symbol: Symbol("AAPL".to_string()),
+            price: Price(150.25),
+            quantity: Quantity(100.0),
+            side: Side::Bid,
+            timestamp: Utc::now(),
+            trade_id: "1".to_string(),
+        });
+        let raw = serde_json::to_vec(&trade).unwrap();
+
+        for compression in [Compression::Lz4, Compression::Zstd] {
+            let payload = encode_payload(compression, &raw).unwrap();
+            assert!(payload.len() <= raw.len() + 1);
+            assert_eq!(decode_payload(&payload).unwrap(), raw);
+        }
+    }
+
+    #[test]
+    fn test_decode_rejects_bad_header() {
+        assert!(decode_payload(&[]).is_err());
+        assert!(decode_payload(&[9, 1, 2, 3]).is_err());
+    }
 }
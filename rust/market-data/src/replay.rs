@@ -0,0 +1,205 @@
+use chrono::{DateTime, Utc};
+use common::types::{Level, OrderBook, Price, Quantity, Side, Symbol, Trade};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// One reconstructed book state
+#[derive(Debug, Clone)]
+pub struct ReplayEvent {
+    /// Timestamp of the trade that produced this state
+    pub timestamp: DateTime<Utc>,
+    /// Wall-clock pause before this event at the configured replay speed
+    pub delay: Duration,
+    /// Top-of-book snapshot for the traded symbol
+    pub book: OrderBook,
+}
+
+/// Last known touch for one symbol
+#[derive(Debug, Clone, Default)]
+struct Touch {
+    bid: Option<(Price, Quantity)>,
+    ask: Option<(Price, Quantity)>,
+    sequence: u64,
+}
+
+/// Deterministic top-of-book replay from recorded trades
+///
+/// Trades only reveal the side that was hit, so the book is approximated:
+/// a buyer-initiated trade (`Side::Bid`) sets the best ask to the trade price
+/// and size, and a seller-initiated trade sets the best bid. If that would
+/// cross the book, the stale opposite side is dropped. Each trade yields a
+/// snapshot whose `timestamp` is the trade time; snapshots can be fed to
+/// `FastOrderBook::apply_snapshot` to drive code that expects a live book.
+///
+/// Iterating directly replays as fast as possible; `next_paced` sleeps each
+/// event's `delay` to reproduce the recorded timing scaled by `speed`.
+pub struct OrderBookReplay {
+    trades: std::vec::IntoIter<Trade>,
+    touches: HashMap<String, Touch>,
+    speed: f64,
+    last_timestamp: Option<DateTime<Utc>>,
+}
+
+impl OrderBookReplay {
+    /// Build a replay from trades, ordered by timestamp
+    pub fn from_trades(mut trades: Vec<Trade>) -> Self {
+        trades.sort_by_key(|trade| trade.timestamp);
+
+        Self {
+            trades: trades.into_iter(),
+            touches: HashMap::new(),
+            speed: 0.0,
+            last_timestamp: None,
+        }
+    }
+
+    /// Replay speed relative to recorded time
+    ///
+    /// `1.0` is real time and `10.0` is ten times faster. Zero, negative or
+    /// non-finite values replay as fast as possible (no delays).
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Next event, after sleeping its `delay`
+    pub async fn next_paced(&mut self) -> Option<ReplayEvent> {
+        let event = self.next()?;
+        if !event.delay.is_zero() {
+            tokio::time::sleep(event.delay).await;
+        }
+        Some(event)
+    }
+
+    fn delay_until(&self, timestamp: DateTime<Utc>) -> Duration {
+        if self.speed <= 0.0 || !self.speed.is_finite() {
+            return Duration::ZERO;
+        }
+
+        self.last_timestamp
+            .and_then(|last| (timestamp - last).to_std().ok())
+            .map(|gap| gap.div_f64(self.speed))
+            .unwrap_or(Duration::ZERO)
+    }
+}
+
+impl Iterator for OrderBookReplay {
+    type Item = ReplayEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let trade = self.trades.next()?;
+        let delay = self.delay_until(trade.timestamp);
+        self.last_timestamp = Some(trade.timestamp);
+
+        let touch = self.touches.entry(trade.symbol.0.clone()).or_default();
+        let level = Some((trade.price, trade.quantity));
+        match trade.side {
+            Side::Bid => {
+                touch.ask = level;
+                if touch.bid.is_some_and(|(bid, _)| bid.0 >= trade.price.0) {
+                    touch.bid = None;
+                }
+            }
+            Side::Ask => {
+                touch.bid = level;
+                if touch.ask.is_some_and(|(ask, _)| ask.0 <= trade.price.0) {
+                    touch.ask = None;
+                }
+            }
+        }
+        touch.sequence += 1;
+
+        let to_levels = |side: Option<(Price, Quantity)>| -> Vec<Level> {
+            side.into_iter()
+                .map(|(price, quantity)| Level {
+                    price,
+                    quantity,
+                    timestamp: trade.timestamp,
+                })
+                .collect()
+        };
+
+        Some(ReplayEvent {
+            timestamp: trade.timestamp,
+            delay,
+            book: OrderBook {
+                symbol: Symbol(trade.symbol.0.clone()),
+                bids: to_levels(touch.bid),
+                asks: to_levels(touch.ask),
+                timestamp: trade.timestamp,
+                sequence: touch.sequence,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::FastOrderBook;
+
+    fn trade(symbol: &str, side: Side, price: f64, millis: i64) -> Trade {
+        Trade {
+            symbol: Symbol(symbol.to_string()),
+            price: Price(price),
+            quantity: Quantity(100.0),
+            side,
+            timestamp: DateTime::from_timestamp_millis(1_700_000_000_000 + millis).unwrap(),
+            trade_id: millis.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_replay_reconstructs_touch_in_order() {
+        let trades = vec![
+            trade("AAPL", Side::Ask, 149.9, 200),
+            trade("AAPL", Side::Bid, 150.1, 0),
+            trade("MSFT", Side::Bid, 400.0, 100),
+        ];
+
+        let events: Vec<ReplayEvent> = OrderBookReplay::from_trades(trades).collect();
+        assert_eq!(events.len(), 3);
+        assert!(events.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+        assert!(events.iter().all(|e| e.delay.is_zero()));
+
+        let last = &events[2].book;
+        assert_eq!(last.symbol, Symbol("AAPL".to_string()));
+        assert_eq!(last.sequence, 2);
+
+        let mut book = FastOrderBook::new(Symbol("AAPL".to_string()));
+        book.apply_snapshot(last);
+        assert_eq!(book.best_bid(), Some(Price(149.9)));
+        assert_eq!(book.best_ask(), Some(Price(150.1)));
+    }
+
+    #[test]
+    fn test_replay_drops_crossed_side() {
+        let trades = vec![
+            trade("AAPL", Side::Bid, 150.0, 0),
+            trade("AAPL", Side::Ask, 150.5, 10),
+        ];
+
+        let events: Vec<ReplayEvent> = OrderBookReplay::from_trades(trades).collect();
+        let book = &events[1].book;
+        assert_eq!(book.bids.len(), 1);
+        assert!(book.asks.is_empty());
+    }
+
+    #[test]
+    fn test_replay_speed_scales_delays() {
+        let trades = vec![
+            trade("AAPL", Side::Bid, 150.0, 0),
+            trade("AAPL", Side::Bid, 150.0, 1000),
+        ];
+
+        let events: Vec<ReplayEvent> = OrderBookReplay::from_trades(trades).with_speed(10.0).collect();
+        assert_eq!(events[0].delay, Duration::ZERO);
+        assert_eq!(events[1].delay, Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_replay_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<OrderBookReplay>();
+    }
+}
@@ -0,0 +1,149 @@
+use crate::publisher::decode_payload;
+use common::messaging::{topics, Envelope, Message};
+use common::types::{Bar, OrderBook, Signal, Trade};
+use common::{Result, TradingError};
+use std::thread;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+/// Receive timeout for the socket thread, bounding how long it takes to
+/// notice the subscriber was dropped
+const POLL_TIMEOUT_MS: i32 = 100;
+
+/// Buffered messages between the socket thread and `recv`
+const CHANNEL_CAPACITY: usize = 10_000;
+
+/// Market data received from a `MarketDataPublisher`
+#[derive(Debug, Clone)]
+pub enum MarketMessage {
+    Trade(Trade),
+    Bar(Bar),
+    OrderBook(OrderBook),
+    /// Published by the signal bridge; only received when subscribed to a
+    /// `signal` topic
+    Signal(Signal),
+}
+
+impl MarketMessage {
+    pub fn symbol(&self) -> &str {
+        match self {
+            MarketMessage::Trade(trade) => &trade.symbol.0,
+            MarketMessage::Bar(bar) => &bar.symbol.0,
+            MarketMessage::OrderBook(book) => &book.symbol.0,
+            MarketMessage::Signal(signal) => &signal.symbol.0,
+        }
+    }
+
+    fn from_message(message: Message) -> Option<Self> {
+        match message {
+            Message::TradeUpdate(trade) => Some(MarketMessage::Trade(trade)),
+            Message::BarUpdate(bar) => Some(MarketMessage::Bar(bar)),
+            Message::OrderBookUpdate(book) => Some(MarketMessage::OrderBook(book)),
+            Message::SignalGenerated(signal) => Some(MarketMessage::Signal(signal)),
+            _ => None,
+        }
+    }
+}
+
+/// ZMQ SUB counterpart to `MarketDataPublisher`
+///
+/// Topics are prefixes of the publisher's `market.<kind>.<symbol>` topic
+/// frames, so `market.bar.AAPL` receives only AAPL bars and `market.trade`
+/// receives trades for every symbol. An empty topic list subscribes to all
+/// market data; signals (`signal.<symbol>`) must be subscribed explicitly.
+/// The blocking socket lives on its own thread, which exits once the
+/// subscriber is dropped.
+pub struct MarketDataSubscriber {
+    receiver: mpsc::Receiver<Result<MarketMessage>>,
+}
+
+impl MarketDataSubscriber {
+    pub fn connect(address: &str, topics: &[String]) -> Result<Self> {
+        let context = zmq::Context::new();
+        let socket = context
+            .socket(zmq::SUB)
+            .map_err(|e| TradingError::Messaging(format!("Failed to create SUB socket: {}", e)))?;
+
+        socket
+            .connect(address)
+            .map_err(|e| TradingError::Messaging(format!("Failed to connect {}: {}", address, e)))?;
+        socket
+            .set_rcvtimeo(POLL_TIMEOUT_MS)
+            .map_err(|e| TradingError::Messaging(format!("Failed to set receive timeout: {}", e)))?;
+
+        let filters: Vec<&str> = if topics.is_empty() {
+            vec![topics::MARKET_DATA]
+        } else {
+            topics.iter().map(String::as_str).collect()
+        };
+        for topic in &filters {
+            socket
+                .set_subscribe(topic.as_bytes())
+                .map_err(|e| TradingError::Messaging(format!("Failed to subscribe {}: {}", topic, e)))?;
+        }
+
+        info!("Market data subscriber connected to {} (topics: {:?})", address, filters);
+
+        let (sender, receiver) = mpsc::channel(CHANNEL_CAPACITY);
+        thread::spawn(move || Self::receive_loop(socket, sender));
+
+        Ok(Self { receiver })
+    }
+
+    /// Wait for the next trade, bar or book snapshot
+    pub async fn recv(&mut self) -> Result<MarketMessage> {
+        self.receiver
+            .recv()
+            .await
+            .unwrap_or_else(|| Err(TradingError::Messaging("Market data subscriber closed".to_string())))
+    }
+
+    /// True once the socket thread has exited and every buffered message
+    /// has been received
+    pub fn is_closed(&self) -> bool {
+        self.receiver.is_closed() && self.receiver.is_empty()
+    }
+
+    fn receive_loop(socket: zmq::Socket, sender: mpsc::Sender<Result<MarketMessage>>) {
+        while !sender.is_closed() {
+            let frames = match socket.recv_multipart(0) {
+                Ok(frames) => frames,
+                Err(zmq::Error::EAGAIN) => continue,
+                Err(e) => {
+                    let _ = sender.blocking_send(Err(TradingError::Messaging(format!(
+                        "Receive failed: {}",
+                        e
+                    ))));
+                    return;
+                }
+            };
+
+            let Some(payload) = frames.last() else {
+                continue;
+            };
+
+            let message = match decode_payload(payload)
+                .and_then(|bytes| Envelope::<Message>::decode(&bytes))
+                .map(|envelope| envelope.payload)
+            {
+                Ok(message) => message,
+                Err(e) => {
+                    warn!("Dropping undecodable market data message: {}", e);
+                    if sender.blocking_send(Err(e)).is_err() {
+                        return;
+                    }
+                    continue;
+                }
+            };
+
+            match MarketMessage::from_message(message) {
+                Some(market_message) => {
+                    if sender.blocking_send(Ok(market_message)).is_err() {
+                        return;
+                    }
+                }
+                None => debug!("Ignoring non market data message"),
+            }
+        }
+    }
+}
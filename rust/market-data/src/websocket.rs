@@ -1,74 +1,39 @@
-use common::{Result, TradingError};
+use crate::exchange::{AlpacaAdapter, ExchangeAdapter};
+use crate::subscriber::MarketMessage;
+use chrono::{DateTime, Utc};
+use common::{Result, SharedClock, SystemClock, TradingError};
 use futures_util::{SinkExt, StreamExt};
-use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use tokio::net::TcpStream;
 use tokio::time::{Duration, sleep};
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
 use tracing::{debug, error, info, warn};
 use url::Url;
 
-const ALPACA_WSS_URL: &str = "wss://stream.data.alpaca.markets/v2/iex";
 const RECONNECT_DELAY_MS: u64 = 5000;
 const HEARTBEAT_INTERVAL_MS: u64 = 30000;
+const INITIAL_BACKOFF_MS: u64 = 500;
+const MAX_BACKOFF_MS: u64 = 60000;
+const MAX_RECONNECT_ATTEMPTS: u32 = 10;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "T")]
-pub enum AlpacaMessage {
-    #[serde(rename = "t")]
-    Trade {
-        #[serde(rename = "S")]
-        symbol: String,
-        #[serde(rename = "p")]
-        price: f64,
-        #[serde(rename = "s")]
-        size: f64,
-        #[serde(rename = "t")]
-        timestamp: String,
-        #[serde(rename = "i")]
-        id: u64,
-    },
-    #[serde(rename = "q")]
-    Quote {
-        #[serde(rename = "S")]
-        symbol: String,
-        #[serde(rename = "bp")]
-        bid_price: f64,
-        #[serde(rename = "bs")]
-        bid_size: f64,
-        #[serde(rename = "ap")]
-        ask_price: f64,
-        #[serde(rename = "as")]
-        ask_size: f64,
-        #[serde(rename = "t")]
-        timestamp: String,
-    },
-    #[serde(rename = "b")]
-    Bar {
-        #[serde(rename = "S")]
-        symbol: String,
-        #[serde(rename = "o")]
-        open: f64,
-        #[serde(rename = "h")]
-        high: f64,
-        #[serde(rename = "l")]
-        low: f64,
-        #[serde(rename = "c")]
-        close: f64,
-        #[serde(rename = "v")]
-        volume: f64,
-        #[serde(rename = "t")]
-        timestamp: String,
-    },
-    #[serde(other)]
-    Unknown,
-}
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
 pub struct WebSocketClient {
+    adapter: Box<dyn ExchangeAdapter>,
     url: Url,
     api_key: String,
     api_secret: String,
     symbols: Vec<String>,
     reconnect_delay: Duration,
+    max_backoff: Duration,
+    max_reconnect_attempts: u32,
+    /// Connect attempts made so far; every one after the first is a reconnect
+    connect_attempts: u64,
+    stream: Option<WsStream>,
+    pending: VecDeque<MarketMessage>,
+    last_seen: HashMap<String, DateTime<Utc>>,
+    last_message_at: Option<DateTime<Utc>>,
+    clock: SharedClock,
 }
 
 impl WebSocketClient {
@@ -77,21 +42,211 @@ impl WebSocketClient {
         api_secret: String,
         symbols: Vec<String>,
     ) -> Result<Self> {
-        let url = Url::parse(ALPACA_WSS_URL)
+        Self::with_adapter(Box::new(AlpacaAdapter::default()), api_key, api_secret, symbols)
+    }
+
+    /// Client speaking an exchange-specific protocol
+    pub fn with_adapter(
+        adapter: Box<dyn ExchangeAdapter>,
+        api_key: String,
+        api_secret: String,
+        symbols: Vec<String>,
+    ) -> Result<Self> {
+        let url = Url::parse(adapter.url())
             .map_err(|e| TradingError::Configuration(format!("Invalid WebSocket URL: {}", e)))?;
 
         Ok(Self {
+            adapter,
             url,
             api_key,
             api_secret,
             symbols,
             reconnect_delay: Duration::from_millis(RECONNECT_DELAY_MS),
+            max_backoff: Duration::from_millis(MAX_BACKOFF_MS),
+            max_reconnect_attempts: MAX_RECONNECT_ATTEMPTS,
+            connect_attempts: 0,
+            stream: None,
+            pending: VecDeque::new(),
+            last_seen: HashMap::new(),
+            last_message_at: None,
+            clock: SystemClock::shared(),
         })
     }
 
+    /// Clock used for staleness tracking
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Cap on the backoff delay between reconnect attempts
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Number of connection attempts before `connect_with_retry` gives up
+    pub fn with_max_reconnect_attempts(mut self, attempts: u32) -> Self {
+        self.max_reconnect_attempts = attempts.max(1);
+        self
+    }
+
+    pub fn adapter(&self) -> &dyn ExchangeAdapter {
+        self.adapter.as_ref()
+    }
+
+    /// Delay to wait before re-establishing a dropped connection
+    pub fn reconnect_delay(&self) -> Duration {
+        self.reconnect_delay
+    }
+
+    /// Whether a stream opened with `connect_stream` is still live
+    pub fn is_connected(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    /// Open, authenticate and subscribe a stream for `next_message`
+    pub async fn connect_stream(&mut self) -> Result<()> {
+        let stream = self.handshake().await?;
+        self.stream = Some(stream);
+        // A fresh connection restarts the overall staleness clock
+        self.last_message_at = Some(self.clock.now());
+        Ok(())
+    }
+
+    /// Drop the current stream so the next connect starts from scratch
+    pub fn disconnect(&mut self) {
+        self.stream = None;
+        self.pending.clear();
+    }
+
+    /// Whether `symbol` has had no data message within `max_age`
+    ///
+    /// Symbols that have never received data are stale.
+    pub fn is_stale(&self, symbol: &str, max_age: Duration) -> bool {
+        match self.last_seen.get(symbol) {
+            Some(seen) => self.clock.elapsed(*seen) > max_age,
+            None => true,
+        }
+    }
+
+    /// Time since the last data message on any symbol, or since the last
+    /// successful connect if no data has arrived yet
+    pub fn last_message_age(&self) -> Option<Duration> {
+        self.last_message_at.map(|at| self.clock.elapsed(at))
+    }
+
+    fn record_message(&mut self, msg: &MarketMessage) {
+        let now = self.clock.now();
+        self.last_message_at = Some(now);
+        let symbol = msg.symbol();
+        match self.last_seen.get_mut(symbol) {
+            Some(seen) => *seen = now,
+            None => {
+                self.last_seen.insert(symbol.to_string(), now);
+            }
+        }
+    }
+
+    /// Connect with exponential backoff and full jitter
+    ///
+    /// Each failed attempt sleeps a random duration in
+    /// `[0, min(max_backoff, 500ms * 2^(attempt - 1))]`. A successful
+    /// handshake re-subscribes to `self.symbols`. After
+    /// `max_reconnect_attempts` failures the last error is returned as
+    /// `TradingError::Network`. Every attempt after the client's very first
+    /// counts toward `websocket_reconnects_total`.
+    pub async fn connect_with_retry(&mut self) -> Result<()> {
+        let mut last_error = None;
+
+        for attempt in 1..=self.max_reconnect_attempts {
+            if self.connect_attempts > 0 {
+                metrics::counter!("websocket_reconnects_total").increment(1);
+            }
+            self.connect_attempts += 1;
+            info!("WebSocket connect attempt {}/{}", attempt, self.max_reconnect_attempts);
+
+            match self.connect_stream().await {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    if attempt == self.max_reconnect_attempts {
+                        last_error = Some(e);
+                        break;
+                    }
+
+                    let ceiling = self.backoff_ceiling(attempt);
+                    let delay = ceiling.mul_f64(rand::random::<f64>());
+                    warn!(
+                        "WebSocket connect attempt {}/{} failed: {}, retrying in {:?}",
+                        attempt, self.max_reconnect_attempts, e, delay
+                    );
+                    last_error = Some(e);
+                    sleep(delay).await;
+                }
+            }
+        }
+
+        Err(TradingError::Network(format!(
+            "WebSocket reconnect gave up after {} attempts: {}",
+            self.max_reconnect_attempts,
+            last_error.map(|e| e.to_string()).unwrap_or_default()
+        )))
+    }
+
+    /// Upper bound of the jittered delay after the given failed attempt
+    fn backoff_ceiling(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(32);
+        let delay_ms = INITIAL_BACKOFF_MS.saturating_mul(1u64 << exponent);
+        Duration::from_millis(delay_ms).min(self.max_backoff)
+    }
+
+    /// Receive the next market data message from the stream
+    ///
+    /// Returns `None` once the connection is closed or fails; the caller is
+    /// expected to reconnect with `connect_stream`.
+    pub async fn next_message(&mut self) -> Option<MarketMessage> {
+        loop {
+            if let Some(msg) = self.pending.pop_front() {
+                self.record_message(&msg);
+                return Some(msg);
+            }
+
+            let stream = self.stream.as_mut()?;
+            let msg = match stream.next().await {
+                Some(Ok(msg)) => msg,
+                Some(Err(e)) => {
+                    warn!("WebSocket receive error: {}", e);
+                    self.stream = None;
+                    return None;
+                }
+                None => {
+                    info!("WebSocket stream ended");
+                    self.stream = None;
+                    return None;
+                }
+            };
+
+            match msg {
+                Message::Text(text) => {
+                    self.pending.extend(self.adapter.parse_message(&text));
+                }
+                Message::Binary(data) => {
+                    debug!("Received binary message: {} bytes", data.len());
+                }
+                Message::Close(frame) => {
+                    info!("Received close frame: {:?}", frame);
+                    self.stream = None;
+                    return None;
+                }
+                // Pong replies to pings are sent automatically by tokio-tungstenite
+                Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => {}
+            }
+        }
+    }
+
     pub async fn connect<F>(&self, mut on_message: F) -> Result<()>
     where
-        F: FnMut(AlpacaMessage) -> Result<()> + Send + 'static,
+        F: FnMut(MarketMessage) -> Result<()> + Send + 'static,
     {
         loop {
             match self.connect_inner(&mut on_message).await {
@@ -110,52 +265,9 @@ impl WebSocketClient {
 
     async fn connect_inner<F>(&self, on_message: &mut F) -> Result<()>
     where
-        F: FnMut(AlpacaMessage) -> Result<()>,
+        F: FnMut(MarketMessage) -> Result<()>,
     {
-        info!("Connecting to Alpaca WebSocket: {}", self.url);
-
-        let (ws_stream, _) = connect_async(self.url.as_str())
-            .await
-            .map_err(|e| TradingError::Network(format!("Connection failed: {}", e)))?;
-
-        info!("WebSocket connected successfully");
-
-        let (mut write, mut read) = ws_stream.split();
-
-        // Send authentication
-        let auth_msg = json!({
-            "action": "auth",
-            "key": self.api_key,
-            "secret": self.api_secret
-        });
-
-        write
-            .send(Message::Text(auth_msg.to_string()))
-            .await
-            .map_err(|e| TradingError::Network(format!("Auth failed: {}", e)))?;
-
-        info!("Authentication sent");
-
-        // Wait for auth confirmation
-        if let Some(msg) = read.next().await {
-            let msg = msg.map_err(|e| TradingError::Network(format!("Auth response error: {}", e)))?;
-            debug!("Auth response: {:?}", msg);
-        }
-
-        // Subscribe to symbols
-        let subscribe_msg = json!({
-            "action": "subscribe",
-            "trades": self.symbols,
-            "quotes": self.symbols,
-            "bars": self.symbols
-        });
-
-        write
-            .send(Message::Text(subscribe_msg.to_string()))
-            .await
-            .map_err(|e| TradingError::Network(format!("Subscribe failed: {}", e)))?;
-
-        info!("Subscribed to symbols: {:?}", self.symbols);
+        let mut read = self.handshake().await?;
 
         // Process messages (heartbeat is handled automatically by tokio-tungstenite ping/pong)
         while let Some(msg) = read.next().await {
@@ -189,27 +301,51 @@ impl WebSocketClient {
         Ok(())
     }
 
+    /// Connect, authenticate and subscribe to `self.symbols`
+    async fn handshake(&self) -> Result<WsStream> {
+        info!("Connecting to {} WebSocket: {}", self.adapter.name(), self.url);
+
+        let (mut ws_stream, _) = connect_async(self.url.as_str())
+            .await
+            .map_err(|e| TradingError::Network(format!("Connection failed: {}", e)))?;
+
+        info!("WebSocket connected successfully");
+
+        // Send authentication
+        if let Some(auth_msg) = self.adapter.auth(&self.api_key, &self.api_secret) {
+            ws_stream
+                .send(Message::Text(auth_msg))
+                .await
+                .map_err(|e| TradingError::Network(format!("Auth failed: {}", e)))?;
+
+            info!("Authentication sent");
+
+            // Wait for auth confirmation
+            if let Some(msg) = ws_stream.next().await {
+                let msg = msg.map_err(|e| TradingError::Network(format!("Auth response error: {}", e)))?;
+                debug!("Auth response: {:?}", msg);
+            }
+        }
+
+        // Subscribe to symbols
+        let subscribe_msg = self.adapter.subscribe_message(&self.symbols);
+
+        ws_stream
+            .send(Message::Text(subscribe_msg))
+            .await
+            .map_err(|e| TradingError::Network(format!("Subscribe failed: {}", e)))?;
+
+        info!("Subscribed to symbols: {:?}", self.symbols);
+
+        Ok(ws_stream)
+    }
+
     fn handle_text_message<F>(&self, text: &str, on_message: &mut F) -> Result<()>
     where
-        F: FnMut(AlpacaMessage) -> Result<()>,
+        F: FnMut(MarketMessage) -> Result<()>,
     {
-        // Try to parse as array of messages
-        if let Ok(messages) = serde_json::from_str::<Vec<AlpacaMessage>>(text) {
-            for msg in messages {
-                match msg {
-                    AlpacaMessage::Unknown => {
-                        debug!("Unknown message type: {}", text);
-                    }
-                    _ => {
-                        on_message(msg)?;
-                    }
-                }
-            }
-        } else if let Ok(value) = serde_json::from_str::<Value>(text) {
-            // Handle control messages (auth confirmation, subscription confirmation, etc.)
-            debug!("Control message: {:?}", value);
-        } else {
-            warn!("Failed to parse message: {}", text);
+        for msg in self.adapter.parse_message(text) {
+            on_message(msg)?;
         }
 
         Ok(())
@@ -219,6 +355,7 @@ impl WebSocketClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::exchange::AlpacaMessage;
 
     #[test]
     fn test_parse_trade_message() {
@@ -233,4 +370,55 @@ mod tests {
         let messages: Vec<AlpacaMessage> = serde_json::from_str(json).unwrap();
         assert_eq!(messages.len(), 1);
     }
+
+    #[test]
+    fn test_backoff_ceiling() {
+        let client = WebSocketClient::new("key".into(), "secret".into(), vec![])
+            .unwrap()
+            .with_max_backoff(Duration::from_secs(5));
+
+        assert_eq!(client.backoff_ceiling(1), Duration::from_millis(500));
+        assert_eq!(client.backoff_ceiling(2), Duration::from_millis(1000));
+        assert_eq!(client.backoff_ceiling(4), Duration::from_millis(4000));
+        assert_eq!(client.backoff_ceiling(5), Duration::from_secs(5));
+        assert_eq!(client.backoff_ceiling(100), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_staleness_tracking() {
+        let mut client = WebSocketClient::new("key".into(), "secret".into(), vec![]).unwrap();
+        let max_age = Duration::from_secs(30);
+
+        assert!(client.is_stale("AAPL", max_age));
+        assert!(client.last_message_age().is_none());
+
+        let messages = client.adapter().parse_message(
+            r#"[{"T":"t","S":"AAPL","p":150.25,"s":100,"t":"2024-01-01T10:00:00Z","i":1}]"#,
+        );
+        client.record_message(&messages[0]);
+
+        assert!(!client.is_stale("AAPL", max_age));
+        assert!(client.is_stale("MSFT", max_age));
+        assert!(client.last_message_age().unwrap() < max_age);
+    }
+
+    #[test]
+    fn test_staleness_with_mock_clock() {
+        let clock = common::MockClock::starting_now();
+        let mut client = WebSocketClient::new("key".into(), "secret".into(), vec![])
+            .unwrap()
+            .with_clock(clock.clone());
+        let max_age = Duration::from_secs(30);
+
+        let messages = client.adapter().parse_message(
+            r#"[{"T":"t","S":"AAPL","p":150.25,"s":100,"t":"2024-01-01T10:00:00Z","i":1}]"#,
+        );
+        client.record_message(&messages[0]);
+
+        clock.advance(Duration::from_secs(29));
+        assert!(!client.is_stale("AAPL", max_age));
+        clock.advance(Duration::from_secs(2));
+        assert!(client.is_stale("AAPL", max_age));
+        assert_eq!(client.last_message_age(), Some(Duration::from_secs(31)));
+    }
 }
@@ -0,0 +1,79 @@
+// Round-trip tests between MarketDataPublisher and MarketDataSubscriber
+use chrono::Utc;
+use common::messaging::{Codec, Message};
+use common::types::{Bar, Price, Quantity, Symbol};
+use market_data::{Compression, MarketDataPublisher, MarketDataSubscriber, MarketMessage};
+use std::time::Duration;
+
+fn bar(symbol: &str, close: f64) -> Bar {
+    Bar {
+        symbol: Symbol(symbol.to_string()),
+        open: Price(close),
+        high: Price(close),
+        low: Price(close),
+        close: Price(close),
+        volume: Quantity(1000.0),
+        timestamp: Utc::now(),
+    }
+}
+
+/// Publish until the subscriber receives something, working around the ZMQ
+/// slow-joiner problem where messages sent before the subscription propagates
+/// are dropped
+async fn publish_until_received(
+    publisher: &MarketDataPublisher,
+    subscriber: &mut MarketDataSubscriber,
+) -> MarketMessage {
+    for _ in 0..100 {
+        publisher.publish(Message::BarUpdate(bar("MSFT", 400.0))).unwrap();
+        publisher.publish(Message::BarUpdate(bar("AAPL", 150.0))).unwrap();
+
+        if let Ok(received) = tokio::time::timeout(Duration::from_millis(50), subscriber.recv()).await {
+            return received.unwrap();
+        }
+    }
+    panic!("no message received");
+}
+
+#[tokio::test]
+async fn test_round_trip_with_topic_filter() {
+    let address = "tcp://127.0.0.1:25561";
+    let publisher = MarketDataPublisher::new(address).unwrap();
+    let mut subscriber =
+        MarketDataSubscriber::connect(address, &["market.bar.AAPL".to_string()]).unwrap();
+
+    // Only AAPL bars pass the filter, never the MSFT bar published first
+    for _ in 0..3 {
+        match publish_until_received(&publisher, &mut subscriber).await {
+            MarketMessage::Bar(bar) => {
+                assert_eq!(bar.symbol, Symbol("AAPL".to_string()));
+                assert_eq!(bar.close, Price(150.0));
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_round_trip_compressed() {
+    let address = "tcp://127.0.0.1:25562";
+    let publisher = MarketDataPublisher::with_compression(address, Compression::Zstd).unwrap();
+    let mut subscriber = MarketDataSubscriber::connect(address, &[]).unwrap();
+
+    match publish_until_received(&publisher, &mut subscriber).await {
+        MarketMessage::Bar(_) => {}
+        other => panic!("unexpected message: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_round_trip_bincode() {
+    let address = "tcp://127.0.0.1:25563";
+    let publisher = MarketDataPublisher::new(address).unwrap().with_codec(Codec::Bincode);
+    let mut subscriber = MarketDataSubscriber::connect(address, &[]).unwrap();
+
+    match publish_until_received(&publisher, &mut subscriber).await {
+        MarketMessage::Bar(bar) => assert!(bar.close == Price(400.0) || bar.close == Price(150.0)),
+        other => panic!("unexpected message: {:?}", other),
+    }
+}
@@ -0,0 +1,172 @@
+use crate::stops::{ExitTrigger, StopLossConfig, StopManager, TakeProfitConfig};
+use common::{
+    config::RiskConfig,
+    types::{Position, Price, Symbol},
+    Result,
+};
+use tracing::info;
+
+/// Which leg of a bracket fired; the other leg is cancelled with it
+///
+/// Stop-loss exits carry a `StopLossTrigger` and take-profit exits a
+/// `TakeProfitTrigger`, exactly as `StopManager::check_exit` reports them.
+pub type BracketTrigger = ExitTrigger;
+
+/// Manages one-cancels-other stop-loss / take-profit brackets
+///
+/// Both legs live in a `StopManager`; a bracket is a stop paired with an
+/// absolute take-profit target.
+pub struct BracketManager {
+    stops: StopManager,
+}
+
+impl BracketManager {
+    pub fn new(config: RiskConfig) -> Self {
+        Self {
+            stops: StopManager::new(config),
+        }
+    }
+
+    /// Set a stop-loss and take-profit pair for a position
+    pub fn set_bracket(&mut self, position: &Position, stop: StopLossConfig, take_profit: Price) -> Result<()> {
+        self.stops
+            .set_take_profit(position, TakeProfitConfig::absolute_target(take_profit)?)?;
+        if let Err(e) = self.stops.set_stop(position, stop) {
+            self.stops.remove_take_profit(&position.symbol);
+            return Err(e);
+        }
+
+        info!(
+            "Bracket set for {}: take_profit={:.8}",
+            position.symbol.0, take_profit.0
+        );
+        Ok(())
+    }
+
+    /// Check a position against its bracket
+    ///
+    /// When either leg fires, the other is cancelled and both are removed.
+    /// Partial scale-out stop triggers keep the bracket in place.
+    pub fn check(&mut self, position: &Position) -> Option<BracketTrigger> {
+        if !self.has_bracket(&position.symbol) {
+            return None;
+        }
+        self.stops.check_exit(position)
+    }
+
+    /// Remove both legs of a bracket
+    pub fn remove_bracket(&mut self, symbol: &Symbol) {
+        self.stops.remove_take_profit(symbol);
+        self.stops.remove_stop(symbol);
+    }
+
+    /// Check if a position has an active bracket
+    pub fn has_bracket(&self, symbol: &Symbol) -> bool {
+        self.stops.has_take_profit(symbol)
+    }
+
+    /// Get the take-profit level for a symbol
+    pub fn take_profit(&self, symbol: &Symbol) -> Option<Price> {
+        self.stops.get_take_profit(symbol).map(|state| state.target_price())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stops::TakeProfitType;
+    use chrono::Utc;
+    use common::types::{Currency, Quantity, Side};
+
+    fn create_test_position(symbol: &str, side: Side, entry: f64, current: f64, qty: f64) -> Position {
+        let unrealized_pnl = match side {
+            Side::Bid => (current - entry) * qty,
+            Side::Ask => (entry - current) * qty,
+        };
+
+        Position {
+            symbol: Symbol(symbol.to_string()),
+            side,
+            quantity: Quantity(qty),
+            entry_price: Price(entry),
+            current_price: Price(current),
+            unrealized_pnl,
+            realized_pnl: 0.0,
+            opened_at: Utc::now(),
+            updated_at: Utc::now(),
+            currency: Currency::default(),
+        }
+    }
+
+    fn create_test_config() -> RiskConfig {
+        RiskConfig {
+            max_position_size: 10000.0,
+            max_notional_exposure: 50000.0,
+            max_open_positions: 5,
+            stop_loss_percent: 5.0,
+            trailing_stop_percent: 3.0,
+            enable_circuit_breaker: true,
+            max_loss_threshold: 1000.0,
+            circuit_breaker_cooldown_secs: 300,
+            max_price_velocity_pct: 10.0,
+            max_leverage: 2.0,
+        }
+    }
+
+    #[test]
+    fn test_take_profit_cancels_stop() {
+        let mut manager = BracketManager::new(create_test_config());
+        let position = create_test_position("AAPL", Side::Bid, 100.0, 100.0, 10.0);
+
+        manager
+            .set_bracket(&position, StopLossConfig::static_stop(5.0).unwrap(), Price(110.0))
+            .unwrap();
+
+        let mut pos_updated = position.clone();
+        pos_updated.current_price = Price(109.0);
+        assert!(manager.check(&pos_updated).is_none());
+
+        pos_updated.current_price = Price(110.5);
+        let trigger = manager.check(&pos_updated).unwrap();
+        assert!(matches!(&trigger, BracketTrigger::TakeProfit(tp) if tp.take_profit_type == TakeProfitType::Absolute));
+        assert_eq!(trigger.close_side(), Side::Ask);
+        assert_eq!(trigger.close_quantity().0, 10.0);
+
+        // Both legs gone: a later drop doesn't fire the stop
+        assert!(!manager.has_bracket(&position.symbol));
+        pos_updated.current_price = Price(90.0);
+        assert!(manager.check(&pos_updated).is_none());
+    }
+
+    #[test]
+    fn test_stop_cancels_take_profit() {
+        let mut manager = BracketManager::new(create_test_config());
+        let position = create_test_position("TSLA", Side::Ask, 200.0, 200.0, 5.0);
+
+        manager
+            .set_bracket(&position, StopLossConfig::static_stop(5.0).unwrap(), Price(180.0))
+            .unwrap();
+
+        let mut pos_updated = position.clone();
+        pos_updated.current_price = Price(211.0);
+        let trigger = manager.check(&pos_updated).unwrap();
+        assert!(matches!(trigger, BracketTrigger::StopLoss(_)));
+        assert_eq!(trigger.close_side(), Side::Bid);
+
+        assert!(!manager.has_bracket(&position.symbol));
+        pos_updated.current_price = Price(170.0);
+        assert!(manager.check(&pos_updated).is_none());
+    }
+
+    #[test]
+    fn test_invalid_take_profit() {
+        let mut manager = BracketManager::new(create_test_config());
+        let long = create_test_position("AAPL", Side::Bid, 100.0, 100.0, 1.0);
+        let short = create_test_position("TSLA", Side::Ask, 200.0, 200.0, 1.0);
+        let stop = StopLossConfig::static_stop(5.0).unwrap();
+
+        assert!(manager.set_bracket(&long, stop.clone(), Price(95.0)).is_err());
+        assert!(manager.set_bracket(&short, stop, Price(210.0)).is_err());
+        assert!(!manager.has_bracket(&long.symbol));
+    }
+}
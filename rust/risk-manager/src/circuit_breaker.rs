@@ -1,31 +1,466 @@
-use common::{Result, TradingError, config::RiskConfig};
+use chrono::{DateTime, Utc};
+use common::{Result, SharedClock, SystemClock, TradingError, config::RiskConfig, types::{Price, Symbol}};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Default number of probe orders allowed while half-open
+const DEFAULT_HALF_OPEN_PROBES: u32 = 3;
+
+/// Maximum price samples kept per symbol for volatility checks
+const MAX_PRICE_SAMPLES: usize = 256;
+
+/// Global circuit breaker state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CircuitState {
+    /// Trading allowed
+    Closed,
+    /// Tripped; all orders rejected until the cooldown elapses
+    Open,
+    /// Cooldown elapsed; a limited number of probe orders allowed
+    HalfOpen,
+}
 
 pub struct CircuitBreaker {
-    #[allow(dead_code)]
     config: RiskConfig,
-    is_tripped: bool,
+    state: CircuitState,
+    /// When the breaker last opened
+    opened_at: Option<DateTime<Utc>>,
+    cooldown: Duration,
+    /// Probe orders allowed (and successes required to close) while half-open
+    half_open_probes: u32,
+    /// Probe orders let through since the breaker last opened
+    probes_issued: AtomicU32,
+    probe_successes: u32,
+    /// Loss threshold for a single symbol before it is halted
+    symbol_loss_threshold: f64,
+    /// Accumulated P&L per symbol since its last reset
+    symbol_pnl: HashMap<String, f64>,
+    /// Accumulated P&L across all symbols since the last global reset
+    total_pnl: f64,
+    tripped_symbols: HashSet<String>,
+    /// Recent prices per symbol for volatility checks, oldest first
+    price_samples: HashMap<String, VecDeque<(DateTime<Utc>, f64)>>,
+    clock: SharedClock,
 }
 
 impl CircuitBreaker {
     pub fn new(config: RiskConfig) -> Self {
         Self {
+            symbol_loss_threshold: config.max_loss_threshold,
+            cooldown: Duration::from_secs(config.circuit_breaker_cooldown_secs),
             config,
-            is_tripped: false,
+            state: CircuitState::Closed,
+            opened_at: None,
+            half_open_probes: DEFAULT_HALF_OPEN_PROBES,
+            probes_issued: AtomicU32::new(0),
+            probe_successes: 0,
+            symbol_pnl: HashMap::new(),
+            total_pnl: 0.0,
+            tripped_symbols: HashSet::new(),
+            price_samples: HashMap::new(),
+            clock: SystemClock::shared(),
         }
     }
 
+    /// Clock used for cooldowns and volatility windows
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Check the global breaker
+    ///
+    /// Rejects while open. Once the cooldown has elapsed the breaker is
+    /// half-open and lets through up to `half_open_probes` orders, whose
+    /// outcomes should be reported via `record_result`.
     pub fn check(&self) -> Result<()> {
-        if self.is_tripped {
-            return Err(TradingError::RiskCheck("Circuit breaker tripped".to_string()));
+        match self.state() {
+            CircuitState::Closed => Ok(()),
+            CircuitState::Open => {
+                let remaining = self
+                    .opened_at
+                    .map(|at| self.cooldown.saturating_sub(self.clock.elapsed(at)))
+                    .unwrap_or(self.cooldown);
+                Err(TradingError::RiskCheck(format!(
+                    "Circuit breaker tripped (open, {}s until half-open)",
+                    remaining.as_secs()
+                )))
+            }
+            CircuitState::HalfOpen => {
+                let limit = self.half_open_probes;
+                let admitted = self
+                    .probes_issued
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |issued| {
+                        (issued < limit).then_some(issued + 1)
+                    })
+                    .is_ok();
+
+                if admitted {
+                    Ok(())
+                } else {
+                    Err(TradingError::RiskCheck(
+                        "Circuit breaker half-open, probe limit reached".to_string(),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Current state, moving from open to half-open once the cooldown elapses
+    pub fn state(&self) -> CircuitState {
+        match (self.state, self.opened_at) {
+            (CircuitState::Open, Some(at)) if self.clock.elapsed(at) >= self.cooldown => CircuitState::HalfOpen,
+            (state, _) => state,
+        }
+    }
+
+    /// Report the outcome of an order let through by `check`
+    ///
+    /// While half-open, a failure re-trips the breaker and enough successes
+    /// close it.
+    pub fn record_result(&mut self, success: bool) {
+        if self.state() != CircuitState::HalfOpen {
+            return;
+        }
+
+        if success {
+            self.probe_successes += 1;
+            if self.probe_successes >= self.half_open_probes {
+                info!("Circuit breaker closed after {} successful probes", self.probe_successes);
+                self.reset();
+            }
+        } else {
+            warn!("Circuit breaker probe failed, re-opening");
+            self.trip();
+        }
+    }
+
+    /// Set how many probe orders are allowed while half-open
+    pub fn set_half_open_probes(&mut self, probes: u32) {
+        self.half_open_probes = probes.max(1);
+    }
+
+    /// Check the per-symbol breaker for a symbol
+    pub fn check_symbol(&self, symbol: &Symbol) -> Result<()> {
+        if self.tripped_symbols.contains(&symbol.0) {
+            return Err(TradingError::RiskCheck(format!(
+                "Circuit breaker tripped for {}",
+                symbol.0
+            )));
         }
         Ok(())
     }
 
+    /// Record realized P&L for a symbol
+    ///
+    /// Trips the symbol's breaker once its accumulated loss reaches the
+    /// per-symbol threshold, and the global breaker once the loss across all
+    /// symbols reaches `max_loss_threshold`.
+    pub fn record_pnl(&mut self, symbol: &Symbol, pnl: f64) {
+        let symbol_pnl = self.symbol_pnl.entry(symbol.0.clone()).or_insert(0.0);
+        *symbol_pnl += pnl;
+
+        if *symbol_pnl <= -self.symbol_loss_threshold && self.tripped_symbols.insert(symbol.0.clone()) {
+            warn!(
+                "Circuit breaker tripped for {}: loss {:.2} reached threshold {:.2}",
+                symbol.0, -*symbol_pnl, self.symbol_loss_threshold
+            );
+        }
+
+        self.total_pnl += pnl;
+        if self.config.enable_circuit_breaker
+            && self.state == CircuitState::Closed
+            && self.total_pnl <= -self.config.max_loss_threshold
+        {
+            warn!(
+                "Global circuit breaker tripped: loss {:.2} reached threshold {:.2}",
+                -self.total_pnl, self.config.max_loss_threshold
+            );
+            self.trip();
+        }
+    }
+
+    /// Evaluate `max_loss_threshold` against session P&L
+    ///
+    /// Trips the global breaker when the session loss reaches the threshold,
+    /// independent of the all-time accumulator used by `record_pnl`.
+    pub fn check_session_loss(&mut self, session_pnl: f64) -> Result<()> {
+        if self.config.enable_circuit_breaker
+            && self.state == CircuitState::Closed
+            && session_pnl <= -self.config.max_loss_threshold
+        {
+            warn!(
+                "Global circuit breaker tripped: session loss {:.2} reached threshold {:.2}",
+                -session_pnl, self.config.max_loss_threshold
+            );
+            self.trip();
+        }
+
+        self.check()
+    }
+
+    /// Record a price and halt the symbol if it moved too fast
+    ///
+    /// Compares `current_price` against every sample from the last `window`
+    /// and trips the symbol's breaker when the largest move exceeds
+    /// `max_price_velocity_pct`. Returns Ok until at least two samples exist.
+    pub fn check_volatility(&mut self, symbol: &Symbol, current_price: Price, window: Duration) -> Result<()> {
+        let now = self.clock.now();
+        let clock = &self.clock;
+        let samples = self.price_samples.entry(symbol.0.clone()).or_default();
+
+        // Drop samples outside the window and cap the buffer
+        while samples
+            .front()
+            .is_some_and(|(at, _)| clock.elapsed(*at) > window)
+        {
+            samples.pop_front();
+        }
+        if samples.len() == MAX_PRICE_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back((now, current_price.0));
+
+        if samples.len() < 2 {
+            return Ok(());
+        }
+
+        let max_move_pct = samples
+            .iter()
+            .filter(|(_, price)| *price > 0.0)
+            .map(|(_, price)| ((current_price.0 - price) / price * 100.0).abs())
+            .fold(0.0, f64::max);
+
+        if max_move_pct > self.config.max_price_velocity_pct {
+            if self.tripped_symbols.insert(symbol.0.clone()) {
+                warn!(
+                    "Volatility halt for {}: {:.2}% move within {:?} exceeds {:.2}%",
+                    symbol.0, max_move_pct, window, self.config.max_price_velocity_pct
+                );
+            }
+            return Err(TradingError::RiskCheck(format!(
+                "Volatility halt for {}: {:.2}% move within {}s exceeds {:.2}%",
+                symbol.0,
+                max_move_pct,
+                window.as_secs(),
+                self.config.max_price_velocity_pct
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Set the loss threshold applied to each symbol
+    pub fn set_symbol_loss_threshold(&mut self, threshold: f64) {
+        self.symbol_loss_threshold = threshold;
+    }
+
+    /// Symbols currently halted by their per-symbol breaker
+    pub fn tripped_symbols(&self) -> Vec<Symbol> {
+        let mut symbols: Vec<Symbol> = self
+            .tripped_symbols
+            .iter()
+            .map(|s| Symbol(s.clone()))
+            .collect();
+        symbols.sort_by(|a, b| a.0.cmp(&b.0));
+        symbols
+    }
+
     pub fn trip(&mut self) {
-        self.is_tripped = true;
+        self.state = CircuitState::Open;
+        self.opened_at = Some(self.clock.now());
+        self.probes_issued.store(0, Ordering::SeqCst);
+        self.probe_successes = 0;
+    }
+
+    /// Halt a single symbol
+    pub fn trip_symbol(&mut self, symbol: &Symbol) {
+        self.tripped_symbols.insert(symbol.0.clone());
     }
 
     pub fn reset(&mut self) {
-        self.is_tripped = false;
+        self.state = CircuitState::Closed;
+        self.opened_at = None;
+        self.probes_issued.store(0, Ordering::SeqCst);
+        self.probe_successes = 0;
+        self.total_pnl = 0.0;
+    }
+
+    /// Resume trading a symbol and clear its loss accumulator
+    pub fn reset_symbol(&mut self, symbol: &Symbol) {
+        self.tripped_symbols.remove(&symbol.0);
+        self.symbol_pnl.remove(&symbol.0);
+        self.price_samples.remove(&symbol.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::MockClock;
+
+    fn create_test_config_with_cooldown(cooldown_secs: u64) -> RiskConfig {
+        RiskConfig {
+            circuit_breaker_cooldown_secs: cooldown_secs,
+            ..create_test_config()
+        }
+    }
+
+    fn create_test_config() -> RiskConfig {
+        RiskConfig {
+            max_position_size: 10000.0,
+            max_notional_exposure: 50000.0,
+            max_open_positions: 5,
+            stop_loss_percent: 5.0,
+            trailing_stop_percent: 3.0,
+            enable_circuit_breaker: true,
+            max_loss_threshold: 1000.0,
+            circuit_breaker_cooldown_secs: 300,
+            max_price_velocity_pct: 10.0,
+            max_leverage: 2.0,
+        }
+    }
+
+    fn sym(s: &str) -> Symbol {
+        Symbol(s.to_string())
+    }
+
+    #[test]
+    fn test_symbol_breaker_isolated() {
+        let mut breaker = CircuitBreaker::new(create_test_config());
+        breaker.set_symbol_loss_threshold(300.0);
+
+        breaker.record_pnl(&sym("MEME"), -200.0);
+        assert!(breaker.check_symbol(&sym("MEME")).is_ok());
+
+        breaker.record_pnl(&sym("MEME"), -150.0);
+        assert!(breaker.check_symbol(&sym("MEME")).is_err());
+        assert!(breaker.check_symbol(&sym("SPY")).is_ok());
+        assert!(breaker.check().is_ok());
+        assert_eq!(breaker.tripped_symbols(), vec![sym("MEME")]);
+
+        breaker.reset_symbol(&sym("MEME"));
+        assert!(breaker.check_symbol(&sym("MEME")).is_ok());
+        assert!(breaker.tripped_symbols().is_empty());
+    }
+
+    #[test]
+    fn test_global_breaker_on_aggregate_loss() {
+        let mut breaker = CircuitBreaker::new(create_test_config());
+        breaker.set_symbol_loss_threshold(800.0);
+
+        breaker.record_pnl(&sym("A"), -600.0);
+        breaker.record_pnl(&sym("B"), -500.0);
+
+        // Neither symbol hit its own threshold, but the total did
+        assert!(breaker.tripped_symbols().is_empty());
+        assert!(breaker.check().is_err());
+
+        breaker.reset();
+        assert!(breaker.check().is_ok());
+    }
+
+    #[test]
+    fn test_stays_open_during_cooldown() {
+        let mut breaker = CircuitBreaker::new(create_test_config_with_cooldown(60));
+        breaker.trip();
+
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(breaker.check().is_err());
+
+        // Results reported while open don't change state
+        breaker.record_result(true);
+        assert_eq!(breaker.state(), CircuitState::Open);
+    }
+
+    #[test]
+    fn test_cooldown_elapses_on_mock_clock() {
+        let clock = MockClock::starting_now();
+        let mut breaker = CircuitBreaker::new(create_test_config_with_cooldown(60)).with_clock(clock.clone());
+        breaker.trip();
+
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(breaker.check().unwrap_err().to_string().contains("1s until half-open"));
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.check().is_ok());
+    }
+
+    #[test]
+    fn test_half_open_probes_close_breaker() {
+        let mut breaker = CircuitBreaker::new(create_test_config_with_cooldown(0));
+        breaker.set_half_open_probes(2);
+        breaker.trip();
+
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.check().is_ok());
+        assert!(breaker.check().is_ok());
+        assert!(breaker.check().is_err()); // probe limit reached
+
+        breaker.record_result(true);
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        breaker.record_result(true);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.check().is_ok());
+    }
+
+    #[test]
+    fn test_half_open_failure_reopens() {
+        let mut breaker = CircuitBreaker::new(create_test_config_with_cooldown(0));
+        breaker.trip();
+        assert!(breaker.check().is_ok());
+
+        breaker.record_result(false);
+
+        // Re-opened with a fresh probe budget once the (zero) cooldown elapses
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.check().is_ok());
+
+        breaker.cooldown = Duration::from_secs(60);
+        breaker.record_result(false);
+        assert_eq!(breaker.state(), CircuitState::Open);
+    }
+
+    #[test]
+    fn test_volatility_halt() {
+        let mut breaker = CircuitBreaker::new(create_test_config());
+        let window = Duration::from_secs(60);
+
+        // A single sample is never enough to halt
+        assert!(breaker.check_volatility(&sym("BTC"), Price(100.0), window).is_ok());
+        assert!(breaker.check_volatility(&sym("BTC"), Price(105.0), window).is_ok());
+        assert!(breaker.check_volatility(&sym("ETH"), Price(50.0), window).is_ok());
+
+        // 100 -> 88 is a 12% move, above the 10% threshold
+        let err = breaker.check_volatility(&sym("BTC"), Price(88.0), window).unwrap_err();
+        assert!(err.to_string().contains("Volatility halt for BTC"));
+        assert!(breaker.check_symbol(&sym("BTC")).is_err());
+        assert!(breaker.check_symbol(&sym("ETH")).is_ok());
+    }
+
+    #[test]
+    fn test_volatility_ignores_samples_outside_window() {
+        let clock = MockClock::starting_now();
+        let mut breaker = CircuitBreaker::new(create_test_config()).with_clock(clock.clone());
+        let window = Duration::from_secs(60);
+
+        assert!(breaker.check_volatility(&sym("BTC"), Price(100.0), window).is_ok());
+        clock.advance(Duration::from_secs(61));
+        assert!(breaker.check_volatility(&sym("BTC"), Price(50.0), window).is_ok());
+        assert!(breaker.tripped_symbols().is_empty());
+    }
+
+    #[test]
+    fn test_session_loss_trips_breaker() {
+        let mut breaker = CircuitBreaker::new(create_test_config());
+
+        assert!(breaker.check_session_loss(-500.0).is_ok());
+        assert!(breaker.check_session_loss(-1000.0).is_err());
+        assert_eq!(breaker.state(), CircuitState::Open);
     }
 }
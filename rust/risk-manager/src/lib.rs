@@ -6,20 +6,103 @@ pub mod limits;
 pub mod pnl;
 pub mod stops;
 pub mod circuit_breaker;
+pub mod bracket;
+pub mod sizing;
+pub mod performance;
 
-pub use limits::LimitChecker;
+pub use limits::{LimitChecker, SymbolLimit};
 pub use pnl::PnLTracker;
-pub use stops::{StopManager, StopLossConfig, StopLossType, StopLossTrigger};
-pub use circuit_breaker::CircuitBreaker;
+pub use stops::{
+    ExitTrigger, StopLossConfig, StopLossTrigger, StopLossType, StopManager, TakeProfitConfig,
+    TakeProfitTrigger, TakeProfitType,
+};
+pub use circuit_breaker::{CircuitBreaker, CircuitState};
+pub use bracket::{BracketManager, BracketTrigger};
+pub use sizing::PositionSizer;
+pub use performance::PerformanceStats;
 
-use common::{Result, types::{Order, Position}};
+use chrono::{DateTime, Utc};
+use common::{
+    EventBus, Result, SystemSignal,
+    types::{Order, OrderStatus, OrderType, Position, Price, Quantity, Side, Signal, SignalAction, Symbol, TimeInForce},
+};
+use serde::Serialize;
 use tracing::{info, warn};
 
+/// Point-in-time view of the whole risk picture
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskSnapshot {
+    pub timestamp: DateTime<Utc>,
+    pub total_unrealized_pnl: f64,
+    pub total_realized_pnl: f64,
+    pub open_positions: usize,
+    pub total_notional_exposure: f64,
+    pub active_stops: usize,
+    pub circuit_state: CircuitState,
+    pub halted_symbols: Vec<Symbol>,
+    pub current_drawdown: f64,
+    pub max_drawdown: f64,
+}
+
+/// Relative difference in entry price tolerated before reconciliation
+/// reports a mismatch (brokers round average prices)
+const ENTRY_PRICE_TOLERANCE: f64 = 1e-4;
+
+/// Quantities closer than this are considered equal
+const RECONCILE_QUANTITY_EPSILON: f64 = 1e-9;
+
+/// One symbol whose local and broker positions disagree
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PositionMismatch {
+    pub symbol: Symbol,
+    /// Signed quantity (negative when short)
+    pub local_quantity: f64,
+    pub broker_quantity: f64,
+    pub local_entry_price: Price,
+    pub broker_entry_price: Price,
+}
+
+/// Result of comparing local positions to the broker's
+#[derive(Debug, Clone, Serialize)]
+pub struct ReconcileReport {
+    pub timestamp: DateTime<Utc>,
+    /// Held at the broker but unknown locally
+    pub missing_locally: Vec<Symbol>,
+    /// Tracked locally but not held at the broker
+    pub extra_locally: Vec<Symbol>,
+    /// Held on both sides with different signed quantities
+    pub quantity_mismatches: Vec<PositionMismatch>,
+    /// Same quantity but a different average entry price
+    pub entry_price_mismatches: Vec<PositionMismatch>,
+    /// Whether local state was replaced with the broker's positions
+    pub adopted: bool,
+}
+
+impl ReconcileReport {
+    /// True when local state matches the broker
+    pub fn is_clean(&self) -> bool {
+        self.missing_locally.is_empty()
+            && self.extra_locally.is_empty()
+            && self.quantity_mismatches.is_empty()
+            && self.entry_price_mismatches.is_empty()
+    }
+}
+
+fn signed_quantity(position: &Position) -> f64 {
+    match position.side {
+        Side::Bid => position.quantity.0,
+        Side::Ask => -position.quantity.0,
+    }
+}
+
 pub struct RiskManagerService {
     limit_checker: LimitChecker,
     pnl_tracker: PnLTracker,
     stop_manager: StopManager,
     circuit_breaker: CircuitBreaker,
+    position_sizer: PositionSizer,
+    /// Where stop triggers and breaker trips are published, if wired up
+    events: Option<EventBus>,
 }
 
 impl RiskManagerService {
@@ -29,31 +112,334 @@ impl RiskManagerService {
             limit_checker: LimitChecker::new(config.clone()),
             pnl_tracker: PnLTracker::new(),
             stop_manager: StopManager::new(config.clone()),
+            position_sizer: PositionSizer::new(&config),
             circuit_breaker: CircuitBreaker::new(config),
+            events: None,
         })
     }
 
+    /// Drive every time-based component (stop holding periods, breaker
+    /// cooldowns, P&L timestamps) from `clock`
+    pub fn with_clock(mut self, clock: common::SharedClock) -> Self {
+        self.pnl_tracker = self.pnl_tracker.with_clock(clock.clone());
+        self.stop_manager = self.stop_manager.with_clock(clock.clone());
+        self.circuit_breaker = self.circuit_breaker.with_clock(clock);
+        self
+    }
+
+    /// Publish stop triggers and circuit breaker trips to `bus` so the
+    /// execution engine can act on them
+    pub fn with_event_bus(mut self, bus: EventBus) -> Self {
+        self.events = Some(bus);
+        self
+    }
+
+    fn publish(&self, signal: SystemSignal) {
+        if let Some(bus) = &self.events {
+            if bus.publish(signal) == 0 {
+                warn!("No subscribers for risk event");
+            }
+        }
+    }
+
+    /// Apply reloaded risk limits without restarting the service.
+    ///
+    /// The config is validated first; an invalid config is rejected and the
+    /// current limits stay in effect.
+    pub fn update_risk_config(&mut self, config: common::config::RiskConfig) -> Result<()> {
+        config.validate()?;
+        info!(
+            "Applying risk limits: max_position_size={}, max_notional_exposure={}, max_open_positions={}",
+            config.max_position_size, config.max_notional_exposure, config.max_open_positions
+        );
+        self.stop_manager.update_config(config.clone());
+        self.limit_checker.update_config(config);
+        Ok(())
+    }
+
     pub fn check_order(&self, order: &Order) -> Result<bool> {
-        // Check all risk constraints
+        // Check all risk constraints, rejecting malformed orders first
         self.limit_checker.check(order)?;
+
+        if let Err(e) = self.circuit_breaker.check_symbol(&order.symbol) {
+            warn!(
+                "Order for {} rejected, halted symbols: {:?}",
+                order.symbol.0,
+                self.circuit_breaker.tripped_symbols()
+            );
+            return Err(e);
+        }
         self.circuit_breaker.check()?;
         Ok(true)
     }
 
     pub fn update_position(&mut self, position: Position) -> Option<StopLossTrigger> {
-        // Update P&L tracking
+        // Update P&L and exposure tracking
         self.pnl_tracker.update(&position);
+        self.limit_checker.register_position(&position);
 
         // Check stop-loss and return trigger if activated
         let trigger = self.stop_manager.check(&position);
 
-        if trigger.is_some() {
+        if let Some(trigger) = &trigger {
             warn!("Stop-loss triggered for position: {:?}", position.symbol);
+            self.publish(SystemSignal::StopTriggered(trigger.to_event()));
         }
 
         trigger
     }
 
+    /// Turn a signal into a fixed-fractional sized limit order at `entry`
+    ///
+    /// Returns `None` for `Hold` signals or when sizing yields zero quantity.
+    /// The order still needs to pass `check_order`.
+    pub fn size_signal(
+        &self,
+        signal: &Signal,
+        account_equity: f64,
+        risk_fraction: f64,
+        entry: Price,
+        stop: Price,
+    ) -> Option<Order> {
+        let side = match signal.action {
+            SignalAction::Buy => Side::Bid,
+            SignalAction::Sell => Side::Ask,
+            SignalAction::Hold => return None,
+        };
+
+        let quantity = self
+            .position_sizer
+            .fixed_fractional(account_equity, risk_fraction, entry, stop);
+        if quantity.0 <= 0.0 {
+            return None;
+        }
+
+        let now = Utc::now();
+        Some(Order {
+            order_id: format!("sized-{}-{}", signal.symbol.0, now.timestamp_millis()),
+            client_order_id: format!("sized-{}", now.timestamp_nanos_opt().unwrap_or(0)),
+            symbol: signal.symbol.clone(),
+            side,
+            order_type: OrderType::Limit,
+            quantity,
+            price: Some(entry),
+            stop_price: None,
+            time_in_force: TimeInForce::Gtc,
+            status: OrderStatus::Pending,
+            filled_quantity: Quantity(0.0),
+            average_price: None,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Record a fill against the FIFO cost basis and return the P&L it realized
+    ///
+    /// Realized P&L feeds the circuit breaker: a symbol whose losses reach
+    /// the per-symbol threshold is halted in `check_order`, and losses across
+    /// all symbols can trip the global breaker.
+    pub fn record_fill(&mut self, symbol: &Symbol, side: Side, quantity: Quantity, price: Price) -> f64 {
+        let realized = self.pnl_tracker.record_fill(symbol, side, quantity, price);
+        if realized != 0.0 {
+            let was_open = self.circuit_breaker.state() == CircuitState::Open;
+            self.circuit_breaker.record_pnl(symbol, realized);
+
+            if !was_open && self.circuit_breaker.state() == CircuitState::Open {
+                self.publish(SystemSignal::CircuitBreakerTripped {
+                    reason: format!("realized loss {:.2} on {}", -realized, symbol.0),
+                });
+            }
+        }
+        realized
+    }
+
+    /// Stop tracking a fully closed position: drops its stop-loss, exposure
+    /// and unrealized P&L
+    pub fn close_position(&mut self, symbol: &Symbol, at: DateTime<Utc>) {
+        self.stop_manager.remove_stop(symbol);
+        self.limit_checker.remove_position(symbol);
+        self.pnl_tracker.update(&Position {
+            symbol: symbol.clone(),
+            side: Side::Bid,
+            quantity: Quantity(0.0),
+            entry_price: Price(0.0),
+            current_price: Price(0.0),
+            unrealized_pnl: 0.0,
+            realized_pnl: self.pnl_tracker.realized_pnl(symbol),
+            opened_at: at,
+            updated_at: at,
+            currency: common::types::Currency::default(),
+        });
+    }
+
+    /// Compare locally tracked positions with the broker's and log every
+    /// discrepancy. Local state is left untouched; see
+    /// `adopt_broker_positions` to correct it.
+    pub fn reconcile(&mut self, broker_positions: &[Position]) -> ReconcileReport {
+        let local = self.limit_checker.get_positions();
+        let broker: std::collections::HashMap<&str, &Position> = broker_positions
+            .iter()
+            .filter(|p| p.quantity.0.abs() > RECONCILE_QUANTITY_EPSILON)
+            .map(|p| (p.symbol.0.as_str(), p))
+            .collect();
+
+        let mut report = ReconcileReport {
+            timestamp: Utc::now(),
+            missing_locally: Vec::new(),
+            extra_locally: Vec::new(),
+            quantity_mismatches: Vec::new(),
+            entry_price_mismatches: Vec::new(),
+            adopted: false,
+        };
+
+        for (symbol, broker_position) in &broker {
+            let Some(local_position) = local.get(*symbol) else {
+                warn!(
+                    "Reconcile: {} held at broker ({} @ {}) but not tracked locally",
+                    symbol, signed_quantity(broker_position), broker_position.entry_price.0
+                );
+                report.missing_locally.push(broker_position.symbol.clone());
+                continue;
+            };
+
+            let mismatch = PositionMismatch {
+                symbol: broker_position.symbol.clone(),
+                local_quantity: signed_quantity(local_position),
+                broker_quantity: signed_quantity(broker_position),
+                local_entry_price: local_position.entry_price,
+                broker_entry_price: broker_position.entry_price,
+            };
+            if (mismatch.local_quantity - mismatch.broker_quantity).abs() > RECONCILE_QUANTITY_EPSILON {
+                warn!(
+                    "Reconcile: {} quantity differs, local {} vs broker {}",
+                    symbol, mismatch.local_quantity, mismatch.broker_quantity
+                );
+                report.quantity_mismatches.push(mismatch);
+            } else if (mismatch.local_entry_price.0 - mismatch.broker_entry_price.0).abs()
+                > ENTRY_PRICE_TOLERANCE * mismatch.broker_entry_price.0.abs()
+            {
+                warn!(
+                    "Reconcile: {} entry price differs, local {} vs broker {}",
+                    symbol, mismatch.local_entry_price.0, mismatch.broker_entry_price.0
+                );
+                report.entry_price_mismatches.push(mismatch);
+            }
+        }
+
+        for (symbol, local_position) in local {
+            if !broker.contains_key(symbol.as_str()) {
+                warn!(
+                    "Reconcile: {} tracked locally ({}) but not held at broker",
+                    symbol, signed_quantity(local_position)
+                );
+                report.extra_locally.push(local_position.symbol.clone());
+            }
+        }
+
+        // Stable output regardless of hash map order
+        report.missing_locally.sort_by(|a, b| a.0.cmp(&b.0));
+        report.extra_locally.sort_by(|a, b| a.0.cmp(&b.0));
+        report.quantity_mismatches.sort_by(|a, b| a.symbol.0.cmp(&b.symbol.0));
+        report.entry_price_mismatches.sort_by(|a, b| a.symbol.0.cmp(&b.symbol.0));
+
+        if report.is_clean() {
+            info!("Reconcile: {} positions match the broker", broker.len());
+        }
+        report
+    }
+
+    /// Reconcile, then make the broker's positions the local truth: exposure
+    /// limits and P&L cost basis are reset to the broker's quantities and
+    /// entry prices, and stops are rebuilt for positions that changed.
+    pub fn adopt_broker_positions(&mut self, broker_positions: &[Position]) -> ReconcileReport {
+        let mut report = self.reconcile(broker_positions);
+        if report.is_clean() {
+            return report;
+        }
+
+        let changed: Vec<&Symbol> = report
+            .missing_locally
+            .iter()
+            .chain(report.quantity_mismatches.iter().map(|m| &m.symbol))
+            .chain(report.entry_price_mismatches.iter().map(|m| &m.symbol))
+            .collect();
+        for symbol in changed {
+            if let Some(position) = broker_positions.iter().find(|p| &p.symbol == symbol) {
+                self.stop_manager.remove_stop(symbol);
+                self.limit_checker.register_position(position);
+                self.pnl_tracker.reset_position(position);
+            }
+        }
+
+        let now = Utc::now();
+        for symbol in &report.extra_locally {
+            self.pnl_tracker.reset_position(&Position {
+                symbol: symbol.clone(),
+                side: Side::Bid,
+                quantity: Quantity(0.0),
+                entry_price: Price(0.0),
+                current_price: Price(0.0),
+                unrealized_pnl: 0.0,
+                realized_pnl: 0.0,
+                opened_at: now,
+                updated_at: now,
+                currency: common::types::Currency::default(),
+            });
+            self.close_position(symbol, now);
+        }
+
+        info!(
+            "Reconcile: adopted broker positions ({} missing, {} extra, {} quantity and {} price mismatches)",
+            report.missing_locally.len(),
+            report.extra_locally.len(),
+            report.quantity_mismatches.len(),
+            report.entry_price_mismatches.len()
+        );
+        report.adopted = true;
+        report
+    }
+
+    /// Get position sizer for direct access
+    pub fn position_sizer(&self) -> &PositionSizer {
+        &self.position_sizer
+    }
+
+    /// Start a new trading session for P&L and loss-limit purposes
+    pub fn reset_session(&mut self, at: DateTime<Utc>) {
+        info!("Starting new risk session at {}", at);
+        self.pnl_tracker.reset_session(at);
+    }
+
+    /// Check the session P&L against the circuit breaker loss threshold
+    pub fn check_session_loss(&mut self) -> Result<()> {
+        let session_pnl = self.pnl_tracker.session_pnl();
+        let was_open = self.circuit_breaker.state() == CircuitState::Open;
+        let result = self.circuit_breaker.check_session_loss(session_pnl);
+
+        if !was_open && self.circuit_breaker.state() == CircuitState::Open {
+            self.publish(SystemSignal::CircuitBreakerTripped {
+                reason: format!("session loss {:.2}", -session_pnl),
+            });
+        }
+        result
+    }
+
+    /// Snapshot of P&L, exposure, stops and circuit breaker state
+    pub fn risk_snapshot(&self) -> RiskSnapshot {
+        RiskSnapshot {
+            timestamp: Utc::now(),
+            total_unrealized_pnl: self.pnl_tracker.total_unrealized_pnl(),
+            total_realized_pnl: self.pnl_tracker.total_realized_pnl(),
+            open_positions: self.limit_checker.open_position_count(),
+            total_notional_exposure: self.limit_checker.total_exposure(),
+            active_stops: self.stop_manager.get_active_stops().len(),
+            circuit_state: self.circuit_breaker.state(),
+            halted_symbols: self.circuit_breaker.tripped_symbols(),
+            current_drawdown: self.pnl_tracker.current_drawdown(),
+            max_drawdown: self.pnl_tracker.max_drawdown(),
+        }
+    }
+
     /// Set a custom stop-loss for a position
     pub fn set_stop_loss(&mut self, position: &Position, config: StopLossConfig) -> Result<()> {
         self.stop_manager.set_stop(position, config)
@@ -79,8 +465,201 @@ impl RiskManagerService {
         &self.limit_checker
     }
 
+    /// Get circuit breaker for direct access
+    pub fn circuit_breaker(&self) -> &CircuitBreaker {
+        &self.circuit_breaker
+    }
+
+    /// Get mutable circuit breaker for direct access
+    pub fn circuit_breaker_mut(&mut self) -> &mut CircuitBreaker {
+        &mut self.circuit_breaker
+    }
+
     /// Get P&L tracker for direct access
     pub fn pnl_tracker(&self) -> &PnLTracker {
         &self.pnl_tracker
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::config::RiskConfig;
+    use common::types::Currency;
+
+    fn create_test_config() -> RiskConfig {
+        RiskConfig {
+            max_position_size: 10000.0,
+            max_notional_exposure: 50000.0,
+            max_open_positions: 5,
+            stop_loss_percent: 5.0,
+            trailing_stop_percent: 3.0,
+            enable_circuit_breaker: true,
+            max_loss_threshold: 1000.0,
+            circuit_breaker_cooldown_secs: 300,
+            max_price_velocity_pct: 10.0,
+            max_leverage: 2.0,
+        }
+    }
+
+    fn create_test_position(symbol: &str, entry: f64, current: f64, qty: f64) -> Position {
+        Position {
+            symbol: Symbol(symbol.to_string()),
+            side: Side::Bid,
+            quantity: Quantity(qty),
+            entry_price: Price(entry),
+            current_price: Price(current),
+            unrealized_pnl: (current - entry) * qty,
+            realized_pnl: 0.0,
+            opened_at: Utc::now(),
+            updated_at: Utc::now(),
+            currency: Currency::default(),
+        }
+    }
+
+    #[test]
+    fn test_risk_snapshot() {
+        let mut service = RiskManagerService::new(create_test_config()).unwrap();
+
+        service.update_position(create_test_position("AAPL", 100.0, 110.0, 10.0));
+        service.update_position(create_test_position("MSFT", 200.0, 195.0, 5.0));
+
+        let snapshot = service.risk_snapshot();
+        assert_eq!(snapshot.open_positions, 2);
+        assert_eq!(snapshot.total_notional_exposure, 1100.0 + 975.0);
+        assert_eq!(snapshot.total_unrealized_pnl, 100.0 - 25.0);
+        assert_eq!(snapshot.active_stops, 2); // auto-configured from config
+        assert_eq!(snapshot.circuit_state, CircuitState::Closed);
+        assert_eq!(snapshot.current_drawdown, 25.0);
+
+        let json = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(json["circuit_state"], "Closed");
+        assert_eq!(json["open_positions"], 2);
+    }
+
+    #[test]
+    fn test_size_signal() {
+        let service = RiskManagerService::new(create_test_config()).unwrap();
+        let mut signal = Signal {
+            symbol: Symbol("AAPL".to_string()),
+            action: SignalAction::Buy,
+            confidence: 0.8,
+            features: vec![],
+            timestamp: Utc::now(),
+        };
+
+        let order = service
+            .size_signal(&signal, 100_000.0, 0.005, Price(50.0), Price(45.0))
+            .unwrap();
+        assert_eq!(order.side, Side::Bid);
+        assert_eq!(order.quantity.0, 100.0);
+        assert_eq!(order.price, Some(Price(50.0)));
+        assert!(service.check_order(&order).is_ok());
+
+        signal.action = SignalAction::Hold;
+        assert!(service.size_signal(&signal, 100_000.0, 0.005, Price(50.0), Price(45.0)).is_none());
+    }
+
+    #[test]
+    fn test_update_risk_config_applies_live() {
+        let mut service = RiskManagerService::new(create_test_config()).unwrap();
+        let order = common::OrderBuilder::new().quantity(10.0).limit(500.0).build();
+        assert!(service.check_order(&order).is_ok());
+
+        let mut invalid = create_test_config();
+        invalid.max_position_size = -1.0;
+        assert!(service.update_risk_config(invalid).is_err());
+        assert!(service.check_order(&order).is_ok());
+
+        let mut tighter = create_test_config();
+        tighter.max_position_size = 1000.0;
+        service.update_risk_config(tighter).unwrap();
+        assert!(service.check_order(&order).is_err());
+    }
+
+    #[test]
+    fn test_reconcile_reports_and_adopts() {
+        let mut service = RiskManagerService::new(create_test_config()).unwrap();
+        service.update_position(create_test_position("AAPL", 100.0, 100.0, 10.0));
+        service.update_position(create_test_position("MSFT", 200.0, 200.0, 5.0));
+        service.update_position(create_test_position("TSLA", 250.0, 250.0, 2.0));
+
+        let broker = vec![
+            create_test_position("AAPL", 100.0, 100.0, 10.0),
+            create_test_position("MSFT", 200.0, 200.0, 8.0),
+            create_test_position("TSLA", 240.0, 250.0, 2.0),
+            create_test_position("NVDA", 50.0, 50.0, 20.0),
+        ];
+
+        let report = service.reconcile(&broker);
+        assert!(!report.is_clean());
+        assert!(!report.adopted);
+        assert_eq!(report.missing_locally, vec![Symbol("NVDA".to_string())]);
+        assert!(report.extra_locally.is_empty());
+        assert_eq!(report.quantity_mismatches.len(), 1);
+        assert_eq!(report.quantity_mismatches[0].broker_quantity, 8.0);
+        assert_eq!(report.entry_price_mismatches[0].symbol, Symbol("TSLA".to_string()));
+
+        // Broker no longer holds TSLA
+        let broker: Vec<Position> = broker.into_iter().filter(|p| p.symbol.0 != "TSLA").collect();
+        let report = service.adopt_broker_positions(&broker);
+        assert!(report.adopted);
+        assert_eq!(report.extra_locally, vec![Symbol("TSLA".to_string())]);
+
+        assert!(service.reconcile(&broker).is_clean());
+        assert_eq!(service.risk_snapshot().open_positions, 3);
+        let msft = service.pnl_tracker().get_position("MSFT").unwrap();
+        assert_eq!(msft.quantity, Quantity(8.0));
+    }
+
+    #[test]
+    fn test_losing_symbol_halted_by_fills() {
+        let mut service = RiskManagerService::new(create_test_config()).unwrap();
+        service.circuit_breaker_mut().set_symbol_loss_threshold(400.0);
+
+        let aapl = Symbol("AAPL".to_string());
+        let msft = Symbol("MSFT".to_string());
+        service.record_fill(&aapl, Side::Bid, Quantity(100.0), Price(100.0));
+        service.record_fill(&aapl, Side::Ask, Quantity(100.0), Price(95.0));
+        service.record_fill(&msft, Side::Bid, Quantity(10.0), Price(200.0));
+        service.record_fill(&msft, Side::Ask, Quantity(10.0), Price(210.0));
+
+        // AAPL lost 500, over its 400 limit; the global 1000 limit holds
+        let order = |symbol: &str| common::OrderBuilder::new().symbol(symbol).quantity(10.0).limit(100.0).build();
+        assert!(service.check_order(&order("AAPL")).is_err());
+        assert!(service.check_order(&order("MSFT")).is_ok());
+        assert_eq!(service.risk_snapshot().halted_symbols, vec![aapl]);
+        assert_eq!(service.risk_snapshot().circuit_state, CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_publishes_risk_events() {
+        let bus = EventBus::default();
+        let mut events = bus.subscribe();
+        let mut service = RiskManagerService::new(create_test_config())
+            .unwrap()
+            .with_event_bus(bus);
+
+        // 10% below entry breaches the 5% default stop
+        assert!(service.update_position(create_test_position("AAPL", 100.0, 90.0, 10.0)).is_some());
+        match events.try_recv() {
+            Some(SystemSignal::StopTriggered(stop)) => {
+                assert_eq!(stop.symbol, Symbol("AAPL".to_string()));
+                assert_eq!(stop.close_side, Side::Ask);
+                assert_eq!(stop.quantity, Quantity(10.0));
+            }
+            other => panic!("expected a stop trigger, got {:?}", other),
+        }
+
+        let aapl = Symbol("AAPL".to_string());
+        service.reset_session(Utc::now());
+        service.record_fill(&aapl, Side::Bid, Quantity(100.0), Price(100.0));
+        service.record_fill(&aapl, Side::Ask, Quantity(100.0), Price(85.0));
+        assert!(service.check_session_loss().is_err());
+        assert!(matches!(events.try_recv(), Some(SystemSignal::CircuitBreakerTripped { .. })));
+
+        // Already open: no duplicate trip event
+        assert!(service.check_session_loss().is_err());
+        assert!(events.try_recv().is_none());
+    }
+}
@@ -1,9 +1,28 @@
-use common::{Result, TradingError, types::{Order, Position}, config::RiskConfig};
-use std::collections::HashMap;
+use common::{Result, TradingError, types::{Order, Position, Symbol}, config::RiskConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Per-symbol limits overriding the global `RiskConfig` values
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SymbolLimit {
+    /// Maximum position size (replaces `RiskConfig::max_position_size`)
+    pub max_position_size: f64,
+    /// Maximum notional held in this symbol (position plus order)
+    pub max_notional: f64,
+}
+
+/// Basket of correlated symbols sharing one notional cap
+#[derive(Debug, Clone)]
+struct CorrelationGroup {
+    symbols: HashSet<String>,
+    max_notional: f64,
+}
 
 pub struct LimitChecker {
     config: RiskConfig,
     positions: HashMap<String, Position>,
+    symbol_limits: HashMap<String, SymbolLimit>,
+    correlation_groups: HashMap<String, CorrelationGroup>,
     open_order_count: usize,
     daily_pnl: f64,
 }
@@ -13,13 +32,24 @@ impl LimitChecker {
         Self {
             config,
             positions: HashMap::new(),
+            symbol_limits: HashMap::new(),
+            correlation_groups: HashMap::new(),
             open_order_count: 0,
             daily_pnl: 0.0,
         }
     }
 
+    /// Swap in new risk limits. Tracked positions, per-symbol limits and
+    /// correlation groups are kept; only the global limits change.
+    pub fn update_config(&mut self, config: RiskConfig) {
+        self.config = config;
+    }
+
     /// Multi-level risk check
     pub fn check(&self, order: &Order) -> Result<()> {
+        // Level 0: Reject malformed quantities and prices
+        Self::validate_order(order)?;
+
         // Level 1: Order size check
         self.check_order_size(order)?;
 
@@ -27,10 +57,12 @@ impl LimitChecker {
         self.check_position_size(order)?;
 
         // Level 3: Notional exposure check
+        self.check_symbol_notional(order)?;
+        self.check_correlation_groups(order)?;
         self.check_notional_exposure(order)?;
 
         // Level 4: Open positions count check
-        self.check_open_positions()?;
+        self.check_open_positions(order)?;
 
         // Level 5: Daily loss limit check
         self.check_daily_loss()?;
@@ -38,6 +70,30 @@ impl LimitChecker {
         Ok(())
     }
 
+    /// Reject orders with a non-finite or non-positive quantity, price or stop price
+    ///
+    /// Market orders have no price, so only prices that are set are checked.
+    pub fn validate_order(order: &Order) -> Result<()> {
+        let fields = [
+            ("quantity", Some(order.quantity.0)),
+            ("price", order.price.map(|p| p.0)),
+            ("stop_price", order.stop_price.map(|p| p.0)),
+        ];
+
+        for (field, value) in fields {
+            if let Some(value) = value {
+                if !value.is_finite() || value <= 0.0 {
+                    return Err(TradingError::Risk(format!(
+                        "Order {} for {} has invalid {} {}: must be finite and positive",
+                        order.order_id, order.symbol.0, field, value
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn check_order_size(&self, order: &Order) -> Result<()> {
         let order_value = match order.price {
             Some(price) => price.0 * order.quantity.0,
@@ -47,10 +103,11 @@ impl LimitChecker {
             }
         };
 
-        if order_value > self.config.max_position_size {
+        let (max_position_size, scope) = self.max_position_size(&order.symbol);
+        if order_value > max_position_size {
             return Err(TradingError::Risk(format!(
-                "Order size {} exceeds max position size {}",
-                order_value, self.config.max_position_size
+                "Order size {} for {} exceeds {} max position size {}",
+                order_value, order.symbol.0, scope, max_position_size
             )));
         }
 
@@ -64,10 +121,11 @@ impl LimitChecker {
 
             let new_value = current_value + order_value;
 
-            if new_value > self.config.max_position_size {
+            let (max_position_size, scope) = self.max_position_size(&order.symbol);
+            if new_value > max_position_size {
                 return Err(TradingError::Risk(format!(
-                    "Position size {} would exceed max {}",
-                    new_value, self.config.max_position_size
+                    "Position size {} for {} would exceed {} max position size {}",
+                    new_value, order.symbol.0, scope, max_position_size
                 )));
             }
         }
@@ -75,12 +133,84 @@ impl LimitChecker {
         Ok(())
     }
 
-    fn check_notional_exposure(&self, order: &Order) -> Result<()> {
-        let total_exposure: f64 = self
-            .positions
-            .values()
+    fn check_symbol_notional(&self, order: &Order) -> Result<()> {
+        let Some(limit) = self.symbol_limits.get(&order.symbol.0) else {
+            return Ok(());
+        };
+
+        let new_value = self.position_value(&order.symbol.0) + self.order_value(order);
+
+        if new_value > limit.max_notional {
+            return Err(TradingError::Risk(format!(
+                "Notional {} for {} would exceed per-symbol max notional {}",
+                new_value, order.symbol.0, limit.max_notional
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn check_correlation_groups(&self, order: &Order) -> Result<()> {
+        let order_value = self.order_value(order);
+
+        // Among the groups the order would breach, report the one with the
+        // least headroom
+        let breached = self
+            .correlation_groups
+            .iter()
+            .filter(|(_, group)| group.symbols.contains(&order.symbol.0))
+            .map(|(name, group)| {
+                let current: f64 = group.symbols.iter().map(|s| self.position_value(s)).sum();
+                (name, group, current)
+            })
+            .filter(|(_, group, current)| current + order_value > group.max_notional)
+            .min_by(|(name_a, group_a, current_a), (name_b, group_b, current_b)| {
+                (group_a.max_notional - current_a)
+                    .total_cmp(&(group_b.max_notional - current_b))
+                    .then_with(|| name_a.cmp(name_b))
+            });
+
+        if let Some((name, group, current)) = breached {
+            return Err(TradingError::Risk(format!(
+                "Correlation group '{}' notional {} (current {} + order {}) would exceed max {}",
+                name,
+                current + order_value,
+                current,
+                order_value,
+                group.max_notional
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Current notional held in a symbol
+    fn position_value(&self, symbol: &str) -> f64 {
+        self.positions
+            .get(symbol)
             .map(|p| p.quantity.0 * p.current_price.0)
-            .sum();
+            .unwrap_or(0.0)
+    }
+
+    /// Order notional, valuing market orders at the position's current price
+    fn order_value(&self, order: &Order) -> f64 {
+        let price = order
+            .price
+            .or_else(|| self.positions.get(&order.symbol.0).map(|p| p.current_price))
+            .unwrap_or(common::types::Price(0.0));
+        order.quantity.0 * price.0
+    }
+
+    /// Effective max position size for a symbol and where it came from
+    fn max_position_size(&self, symbol: &Symbol) -> (f64, &'static str) {
+        match self.symbol_limits.get(&symbol.0) {
+            Some(limit) => (limit.max_position_size, "per-symbol"),
+            None => (self.config.max_position_size, "global"),
+        }
+    }
+
+    fn check_notional_exposure(&self, order: &Order) -> Result<()> {
+        let total_exposure = self.total_exposure();
 
         let order_value = order.quantity.0
             * order
@@ -90,8 +220,10 @@ impl LimitChecker {
 
         if total_exposure + order_value > self.config.max_notional_exposure {
             return Err(TradingError::Risk(format!(
-                "Total exposure {} would exceed max {}",
+                "Total exposure {} (current {} + order {}) would exceed max {}",
                 total_exposure + order_value,
+                total_exposure,
+                order_value,
                 self.config.max_notional_exposure
             )));
         }
@@ -99,11 +231,52 @@ impl LimitChecker {
         Ok(())
     }
 
-    fn check_open_positions(&self) -> Result<()> {
-        if self.open_order_count >= self.config.max_open_positions {
+    fn check_open_positions(&self, order: &Order) -> Result<()> {
+        // Orders for symbols already held don't open a new position
+        if self.positions.contains_key(&order.symbol.0) {
+            return Ok(());
+        }
+
+        if self.open_order_count + 1 > self.config.max_open_positions {
             return Err(TradingError::Risk(format!(
-                "Open positions {} would exceed max {}",
-                self.open_order_count, self.config.max_open_positions
+                "Opening {} would bring open positions to {} (current {}), exceeding max {}",
+                order.symbol.0,
+                self.open_order_count + 1,
+                self.open_order_count,
+                self.config.max_open_positions
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Check that the order keeps gross leverage within `max_leverage`
+    ///
+    /// `current_price` values market orders that have no limit price.
+    pub fn check_leverage(
+        &self,
+        order: &Order,
+        account_equity: f64,
+        current_notional: f64,
+        current_price: common::types::Price,
+    ) -> Result<()> {
+        if account_equity <= 0.0 || !account_equity.is_finite() {
+            return Err(TradingError::Risk(format!(
+                "Cannot check leverage with non-positive account equity {}",
+                account_equity
+            )));
+        }
+
+        let order_notional = order.quantity.0 * order.price.unwrap_or(current_price).0;
+        let leverage = (current_notional + order_notional) / account_equity;
+
+        if leverage > self.config.max_leverage {
+            return Err(TradingError::Risk(format!(
+                "Leverage {:.2}x ({} notional on {} equity) would exceed max {:.2}x",
+                leverage,
+                current_notional + order_notional,
+                account_equity,
+                self.config.max_leverage
             )));
         }
 
@@ -123,20 +296,83 @@ impl LimitChecker {
 
     /// Update position tracking
     pub fn update_position(&mut self, position: Position) {
-        let symbol = position.symbol.0.clone();
         self.daily_pnl += position.realized_pnl;
+        self.register_position(&position);
+    }
 
+    /// Track an open position for aggregate exposure and count limits
+    ///
+    /// Re-registering a symbol replaces its previous position; a zero
+    /// quantity removes it.
+    pub fn register_position(&mut self, position: &Position) {
         if position.quantity.0 == 0.0 {
-            self.positions.remove(&symbol);
-            if self.open_order_count > 0 {
-                self.open_order_count -= 1;
-            }
-        } else {
-            if !self.positions.contains_key(&symbol) {
-                self.open_order_count += 1;
-            }
-            self.positions.insert(symbol, position);
+            self.remove_position(&position.symbol);
+            return;
         }
+
+        if self
+            .positions
+            .insert(position.symbol.0.clone(), position.clone())
+            .is_none()
+        {
+            self.open_order_count += 1;
+        }
+    }
+
+    /// Stop tracking a closed position
+    pub fn remove_position(&mut self, symbol: &Symbol) -> Option<Position> {
+        let removed = self.positions.remove(&symbol.0);
+        if removed.is_some() {
+            self.open_order_count = self.open_order_count.saturating_sub(1);
+        }
+        removed
+    }
+
+    /// Total notional across all tracked positions
+    pub fn total_exposure(&self) -> f64 {
+        self.positions
+            .values()
+            .map(|p| p.quantity.0 * p.current_price.0)
+            .sum()
+    }
+
+    /// Number of tracked open positions
+    pub fn open_position_count(&self) -> usize {
+        self.open_order_count
+    }
+
+    /// Override the global position limits for a symbol
+    pub fn set_symbol_limit(&mut self, symbol: &Symbol, limit: SymbolLimit) {
+        self.symbol_limits.insert(symbol.0.clone(), limit);
+    }
+
+    /// Remove a per-symbol override, falling back to the global limits
+    pub fn remove_symbol_limit(&mut self, symbol: &Symbol) -> Option<SymbolLimit> {
+        self.symbol_limits.remove(&symbol.0)
+    }
+
+    /// Get the per-symbol override for a symbol, if any
+    pub fn symbol_limit(&self, symbol: &Symbol) -> Option<&SymbolLimit> {
+        self.symbol_limits.get(&symbol.0)
+    }
+
+    /// Cap the combined notional of a basket of correlated symbols
+    ///
+    /// A symbol may belong to several groups; orders must fit within all of
+    /// them. Setting an existing group name replaces it.
+    pub fn set_correlation_group(&mut self, group: &str, symbols: &[Symbol], max_group_notional: f64) {
+        self.correlation_groups.insert(
+            group.to_string(),
+            CorrelationGroup {
+                symbols: symbols.iter().map(|s| s.0.clone()).collect(),
+                max_notional: max_group_notional,
+            },
+        );
+    }
+
+    /// Remove a correlation group
+    pub fn remove_correlation_group(&mut self, group: &str) {
+        self.correlation_groups.remove(group);
     }
 
     /// Reset daily P&L (call at start of trading day)
@@ -154,3 +390,214 @@ impl LimitChecker {
         self.daily_pnl
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use common::types::{Currency, OrderType, Price, Quantity, Side};
+    use common::OrderBuilder;
+
+    fn create_test_config() -> RiskConfig {
+        RiskConfig {
+            max_position_size: 10000.0,
+            max_notional_exposure: 50000.0,
+            max_open_positions: 5,
+            stop_loss_percent: 5.0,
+            trailing_stop_percent: 3.0,
+            enable_circuit_breaker: true,
+            max_loss_threshold: 1000.0,
+            circuit_breaker_cooldown_secs: 300,
+            max_price_velocity_pct: 10.0,
+            max_leverage: 2.0,
+        }
+    }
+
+    fn create_test_order(symbol: &str, qty: f64, price: f64) -> Order {
+        OrderBuilder::new().symbol(symbol).quantity(qty).limit(price).build()
+    }
+
+    fn create_test_position(symbol: &str, qty: f64, price: f64) -> Position {
+        Position {
+            symbol: Symbol(symbol.to_string()),
+            side: Side::Bid,
+            quantity: Quantity(qty),
+            entry_price: Price(price),
+            current_price: Price(price),
+            unrealized_pnl: 0.0,
+            realized_pnl: 0.0,
+            opened_at: Utc::now(),
+            updated_at: Utc::now(),
+            currency: Currency::default(),
+        }
+    }
+
+    #[test]
+    fn test_global_limit_applies_without_override() {
+        let checker = LimitChecker::new(create_test_config());
+
+        assert!(checker.check(&create_test_order("SPY", 10.0, 500.0)).is_ok());
+        assert!(checker.check(&create_test_order("SPY", 30.0, 500.0)).is_err());
+    }
+
+    #[test]
+    fn test_symbol_limit_overrides_global() {
+        let mut checker = LimitChecker::new(create_test_config());
+        let illiquid = Symbol("ILLQ".to_string());
+        let spy = Symbol("SPY".to_string());
+
+        checker.set_symbol_limit(&illiquid, SymbolLimit { max_position_size: 1000.0, max_notional: 1500.0 });
+        checker.set_symbol_limit(&spy, SymbolLimit { max_position_size: 20000.0, max_notional: 40000.0 });
+
+        // Tighter than global for the illiquid name
+        let err = checker.check(&create_test_order("ILLQ", 20.0, 100.0)).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("ILLQ"));
+        assert!(message.contains("per-symbol max position size 1000"));
+
+        // Looser than global for SPY
+        assert!(checker.check(&create_test_order("SPY", 30.0, 500.0)).is_ok());
+
+        // Removing the override restores the global limit
+        checker.remove_symbol_limit(&spy);
+        assert!(checker.check(&create_test_order("SPY", 30.0, 500.0)).is_err());
+    }
+
+    #[test]
+    fn test_symbol_notional_limit() {
+        let mut checker = LimitChecker::new(create_test_config());
+        let symbol = Symbol("ILLQ".to_string());
+        checker.set_symbol_limit(&symbol, SymbolLimit { max_position_size: 2000.0, max_notional: 1500.0 });
+
+        checker.update_position(create_test_position("ILLQ", 8.0, 100.0));
+
+        let err = checker.check(&create_test_order("ILLQ", 8.0, 100.0)).unwrap_err();
+        assert!(err.to_string().contains("per-symbol max notional 1500"));
+    }
+
+    #[test]
+    fn test_aggregate_notional_exposure() {
+        let mut checker = LimitChecker::new(create_test_config());
+        checker.register_position(&create_test_position("AAPL", 125.0, 200.0));
+        checker.register_position(&create_test_position("MSFT", 50.0, 400.0));
+        assert_eq!(checker.total_exposure(), 45000.0);
+
+        assert!(checker.check(&create_test_order("NVDA", 5.0, 900.0)).is_ok());
+
+        let err = checker.check(&create_test_order("NVDA", 10.0, 900.0)).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("current 45000"));
+        assert!(message.contains("max 50000"));
+
+        checker.remove_position(&Symbol("MSFT".to_string()));
+        assert!(checker.check(&create_test_order("NVDA", 10.0, 900.0)).is_ok());
+    }
+
+    #[test]
+    fn test_open_position_count() {
+        let mut checker = LimitChecker::new(create_test_config());
+        for symbol in ["A", "B", "C", "D", "E"] {
+            checker.register_position(&create_test_position(symbol, 1.0, 10.0));
+        }
+        assert_eq!(checker.open_position_count(), 5);
+
+        // Re-registering doesn't double count
+        checker.register_position(&create_test_position("A", 2.0, 10.0));
+        assert_eq!(checker.open_position_count(), 5);
+
+        // A sixth symbol is rejected, adding to an existing one is not
+        let err = checker.check(&create_test_order("F", 1.0, 10.0)).unwrap_err();
+        assert!(err.to_string().contains("current 5"));
+        assert!(checker.check(&create_test_order("A", 1.0, 10.0)).is_ok());
+
+        checker.remove_position(&Symbol("E".to_string()));
+        assert!(checker.check(&create_test_order("F", 1.0, 10.0)).is_ok());
+    }
+
+    #[test]
+    fn test_check_leverage() {
+        let checker = LimitChecker::new(create_test_config());
+
+        // (10000 + 5000) / 10000 = 1.5x
+        let order = create_test_order("AAPL", 50.0, 100.0);
+        assert!(checker.check_leverage(&order, 10000.0, 10000.0, Price(100.0)).is_ok());
+
+        // (18000 + 5000) / 10000 = 2.3x
+        let err = checker
+            .check_leverage(&order, 10000.0, 18000.0, Price(100.0))
+            .unwrap_err();
+        assert!(err.to_string().contains("2.30x"));
+
+        // Market order valued at the current price
+        let mut market = create_test_order("AAPL", 50.0, 100.0);
+        market.order_type = OrderType::Market;
+        market.price = None;
+        assert!(checker.check_leverage(&market, 10000.0, 10000.0, Price(100.0)).is_ok());
+        assert!(checker.check_leverage(&market, 10000.0, 10000.0, Price(300.0)).is_err());
+
+        assert!(checker.check_leverage(&order, 0.0, 0.0, Price(100.0)).is_err());
+    }
+
+    #[test]
+    fn test_correlation_group_limit() {
+        let mut checker = LimitChecker::new(create_test_config());
+        let tech = [
+            Symbol("AAPL".to_string()),
+            Symbol("MSFT".to_string()),
+            Symbol("GOOGL".to_string()),
+        ];
+        checker.set_correlation_group("tech", &tech, 20000.0);
+        checker.register_position(&create_test_position("AAPL", 50.0, 200.0));
+        checker.register_position(&create_test_position("MSFT", 20.0, 400.0));
+
+        // 18000 held in the group; GOOGL for 1500 fits, 2500 doesn't
+        assert!(checker.check(&create_test_order("GOOGL", 10.0, 150.0)).is_ok());
+        let err = checker.check(&create_test_order("GOOGL", 10.0, 250.0)).unwrap_err();
+        assert!(err.to_string().contains("'tech'"));
+
+        // Symbols outside the group are unaffected
+        assert!(checker.check(&create_test_order("XOM", 10.0, 250.0)).is_ok());
+    }
+
+    #[test]
+    fn test_tightest_correlation_group_reported() {
+        let mut checker = LimitChecker::new(create_test_config());
+        let aapl = Symbol("AAPL".to_string());
+        let msft = Symbol("MSFT".to_string());
+        checker.set_correlation_group("tech", &[aapl.clone(), msft.clone()], 20000.0);
+        checker.set_correlation_group("mega_cap", &[aapl], 6000.0);
+        checker.register_position(&create_test_position("AAPL", 25.0, 200.0));
+
+        let err = checker.check(&create_test_order("AAPL", 10.0, 200.0)).unwrap_err();
+        assert!(err.to_string().contains("'mega_cap'"));
+
+        checker.remove_correlation_group("mega_cap");
+        assert!(checker.check(&create_test_order("AAPL", 10.0, 200.0)).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_invalid_order_values() {
+        let checker = LimitChecker::new(create_test_config());
+
+        for price in [f64::NAN, f64::INFINITY, -100.0, 0.0] {
+            let order = create_test_order("AAPL", 10.0, price);
+            let err = checker.check(&order).unwrap_err();
+            assert!(err.to_string().contains("invalid price"), "{}", err);
+        }
+
+        for qty in [f64::NAN, -1.0, 0.0] {
+            let err = checker.check(&create_test_order("AAPL", qty, 100.0)).unwrap_err();
+            assert!(err.to_string().contains("invalid quantity"), "{}", err);
+        }
+
+        let mut order = create_test_order("AAPL", 10.0, 100.0);
+        order.stop_price = Some(Price(f64::NEG_INFINITY));
+        assert!(checker.check(&order).unwrap_err().to_string().contains("invalid stop_price"));
+
+        // Market orders have no price to validate
+        let mut market = create_test_order("AAPL", 10.0, 100.0);
+        market.order_type = OrderType::Market;
+        market.price = None;
+        assert!(checker.check(&market).is_ok());
+    }
+}
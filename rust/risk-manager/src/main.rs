@@ -16,14 +16,14 @@ async fn main() -> anyhow::Result<()> {
 
     tracing::info!("Risk Manager Service starting...");
 
-    // Load configuration with validation
-    let config = match SystemConfig::from_file("config/system.json") {
-        Ok(cfg) => {
+    // Load configuration with validation and watch it for live changes
+    let (config, mut config_updates) = match SystemConfig::watch("config/system.json") {
+        Ok((cfg, updates)) => {
             tracing::info!(
                 "Configuration loaded successfully - Environment: {}",
                 cfg.environment()
             );
-            cfg
+            (cfg, updates)
         }
         Err(e) => {
             tracing::error!("Failed to load configuration: {}", e);
@@ -62,7 +62,7 @@ async fn main() -> anyhow::Result<()> {
     let max_positions = config.risk.max_open_positions;
 
     // Initialize service
-    let _service = match RiskManagerService::new(config.risk) {
+    let mut service = match RiskManagerService::new(config.risk) {
         Ok(svc) => {
             tracing::info!("✓ Risk Manager initialized successfully");
             svc
@@ -86,8 +86,21 @@ async fn main() -> anyhow::Result<()> {
 
     tracing::info!("🚀 Risk Manager is monitoring");
 
-    // Keep service running
-    tokio::signal::ctrl_c().await?;
+    // Keep service running, applying risk limit changes as the config file is edited
+    loop {
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => {
+                result?;
+                break;
+            }
+            Some(new_config) = config_updates.recv() => {
+                match service.update_risk_config(new_config.risk) {
+                    Ok(()) => tracing::info!("Risk limits reloaded"),
+                    Err(e) => tracing::warn!("Keeping current risk limits, reload rejected: {}", e),
+                }
+            }
+        }
+    }
     tracing::info!("Shutdown signal received, stopping Risk Manager...");
 
     // Stop metrics server
@@ -0,0 +1,252 @@
+use serde::Serialize;
+
+/// Periods per year assumed by `PerformanceStats::from_returns` (daily bars)
+pub const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+
+/// Return-based performance metrics
+///
+/// Ratios that would be undefined (no volatility, no losing periods, fewer
+/// than two returns) are reported as 0.0 rather than NaN or infinity.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct PerformanceStats {
+    /// Annualized mean excess return over its standard deviation
+    pub sharpe: f64,
+    /// Annualized mean excess return over downside deviation
+    pub sortino: f64,
+    /// Largest peak-to-trough decline of the compounded equity curve, as a fraction
+    pub max_drawdown: f64,
+    /// Compound annual growth rate
+    pub cagr: f64,
+    /// Sum of gains over the absolute sum of losses
+    pub profit_factor: f64,
+    /// Fraction of returns above zero
+    pub win_rate: f64,
+    /// Number of returns the stats were computed from
+    pub periods: usize,
+}
+
+impl PerformanceStats {
+    /// Stats for daily returns, with `risk_free` as the per-period risk-free rate
+    pub fn from_returns(returns: &[f64], risk_free: f64) -> Self {
+        Self::from_returns_with_periods(returns, risk_free, TRADING_DAYS_PER_YEAR)
+    }
+
+    /// Stats for returns sampled `periods_per_year` times a year
+    pub fn from_returns_with_periods(returns: &[f64], risk_free: f64, periods_per_year: f64) -> Self {
+        let returns: Vec<f64> = returns.iter().copied().filter(|r| r.is_finite()).collect();
+        if returns.len() < 2 || periods_per_year <= 0.0 || !periods_per_year.is_finite() {
+            return Self::default();
+        }
+
+        let n = returns.len() as f64;
+        let excess: Vec<f64> = returns.iter().map(|r| r - risk_free).collect();
+        let mean_excess = excess.iter().sum::<f64>() / n;
+        let annualizer = periods_per_year.sqrt();
+
+        let variance = excess.iter().map(|r| (r - mean_excess).powi(2)).sum::<f64>() / (n - 1.0);
+        let sharpe = ratio(mean_excess, variance.sqrt()) * annualizer;
+
+        let downside = (excess.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / n).sqrt();
+        let sortino = ratio(mean_excess, downside) * annualizer;
+
+        let mut equity = 1.0;
+        let mut peak = 1.0;
+        let mut max_drawdown: f64 = 0.0;
+        for r in &returns {
+            equity *= 1.0 + r;
+            peak = f64::max(peak, equity);
+            max_drawdown = max_drawdown.max((peak - equity) / peak);
+        }
+
+        let years = n / periods_per_year;
+        let cagr = if equity > 0.0 { equity.powf(1.0 / years) - 1.0 } else { -1.0 };
+
+        let gains: f64 = returns.iter().filter(|r| **r > 0.0).sum();
+        let losses: f64 = returns.iter().filter(|r| **r < 0.0).sum::<f64>().abs();
+
+        Self {
+            sharpe,
+            sortino,
+            max_drawdown,
+            cagr,
+            profit_factor: ratio(gains, losses),
+            win_rate: returns.iter().filter(|r| **r > 0.0).count() as f64 / n,
+            periods: returns.len(),
+        }
+    }
+
+    /// Period returns from successive equity values (e.g. the PnLTracker
+    /// equity curve offset by starting capital); non-positive bases are skipped
+    pub fn returns_from_equity(equity: &[f64]) -> Vec<f64> {
+        equity
+            .windows(2)
+            .filter(|w| w[0] > 0.0)
+            .map(|w| w[1] / w[0] - 1.0)
+            .collect()
+    }
+
+    /// Stats over per-trade returns from executed fills
+    ///
+    /// Fills are matched FIFO per symbol; each closing fill yields one trade
+    /// return of its net P&L (after closing and pro-rata opening commission)
+    /// over the entry value it closed. Returns are annualized by the number
+    /// of trades per year over the fills' time span.
+    #[cfg(feature = "database")]
+    pub fn from_trades(trades: &[database::TradeRecord]) -> Self {
+        let mut fills: Vec<&database::TradeRecord> = trades.iter().collect();
+        fills.sort_by_key(|t| t.timestamp);
+
+        let returns = trade_returns(&fills);
+        let periods_per_year = match (fills.first(), fills.last()) {
+            (Some(first), Some(last)) => {
+                let years = (last.timestamp - first.timestamp).num_seconds() as f64 / (365.25 * 86_400.0);
+                if years > 0.0 { returns.len() as f64 / years } else { TRADING_DAYS_PER_YEAR }
+            }
+            _ => TRADING_DAYS_PER_YEAR,
+        };
+        Self::from_returns_with_periods(&returns, 0.0, periods_per_year)
+    }
+}
+
+/// `numerator / denominator`, or 0.0 when undefined
+fn ratio(numerator: f64, denominator: f64) -> f64 {
+    if denominator > 0.0 && denominator.is_finite() {
+        numerator / denominator
+    } else {
+        0.0
+    }
+}
+
+#[cfg(feature = "database")]
+fn trade_returns(fills: &[&database::TradeRecord]) -> Vec<f64> {
+    use std::collections::{HashMap, VecDeque};
+
+    /// Open lot: signed quantity, price, commission per unit
+    struct Lot {
+        quantity: f64,
+        price: f64,
+        commission_per_unit: f64,
+    }
+
+    let mut lots: HashMap<&str, VecDeque<Lot>> = HashMap::new();
+    let mut returns = Vec::new();
+
+    for fill in fills {
+        let direction = match fill.side.to_ascii_lowercase().as_str() {
+            "buy" | "bid" => 1.0,
+            "sell" | "ask" => -1.0,
+            _ => continue,
+        };
+        if fill.quantity <= 0.0 || !fill.quantity.is_finite() {
+            continue;
+        }
+
+        let queue = lots.entry(fill.symbol.as_str()).or_default();
+        let fill_commission_per_unit = fill.commission / fill.quantity;
+        let mut remaining = fill.quantity;
+        let mut pnl = 0.0;
+        let mut entry_value = 0.0;
+
+        while remaining > 1e-9 {
+            let Some(lot) = queue.front_mut() else {
+                break;
+            };
+            if lot.quantity.signum() == direction {
+                break;
+            }
+
+            let matched = remaining.min(lot.quantity.abs());
+            // Long lots profit when sold higher, short lots when bought back lower
+            pnl += -direction * (fill.price - lot.price) * matched;
+            pnl -= (lot.commission_per_unit + fill_commission_per_unit) * matched;
+            entry_value += lot.price * matched;
+
+            lot.quantity += direction * matched;
+            remaining -= matched;
+            if lot.quantity.abs() <= 1e-9 {
+                queue.pop_front();
+            }
+        }
+
+        if entry_value > 0.0 {
+            returns.push(pnl / entry_value);
+        }
+        if remaining > 1e-9 {
+            queue.push_back(Lot {
+                quantity: direction * remaining,
+                price: fill.price,
+                commission_per_unit: fill_commission_per_unit,
+            });
+        }
+    }
+
+    returns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fewer_than_two_returns_is_zero() {
+        assert_eq!(PerformanceStats::from_returns(&[], 0.0), PerformanceStats::default());
+        assert_eq!(PerformanceStats::from_returns(&[0.05], 0.0), PerformanceStats::default());
+    }
+
+    #[test]
+    fn test_from_returns() {
+        let stats = PerformanceStats::from_returns(&[0.10, -0.05, 0.02, -0.01], 0.0);
+
+        assert_eq!(stats.periods, 4);
+        assert_eq!(stats.win_rate, 0.5);
+        assert!((stats.profit_factor - 0.12 / 0.06).abs() < 1e-12);
+        // Peak 1.10, trough 1.10 * 0.95
+        assert!((stats.max_drawdown - 0.05).abs() < 1e-12);
+        assert!(stats.sharpe > 0.0);
+        assert!(stats.sortino > stats.sharpe);
+        assert!(stats.cagr > 0.0);
+    }
+
+    #[test]
+    fn test_no_losses_has_zero_sortino_and_profit_factor() {
+        let stats = PerformanceStats::from_returns(&[0.01, 0.02, 0.01], 0.0);
+        assert_eq!(stats.sortino, 0.0);
+        assert_eq!(stats.profit_factor, 0.0);
+        assert_eq!(stats.max_drawdown, 0.0);
+        assert_eq!(stats.win_rate, 1.0);
+        assert!(stats.sharpe.is_finite());
+    }
+
+    #[test]
+    fn test_returns_from_equity() {
+        let returns = PerformanceStats::returns_from_equity(&[100.0, 110.0, 99.0]);
+        assert_eq!(returns.len(), 2);
+        assert!((returns[0] - 0.10).abs() < 1e-12);
+        assert!((returns[1] + 0.10).abs() < 1e-12);
+    }
+
+    #[cfg(feature = "database")]
+    #[test]
+    fn test_from_trades_matches_fifo() {
+        use chrono::{Duration, Utc};
+        use database::TradeRecord;
+
+        let start = Utc::now();
+        let fill = |side: &str, qty: f64, price: f64, days: i64| {
+            let mut trade = TradeRecord::new("t", "o", "AAPL", side, qty, price);
+            trade.timestamp = start + Duration::days(days);
+            trade
+        };
+        let trades = vec![
+            fill("buy", 10.0, 100.0, 0),
+            fill("sell", 10.0, 110.0, 10),
+            fill("sell", 5.0, 50.0, 20),
+            fill("buy", 5.0, 55.0, 30),
+        ];
+
+        let stats = PerformanceStats::from_trades(&trades);
+        assert_eq!(stats.periods, 2);
+        assert_eq!(stats.win_rate, 0.5);
+        assert!((stats.profit_factor - 0.10 / 0.10).abs() < 1e-12);
+    }
+}
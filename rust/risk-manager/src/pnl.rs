@@ -1,6 +1,13 @@
-use common::types::{Position, Price, Quantity, Side, Symbol, Trade};
-use chrono::Utc;
-use std::collections::HashMap;
+use common::{SharedClock, SystemClock};
+use common::types::{Currency, Position, Price, Quantity, Side, Symbol, Trade};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, VecDeque};
+
+/// Default number of equity curve points kept in memory
+const DEFAULT_EQUITY_CAPACITY: usize = 10_000;
+
+/// Quantities below this are treated as fully matched (float dust)
+const QUANTITY_EPSILON: f64 = 1e-9;
 
 #[derive(Debug, Clone)]
 pub struct PositionState {
@@ -11,20 +18,59 @@ pub struct PositionState {
     pub total_cost: f64,
 }
 
+/// Open lot in the FIFO cost-basis queue
+#[derive(Debug, Clone)]
+pub struct Lot {
+    pub side: Side,
+    pub quantity: Quantity,
+    pub price: Price,
+}
+
 pub struct PnLTracker {
     positions: HashMap<String, PositionState>,
+    /// Open lots per symbol, oldest first (all on the same side)
+    lots: HashMap<String, VecDeque<Lot>>,
+    /// Realized P&L per symbol from FIFO lot matching
+    realized_by_symbol: HashMap<String, f64>,
     total_realized_pnl: f64,
     daily_pnl: f64,
     trade_count: u64,
+    /// Last reported unrealized P&L per symbol
+    unrealized_by_symbol: HashMap<String, f64>,
+    /// Equity (realized + unrealized P&L) samples, oldest first
+    equity_curve: VecDeque<(DateTime<Utc>, f64)>,
+    equity_capacity: usize,
+    peak_equity: f64,
+    max_drawdown: f64,
+    /// Start of the current trading session, if one was opened
+    session_start: Option<DateTime<Utc>>,
+    /// Total realized P&L when the session opened
+    session_baseline: f64,
+    clock: SharedClock,
 }
 
 impl PnLTracker {
     pub fn new() -> Self {
+        Self::with_equity_capacity(DEFAULT_EQUITY_CAPACITY)
+    }
+
+    /// Create a tracker keeping at most `capacity` equity curve points
+    pub fn with_equity_capacity(capacity: usize) -> Self {
         Self {
             positions: HashMap::new(),
+            lots: HashMap::new(),
+            realized_by_symbol: HashMap::new(),
             total_realized_pnl: 0.0,
             daily_pnl: 0.0,
             trade_count: 0,
+            unrealized_by_symbol: HashMap::new(),
+            equity_curve: VecDeque::with_capacity(capacity.min(DEFAULT_EQUITY_CAPACITY)),
+            equity_capacity: capacity.max(1),
+            peak_equity: 0.0,
+            max_drawdown: 0.0,
+            session_start: None,
+            session_baseline: 0.0,
+            clock: SystemClock::shared(),
         }
     }
 
@@ -89,6 +135,121 @@ impl PnLTracker {
         self.trade_count += 1;
     }
 
+    /// Record a fill against the FIFO lot queue and return the P&L it realized
+    ///
+    /// Fills on the same side as the open lots add a new lot; opposite fills
+    /// close the oldest lots first. If a closing fill exceeds the open
+    /// quantity, the remainder opens a new lot on the other side. This is an
+    /// alternative to `update_with_trade` (average-cost) and the two should
+    /// not be mixed for the same symbol.
+    pub fn record_fill(&mut self, symbol: &Symbol, side: Side, quantity: Quantity, price: Price) -> f64 {
+        let lots = self.lots.entry(symbol.0.clone()).or_default();
+        let mut remaining = quantity.0;
+        let mut pnl = 0.0;
+
+        while remaining > QUANTITY_EPSILON {
+            let Some(lot) = lots.front_mut() else {
+                break;
+            };
+            if lot.side == side {
+                break;
+            }
+
+            let matched = remaining.min(lot.quantity.0);
+            pnl += match lot.side {
+                Side::Bid => (price.0 - lot.price.0) * matched, // Closing long
+                Side::Ask => (lot.price.0 - price.0) * matched, // Closing short
+            };
+
+            lot.quantity = Quantity(lot.quantity.0 - matched);
+            remaining -= matched;
+
+            if lot.quantity.0 <= QUANTITY_EPSILON {
+                lots.pop_front();
+            }
+        }
+
+        // Anything left opens (or adds to) a position on the fill side
+        if remaining > QUANTITY_EPSILON {
+            lots.push_back(Lot {
+                side,
+                quantity: Quantity(remaining),
+                price,
+            });
+        }
+
+        *self.realized_by_symbol.entry(symbol.0.clone()).or_insert(0.0) += pnl;
+        self.total_realized_pnl += pnl;
+        self.daily_pnl += pnl;
+        self.trade_count += 1;
+
+        self.sync_position_from_lots(symbol);
+        pnl
+    }
+
+    /// Replace a symbol's open lots with a single lot matching `position`,
+    /// e.g. when adopting the broker's view after reconciliation. Realized
+    /// P&L is kept; a zero quantity clears the symbol.
+    pub fn reset_position(&mut self, position: &Position) {
+        let symbol = &position.symbol;
+        if position.quantity.0 > QUANTITY_EPSILON {
+            self.lots.insert(
+                symbol.0.clone(),
+                VecDeque::from([Lot {
+                    side: position.side,
+                    quantity: position.quantity,
+                    price: position.entry_price,
+                }]),
+            );
+            self.unrealized_by_symbol.insert(symbol.0.clone(), position.unrealized_pnl);
+        } else {
+            self.lots.remove(&symbol.0);
+            self.unrealized_by_symbol.remove(&symbol.0);
+        }
+        self.sync_position_from_lots(symbol);
+    }
+
+    /// Rebuild the aggregate position state from a symbol's open lots
+    fn sync_position_from_lots(&mut self, symbol: &Symbol) {
+        let lots = match self.lots.get(&symbol.0) {
+            Some(lots) if !lots.is_empty() => lots,
+            _ => {
+                self.lots.remove(&symbol.0);
+                self.positions.remove(&symbol.0);
+                return;
+            }
+        };
+
+        let quantity: f64 = lots.iter().map(|lot| lot.quantity.0).sum();
+        let total_cost: f64 = lots.iter().map(|lot| lot.quantity.0 * lot.price.0).sum();
+
+        self.positions.insert(
+            symbol.0.clone(),
+            PositionState {
+                quantity: Quantity(quantity),
+                avg_entry_price: Price(total_cost / quantity),
+                side: lots[0].side,
+                realized_pnl: self.realized_by_symbol.get(&symbol.0).copied().unwrap_or(0.0),
+                total_cost,
+            },
+        );
+    }
+
+    /// Realized P&L for a symbol from FIFO lot matching
+    pub fn realized_pnl(&self, symbol: &Symbol) -> f64 {
+        self.realized_by_symbol.get(&symbol.0).copied().unwrap_or(0.0)
+    }
+
+    /// Total realized P&L across all symbols
+    pub fn total_realized_pnl(&self) -> f64 {
+        self.total_realized_pnl
+    }
+
+    /// Open FIFO lots for a symbol, oldest first
+    pub fn lots(&self, symbol: &Symbol) -> Option<&VecDeque<Lot>> {
+        self.lots.get(&symbol.0)
+    }
+
     /// Calculate unrealized P&L for a position
     pub fn calculate_unrealized_pnl(&self, symbol: &str, current_price: Price) -> f64 {
         if let Some(position) = self.positions.get(symbol) {
@@ -116,6 +277,23 @@ impl PnLTracker {
             .sum()
     }
 
+    /// Total unrealized P&L from the last reported position updates
+    pub fn total_unrealized_pnl(&self) -> f64 {
+        self.unrealized_by_symbol.values().sum()
+    }
+
+    /// Clock used to timestamp positions built by `to_position`
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Start a new trading session now
+    pub fn start_session(&mut self) {
+        let now = self.clock.now();
+        self.reset_session(now);
+    }
+
     /// Get total P&L (realized + unrealized)
     pub fn get_total_pnl(&self, current_prices: &HashMap<String, Price>) -> f64 {
         self.total_realized_pnl + self.get_unrealized_pnl(current_prices)
@@ -123,6 +301,7 @@ impl PnLTracker {
 
     /// Convert internal state to Position for compatibility
     pub fn to_position(&self, symbol: &str, current_price: Price) -> Option<Position> {
+        let now = self.clock.now();
         self.positions.get(symbol).map(|state| Position {
             symbol: Symbol(symbol.to_string()),
             side: state.side,
@@ -131,8 +310,9 @@ impl PnLTracker {
             current_price,
             unrealized_pnl: self.calculate_unrealized_pnl(symbol, current_price),
             realized_pnl: state.realized_pnl,
-            opened_at: Utc::now(),
-            updated_at: Utc::now(),
+            opened_at: now,
+            updated_at: now,
+            currency: Currency::default(),
         })
     }
 
@@ -146,6 +326,25 @@ impl PnLTracker {
         self.daily_pnl = 0.0;
     }
 
+    /// Start a new trading session at `at`
+    ///
+    /// Snapshots the all-time realized total as the session baseline;
+    /// lifetime totals are left untouched.
+    pub fn reset_session(&mut self, at: DateTime<Utc>) {
+        self.session_start = Some(at);
+        self.session_baseline = self.total_realized_pnl;
+    }
+
+    /// Realized P&L since the session opened (all-time if never reset)
+    pub fn session_pnl(&self) -> f64 {
+        self.total_realized_pnl - self.session_baseline
+    }
+
+    /// Start of the current session
+    pub fn session_start(&self) -> Option<DateTime<Utc>> {
+        self.session_start
+    }
+
     /// Get position state
     pub fn get_position(&self, symbol: &str) -> Option<&PositionState> {
         self.positions.get(symbol)
@@ -178,9 +377,52 @@ impl PnLTracker {
 
         if position.quantity.0 == 0.0 {
             self.positions.remove(&position.symbol.0);
+            self.unrealized_by_symbol.remove(&position.symbol.0);
         } else {
             self.positions.insert(position.symbol.0.clone(), state);
+            self.unrealized_by_symbol
+                .insert(position.symbol.0.clone(), position.unrealized_pnl);
+        }
+
+        let equity = self.total_realized_pnl + self.total_unrealized_pnl();
+        self.record_equity(position.updated_at, equity);
+    }
+
+    /// Append an equity sample, evicting the oldest beyond capacity
+    ///
+    /// Peak and max drawdown are tracked incrementally, so they cover the
+    /// whole session even after old samples are evicted.
+    fn record_equity(&mut self, timestamp: DateTime<Utc>, equity: f64) {
+        if self.equity_curve.len() == self.equity_capacity {
+            self.equity_curve.pop_front();
         }
+        self.equity_curve.push_back((timestamp, equity));
+
+        self.peak_equity = self.peak_equity.max(equity);
+        self.max_drawdown = self.max_drawdown.max(self.peak_equity - equity);
+    }
+
+    /// Equity curve samples (realized + unrealized P&L), oldest first
+    pub fn equity_curve(&self) -> &VecDeque<(DateTime<Utc>, f64)> {
+        &self.equity_curve
+    }
+
+    /// Highest equity seen this session
+    pub fn peak_equity(&self) -> f64 {
+        self.peak_equity
+    }
+
+    /// Largest peak-to-trough equity decline seen this session
+    pub fn max_drawdown(&self) -> f64 {
+        self.max_drawdown
+    }
+
+    /// Decline of the latest equity sample from the peak
+    pub fn current_drawdown(&self) -> f64 {
+        self.equity_curve
+            .back()
+            .map(|&(_, equity)| self.peak_equity - equity)
+            .unwrap_or(0.0)
     }
 }
 
@@ -189,3 +431,175 @@ impl Default for PnLTracker {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sym(s: &str) -> Symbol {
+        Symbol(s.to_string())
+    }
+
+    #[test]
+    fn test_fifo_partial_close() {
+        let mut tracker = PnLTracker::new();
+        let aapl = sym("AAPL");
+
+        tracker.record_fill(&aapl, Side::Bid, Quantity(10.0), Price(100.0));
+        tracker.record_fill(&aapl, Side::Bid, Quantity(10.0), Price(110.0));
+
+        // Sell 15: 10 @ 100 then 5 @ 110 against 120
+        let pnl = tracker.record_fill(&aapl, Side::Ask, Quantity(15.0), Price(120.0));
+        assert_eq!(pnl, 10.0 * 20.0 + 5.0 * 10.0);
+        assert_eq!(tracker.realized_pnl(&aapl), 250.0);
+
+        let lots = tracker.lots(&aapl).unwrap();
+        assert_eq!(lots.len(), 1);
+        assert_eq!(lots[0].quantity.0, 5.0);
+        assert_eq!(lots[0].price.0, 110.0);
+
+        let state = tracker.get_position("AAPL").unwrap();
+        assert_eq!(state.quantity.0, 5.0);
+        assert_eq!(state.avg_entry_price.0, 110.0);
+    }
+
+    #[test]
+    fn test_fifo_short_round_trip() {
+        let mut tracker = PnLTracker::new();
+        let tsla = sym("TSLA");
+
+        tracker.record_fill(&tsla, Side::Ask, Quantity(4.0), Price(250.0));
+        let pnl = tracker.record_fill(&tsla, Side::Bid, Quantity(4.0), Price(240.0));
+        assert_eq!(pnl, 40.0);
+        assert!(tracker.lots(&tsla).is_none());
+        assert!(tracker.get_position("TSLA").is_none());
+    }
+
+    #[test]
+    fn test_fifo_position_flip() {
+        let mut tracker = PnLTracker::new();
+        let btc = sym("BTCUSD");
+
+        tracker.record_fill(&btc, Side::Bid, Quantity(2.0), Price(100.0));
+        let pnl = tracker.record_fill(&btc, Side::Ask, Quantity(5.0), Price(90.0));
+        assert_eq!(pnl, -20.0);
+
+        // Remaining 3 open a short lot at the fill price
+        let lots = tracker.lots(&btc).unwrap();
+        assert_eq!(lots.len(), 1);
+        assert_eq!(lots[0].side, Side::Ask);
+        assert_eq!(lots[0].quantity.0, 3.0);
+        assert_eq!(tracker.get_position("BTCUSD").unwrap().side, Side::Ask);
+
+        let pnl = tracker.record_fill(&btc, Side::Bid, Quantity(3.0), Price(80.0));
+        assert_eq!(pnl, 30.0);
+        assert_eq!(tracker.realized_pnl(&btc), 10.0);
+    }
+
+    fn position_with_pnl(symbol: &str, unrealized_pnl: f64) -> Position {
+        Position {
+            symbol: sym(symbol),
+            side: Side::Bid,
+            quantity: Quantity(1.0),
+            entry_price: Price(100.0),
+            current_price: Price(100.0 + unrealized_pnl),
+            unrealized_pnl,
+            realized_pnl: 0.0,
+            opened_at: Utc::now(),
+            updated_at: Utc::now(),
+            currency: Currency::default(),
+        }
+    }
+
+    #[test]
+    fn test_drawdown_on_fresh_tracker() {
+        let tracker = PnLTracker::new();
+        assert_eq!(tracker.max_drawdown(), 0.0);
+        assert_eq!(tracker.current_drawdown(), 0.0);
+        assert_eq!(tracker.peak_equity(), 0.0);
+    }
+
+    #[test]
+    fn test_equity_curve_drawdown() {
+        let mut tracker = PnLTracker::new();
+
+        tracker.update(&position_with_pnl("A", 50.0));
+        tracker.update(&position_with_pnl("B", 30.0)); // equity 80
+        tracker.update(&position_with_pnl("A", -10.0)); // equity 20
+        assert_eq!(tracker.peak_equity(), 80.0);
+        assert_eq!(tracker.current_drawdown(), 60.0);
+
+        tracker.update(&position_with_pnl("A", 20.0)); // equity 50
+        assert_eq!(tracker.current_drawdown(), 30.0);
+        assert_eq!(tracker.max_drawdown(), 60.0);
+        assert_eq!(tracker.equity_curve().len(), 4);
+    }
+
+    #[test]
+    fn test_equity_curve_capacity() {
+        let mut tracker = PnLTracker::with_equity_capacity(3);
+
+        tracker.update(&position_with_pnl("A", 100.0));
+        for pnl in [40.0, 60.0, 70.0, 80.0] {
+            tracker.update(&position_with_pnl("A", pnl));
+        }
+
+        // Oldest samples evicted, but the session peak and drawdown remain
+        assert_eq!(tracker.equity_curve().len(), 3);
+        assert_eq!(tracker.equity_curve().front().unwrap().1, 60.0);
+        assert_eq!(tracker.peak_equity(), 100.0);
+        assert_eq!(tracker.max_drawdown(), 60.0);
+    }
+
+    #[test]
+    fn test_total_realized_pnl_across_symbols() {
+        let mut tracker = PnLTracker::new();
+
+        tracker.record_fill(&sym("A"), Side::Bid, Quantity(1.0), Price(10.0));
+        tracker.record_fill(&sym("A"), Side::Ask, Quantity(1.0), Price(15.0));
+        tracker.record_fill(&sym("B"), Side::Bid, Quantity(2.0), Price(10.0));
+        tracker.record_fill(&sym("B"), Side::Ask, Quantity(2.0), Price(8.0));
+
+        assert_eq!(tracker.total_realized_pnl(), 1.0);
+        assert_eq!(tracker.get_daily_pnl(), 1.0);
+        assert_eq!(tracker.get_trade_count(), 4);
+    }
+
+    #[test]
+    fn test_session_pnl() {
+        let mut tracker = PnLTracker::new();
+        let aapl = sym("AAPL");
+        assert!(tracker.session_start().is_none());
+
+        tracker.record_fill(&aapl, Side::Bid, Quantity(10.0), Price(100.0));
+        tracker.record_fill(&aapl, Side::Ask, Quantity(10.0), Price(110.0));
+        assert_eq!(tracker.session_pnl(), 100.0);
+
+        let open = Utc::now();
+        tracker.reset_session(open);
+        assert_eq!(tracker.session_start(), Some(open));
+        assert_eq!(tracker.session_pnl(), 0.0);
+
+        tracker.record_fill(&aapl, Side::Bid, Quantity(10.0), Price(110.0));
+        tracker.record_fill(&aapl, Side::Ask, Quantity(10.0), Price(105.0));
+        assert_eq!(tracker.session_pnl(), -50.0);
+
+        // Lifetime totals are unaffected by the session reset
+        assert_eq!(tracker.total_realized_pnl(), 50.0);
+        assert_eq!(tracker.realized_pnl(&aapl), 50.0);
+    }
+
+    #[test]
+    fn test_clock_drives_timestamps() {
+        let start = Utc::now() - chrono::Duration::days(1);
+        let clock = common::MockClock::new(start);
+        let mut tracker = PnLTracker::new().with_clock(clock.clone());
+
+        tracker.record_fill(&sym("AAPL"), Side::Bid, Quantity(10.0), Price(100.0));
+        assert_eq!(tracker.to_position("AAPL", Price(101.0)).unwrap().updated_at, start);
+
+        clock.advance(std::time::Duration::from_secs(3600));
+        tracker.start_session();
+        assert_eq!(tracker.session_start(), Some(start + chrono::Duration::hours(1)));
+    }
+}
@@ -0,0 +1,139 @@
+use common::{
+    config::RiskConfig,
+    types::{Price, Quantity},
+};
+
+/// Position sizing from account equity and trade risk
+///
+/// All sizes are clamped so the position value stays within
+/// `RiskConfig::max_position_size`. Degenerate inputs size to zero.
+pub struct PositionSizer {
+    max_position_size: f64,
+}
+
+impl PositionSizer {
+    pub fn new(config: &RiskConfig) -> Self {
+        Self {
+            max_position_size: config.max_position_size,
+        }
+    }
+
+    /// Size so that hitting the stop loses `risk_fraction` of equity
+    ///
+    /// Quantity is `equity * risk_fraction / |entry - stop|`.
+    pub fn fixed_fractional(&self, equity: f64, risk_fraction: f64, entry: Price, stop: Price) -> Quantity {
+        let per_unit_risk = (entry.0 - stop.0).abs();
+        let valid = Self::is_valid_equity(equity)
+            && risk_fraction > 0.0
+            && risk_fraction <= 1.0
+            && per_unit_risk.is_finite()
+            && per_unit_risk != 0.0;
+        if !valid {
+            return Quantity(0.0);
+        }
+
+        self.clamp(equity * risk_fraction / per_unit_risk, entry)
+    }
+
+    /// Size by the Kelly criterion
+    ///
+    /// Allocates `p - (1 - p) / b` of equity, where `p` is the win probability
+    /// and `b` the average win/loss ratio. A non-positive edge sizes to zero.
+    pub fn kelly(&self, win_prob: f64, win_loss_ratio: f64, equity: f64, price: Price) -> Quantity {
+        let valid = Self::is_valid_equity(equity)
+            && (0.0..=1.0).contains(&win_prob)
+            && win_loss_ratio > 0.0
+            && win_loss_ratio.is_finite();
+        if !valid {
+            return Quantity(0.0);
+        }
+
+        let fraction = win_prob - (1.0 - win_prob) / win_loss_ratio;
+        if fraction <= 0.0 {
+            return Quantity(0.0);
+        }
+
+        self.clamp(equity * fraction / price.0, price)
+    }
+
+    fn is_valid_equity(equity: f64) -> bool {
+        equity > 0.0 && equity.is_finite()
+    }
+
+    /// Clamp a raw quantity to the max position value at `price`
+    fn clamp(&self, quantity: f64, price: Price) -> Quantity {
+        let valid = price.0 > 0.0 && price.0.is_finite() && quantity.is_finite();
+        if !valid {
+            return Quantity(0.0);
+        }
+
+        Quantity(quantity.min(self.max_position_size / price.0).max(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_config() -> RiskConfig {
+        RiskConfig {
+            max_position_size: 10000.0,
+            max_notional_exposure: 50000.0,
+            max_open_positions: 5,
+            stop_loss_percent: 5.0,
+            trailing_stop_percent: 3.0,
+            enable_circuit_breaker: true,
+            max_loss_threshold: 1000.0,
+            circuit_breaker_cooldown_secs: 300,
+            max_price_velocity_pct: 10.0,
+            max_leverage: 2.0,
+        }
+    }
+
+    #[test]
+    fn test_fixed_fractional() {
+        let sizer = PositionSizer::new(&create_test_config());
+
+        // Risk 1% of 100k = 1000 over a 5.0 stop distance = 200 shares,
+        // clamped to 10000 / 50 = 200
+        let qty = sizer.fixed_fractional(100_000.0, 0.01, Price(50.0), Price(45.0));
+        assert_eq!(qty.0, 200.0);
+
+        // Smaller risk stays under the cap
+        let qty = sizer.fixed_fractional(100_000.0, 0.005, Price(50.0), Price(45.0));
+        assert_eq!(qty.0, 100.0);
+
+        // Clamped to max position value
+        let qty = sizer.fixed_fractional(1_000_000.0, 0.02, Price(50.0), Price(49.0));
+        assert_eq!(qty.0, 200.0);
+    }
+
+    #[test]
+    fn test_fixed_fractional_degenerate_inputs() {
+        let sizer = PositionSizer::new(&create_test_config());
+
+        assert_eq!(sizer.fixed_fractional(100_000.0, 0.01, Price(50.0), Price(50.0)).0, 0.0);
+        assert_eq!(sizer.fixed_fractional(0.0, 0.01, Price(50.0), Price(45.0)).0, 0.0);
+        assert_eq!(sizer.fixed_fractional(100_000.0, f64::NAN, Price(50.0), Price(45.0)).0, 0.0);
+        assert_eq!(sizer.fixed_fractional(100_000.0, 0.01, Price(f64::NAN), Price(45.0)).0, 0.0);
+    }
+
+    #[test]
+    fn test_kelly() {
+        let sizer = PositionSizer::new(&create_test_config());
+
+        // p = 0.55, b = 1.0 -> f = 0.10 of 50k = 5000 / 100 = 50 units
+        let qty = sizer.kelly(0.55, 1.0, 50_000.0, Price(100.0));
+        assert!((qty.0 - 50.0).abs() < 1e-9);
+
+        // No edge
+        assert_eq!(sizer.kelly(0.4, 1.0, 50_000.0, Price(100.0)).0, 0.0);
+        assert_eq!(sizer.kelly(1.5, 1.0, 50_000.0, Price(100.0)).0, 0.0);
+        assert_eq!(sizer.kelly(0.55, 0.0, 50_000.0, Price(100.0)).0, 0.0);
+        assert_eq!(sizer.kelly(0.55, 1.0, 50_000.0, Price(0.0)).0, 0.0);
+
+        // Clamped to max position value
+        let qty = sizer.kelly(0.9, 3.0, 1_000_000.0, Price(100.0));
+        assert_eq!(qty.0, 100.0);
+    }
+}
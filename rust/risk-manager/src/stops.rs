@@ -1,8 +1,9 @@
 use common::{
     config::RiskConfig,
-    types::{Position, Price, Side, Symbol},
-    Result, TradingError,
+    types::{Position, Price, Quantity, Side, Symbol},
+    Result, SharedClock, StopTriggered, SystemClock, TradingError,
 };
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tracing::{debug, info, warn};
@@ -16,6 +17,10 @@ pub enum StopLossType {
     Trailing,
     /// Absolute stop-loss at a specific price level
     Absolute,
+    /// Volatility-scaled stop at a multiple of ATR from entry
+    Atr,
+    /// Time exit after the position has been held too long (see `max_hold_duration`)
+    Time,
 }
 
 /// Stop-loss configuration per position
@@ -29,6 +34,32 @@ pub struct StopLossConfig {
     pub price_level: Option<Price>,
     /// Maximum loss in absolute value (currency units)
     pub max_loss_value: Option<f64>,
+    /// ATR multiple (for Atr type and Chandelier trailing stops)
+    #[serde(default)]
+    pub atr_multiple: Option<f64>,
+    /// ATR value in price units (for Atr type and Chandelier trailing stops)
+    #[serde(default)]
+    pub atr_value: Option<f64>,
+    /// Exit once the position has been open this long, regardless of price
+    #[serde(default, with = "duration_secs")]
+    pub max_hold_duration: Option<Duration>,
+    /// Scale-out ladder of (loss % from entry, fraction of initial quantity to close)
+    #[serde(default)]
+    pub scale_out: Vec<(f64, f64)>,
+}
+
+/// Serialize `Option<chrono::Duration>` as whole seconds
+mod duration_secs {
+    use chrono::Duration;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Option<Duration>, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        value.map(|d| d.num_seconds()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Option<Duration>, D::Error> {
+        Ok(Option::<i64>::deserialize(deserializer)?.map(Duration::seconds))
+    }
 }
 
 impl StopLossConfig {
@@ -44,6 +75,10 @@ impl StopLossConfig {
             percentage: Some(percentage),
             price_level: None,
             max_loss_value: None,
+            atr_multiple: None,
+            atr_value: None,
+            max_hold_duration: None,
+            scale_out: Vec::new(),
         })
     }
 
@@ -59,6 +94,10 @@ impl StopLossConfig {
             percentage: Some(percentage),
             price_level: None,
             max_loss_value: None,
+            atr_multiple: None,
+            atr_value: None,
+            max_hold_duration: None,
+            scale_out: Vec::new(),
         })
     }
 
@@ -74,9 +113,50 @@ impl StopLossConfig {
             percentage: None,
             price_level: Some(price_level),
             max_loss_value: None,
+            atr_multiple: None,
+            atr_value: None,
+            max_hold_duration: None,
+            scale_out: Vec::new(),
         })
     }
 
+    /// Create a volatility-scaled stop `atr_multiple * atr_value` away from entry
+    pub fn atr_stop(atr_multiple: f64, atr_value: f64) -> Result<Self> {
+        if atr_multiple <= 0.0 || !atr_multiple.is_finite() {
+            return Err(TradingError::Configuration(
+                "ATR multiple must be positive".to_string(),
+            ));
+        }
+        if atr_value <= 0.0 || !atr_value.is_finite() {
+            return Err(TradingError::Configuration(
+                "ATR value must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            stop_type: StopLossType::Atr,
+            percentage: None,
+            price_level: None,
+            max_loss_value: None,
+            atr_multiple: Some(atr_multiple),
+            atr_value: Some(atr_value),
+            max_hold_duration: None,
+            scale_out: Vec::new(),
+        })
+    }
+
+    /// Create a Chandelier exit: a trailing stop `atr_multiple * atr` off the
+    /// highest price seen (lowest for shorts)
+    pub fn chandelier(atr_multiple: f64, atr: f64) -> Result<Self> {
+        let mut config = Self::atr_stop(atr_multiple, atr)?;
+        config.stop_type = StopLossType::Trailing;
+        Ok(config)
+    }
+
+    /// Stop distance in price units for ATR-based stops
+    fn atr_distance(&self) -> Option<f64> {
+        Some(self.atr_multiple? * self.atr_value?)
+    }
+
     /// Add maximum loss value constraint
     pub fn with_max_loss(mut self, max_loss: f64) -> Result<Self> {
         if max_loss <= 0.0 {
@@ -87,6 +167,57 @@ impl StopLossConfig {
         self.max_loss_value = Some(max_loss);
         Ok(self)
     }
+
+    /// Add maximum holding time constraint
+    pub fn with_max_hold(mut self, max_hold: Duration) -> Result<Self> {
+        if max_hold <= Duration::zero() {
+            return Err(TradingError::Configuration(
+                "Maximum hold duration must be positive".to_string(),
+            ));
+        }
+        self.max_hold_duration = Some(max_hold);
+        Ok(self)
+    }
+
+    /// Add a scale-out ladder of partial exits
+    ///
+    /// Each rung is `(trigger percentage from entry, fraction of the initial
+    /// quantity to close)`. Rungs must be ordered by increasing percentage;
+    /// the final rung closes whatever quantity remains. The configured stop
+    /// (and any max loss or hold limits) still closes the full position.
+    pub fn with_scale_out(mut self, ladder: Vec<(f64, f64)>) -> Result<Self> {
+        if ladder.is_empty() {
+            return Err(TradingError::Configuration(
+                "Scale-out ladder must have at least one rung".to_string(),
+            ));
+        }
+
+        let mut last_pct = 0.0;
+        let mut total_fraction = 0.0;
+        for &(pct, fraction) in &ladder {
+            if pct <= last_pct || pct > 100.0 {
+                return Err(TradingError::Configuration(
+                    "Scale-out percentages must be increasing and between 0 and 100".to_string(),
+                ));
+            }
+            if fraction <= 0.0 || fraction > 1.0 {
+                return Err(TradingError::Configuration(
+                    "Scale-out fractions must be between 0 and 1".to_string(),
+                ));
+            }
+            last_pct = pct;
+            total_fraction += fraction;
+        }
+
+        if total_fraction > 1.0 + 1e-9 {
+            return Err(TradingError::Configuration(
+                "Scale-out fractions must not sum to more than 1".to_string(),
+            ));
+        }
+
+        self.scale_out = ladder;
+        Ok(self)
+    }
 }
 
 /// Tracked stop-loss state for a position
@@ -95,9 +226,9 @@ pub struct StopLossState {
     config: StopLossConfig,
     /// Current stop-loss trigger price
     trigger_price: Price,
-    /// Highest price seen (for trailing stops on long positions)
+    /// Highest price seen since entry (for trailing stops on long positions)
     highest_price: Price,
-    /// Lowest price seen (for trailing stops on short positions)
+    /// Lowest price seen since entry (for trailing stops on short positions)
     lowest_price: Price,
     /// Position entry price
     entry_price: Price,
@@ -105,6 +236,11 @@ pub struct StopLossState {
     side: Side,
     /// Total loss accumulated
     current_loss: f64,
+    /// Position quantity when the stop was set (scale-out fractions apply to it)
+    initial_quantity: Quantity,
+    /// Number of scale-out rungs already fired
+    #[serde(default)]
+    rungs_fired: usize,
 }
 
 impl StopLossState {
@@ -115,15 +251,23 @@ impl StopLossState {
             &config,
         )?;
 
-        Ok(Self {
+        // Extremes start at entry rather than the first observed price: a
+        // stop set after an adverse move must not trail off that worse price,
+        // and one set after a favorable move locks in the gain right away
+        let mut state = Self {
             config,
             trigger_price,
-            highest_price: position.current_price,
-            lowest_price: position.current_price,
+            highest_price: position.entry_price,
+            lowest_price: position.entry_price,
             entry_price: position.entry_price,
             side: position.side,
             current_loss: position.unrealized_pnl,
-        })
+            initial_quantity: position.quantity,
+            rungs_fired: 0,
+        };
+        state.track_extremes(position.current_price);
+        state.ratchet_trailing();
+        Ok(state)
     }
 
     /// Calculate initial trigger price based on configuration
@@ -133,6 +277,16 @@ impl StopLossState {
         config: &StopLossConfig,
     ) -> Result<Price> {
         match config.stop_type {
+            StopLossType::Trailing if config.atr_distance().is_some() => {
+                // Chandelier exit: start one ATR distance from entry
+                let distance = config.atr_distance().unwrap_or_default();
+                let trigger = match side {
+                    Side::Bid => entry_price.0 - distance,
+                    Side::Ask => entry_price.0 + distance,
+                };
+
+                Ok(Price(trigger))
+            }
             StopLossType::Static | StopLossType::Trailing => {
                 let percentage = config.percentage.ok_or_else(|| {
                     TradingError::Configuration(
@@ -155,53 +309,87 @@ impl StopLossState {
                     )
                 })
             }
+            StopLossType::Atr => {
+                let distance = config.atr_distance().ok_or_else(|| {
+                    TradingError::Configuration(
+                        "ATR multiple and value required for ATR stop".to_string(),
+                    )
+                })?;
+
+                let trigger = match side {
+                    Side::Bid => entry_price.0 - distance, // Long: stop below entry
+                    Side::Ask => entry_price.0 + distance, // Short: stop above entry
+                };
+
+                Ok(Price(trigger))
+            }
+            StopLossType::Time => Err(TradingError::Configuration(
+                "Time exits are configured via max_hold_duration".to_string(),
+            )),
         }
     }
 
     /// Update stop-loss state with new price
     fn update(&mut self, current_price: Price) -> bool {
-        // Track price extremes
-        if current_price.0 > self.highest_price.0 {
-            self.highest_price = current_price;
-        }
-        if current_price.0 < self.lowest_price.0 {
-            self.lowest_price = current_price;
-        }
-
-        // Update trailing stop if applicable
-        if self.config.stop_type == StopLossType::Trailing {
-            if let Some(percentage) = self.config.percentage {
-                match self.side {
-                    Side::Bid => {
-                        // Long position: trail up with price
-                        let new_trigger = self.highest_price.0 * (1.0 - percentage / 100.0);
-                        if new_trigger > self.trigger_price.0 {
-                            debug!(
-                                "Trailing stop updated: {} -> {}",
-                                self.trigger_price.0, new_trigger
-                            );
-                            self.trigger_price = Price(new_trigger);
-                        }
-                    }
-                    Side::Ask => {
-                        // Short position: trail down with price
-                        let new_trigger = self.lowest_price.0 * (1.0 + percentage / 100.0);
-                        if new_trigger < self.trigger_price.0 {
-                            debug!(
-                                "Trailing stop updated: {} -> {}",
-                                self.trigger_price.0, new_trigger
-                            );
-                            self.trigger_price = Price(new_trigger);
-                        }
-                    }
-                }
-            }
-        }
+        self.track_extremes(current_price);
+        self.ratchet_trailing();
 
         // Check if stop was hit
         self.is_triggered(current_price)
     }
 
+    /// Fold a price into the extremes seen since entry
+    fn track_extremes(&mut self, price: Price) {
+        if price.0 > self.highest_price.0 {
+            self.highest_price = price;
+        }
+        if price.0 < self.lowest_price.0 {
+            self.lowest_price = price;
+        }
+    }
+
+    /// Tighten a trailing stop to the favorable extreme; it never loosens
+    fn ratchet_trailing(&mut self) {
+        if self.config.stop_type != StopLossType::Trailing {
+            return;
+        }
+
+        let (extreme, tighter): (Price, fn(f64, f64) -> bool) = match self.side {
+            // Long position: trail up with the high
+            Side::Bid => (self.highest_price, |new, old| new > old),
+            // Short position: trail down with the low
+            Side::Ask => (self.lowest_price, |new, old| new < old),
+        };
+
+        if let Some(new_trigger) = self.trailing_trigger(extreme) {
+            if tighter(new_trigger, self.trigger_price.0) {
+                debug!(
+                    "Trailing stop updated: {} -> {}",
+                    self.trigger_price.0, new_trigger
+                );
+                self.trigger_price = Price(new_trigger);
+            }
+        }
+    }
+
+    /// Trailing trigger level off a price extreme
+    ///
+    /// Chandelier exits trail a fixed ATR distance; plain trailing stops trail
+    /// a percentage.
+    fn trailing_trigger(&self, extreme: Price) -> Option<f64> {
+        if let Some(distance) = self.config.atr_distance() {
+            return Some(match self.side {
+                Side::Bid => extreme.0 - distance,
+                Side::Ask => extreme.0 + distance,
+            });
+        }
+
+        self.config.percentage.map(|percentage| match self.side {
+            Side::Bid => extreme.0 * (1.0 - percentage / 100.0),
+            Side::Ask => extreme.0 * (1.0 + percentage / 100.0),
+        })
+    }
+
     /// Check if stop-loss is triggered
     fn is_triggered(&self, current_price: Price) -> bool {
         match self.side {
@@ -217,6 +405,171 @@ impl StopLossState {
         }
         false
     }
+
+    /// Price level at which a scale-out rung fires
+    fn rung_price(&self, percentage: f64) -> Price {
+        match self.side {
+            Side::Bid => Price(self.entry_price.0 * (1.0 - percentage / 100.0)),
+            Side::Ask => Price(self.entry_price.0 * (1.0 + percentage / 100.0)),
+        }
+    }
+
+    /// Fire every scale-out rung the current price has crossed
+    ///
+    /// Returns the deepest crossed rung price, the combined fraction to close
+    /// and whether the final rung has now fired.
+    fn fire_scale_out(&mut self, current_price: Price) -> Option<(Price, f64, bool)> {
+        let mut fired = None;
+        let mut fraction = 0.0;
+
+        while let Some(&(percentage, rung_fraction)) = self.config.scale_out.get(self.rungs_fired) {
+            let level = self.rung_price(percentage);
+            let crossed = match self.side {
+                Side::Bid => current_price.0 <= level.0,
+                Side::Ask => current_price.0 >= level.0,
+            };
+            if !crossed {
+                break;
+            }
+            fraction += rung_fraction;
+            fired = Some(level);
+            self.rungs_fired += 1;
+        }
+
+        fired.map(|level| (level, fraction, self.rungs_fired == self.config.scale_out.len()))
+    }
+
+    /// Number of scale-out rungs already fired
+    pub fn rungs_fired(&self) -> usize {
+        self.rungs_fired
+    }
+
+    /// Check if the position has been held longer than allowed
+    fn is_max_hold_exceeded(&self, position: &Position, now: DateTime<Utc>) -> bool {
+        match self.config.max_hold_duration {
+            Some(max_hold) => now - position.opened_at >= max_hold,
+            None => false,
+        }
+    }
+}
+
+/// Take-profit type configuration
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TakeProfitType {
+    /// Target at a fixed percentage from entry
+    Percentage,
+    /// Target at a specific price level
+    Absolute,
+}
+
+/// Take-profit configuration per position
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TakeProfitConfig {
+    /// Type of take-profit
+    pub take_profit_type: TakeProfitType,
+    /// Percentage-based target (for Percentage type)
+    pub percentage: Option<f64>,
+    /// Absolute price level (for Absolute type)
+    pub price_level: Option<Price>,
+}
+
+impl TakeProfitConfig {
+    /// Create a target at a percentage gain from entry
+    pub fn percentage_target(percentage: f64) -> Result<Self> {
+        if percentage <= 0.0 || !percentage.is_finite() {
+            return Err(TradingError::Configuration(
+                "Take-profit percentage must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            take_profit_type: TakeProfitType::Percentage,
+            percentage: Some(percentage),
+            price_level: None,
+        })
+    }
+
+    /// Create a target at an absolute price level
+    pub fn absolute_target(price_level: Price) -> Result<Self> {
+        if price_level.0 <= 0.0 {
+            return Err(TradingError::Configuration(
+                "Take-profit price must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            take_profit_type: TakeProfitType::Absolute,
+            percentage: None,
+            price_level: Some(price_level),
+        })
+    }
+}
+
+/// Tracked take-profit state for a position
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TakeProfitState {
+    config: TakeProfitConfig,
+    /// Price at which the target fires
+    target_price: Price,
+    /// Position entry price
+    entry_price: Price,
+    /// Position side
+    side: Side,
+}
+
+impl TakeProfitState {
+    fn new(position: &Position, config: TakeProfitConfig) -> Result<Self> {
+        let entry_price = position.entry_price;
+        let target_price = match config.take_profit_type {
+            TakeProfitType::Percentage => {
+                let percentage = config.percentage.ok_or_else(|| {
+                    TradingError::Configuration(
+                        "Percentage required for percentage take-profit".to_string(),
+                    )
+                })?;
+                // Offset form keeps round targets exact (50000 * 1.1 != 55000 in f64)
+                let offset = entry_price.0 * percentage / 100.0;
+                match position.side {
+                    Side::Bid => Price(entry_price.0 + offset), // Long: target above entry
+                    Side::Ask => Price(entry_price.0 - offset), // Short: target below entry
+                }
+            }
+            TakeProfitType::Absolute => config.price_level.ok_or_else(|| {
+                TradingError::Configuration(
+                    "Price level required for absolute take-profit".to_string(),
+                )
+            })?,
+        };
+
+        let in_profit = match position.side {
+            Side::Bid => target_price.0 > entry_price.0,
+            Side::Ask => target_price.0 < entry_price.0 && target_price.0 > 0.0,
+        };
+        if !in_profit {
+            return Err(TradingError::Configuration(format!(
+                "Take-profit {:.8} must be on the profit side of entry {:.8} for {:?} position",
+                target_price.0, entry_price.0, position.side
+            )));
+        }
+
+        Ok(Self {
+            config,
+            target_price,
+            entry_price,
+            side: position.side,
+        })
+    }
+
+    /// Check if the target is reached
+    fn is_triggered(&self, current_price: Price) -> bool {
+        match self.side {
+            Side::Bid => current_price.0 >= self.target_price.0, // Long: price rose to target
+            Side::Ask => current_price.0 <= self.target_price.0, // Short: price fell to target
+        }
+    }
+
+    /// Get the target price
+    pub fn target_price(&self) -> Price {
+        self.target_price
+    }
 }
 
 /// Manages stop-loss orders and triggers
@@ -224,8 +577,11 @@ pub struct StopManager {
     config: RiskConfig,
     /// Active stop-loss states per symbol
     stops: HashMap<String, StopLossState>,
+    /// Active take-profit states per symbol
+    take_profits: HashMap<String, TakeProfitState>,
     /// Triggered stops pending execution
     triggered_stops: Vec<(Symbol, String)>, // (symbol, reason)
+    clock: SharedClock,
 }
 
 impl StopManager {
@@ -235,16 +591,31 @@ impl StopManager {
         Self {
             config,
             stops: HashMap::new(),
+            take_profits: HashMap::new(),
             triggered_stops: Vec::new(),
+            clock: SystemClock::shared(),
         }
     }
 
+    /// Clock used for holding-period exits
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Swap in new risk limits. Existing stops keep their trigger levels;
+    /// the new defaults apply to stops configured from now on.
+    pub fn update_config(&mut self, config: RiskConfig) {
+        self.config = config;
+    }
+
     /// Add or update stop-loss for a position
     pub fn set_stop(&mut self, position: &Position, config: StopLossConfig) -> Result<()> {
         let symbol_key = position.symbol.0.clone();
 
         // Validate configuration
         match config.stop_type {
+            StopLossType::Trailing if config.atr_distance().is_some() => {}
             StopLossType::Static | StopLossType::Trailing => {
                 if config.percentage.is_none() {
                     return Err(TradingError::Configuration(
@@ -259,6 +630,18 @@ impl StopManager {
                     ));
                 }
             }
+            StopLossType::Atr => {
+                if config.atr_distance().is_none() {
+                    return Err(TradingError::Configuration(
+                        "ATR multiple and value required for ATR stop".to_string(),
+                    ));
+                }
+            }
+            StopLossType::Time => {
+                return Err(TradingError::Configuration(
+                    "Time exits are configured via max_hold_duration".to_string(),
+                ));
+            }
         }
 
         let state = StopLossState::new(position, config)?;
@@ -299,13 +682,16 @@ impl StopManager {
             }
         }
 
+        let now = self.clock.now();
         let state = self.stops.get_mut(symbol_key)?;
 
         // Update state with current price
         let price_triggered = state.update(position.current_price);
         let loss_triggered = state.is_max_loss_exceeded(position.unrealized_pnl);
+        let time_triggered = state.is_max_hold_exceeded(position, now);
 
-        if price_triggered || loss_triggered {
+        if price_triggered || loss_triggered || time_triggered {
+            let mut stop_type = state.config.stop_type;
             let reason = if price_triggered && loss_triggered {
                 format!(
                     "Price stop at {:.8} and max loss ${:.2} both triggered",
@@ -316,12 +702,19 @@ impl StopManager {
                     "{:?} stop triggered at {:.8} (current: {:.8})",
                     state.config.stop_type, state.trigger_price.0, position.current_price.0
                 )
-            } else {
+            } else if loss_triggered {
                 format!(
                     "Max loss ${:.2} exceeded (current loss: ${:.2})",
                     state.config.max_loss_value.unwrap_or(0.0),
                     -position.unrealized_pnl
                 )
+            } else {
+                stop_type = StopLossType::Time;
+                format!(
+                    "Max hold duration exceeded (held {}m, limit {}m)",
+                    (now - position.opened_at).num_minutes(),
+                    state.config.max_hold_duration.unwrap_or_else(Duration::zero).num_minutes()
+                )
             };
 
             warn!("STOP-LOSS TRIGGERED for {}: {}", symbol_key, reason);
@@ -332,8 +725,9 @@ impl StopManager {
                 trigger_price: state.trigger_price,
                 current_price: position.current_price,
                 unrealized_pnl: position.unrealized_pnl,
-                stop_type: state.config.stop_type,
+                stop_type,
                 reason: reason.clone(),
+                quantity: position.quantity,
             };
 
             self.triggered_stops.push((position.symbol.clone(), reason));
@@ -344,9 +738,118 @@ impl StopManager {
             return Some(trigger);
         }
 
+        // Partial exits from the scale-out ladder
+        if let Some((rung_price, fraction, is_final)) = state.fire_scale_out(position.current_price) {
+            let quantity = if is_final {
+                position.quantity
+            } else {
+                Quantity((state.initial_quantity.0 * fraction).min(position.quantity.0))
+            };
+
+            let reason = format!(
+                "Scale-out rung {}/{} triggered at {:.8} (current: {:.8}), closing {:.8}",
+                state.rungs_fired,
+                state.config.scale_out.len(),
+                rung_price.0,
+                position.current_price.0,
+                quantity.0
+            );
+
+            warn!("STOP-LOSS TRIGGERED for {}: {}", symbol_key, reason);
+
+            let trigger = StopLossTrigger {
+                symbol: position.symbol.clone(),
+                position: position.clone(),
+                trigger_price: rung_price,
+                current_price: position.current_price,
+                unrealized_pnl: position.unrealized_pnl,
+                stop_type: state.config.stop_type,
+                reason: reason.clone(),
+                quantity,
+            };
+
+            self.triggered_stops.push((position.symbol.clone(), reason));
+
+            // Keep the stop active until the last rung fires
+            if is_final {
+                self.stops.remove(symbol_key);
+            }
+
+            return Some(trigger);
+        }
+
         None
     }
 
+    /// Add or update take-profit for a position
+    pub fn set_take_profit(&mut self, position: &Position, config: TakeProfitConfig) -> Result<()> {
+        let state = TakeProfitState::new(position, config)?;
+
+        info!(
+            "Take-profit set for {}: type={:?}, target_price={:.8}, entry_price={:.8}",
+            position.symbol.0, state.config.take_profit_type, state.target_price.0, state.entry_price.0
+        );
+
+        self.take_profits.insert(position.symbol.0.clone(), state);
+        Ok(())
+    }
+
+    /// Remove take-profit for a symbol
+    pub fn remove_take_profit(&mut self, symbol: &Symbol) {
+        if self.take_profits.remove(&symbol.0).is_some() {
+            info!("Take-profit removed for {}", symbol.0);
+        }
+    }
+
+    /// Check position against both stop-loss and take-profit rules
+    ///
+    /// A full exit from either side removes both, since the position is
+    /// being closed. Partial scale-out stop triggers leave the target active.
+    pub fn check_exit(&mut self, position: &Position) -> Option<ExitTrigger> {
+        if let Some(trigger) = self.check(position) {
+            if !self.has_stop(&position.symbol) {
+                self.take_profits.remove(&position.symbol.0);
+            }
+            return Some(ExitTrigger::StopLoss(trigger));
+        }
+
+        let state = self.take_profits.get(&position.symbol.0)?;
+        if !state.is_triggered(position.current_price) {
+            return None;
+        }
+
+        let reason = format!(
+            "{:?} take-profit triggered at {:.8} (current: {:.8})",
+            state.config.take_profit_type, state.target_price.0, position.current_price.0
+        );
+        info!("TAKE-PROFIT TRIGGERED for {}: {}", position.symbol.0, reason);
+
+        let trigger = TakeProfitTrigger {
+            symbol: position.symbol.clone(),
+            position: position.clone(),
+            target_price: state.target_price,
+            current_price: position.current_price,
+            unrealized_pnl: position.unrealized_pnl,
+            take_profit_type: state.config.take_profit_type,
+            reason,
+        };
+
+        self.take_profits.remove(&position.symbol.0);
+        self.stops.remove(&position.symbol.0);
+
+        Some(ExitTrigger::TakeProfit(trigger))
+    }
+
+    /// Get take-profit state for a specific symbol
+    pub fn get_take_profit(&self, symbol: &Symbol) -> Option<&TakeProfitState> {
+        self.take_profits.get(&symbol.0)
+    }
+
+    /// Check if a position has an active take-profit
+    pub fn has_take_profit(&self, symbol: &Symbol) -> bool {
+        self.take_profits.contains_key(&symbol.0)
+    }
+
     /// Get all active stop-loss states
     pub fn get_active_stops(&self) -> &HashMap<String, StopLossState> {
         &self.stops
@@ -383,11 +886,52 @@ pub struct StopLossTrigger {
     pub unrealized_pnl: f64,
     pub stop_type: StopLossType,
     pub reason: String,
+    /// Quantity to close (less than the position for scale-out rungs)
+    pub quantity: Quantity,
 }
 
 impl StopLossTrigger {
+    /// Get the quantity to close
+    pub fn close_quantity(&self) -> Quantity {
+        self.quantity
+    }
+
+    /// Get the side for the closing order (opposite of position side)
+    pub fn close_side(&self) -> Side {
+        match self.position.side {
+            Side::Bid => Side::Ask, // Close long with sell
+            Side::Ask => Side::Bid, // Close short with buy
+        }
+    }
+
+    /// The event bus form of this trigger
+    pub fn to_event(&self) -> StopTriggered {
+        StopTriggered {
+            symbol: self.symbol.clone(),
+            close_side: self.close_side(),
+            quantity: self.quantity,
+            trigger_price: self.trigger_price,
+            current_price: self.current_price,
+            reason: self.reason.clone(),
+        }
+    }
+}
+
+/// Take-profit trigger event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TakeProfitTrigger {
+    pub symbol: Symbol,
+    pub position: Position,
+    pub target_price: Price,
+    pub current_price: Price,
+    pub unrealized_pnl: f64,
+    pub take_profit_type: TakeProfitType,
+    pub reason: String,
+}
+
+impl TakeProfitTrigger {
     /// Get the quantity to close (full position)
-    pub fn close_quantity(&self) -> common::types::Quantity {
+    pub fn close_quantity(&self) -> Quantity {
         self.position.quantity
     }
 
@@ -400,15 +944,41 @@ impl StopLossTrigger {
     }
 }
 
+/// Exit event from `StopManager::check_exit`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ExitTrigger {
+    StopLoss(StopLossTrigger),
+    TakeProfit(TakeProfitTrigger),
+}
+
+impl ExitTrigger {
+    /// Get the quantity to close
+    pub fn close_quantity(&self) -> Quantity {
+        match self {
+            ExitTrigger::StopLoss(trigger) => trigger.close_quantity(),
+            ExitTrigger::TakeProfit(trigger) => trigger.close_quantity(),
+        }
+    }
+
+    /// Get the side for the closing order
+    pub fn close_side(&self) -> Side {
+        match self {
+            ExitTrigger::StopLoss(trigger) => trigger.close_side(),
+            ExitTrigger::TakeProfit(trigger) => trigger.close_side(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Utc;
+    use common::types::Currency;
+    use common::{Clock, MockClock};
 
     fn create_test_position(symbol: &str, side: Side, entry: f64, current: f64, qty: f64) -> Position {
         let entry_price = Price(entry);
         let current_price = Price(current);
-        let quantity = common::types::Quantity(qty);
+        let quantity = Quantity(qty);
 
         let unrealized_pnl = match side {
             Side::Bid => (current - entry) * qty,
@@ -425,6 +995,7 @@ mod tests {
             realized_pnl: 0.0,
             opened_at: Utc::now(),
             updated_at: Utc::now(),
+            currency: Currency::default(),
         }
     }
 
@@ -437,6 +1008,9 @@ mod tests {
             trailing_stop_percent: 3.0,
             enable_circuit_breaker: true,
             max_loss_threshold: 1000.0,
+            circuit_breaker_cooldown_secs: 300,
+            max_price_velocity_pct: 10.0,
+            max_leverage: 2.0,
         }
     }
 
@@ -513,6 +1087,117 @@ mod tests {
         assert!(trigger.is_some());
     }
 
+    #[test]
+    fn test_chandelier_exit_ratchets() {
+        let mut manager = StopManager::new(create_test_config());
+        let position = create_test_position("BTCUSDT", Side::Bid, 100.0, 100.0, 1.0);
+
+        let config = StopLossConfig::chandelier(3.0, 2.0).unwrap();
+        manager.set_stop(&position, config).unwrap();
+        assert_eq!(manager.get_stop(&position.symbol).unwrap().trigger_price.0, 94.0);
+
+        // New high: stop trails to 110 - 6
+        let mut pos_updated = position.clone();
+        pos_updated.current_price = Price(110.0);
+        assert!(manager.check(&pos_updated).is_none());
+        assert_eq!(manager.get_stop(&position.symbol).unwrap().trigger_price.0, 104.0);
+
+        // Pullback does not lower the stop
+        pos_updated.current_price = Price(105.0);
+        assert!(manager.check(&pos_updated).is_none());
+        assert_eq!(manager.get_stop(&position.symbol).unwrap().trigger_price.0, 104.0);
+
+        pos_updated.current_price = Price(104.0);
+        let trigger = manager.check(&pos_updated).unwrap();
+        assert_eq!(trigger.stop_type, StopLossType::Trailing);
+    }
+
+    #[test]
+    fn test_chandelier_exit_short() {
+        let mut manager = StopManager::new(create_test_config());
+        let position = create_test_position("ETHUSDT", Side::Ask, 100.0, 100.0, 1.0);
+
+        manager
+            .set_stop(&position, StopLossConfig::chandelier(2.0, 1.5).unwrap())
+            .unwrap();
+
+        let mut pos_updated = position.clone();
+        pos_updated.current_price = Price(90.0);
+        assert!(manager.check(&pos_updated).is_none());
+        assert_eq!(manager.get_stop(&position.symbol).unwrap().trigger_price.0, 93.0);
+
+        pos_updated.current_price = Price(93.5);
+        assert!(manager.check(&pos_updated).is_some());
+    }
+
+    #[test]
+    fn test_trailing_stop_short_profits_then_reverses() {
+        let mut manager = StopManager::new(create_test_config());
+        let position = create_test_position("ETHUSDT", Side::Ask, 100.0, 100.0, 10.0);
+        manager.set_stop(&position, StopLossConfig::trailing_stop(5.0).unwrap()).unwrap();
+        let trigger_price = |manager: &StopManager| manager.get_stop(&position.symbol).unwrap().trigger_price.0;
+        assert!((trigger_price(&manager) - 105.0).abs() < 1e-9);
+
+        // Favorable run: the stop follows the low down
+        let mut pos_updated = position.clone();
+        for (price, expected) in [(95.0, 99.75), (90.0, 94.5)] {
+            pos_updated.current_price = Price(price);
+            assert!(manager.check(&pos_updated).is_none());
+            assert!((trigger_price(&manager) - expected).abs() < 1e-9);
+        }
+
+        // Reversal: the stop holds at 94.5 rather than loosening
+        pos_updated.current_price = Price(93.0);
+        assert!(manager.check(&pos_updated).is_none());
+        assert!((trigger_price(&manager) - 94.5).abs() < 1e-9);
+
+        // Exit at the locked level, 5.5 below entry
+        pos_updated.current_price = Price(94.6);
+        let trigger = manager.check(&pos_updated).unwrap();
+        assert_eq!(trigger.stop_type, StopLossType::Trailing);
+        assert_eq!(trigger.close_side(), Side::Bid);
+        assert!((trigger.trigger_price.0 - 94.5).abs() < 1e-9);
+        assert!(trigger.trigger_price.0 < position.entry_price.0);
+    }
+
+    #[test]
+    fn test_trailing_stop_short_seeds_from_entry() {
+        // Set while already in profit: the gain is locked in immediately
+        let mut manager = StopManager::new(create_test_config());
+        let position = create_test_position("ETHUSDT", Side::Ask, 100.0, 90.0, 10.0);
+        manager.set_stop(&position, StopLossConfig::trailing_stop(5.0).unwrap()).unwrap();
+        let state = manager.get_stop(&position.symbol).unwrap();
+        assert_eq!(state.lowest_price, Price(90.0));
+        assert!((state.trigger_price.0 - 94.5).abs() < 1e-9);
+
+        // Set after an adverse move: the stop is measured from entry, and
+        // the low starts at entry, not at the worse first price
+        let position = create_test_position("SOLUSDT", Side::Ask, 100.0, 102.0, 10.0);
+        manager.set_stop(&position, StopLossConfig::trailing_stop(5.0).unwrap()).unwrap();
+        let state = manager.get_stop(&position.symbol).unwrap();
+        assert_eq!(state.lowest_price, Price(100.0));
+        assert!((state.trigger_price.0 - 105.0).abs() < 1e-9);
+
+        let mut pos_updated = position.clone();
+        pos_updated.current_price = Price(98.0);
+        assert!(manager.check(&pos_updated).is_none());
+        assert!((manager.get_stop(&position.symbol).unwrap().trigger_price.0 - 102.9).abs() < 1e-9);
+
+        pos_updated.current_price = Price(103.0);
+        assert!(manager.check(&pos_updated).is_some());
+    }
+
+    #[test]
+    fn test_trailing_stop_long_seeds_from_entry() {
+        let mut manager = StopManager::new(create_test_config());
+        let position = create_test_position("BTCUSDT", Side::Bid, 100.0, 98.0, 1.0);
+        manager.set_stop(&position, StopLossConfig::trailing_stop(5.0).unwrap()).unwrap();
+
+        let state = manager.get_stop(&position.symbol).unwrap();
+        assert_eq!(state.highest_price, Price(100.0));
+        assert!((state.trigger_price.0 - 95.0).abs() < 1e-9);
+    }
+
     #[test]
     fn test_absolute_stop() {
         let mut manager = StopManager::new(create_test_config());
@@ -532,6 +1217,37 @@ mod tests {
         assert!(trigger.is_some());
     }
 
+    #[test]
+    fn test_atr_stop_long_and_short() {
+        let mut manager = StopManager::new(create_test_config());
+
+        // Long: 2 x 500 ATR below entry
+        let long = create_test_position("BTCUSDT", Side::Bid, 50000.0, 50000.0, 1.0);
+        manager
+            .set_stop(&long, StopLossConfig::atr_stop(2.0, 500.0).unwrap())
+            .unwrap();
+        assert_eq!(manager.get_stop(&long.symbol).unwrap().trigger_price.0, 49000.0);
+
+        let mut pos_updated = long.clone();
+        pos_updated.current_price = Price(49100.0);
+        assert!(manager.check(&pos_updated).is_none());
+
+        pos_updated.current_price = Price(49000.0);
+        let trigger = manager.check(&pos_updated).unwrap();
+        assert_eq!(trigger.stop_type, StopLossType::Atr);
+
+        // Short: 1.5 x 20 ATR above entry
+        let short = create_test_position("ETHUSDT", Side::Ask, 3000.0, 3000.0, 10.0);
+        manager
+            .set_stop(&short, StopLossConfig::atr_stop(1.5, 20.0).unwrap())
+            .unwrap();
+        assert_eq!(manager.get_stop(&short.symbol).unwrap().trigger_price.0, 3030.0);
+
+        let mut pos_updated = short.clone();
+        pos_updated.current_price = Price(3031.0);
+        assert!(manager.check(&pos_updated).is_some());
+    }
+
     #[test]
     fn test_max_loss_value() {
         let mut manager = StopManager::new(create_test_config());
@@ -556,6 +1272,191 @@ mod tests {
         assert!(trigger.is_some());
     }
 
+    #[test]
+    fn test_max_hold_duration() {
+        let mut manager = StopManager::new(create_test_config());
+        let mut position = create_test_position("BTCUSDT", Side::Bid, 50000.0, 50000.0, 1.0);
+
+        let config = StopLossConfig::static_stop(5.0)
+            .unwrap()
+            .with_max_hold(Duration::minutes(30))
+            .unwrap();
+        manager.set_stop(&position, config).unwrap();
+
+        // Held 10 minutes, price flat
+        position.opened_at = Utc::now() - Duration::minutes(10);
+        assert!(manager.check(&position).is_none());
+
+        // Held past the limit, price still flat
+        position.opened_at = Utc::now() - Duration::minutes(45);
+        let trigger = manager.check(&position).unwrap();
+        assert_eq!(trigger.stop_type, StopLossType::Time);
+        assert!(trigger.reason.contains("Max hold duration exceeded"));
+        assert!(!manager.has_stop(&position.symbol));
+    }
+
+    #[test]
+    fn test_time_exit_on_mock_clock() {
+        let clock = MockClock::starting_now();
+        let mut manager = StopManager::new(create_test_config()).with_clock(clock.clone());
+        let mut position = create_test_position("BTCUSDT", Side::Bid, 50000.0, 50000.0, 1.0);
+        position.opened_at = clock.now();
+
+        let config = StopLossConfig::static_stop(5.0)
+            .unwrap()
+            .with_max_hold(Duration::minutes(30))
+            .unwrap();
+        manager.set_stop(&position, config).unwrap();
+
+        clock.advance(std::time::Duration::from_secs(29 * 60));
+        assert!(manager.check(&position).is_none());
+
+        clock.advance(std::time::Duration::from_secs(60));
+        let trigger = manager.check(&position).unwrap();
+        assert_eq!(trigger.stop_type, StopLossType::Time);
+        assert!(trigger.reason.contains("held 30m"));
+    }
+
+    #[test]
+    fn test_price_stop_takes_precedence_over_time_exit() {
+        let mut manager = StopManager::new(create_test_config());
+        let mut position = create_test_position("BTCUSDT", Side::Bid, 50000.0, 50000.0, 1.0);
+
+        let config = StopLossConfig::static_stop(5.0)
+            .unwrap()
+            .with_max_hold(Duration::minutes(30))
+            .unwrap();
+        manager.set_stop(&position, config).unwrap();
+
+        position.opened_at = Utc::now() - Duration::hours(1);
+        position.current_price = Price(47000.0);
+        let trigger = manager.check(&position).unwrap();
+        assert_eq!(trigger.stop_type, StopLossType::Static);
+    }
+
+    #[test]
+    fn test_scale_out_ladder() {
+        let mut manager = StopManager::new(create_test_config());
+        let position = create_test_position("BTCUSDT", Side::Bid, 100.0, 100.0, 10.0);
+
+        let config = StopLossConfig::static_stop(20.0)
+            .unwrap()
+            .with_scale_out(vec![(2.0, 0.5), (4.0, 0.5)])
+            .unwrap();
+        manager.set_stop(&position, config).unwrap();
+
+        let mut pos_updated = position.clone();
+        pos_updated.current_price = Price(99.0);
+        assert!(manager.check(&pos_updated).is_none());
+
+        // First rung closes half, stop stays active
+        pos_updated.current_price = Price(98.0);
+        let trigger = manager.check(&pos_updated).unwrap();
+        assert_eq!(trigger.close_quantity().0, 5.0);
+        assert_eq!(trigger.trigger_price.0, 98.0);
+        assert!(manager.has_stop(&position.symbol));
+        assert_eq!(manager.get_stop(&position.symbol).unwrap().rungs_fired(), 1);
+
+        // Same rung does not fire twice
+        pos_updated.quantity = Quantity(5.0);
+        assert!(manager.check(&pos_updated).is_none());
+
+        // Final rung closes the remainder and removes the stop
+        pos_updated.current_price = Price(96.0);
+        let trigger = manager.check(&pos_updated).unwrap();
+        assert_eq!(trigger.close_quantity().0, 5.0);
+        assert!(!manager.has_stop(&position.symbol));
+    }
+
+    #[test]
+    fn test_scale_out_gap_fires_multiple_rungs() {
+        let mut manager = StopManager::new(create_test_config());
+        let position = create_test_position("ETHUSDT", Side::Ask, 100.0, 100.0, 10.0);
+
+        let config = StopLossConfig::static_stop(20.0)
+            .unwrap()
+            .with_scale_out(vec![(1.0, 0.25), (2.0, 0.25), (3.0, 0.5)])
+            .unwrap();
+        manager.set_stop(&position, config).unwrap();
+
+        // Short gaps through two rungs at once
+        let mut pos_updated = position.clone();
+        pos_updated.current_price = Price(102.5);
+        let trigger = manager.check(&pos_updated).unwrap();
+        assert_eq!(trigger.close_quantity().0, 5.0);
+        assert_eq!(manager.get_stop(&position.symbol).unwrap().rungs_fired(), 2);
+    }
+
+    #[test]
+    fn test_take_profit_percentage_long() {
+        let mut manager = StopManager::new(create_test_config());
+        let position = create_test_position("BTCUSDT", Side::Bid, 50000.0, 50000.0, 2.0);
+
+        manager.set_stop(&position, StopLossConfig::static_stop(5.0).unwrap()).unwrap();
+        manager
+            .set_take_profit(&position, TakeProfitConfig::percentage_target(10.0).unwrap())
+            .unwrap();
+        assert_eq!(manager.get_take_profit(&position.symbol).unwrap().target_price().0, 55000.0);
+
+        let mut pos_updated = position.clone();
+        pos_updated.current_price = Price(54000.0);
+        assert!(manager.check_exit(&pos_updated).is_none());
+
+        pos_updated.current_price = Price(55000.0);
+        let exit = manager.check_exit(&pos_updated).unwrap();
+        assert!(matches!(exit, ExitTrigger::TakeProfit(_)));
+        assert_eq!(exit.close_side(), Side::Ask);
+        assert_eq!(exit.close_quantity().0, 2.0);
+
+        // Position closed: both sides removed
+        assert!(!manager.has_take_profit(&position.symbol));
+        assert!(!manager.has_stop(&position.symbol));
+    }
+
+    #[test]
+    fn test_take_profit_absolute_short_and_stop_exit() {
+        let mut manager = StopManager::new(create_test_config());
+        let position = create_test_position("ETHUSDT", Side::Ask, 3000.0, 3000.0, 1.0);
+
+        manager
+            .set_take_profit(&position, TakeProfitConfig::absolute_target(Price(2700.0)).unwrap())
+            .unwrap();
+
+        // Stop (auto-configured at 5%) fires first and cancels the target
+        let mut pos_updated = position.clone();
+        pos_updated.current_price = Price(3200.0);
+        let exit = manager.check_exit(&pos_updated).unwrap();
+        assert!(matches!(exit, ExitTrigger::StopLoss(_)));
+        assert!(!manager.has_take_profit(&position.symbol));
+
+        manager
+            .set_take_profit(&position, TakeProfitConfig::absolute_target(Price(2700.0)).unwrap())
+            .unwrap();
+        pos_updated.current_price = Price(2650.0);
+        let exit = manager.check_exit(&pos_updated).unwrap();
+        assert!(matches!(exit, ExitTrigger::TakeProfit(_)));
+        assert_eq!(exit.close_side(), Side::Bid);
+    }
+
+    #[test]
+    fn test_take_profit_must_be_in_profit() {
+        let mut manager = StopManager::new(create_test_config());
+        let long = create_test_position("BTCUSDT", Side::Bid, 50000.0, 50000.0, 1.0);
+        let short = create_test_position("ETHUSDT", Side::Ask, 3000.0, 3000.0, 1.0);
+
+        assert!(TakeProfitConfig::percentage_target(0.0).is_err());
+        assert!(manager
+            .set_take_profit(&long, TakeProfitConfig::absolute_target(Price(49000.0)).unwrap())
+            .is_err());
+        assert!(manager
+            .set_take_profit(&short, TakeProfitConfig::absolute_target(Price(3100.0)).unwrap())
+            .is_err());
+        // 150% below entry would be a negative price for a short
+        assert!(manager
+            .set_take_profit(&short, TakeProfitConfig::percentage_target(150.0).unwrap())
+            .is_err());
+    }
+
     #[test]
     fn test_auto_configure_from_config() {
         let config = create_test_config();
@@ -595,8 +1496,16 @@ mod tests {
         assert!(StopLossConfig::static_stop(150.0).is_err());
         assert!(StopLossConfig::trailing_stop(-5.0).is_err());
         assert!(StopLossConfig::absolute_stop(Price(-100.0)).is_err());
+        assert!(StopLossConfig::atr_stop(0.0, 10.0).is_err());
+        assert!(StopLossConfig::atr_stop(2.0, -1.0).is_err());
+        assert!(StopLossConfig::atr_stop(2.0, f64::NAN).is_err());
+        assert!(StopLossConfig::chandelier(-3.0, 2.0).is_err());
 
         let config = StopLossConfig::static_stop(5.0).unwrap();
-        assert!(config.with_max_loss(-100.0).is_err());
+        assert!(config.clone().with_max_loss(-100.0).is_err());
+        assert!(config.clone().with_max_hold(Duration::zero()).is_err());
+        assert!(config.clone().with_scale_out(vec![]).is_err());
+        assert!(config.clone().with_scale_out(vec![(3.0, 0.5), (2.0, 0.5)]).is_err());
+        assert!(config.with_scale_out(vec![(1.0, 0.7), (2.0, 0.7)]).is_err());
     }
 }
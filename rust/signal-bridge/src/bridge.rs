@@ -1,7 +1,10 @@
+use ndarray::Array2;
+use numpy::{IntoPyArray, PyArray1, PyArray2, PyReadonlyArray1, PyReadonlyArray2};
+use pyo3::exceptions::{PyKeyError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::PyList;
+use pyo3::types::{PyDict, PyList};
 use crate::features::FeatureEngine;
-use crate::indicators::{RSI, MACD, EMA, SMA, calculate_returns_simd, calculate_momentum_simd};
+use crate::indicators::{RSI, MACD, EMA, SMA, calculate_returns_simd, calculate_momentum_simd, macd, rsi};
 
 #[pyclass]
 #[derive(Clone)]
@@ -38,6 +41,182 @@ impl Bar {
     }
 }
 
+impl Bar {
+    fn to_common(&self) -> common::types::Bar {
+        use chrono::TimeZone;
+
+        common::types::Bar {
+            symbol: common::types::Symbol(self.symbol.clone()),
+            open: common::types::Price(self.open),
+            high: common::types::Price(self.high),
+            low: common::types::Price(self.low),
+            close: common::types::Price(self.close),
+            volume: common::types::Quantity(self.volume),
+            // Timestamps from Python are Unix epoch milliseconds
+            timestamp: chrono::Utc
+                .timestamp_millis_opt(self.timestamp)
+                .single()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Bar argument accepted from Python: either a `Bar` instance or a dict
+/// with `open`, `high`, `low`, `close` and `volume` keys (`symbol` and
+/// `timestamp` are optional)
+pub struct PyBar(pub common::types::Bar);
+
+impl<'py> FromPyObject<'py> for PyBar {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(bar) = ob.extract::<Bar>() {
+            return Ok(PyBar(bar.to_common()));
+        }
+
+        let dict = ob.downcast::<PyDict>()?;
+        let field = |key: &str| -> PyResult<f64> {
+            dict.get_item(key)?
+                .ok_or_else(|| PyKeyError::new_err(format!("bar is missing '{}'", key)))?
+                .extract()
+        };
+
+        let bar = Bar {
+            symbol: match dict.get_item("symbol")? {
+                Some(symbol) => symbol.extract()?,
+                None => String::new(),
+            },
+            open: field("open")?,
+            high: field("high")?,
+            low: field("low")?,
+            close: field("close")?,
+            volume: field("volume")?,
+            timestamp: match dict.get_item("timestamp")? {
+                Some(ts) => ts.extract()?,
+                None => 0,
+            },
+        };
+        Ok(PyBar(bar.to_common()))
+    }
+}
+
+/// Compute `FeatureEngine::compute` features for every rolling window of
+/// `window` bars in a single call.
+///
+/// Returns one row per window (`len(bars) - window + 1` rows) in the column
+/// order given by `batch_feature_names()`; missing values are NaN. The GIL
+/// is released while computing.
+#[pyfunction]
+pub fn compute_features_batch(py: Python<'_>, bars: Vec<PyBar>, window: usize) -> PyResult<Vec<Vec<f64>>> {
+    if window == 0 {
+        return Err(PyValueError::new_err("window must be at least 1"));
+    }
+
+    let bars: Vec<common::types::Bar> = bars.into_iter().map(|b| b.0).collect();
+    let engine = FeatureEngine::new();
+    Ok(py.allow_threads(|| engine.compute_rolling(&bars, window)))
+}
+
+/// Column names of `compute_features_batch` rows, in order
+#[pyfunction]
+pub fn batch_feature_names() -> Vec<String> {
+    FeatureEngine::new().feature_names()
+}
+
+/// Column order of OHLCV matrices passed to `compute_features_batch_np`
+const OHLCV_COLUMNS: usize = 5;
+
+/// NumPy variant of `compute_features_batch`.
+///
+/// `ohlcv` is an `(n, 5)` float64 matrix with columns open, high, low,
+/// close, volume. Returns a `(n - window + 1, n_features)` matrix whose
+/// buffer is handed to NumPy without copying.
+#[pyfunction]
+pub fn compute_features_batch_np<'py>(
+    py: Python<'py>,
+    ohlcv: PyReadonlyArray2<'py, f64>,
+    window: usize,
+) -> PyResult<Bound<'py, PyArray2<f64>>> {
+    if window == 0 {
+        return Err(PyValueError::new_err("window must be at least 1"));
+    }
+    let view = ohlcv.as_array();
+    if view.ncols() != OHLCV_COLUMNS {
+        return Err(PyValueError::new_err(format!(
+            "ohlcv must have {} columns (open, high, low, close, volume), got {}",
+            OHLCV_COLUMNS,
+            view.ncols()
+        )));
+    }
+
+    let bars: Vec<common::types::Bar> = view
+        .rows()
+        .into_iter()
+        .map(|row| common::types::Bar {
+            symbol: common::types::Symbol(String::new()),
+            open: common::types::Price(row[0]),
+            high: common::types::Price(row[1]),
+            low: common::types::Price(row[2]),
+            close: common::types::Price(row[3]),
+            volume: common::types::Quantity(row[4]),
+            timestamp: chrono::DateTime::default(),
+        })
+        .collect();
+
+    let engine = FeatureEngine::new();
+    let n_features = engine.feature_names().len();
+    let flat: Vec<f64> = py.allow_threads(|| {
+        engine.compute_rolling(&bars, window).into_iter().flatten().collect()
+    });
+
+    let rows = flat.len() / n_features.max(1);
+    let matrix = Array2::from_shape_vec((rows, n_features), flat)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(matrix.into_pyarray_bound(py))
+}
+
+/// Read a 1-D array without copying when it is contiguous
+fn with_slice<T>(prices: &PyReadonlyArray1<'_, f64>, f: impl FnOnce(&[f64]) -> T) -> T {
+    match prices.as_slice() {
+        Ok(slice) => f(slice),
+        Err(_) => f(&prices.as_array().to_vec()),
+    }
+}
+
+/// RSI over a NumPy price array; output starts after the warm-up period
+#[pyfunction]
+pub fn rsi_np<'py>(py: Python<'py>, prices: PyReadonlyArray1<'py, f64>, period: usize) -> Bound<'py, PyArray1<f64>> {
+    with_slice(&prices, |p| rsi(p, period)).into_pyarray_bound(py)
+}
+
+/// MACD line over a NumPy price array
+#[pyfunction]
+pub fn macd_np<'py>(
+    py: Python<'py>,
+    prices: PyReadonlyArray1<'py, f64>,
+    fast: usize,
+    slow: usize,
+    signal: usize,
+) -> Bound<'py, PyArray1<f64>> {
+    with_slice(&prices, |p| macd(p, fast, slow, signal)).into_pyarray_bound(py)
+}
+
+/// Log returns over a NumPy price array
+#[pyfunction]
+pub fn log_returns_np<'py>(py: Python<'py>, prices: PyReadonlyArray1<'py, f64>) -> Bound<'py, PyArray1<f64>> {
+    with_slice(&prices, calculate_returns_simd).into_pyarray_bound(py)
+}
+
+/// Percentage momentum over `period` bars for a NumPy price array
+#[pyfunction]
+pub fn momentum_np<'py>(py: Python<'py>, prices: PyReadonlyArray1<'py, f64>, period: usize) -> Bound<'py, PyArray1<f64>> {
+    with_slice(&prices, |p| calculate_momentum_simd(p, period)).into_pyarray_bound(py)
+}
+
+/// List-based RSI for callers without NumPy
+#[pyfunction]
+pub fn rsi_list(prices: Vec<f64>, period: usize) -> Vec<f64> {
+    rsi(&prices, period)
+}
+
 #[pyclass]
 pub struct FeatureComputer {
     engine: FeatureEngine,
@@ -197,5 +376,13 @@ impl FeatureComputer {
 fn signal_bridge(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<FeatureComputer>()?;
     m.add_class::<Bar>()?;
+    m.add_function(wrap_pyfunction!(compute_features_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(batch_feature_names, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_features_batch_np, m)?)?;
+    m.add_function(wrap_pyfunction!(rsi_np, m)?)?;
+    m.add_function(wrap_pyfunction!(macd_np, m)?)?;
+    m.add_function(wrap_pyfunction!(log_returns_np, m)?)?;
+    m.add_function(wrap_pyfunction!(momentum_np, m)?)?;
+    m.add_function(wrap_pyfunction!(rsi_list, m)?)?;
     Ok(())
 }
@@ -1,29 +1,320 @@
-use common::types::{Bar, OrderBook};
-use crate::indicators::{RSI, MACD, EMA, SMA, BollingerBands, calculate_returns_simd, calculate_momentum_simd};
+use common::{Result, TradingError, types::{Bar, OrderBook}};
+use market_data::orderbook::FastOrderBook;
+use serde::{Deserialize, Serialize};
+use crate::indicators::{RsiState, MacdState, EmaState, AtrState, SMA, BollingerBands, calculate_returns_simd, calculate_momentum_simd};
 
+/// Value emitted by `FeatureEngine::compute` for a feature that cannot be
+/// computed (window too short for warm-up, no order book, invalid prices).
+/// NaN is treated as "missing" by common tree-based model libraries.
+pub const MISSING_FEATURE: f64 = f64::NAN;
+
+/// A feature column produced by `FeatureEngine::compute`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Feature {
+    /// Log return of the last bar's close over the previous close
+    LogReturn,
+    /// RSI over the given period
+    Rsi(usize),
+    /// MACD line, signal line and histogram (three columns)
+    Macd { fast: usize, slow: usize, signal: usize },
+    /// Average True Range over the given period
+    Atr(usize),
+    /// Order book depth imbalance over the top N levels
+    BookImbalance(usize),
+    /// Quoted spread in basis points
+    SpreadBps,
+}
+
+impl Feature {
+    /// Column names this feature contributes, in output order
+    fn names(&self) -> Vec<String> {
+        match self {
+            Feature::LogReturn => vec!["log_return".to_string()],
+            Feature::Rsi(period) => vec![format!("rsi_{}", period)],
+            Feature::Macd { fast, slow, signal } => vec![
+                format!("macd_{}_{}_{}", fast, slow, signal),
+                format!("macd_signal_{}_{}_{}", fast, slow, signal),
+                format!("macd_hist_{}_{}_{}", fast, slow, signal),
+            ],
+            Feature::Atr(period) => vec![format!("atr_{}", period)],
+            Feature::BookImbalance(levels) => vec![format!("book_imbalance_{}", levels)],
+            Feature::SpreadBps => vec!["spread_bps".to_string()],
+        }
+    }
+}
+
+/// Feature set used when none is configured
+fn default_feature_set() -> Vec<Feature> {
+    vec![
+        Feature::LogReturn,
+        Feature::Rsi(14),
+        Feature::Macd { fast: 12, slow: 26, signal: 9 },
+        Feature::Atr(14),
+        Feature::BookImbalance(5),
+    ]
+}
+
+/// How a `Scaler` normalizes each column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScalerMode {
+    /// z-score: (x - mean) / std
+    Standard,
+    /// (x - min) / (max - min), mapping the fitted range onto [0, 1]
+    MinMax,
+}
+
+/// Per-column feature scaler whose fitted parameters can be persisted as JSON,
+/// so inference applies exactly the transform used in training.
+///
+/// Missing values (NaN) are ignored when fitting and stay NaN when
+/// transforming. A column with zero variance (or zero range) gets a scale
+/// of 1.0 so it is only shifted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scaler {
+    mode: ScalerMode,
+    /// Mean (standard) or minimum (min-max) per column
+    center: Vec<f64>,
+    /// Standard deviation (standard) or range (min-max) per column
+    scale: Vec<f64>,
+}
+
+impl Scaler {
+    pub fn new(mode: ScalerMode) -> Self {
+        Self {
+            mode,
+            center: Vec::new(),
+            scale: Vec::new(),
+        }
+    }
+
+    pub fn mode(&self) -> ScalerMode {
+        self.mode
+    }
+
+    /// Number of fitted columns (0 before `fit`)
+    pub fn len(&self) -> usize {
+        self.center.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.center.is_empty()
+    }
+
+    /// Fit per-column parameters from rows of equal length
+    pub fn fit(&mut self, data: &[Vec<f64>]) -> Result<()> {
+        let width = match data.first() {
+            Some(row) => row.len(),
+            None => {
+                return Err(TradingError::Configuration(
+                    "Cannot fit scaler on empty data".to_string()
+                ))
+            }
+        };
+        if let Some(row) = data.iter().find(|r| r.len() != width) {
+            return Err(TradingError::Configuration(format!(
+                "Scaler rows must all have {} columns, found one with {}",
+                width,
+                row.len()
+            )));
+        }
+
+        let mut center = Vec::with_capacity(width);
+        let mut scale = Vec::with_capacity(width);
+
+        for col in 0..width {
+            let values: Vec<f64> = data.iter().map(|r| r[col]).filter(|v| !v.is_nan()).collect();
+            let (c, s) = match self.mode {
+                ScalerMode::Standard if !values.is_empty() => {
+                    let n = values.len() as f64;
+                    let mean = values.iter().sum::<f64>() / n;
+                    let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+                    (mean, var.sqrt())
+                }
+                ScalerMode::MinMax if !values.is_empty() => {
+                    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+                    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                    (min, max - min)
+                }
+                // Column never observed: leave it unchanged
+                _ => (0.0, 1.0),
+            };
+            center.push(c);
+            scale.push(if s > 0.0 && s.is_finite() { s } else { 1.0 });
+        }
+
+        self.center = center;
+        self.scale = scale;
+        Ok(())
+    }
+
+    /// Scale a row. A row whose length does not match the fitted width is
+    /// returned as all `MISSING_FEATURE`.
+    pub fn transform(&self, row: &[f64]) -> Vec<f64> {
+        if row.len() != self.center.len() {
+            tracing::warn!(
+                "Scaler fitted for {} columns got a row of {}",
+                self.center.len(),
+                row.len()
+            );
+            return vec![MISSING_FEATURE; row.len()];
+        }
+
+        row.iter()
+            .zip(self.center.iter().zip(&self.scale))
+            .map(|(x, (c, s))| (x - c) / s)
+            .collect()
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let scaler: Self = serde_json::from_str(json)?;
+        if scaler.center.len() != scaler.scale.len() {
+            return Err(TradingError::Configuration(
+                "Scaler center and scale lengths differ".to_string()
+            ));
+        }
+        Ok(scaler)
+    }
+
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+}
+
+/// Streaming feature computation; indicator state advances by one bar per call
 pub struct FeatureEngine {
-    rsi_14: RSI,
-    macd: MACD,
-    ema_9: EMA,
-    ema_21: EMA,
+    rsi_14: RsiState,
+    macd: MacdState,
+    ema_9: EmaState,
+    ema_21: EmaState,
     sma_50: SMA,
     sma_200: SMA,
     bb: BollingerBands,
+    atr_14: AtrState,
+    /// Ordered feature set for `compute`
+    feature_set: Vec<Feature>,
+    /// Applied to `compute` output when set
+    scaler: Option<Scaler>,
 }
 
 impl FeatureEngine {
     pub fn new() -> Self {
         Self {
-            rsi_14: RSI::new(14),
-            macd: MACD::new(12, 26, 9),
-            ema_9: EMA::new(9),
-            ema_21: EMA::new(21),
+            rsi_14: RsiState::new(14),
+            macd: MacdState::new(12, 26, 9),
+            ema_9: EmaState::new(9),
+            ema_21: EmaState::new(21),
             sma_50: SMA::new(50),
             sma_200: SMA::new(200),
             bb: BollingerBands::new(20),
+            atr_14: AtrState::new(14),
+            feature_set: default_feature_set(),
+            scaler: None,
         }
     }
 
+    /// Scale `compute` output with a fitted scaler.
+    /// The scaler's width must match `feature_names()`.
+    pub fn with_scaler(mut self, scaler: Scaler) -> Result<Self> {
+        check_scaler_width(&scaler, self.feature_names().len())?;
+        self.scaler = Some(scaler);
+        Ok(self)
+    }
+
+    /// Use an explicit, ordered feature set for `compute`.
+    /// Fails if a scaler is already set and the new width does not match it.
+    pub fn with_features(mut self, features: Vec<Feature>) -> Result<Self> {
+        self.feature_set = features;
+        if let Some(scaler) = &self.scaler {
+            check_scaler_width(scaler, self.feature_names().len())?;
+        }
+        Ok(self)
+    }
+
+    /// Column names of `compute`'s output, in order
+    pub fn feature_names(&self) -> Vec<String> {
+        self.feature_set.iter().flat_map(|f| f.names()).collect()
+    }
+
+    /// Compute the configured feature set over a window of bars (oldest first).
+    ///
+    /// Indicators are recomputed from scratch over the window, so the result
+    /// depends only on the inputs. The output always has one value per name in
+    /// `feature_names()`; anything that cannot be computed is `MISSING_FEATURE`.
+    /// If a scaler is configured the output is scaled.
+    pub fn compute(&self, window: &[Bar], book: Option<&FastOrderBook>) -> Vec<f64> {
+        let mut features = Vec::with_capacity(self.feature_set.len() + 2);
+
+        for feature in &self.feature_set {
+            match *feature {
+                Feature::LogReturn => {
+                    let value = match window {
+                        [.., prev, last] if prev.close.0 > 0.0 && last.close.0 > 0.0 => {
+                            (last.close.0 / prev.close.0).ln()
+                        }
+                        _ => MISSING_FEATURE,
+                    };
+                    features.push(value);
+                }
+                Feature::Rsi(period) => {
+                    let mut rsi = RsiState::new(period);
+                    let value = window.iter().filter_map(|b| rsi.update(b.close.0)).last();
+                    features.push(value.unwrap_or(MISSING_FEATURE));
+                }
+                Feature::Macd { fast, slow, signal } => {
+                    let mut macd = MacdState::new(fast, slow, signal);
+                    match window.iter().filter_map(|b| macd.update(b.close.0)).last() {
+                        Some((line, signal, histogram)) => {
+                            features.extend_from_slice(&[line, signal, histogram]);
+                        }
+                        None => features.extend_from_slice(&[MISSING_FEATURE; 3]),
+                    }
+                }
+                Feature::Atr(period) => {
+                    let mut atr = AtrState::new(period);
+                    let value = window.iter().filter_map(|b| atr.update_bar(b)).last();
+                    features.push(value.unwrap_or(MISSING_FEATURE));
+                }
+                Feature::BookImbalance(levels) => {
+                    let value = match book {
+                        Some(book) if book.best_bid().is_some() || book.best_ask().is_some() => {
+                            book.imbalance(levels)
+                        }
+                        _ => MISSING_FEATURE,
+                    };
+                    features.push(value);
+                }
+                Feature::SpreadBps => {
+                    let value = book.and_then(|b| b.spread_bps()).unwrap_or(MISSING_FEATURE);
+                    features.push(value);
+                }
+            }
+        }
+
+        match &self.scaler {
+            Some(scaler) => scaler.transform(&features),
+            None => features,
+        }
+    }
+
+    /// `compute` over every rolling window of `window` bars (without an
+    /// order book), one row per window ending at `bars[window - 1..]`.
+    pub fn compute_rolling(&self, bars: &[Bar], window: usize) -> Vec<Vec<f64>> {
+        if window == 0 {
+            return Vec::new();
+        }
+        bars.windows(window).map(|w| self.compute(w, None)).collect()
+    }
+
     pub fn compute_features(&mut self, bars: &[Bar], orderbook: &OrderBook) -> Vec<f64> {
         let mut features = Vec::with_capacity(30);
 
@@ -47,13 +338,13 @@ impl FeatureEngine {
             features.push(50.0);
         }
 
-        let (macd_line, signal, histogram) = self.macd.update(close);
+        let (macd_line, signal, histogram) = self.macd.update(close).unwrap_or((0.0, 0.0, 0.0));
         features.push(macd_line);
         features.push(signal);
         features.push(histogram);
 
-        let ema9 = self.ema_9.update(close);
-        let ema21 = self.ema_21.update(close);
+        let ema9 = self.ema_9.update(close).unwrap_or(close);
+        let ema21 = self.ema_21.update(close).unwrap_or(close);
         features.push(ema9);
         features.push(ema21);
         features.push(ema9 - ema21); // EMA spread
@@ -90,6 +381,8 @@ impl FeatureEngine {
             features.push(0.5);
         }
 
+        let atr = self.atr_14.update(current.high.0, current.low.0, close).unwrap_or(0.0);
+
         // 3. Volume features
         features.push(current.volume.0);
 
@@ -159,6 +452,9 @@ impl FeatureEngine {
             features.push(0.0);
         }
 
+        // 6. Volatility (ATR, 0.0 until warmed up)
+        features.push(atr);
+
         features
     }
 }
@@ -168,3 +464,129 @@ impl Default for FeatureEngine {
         Self::new()
     }
 }
+
+/// Reject a scaler whose column count differs from the feature width
+fn check_scaler_width(scaler: &Scaler, width: usize) -> Result<()> {
+    if scaler.len() != width {
+        return Err(TradingError::Configuration(format!(
+            "Scaler has {} columns but the feature set has {}",
+            scaler.len(),
+            width
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::types::{Price, Quantity, Symbol};
+
+    fn create_bars(n: usize) -> Vec<Bar> {
+        (0..n)
+            .map(|i| {
+                let close = 100.0 + (i as f64 * 0.5).sin() * 2.0;
+                Bar {
+                    symbol: Symbol("AAPL".to_string()),
+                    open: Price(close),
+                    high: Price(close + 1.0),
+                    low: Price(close - 1.0),
+                    close: Price(close),
+                    volume: Quantity(1000.0),
+                    timestamp: chrono::Utc::now(),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_names_match_output() {
+        let engine = FeatureEngine::new();
+        let names = engine.feature_names();
+        assert_eq!(
+            names,
+            vec!["log_return", "rsi_14", "macd_12_26_9", "macd_signal_12_26_9", "macd_hist_12_26_9", "atr_14", "book_imbalance_5"]
+        );
+
+        let mut book = FastOrderBook::new(Symbol("AAPL".to_string()));
+        book.update_bid(Price(99.9), Quantity(300.0));
+        book.update_ask(Price(100.1), Quantity(100.0));
+
+        let features = engine.compute(&create_bars(60), Some(&book));
+        assert_eq!(features.len(), names.len());
+        assert!(features.iter().all(|f| f.is_finite()));
+        assert_eq!(features[6], 0.5);
+    }
+
+    #[test]
+    fn test_missing_data_uses_sentinel() {
+        let engine = FeatureEngine::new();
+        let features = engine.compute(&create_bars(1), None);
+
+        assert_eq!(features.len(), engine.feature_names().len());
+        assert!(features.iter().all(|f| f.is_nan()));
+        assert!(engine.compute(&[], None).iter().all(|f| f.is_nan()));
+    }
+
+    #[test]
+    fn test_compute_rolling() {
+        let engine = FeatureEngine::new().with_features(vec![Feature::LogReturn]).unwrap();
+        let bars = create_bars(10);
+
+        let rows = engine.compute_rolling(&bars, 4);
+        assert_eq!(rows.len(), 7);
+        assert_eq!(rows[6], engine.compute(&bars[6..], None));
+        assert!(engine.compute_rolling(&bars, 0).is_empty());
+        assert!(engine.compute_rolling(&bars, 11).is_empty());
+    }
+
+    #[test]
+    fn test_standard_scaler() {
+        let mut scaler = Scaler::new(ScalerMode::Standard);
+        scaler.fit(&[vec![1.0, 5.0], vec![3.0, 5.0]]).unwrap();
+
+        // Second column has zero variance, so it is only shifted
+        assert_eq!(scaler.transform(&[3.0, 7.0]), vec![1.0, 2.0]);
+        assert!(scaler.transform(&[f64::NAN, 5.0])[0].is_nan());
+    }
+
+    #[test]
+    fn test_min_max_scaler_round_trip() {
+        let mut scaler = Scaler::new(ScalerMode::MinMax);
+        scaler.fit(&[vec![10.0], vec![20.0], vec![f64::NAN]]).unwrap();
+        assert_eq!(scaler.transform(&[15.0]), vec![0.5]);
+
+        let restored = Scaler::from_json(&scaler.to_json().unwrap()).unwrap();
+        assert_eq!(restored, scaler);
+    }
+
+    #[test]
+    fn test_scaler_rejects_bad_input() {
+        let mut scaler = Scaler::new(ScalerMode::Standard);
+        assert!(scaler.fit(&[]).is_err());
+        assert!(scaler.fit(&[vec![1.0], vec![1.0, 2.0]]).is_err());
+
+        scaler.fit(&[vec![1.0], vec![2.0]]).unwrap();
+        assert!(FeatureEngine::new().with_scaler(scaler.clone()).is_err());
+
+        // Narrowing the feature set after the scaler is set is caught too
+        let engine = FeatureEngine::new()
+            .with_features(vec![Feature::LogReturn])
+            .unwrap()
+            .with_scaler(scaler)
+            .unwrap();
+        assert!(engine.with_features(vec![Feature::LogReturn, Feature::SpreadBps]).is_err());
+    }
+
+    #[test]
+    fn test_custom_feature_order() {
+        let engine = FeatureEngine::new()
+            .with_features(vec![Feature::SpreadBps, Feature::Rsi(5)])
+            .unwrap();
+        assert_eq!(engine.feature_names(), vec!["spread_bps", "rsi_5"]);
+
+        let features = engine.compute(&create_bars(10), None);
+        assert!(features[0].is_nan());
+        assert!(features[1].is_finite());
+    }
+}
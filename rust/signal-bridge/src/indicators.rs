@@ -1,4 +1,5 @@
 /// High-performance technical indicators with SIMD optimization
+use common::types::{Bar, Price};
 use wide::f64x4;
 
 /// Simple Moving Average (SMA)
@@ -189,6 +190,260 @@ impl BollingerBands {
     }
 }
 
+/// Incremental EMA seeded with the simple average of the first `period` values.
+/// Returns `None` until `period` values have been seen.
+pub struct EmaState {
+    period: usize,
+    alpha: f64,
+    count: usize,
+    sum: f64,
+    value: Option<f64>,
+}
+
+impl EmaState {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            alpha: 2.0 / (period as f64 + 1.0),
+            count: 0,
+            sum: 0.0,
+            value: None,
+        }
+    }
+
+    #[inline]
+    pub fn update(&mut self, price: f64) -> Option<f64> {
+        if let Some(prev) = self.value {
+            let next = self.alpha * price + (1.0 - self.alpha) * prev;
+            self.value = Some(next);
+            return self.value;
+        }
+
+        self.sum += price;
+        self.count += 1;
+        if self.count == self.period {
+            self.value = Some(self.sum / self.period as f64);
+        }
+        self.value
+    }
+
+    /// Latest value, if warmed up
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+/// Incremental RSI using Wilder's smoothing of average gains and losses.
+/// Returns `None` until `period` price changes (`period + 1` prices) have
+/// been seen, the same warm-up as the batch `rsi`.
+pub struct RsiState {
+    period: usize,
+    prev_price: Option<f64>,
+    count: usize,
+    avg_gain: f64,
+    avg_loss: f64,
+}
+
+impl RsiState {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            prev_price: None,
+            count: 0,
+            avg_gain: 0.0,
+            avg_loss: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, price: f64) -> Option<f64> {
+        let prev = self.prev_price.replace(price)?;
+        let change = price - prev;
+        let gain = change.max(0.0);
+        let loss = (-change).max(0.0);
+        let period = self.period as f64;
+
+        if self.count < self.period {
+            self.avg_gain += gain;
+            self.avg_loss += loss;
+            self.count += 1;
+            if self.count < self.period {
+                return None;
+            }
+            self.avg_gain /= period;
+            self.avg_loss /= period;
+        } else {
+            self.avg_gain = (self.avg_gain * (period - 1.0) + gain) / period;
+            self.avg_loss = (self.avg_loss * (period - 1.0) + loss) / period;
+        }
+
+        let rsi = if self.avg_loss == 0.0 {
+            // Flat prices are neutral; only gains means maximally overbought
+            if self.avg_gain == 0.0 { 50.0 } else { 100.0 }
+        } else {
+            let rs = self.avg_gain / self.avg_loss;
+            100.0 - (100.0 / (1.0 + rs))
+        };
+        Some(rsi)
+    }
+}
+
+/// Incremental MACD built on warmed-up EMAs.
+/// Returns `(MACD line, Signal line, Histogram)` once the slow EMA and then
+/// the signal EMA have warmed up.
+pub struct MacdState {
+    fast: EmaState,
+    slow: EmaState,
+    signal: EmaState,
+}
+
+impl MacdState {
+    pub fn new(fast_period: usize, slow_period: usize, signal_period: usize) -> Self {
+        Self {
+            fast: EmaState::new(fast_period),
+            slow: EmaState::new(slow_period),
+            signal: EmaState::new(signal_period),
+        }
+    }
+
+    pub fn update(&mut self, price: f64) -> Option<(f64, f64, f64)> {
+        let fast = self.fast.update(price);
+        let slow = self.slow.update(price);
+        match (fast, slow) {
+            (Some(fast), Some(slow)) => {
+                let macd = fast - slow;
+                let signal = self.signal.update(macd)?;
+                Some((macd, signal, macd - signal))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Incremental Average True Range using Wilder's smoothing.
+/// The first bar only provides a previous close, so the first value is
+/// returned after `period + 1` bars, the same warm-up as the batch `atr`.
+pub struct AtrState {
+    period: usize,
+    prev_close: Option<f64>,
+    count: usize,
+    sum: f64,
+    value: Option<f64>,
+}
+
+impl AtrState {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            prev_close: None,
+            count: 0,
+            sum: 0.0,
+            value: None,
+        }
+    }
+
+    pub fn update(&mut self, high: f64, low: f64, close: f64) -> Option<f64> {
+        let prev_close = self.prev_close.replace(close)?;
+        let tr = true_range_hlc(prev_close, high, low);
+        let period = self.period as f64;
+
+        self.value = match self.value {
+            Some(atr) => Some((atr * (period - 1.0) + tr) / period),
+            None => {
+                self.sum += tr;
+                self.count += 1;
+                (self.count == self.period).then_some(self.sum / period)
+            }
+        };
+        self.value
+    }
+
+    /// Update from an OHLC bar
+    #[inline]
+    pub fn update_bar(&mut self, bar: &Bar) -> Option<f64> {
+        self.update(bar.high.0, bar.low.0, bar.close.0)
+    }
+
+    /// Latest value, if warmed up
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+/// RiskMetrics decay for daily returns
+pub const RISKMETRICS_DAILY_LAMBDA: f64 = 0.94;
+
+/// Incremental exponentially weighted realized volatility:
+/// `var = lambda * var + (1 - lambda) * ret^2`, returning `sqrt(var)`.
+///
+/// The first return seeds the variance with `ret^2`. Returns are taken as
+/// having zero mean, as is usual for short-horizon returns. The result is
+/// per period of the input returns; see `annualize_vol` to rescale it.
+/// `lambda` is clamped to `[0, 1]`; higher values react more slowly.
+pub struct EwmaVolState {
+    lambda: f64,
+    variance: Option<f64>,
+}
+
+impl EwmaVolState {
+    pub fn new(lambda: f64) -> Self {
+        Self {
+            lambda: lambda.clamp(0.0, 1.0),
+            variance: None,
+        }
+    }
+
+    /// Add one period's return and return the updated volatility.
+    /// Non-finite returns are ignored.
+    #[inline]
+    pub fn update(&mut self, ret: f64) -> f64 {
+        if ret.is_finite() {
+            let squared = ret * ret;
+            self.variance = Some(match self.variance {
+                Some(variance) => self.lambda * variance + (1.0 - self.lambda) * squared,
+                None => squared,
+            });
+        }
+        self.value().unwrap_or(0.0)
+    }
+
+    /// Latest volatility, if any return has been seen
+    pub fn value(&self) -> Option<f64> {
+        self.variance.map(f64::sqrt)
+    }
+}
+
+/// EWMA volatility after each return, one output per input
+pub fn ewma_vol(returns: &[f64], lambda: f64) -> Vec<f64> {
+    let mut state = EwmaVolState::new(lambda);
+    returns.iter().map(|&ret| state.update(ret)).collect()
+}
+
+/// Scale a per-period volatility to a longer horizon by `sqrt(periods)`.
+///
+/// `periods` is how many input periods make up the target horizon, e.g.
+/// 390 one-minute bars per US equity session gives daily vol, and 252
+/// (`TRADING_DAYS_PER_YEAR` in the risk manager) turns daily vol into annual.
+/// Minute vol to annual is therefore `annualize_vol(vol, 390.0 * 252.0)`.
+#[inline]
+pub fn annualize_vol(vol: f64, periods: f64) -> f64 {
+    vol * periods.sqrt()
+}
+
+/// True range of a bar given the previous close:
+/// max(high - low, |high - prev_close|, |low - prev_close|)
+#[inline]
+pub fn true_range(prev_close: Price, bar: &Bar) -> f64 {
+    true_range_hlc(prev_close.0, bar.high.0, bar.low.0)
+}
+
+#[inline]
+fn true_range_hlc(prev_close: f64, high: f64, low: f64) -> f64 {
+    (high - low)
+        .max((high - prev_close).abs())
+        .max((low - prev_close).abs())
+}
+
 /// SIMD-accelerated price momentum calculation
 #[inline]
 pub fn calculate_momentum_simd(prices: &[f64], period: usize) -> Vec<f64> {
@@ -318,19 +573,366 @@ pub fn bollinger_bands(prices: &[f64], period: usize, _std_dev: f64) -> (Vec<f64
     (lower, middle, upper)
 }
 
-pub fn atr(highs: &[f64], lows: &[f64], closes: &[f64], period: usize) -> Vec<f64> {
-    let mut results = Vec::new();
-    let mut sma = SMA::new(period);
+/// Average True Range over OHLC bars (Wilder's smoothing).
+/// The first value corresponds to `bars[period]`, since the first bar only
+/// provides a previous close.
+pub fn atr(bars: &[Bar], period: usize) -> Vec<f64> {
+    let mut atr_calc = AtrState::new(period);
+    bars.iter().filter_map(|bar| atr_calc.update_bar(bar)).collect()
+}
 
-    for i in 1..closes.len() {
-        let tr = (highs[i] - lows[i])
-            .max((highs[i] - closes[i - 1]).abs())
-            .max((lows[i] - closes[i - 1]).abs());
+/// Mean and population variance of a window
+#[inline]
+fn mean_variance(window: &[f64]) -> (f64, f64) {
+    let n = window.len() as f64;
+    let mean = window.iter().sum::<f64>() / n;
+    let variance = window.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+    (mean, variance)
+}
+
+/// Rolling z-score of the last value in each window: (x - mean) / std.
+///
+/// Output is aligned with the input; the first `window - 1` values are NaN.
+/// A window with zero variance yields 0.0, since the value equals the mean.
+pub fn rolling_zscore(series: &[f64], window: usize) -> Vec<f64> {
+    let mut result = vec![f64::NAN; series.len()];
+    if window == 0 {
+        return result;
+    }
 
-        if let Some(atr_value) = sma.update(tr) {
-            results.push(atr_value);
+    for (i, values) in series.windows(window).enumerate() {
+        let (mean, variance) = mean_variance(values);
+        let last = values[window - 1];
+        result[i + window - 1] = if variance > 0.0 { (last - mean) / variance.sqrt() } else { 0.0 };
+    }
+    result
+}
+
+/// Rolling Pearson correlation of two series.
+///
+/// Only the overlapping prefix (`min(a.len(), b.len())`) is used and the
+/// output has that length; the first `window - 1` values are NaN. A window
+/// in which either series has zero variance yields NaN, as correlation is
+/// undefined there.
+pub fn rolling_correlation(a: &[f64], b: &[f64], window: usize) -> Vec<f64> {
+    let len = a.len().min(b.len());
+    let mut result = vec![f64::NAN; len];
+    if window == 0 || len < window {
+        return result;
+    }
+
+    for end in window..=len {
+        let (xs, ys) = (&a[end - window..end], &b[end - window..end]);
+        let (mean_x, var_x) = mean_variance(xs);
+        let (mean_y, var_y) = mean_variance(ys);
+        if var_x > 0.0 && var_y > 0.0 {
+            let covariance = xs
+                .iter()
+                .zip(ys)
+                .map(|(x, y)| (x - mean_x) * (y - mean_y))
+                .sum::<f64>()
+                / window as f64;
+            result[end - 1] = (covariance / (var_x * var_y).sqrt()).clamp(-1.0, 1.0);
         }
     }
+    result
+}
 
-    results
+/// Body-to-range ratio at or below which a bar counts as a doji
+const DOJI_BODY_RATIO: f64 = 0.1;
+/// Minimum shadow-to-body ratio for a hammer's long shadow
+const HAMMER_SHADOW_RATIO: f64 = 2.0;
+
+/// Candlestick pattern kinds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CandlePattern {
+    Engulfing,
+    Doji,
+    /// Long lower shadow, small body near the high
+    Hammer,
+    /// Long upper shadow, small body near the low (inverted hammer shape)
+    ShootingStar,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternDirection {
+    Bullish,
+    Bearish,
+    /// Indecision, e.g. a doji
+    Neutral,
+}
+
+/// A pattern found at `index` of the input bars
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatternHit {
+    pub index: usize,
+    pub pattern: CandlePattern,
+    pub direction: PatternDirection,
+}
+
+#[inline]
+fn body(bar: &Bar) -> f64 {
+    (bar.close.0 - bar.open.0).abs()
+}
+
+#[inline]
+fn is_doji(bar: &Bar) -> bool {
+    let range = bar.high.0 - bar.low.0;
+    // Zero-range bars have no body either
+    range <= 0.0 || body(bar) <= DOJI_BODY_RATIO * range
+}
+
+/// Bullish engulfing: a down bar followed by an up bar whose body covers it.
+/// Bearish engulfing is the mirror image. Never emits at index 0.
+pub fn detect_engulfing(bars: &[Bar]) -> Vec<PatternHit> {
+    bars.windows(2)
+        .enumerate()
+        .filter_map(|(i, pair)| {
+            let (prev, cur) = (&pair[0], &pair[1]);
+            if body(cur) <= body(prev) {
+                return None;
+            }
+            let direction = if prev.close.0 < prev.open.0
+                && cur.close.0 > cur.open.0
+                && cur.open.0 <= prev.close.0
+                && cur.close.0 >= prev.open.0
+            {
+                PatternDirection::Bullish
+            } else if prev.close.0 > prev.open.0
+                && cur.close.0 < cur.open.0
+                && cur.open.0 >= prev.close.0
+                && cur.close.0 <= prev.open.0
+            {
+                PatternDirection::Bearish
+            } else {
+                return None;
+            };
+            Some(PatternHit { index: i + 1, pattern: CandlePattern::Engulfing, direction })
+        })
+        .collect()
+}
+
+/// Bars whose body is at most 10% of their range, including zero-range bars
+pub fn detect_doji(bars: &[Bar]) -> Vec<PatternHit> {
+    bars.iter()
+        .enumerate()
+        .filter(|(_, bar)| is_doji(bar))
+        .map(|(index, _)| PatternHit {
+            index,
+            pattern: CandlePattern::Doji,
+            direction: PatternDirection::Neutral,
+        })
+        .collect()
+}
+
+/// Hammers (bullish) and shooting stars (bearish): one shadow at least twice
+/// the body and the other no longer than the body. Doji bars are excluded.
+pub fn detect_hammer(bars: &[Bar]) -> Vec<PatternHit> {
+    bars.iter()
+        .enumerate()
+        .filter(|(_, bar)| !is_doji(bar))
+        .filter_map(|(index, bar)| {
+            let body = body(bar);
+            let upper = bar.high.0 - bar.open.0.max(bar.close.0);
+            let lower = bar.open.0.min(bar.close.0) - bar.low.0;
+            let (pattern, direction) = if lower >= HAMMER_SHADOW_RATIO * body && upper <= body {
+                (CandlePattern::Hammer, PatternDirection::Bullish)
+            } else if upper >= HAMMER_SHADOW_RATIO * body && lower <= body {
+                (CandlePattern::ShootingStar, PatternDirection::Bearish)
+            } else {
+                return None;
+            };
+            Some(PatternHit { index, pattern, direction })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ema_state_warm_up() {
+        let mut ema = EmaState::new(3);
+        assert_eq!(ema.update(1.0), None);
+        assert_eq!(ema.update(2.0), None);
+        assert_eq!(ema.update(3.0), Some(2.0));
+        assert_eq!(ema.update(4.0), Some(3.0));
+    }
+
+    #[test]
+    fn test_rsi_state_matches_batch_warm_up() {
+        let prices: Vec<f64> = (0..30).map(|i| 100.0 + (i as f64 * 0.7).sin()).collect();
+        let mut state = RsiState::new(14);
+        let streamed: Vec<f64> = prices.iter().filter_map(|&p| state.update(p)).collect();
+
+        assert_eq!(streamed.len(), rsi(&prices, 14).len());
+        assert!(streamed.iter().all(|v| (0.0..=100.0).contains(v)));
+    }
+
+    #[test]
+    fn test_rsi_state_extremes() {
+        let mut rising = RsiState::new(5);
+        let last = (0..10).filter_map(|i| rising.update(i as f64)).last();
+        assert_eq!(last, Some(100.0));
+
+        let mut flat = RsiState::new(5);
+        let last = (0..10).filter_map(|_| flat.update(10.0)).last();
+        assert_eq!(last, Some(50.0));
+    }
+
+    #[test]
+    fn test_macd_state_warm_up() {
+        let mut macd = MacdState::new(3, 5, 2);
+        let outputs: Vec<_> = (0..10).map(|i| macd.update(100.0 + i as f64)).collect();
+
+        // Slow EMA ready at 5 prices, signal needs 2 MACD values
+        assert!(outputs[..5].iter().all(|o| o.is_none()));
+        let (line, signal, hist) = outputs[5].unwrap();
+        assert!(line > 0.0);
+        assert!((line - signal - hist).abs() < 1e-12);
+    }
+
+    fn bar(high: f64, low: f64, close: f64) -> Bar {
+        Bar {
+            symbol: common::types::Symbol("TEST".to_string()),
+            open: Price(close),
+            high: Price(high),
+            low: Price(low),
+            close: Price(close),
+            volume: common::types::Quantity(1000.0),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_ewma_vol_seeds_with_first_return() {
+        let mut vol = EwmaVolState::new(0.9);
+        assert_eq!(vol.value(), None);
+        assert!((vol.update(0.02) - 0.02).abs() < 1e-12);
+
+        // 0.9 * 0.0004 + 0.1 * 0.0001 = 0.00037
+        assert!((vol.update(-0.01) - 0.00037_f64.sqrt()).abs() < 1e-12);
+
+        // Non-finite returns leave the estimate unchanged
+        assert!((vol.update(f64::NAN) - 0.00037_f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_ewma_vol_batch_matches_streaming() {
+        let returns: Vec<f64> = (0..50).map(|i| (i as f64 * 0.9).sin() * 0.01).collect();
+        let mut state = EwmaVolState::new(RISKMETRICS_DAILY_LAMBDA);
+        let streamed: Vec<f64> = returns.iter().map(|&r| state.update(r)).collect();
+
+        assert_eq!(ewma_vol(&returns, RISKMETRICS_DAILY_LAMBDA), streamed);
+        assert!(ewma_vol(&[], 0.94).is_empty());
+
+        // Constant absolute returns converge to that size
+        let last = *ewma_vol(&[0.01, -0.01, 0.01, -0.01], 0.94).last().unwrap();
+        assert!((last - 0.01).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_annualize_vol() {
+        assert!((annualize_vol(0.01, 252.0) - 0.01 * 252.0_f64.sqrt()).abs() < 1e-12);
+        assert_eq!(annualize_vol(0.01, 1.0), 0.01);
+    }
+
+    #[test]
+    fn test_true_range_uses_previous_close() {
+        // Gap up: the range to the previous close dominates
+        assert_eq!(true_range(Price(95.0), &bar(102.0, 100.0, 101.0)), 7.0);
+        // Gap down
+        assert_eq!(true_range(Price(105.0), &bar(102.0, 100.0, 101.0)), 5.0);
+        // Inside bar
+        assert_eq!(true_range(Price(101.0), &bar(102.0, 100.0, 101.0)), 2.0);
+    }
+
+    #[test]
+    fn test_atr_over_bars() {
+        let bars: Vec<Bar> = (0..6).map(|_| bar(11.0, 9.0, 10.0)).collect();
+        let values = atr(&bars, 3);
+        assert_eq!(values, vec![2.0, 2.0, 2.0]);
+        assert!(atr(&bars[..3], 3).is_empty());
+    }
+
+    #[test]
+    fn test_atr_state_constant_range() {
+        let mut atr = AtrState::new(3);
+        assert_eq!(atr.update(11.0, 9.0, 10.0), None);
+        assert_eq!(atr.update(11.0, 9.0, 10.0), None);
+        assert_eq!(atr.update(11.0, 9.0, 10.0), None);
+        assert_eq!(atr.update(11.0, 9.0, 10.0), Some(2.0));
+        assert_eq!(atr.update(11.0, 9.0, 10.0), Some(2.0));
+    }
+
+    fn ohlc(open: f64, high: f64, low: f64, close: f64) -> Bar {
+        Bar {
+            open: Price(open),
+            ..bar(high, low, close)
+        }
+    }
+
+    #[test]
+    fn test_detect_engulfing() {
+        let bars = vec![
+            ohlc(10.0, 10.2, 9.4, 9.5),
+            ohlc(9.4, 10.3, 9.3, 10.2),
+            ohlc(10.3, 10.4, 9.0, 9.1),
+        ];
+        let hits = detect_engulfing(&bars);
+
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0], PatternHit { index: 1, pattern: CandlePattern::Engulfing, direction: PatternDirection::Bullish });
+        assert_eq!(hits[1].index, 2);
+        assert_eq!(hits[1].direction, PatternDirection::Bearish);
+        assert!(detect_engulfing(&bars[..1]).is_empty());
+    }
+
+    #[test]
+    fn test_detect_doji_zero_range() {
+        let bars = vec![ohlc(10.0, 10.0, 10.0, 10.0), ohlc(10.0, 11.0, 9.0, 10.05), ohlc(10.0, 11.0, 9.0, 11.0)];
+        let indices: Vec<usize> = detect_doji(&bars).iter().map(|h| h.index).collect();
+        assert_eq!(indices, vec![0, 1]);
+        assert!(detect_hammer(&bars[..1]).is_empty());
+    }
+
+    #[test]
+    fn test_detect_hammer_and_shooting_star() {
+        let bars = vec![ohlc(10.0, 10.6, 8.0, 10.5), ohlc(10.5, 12.0, 9.9, 10.0), ohlc(10.0, 11.0, 9.0, 10.8)];
+        let hits = detect_hammer(&bars);
+
+        assert_eq!(hits.len(), 2);
+        assert_eq!((hits[0].index, hits[0].pattern), (0, CandlePattern::Hammer));
+        assert_eq!(hits[0].direction, PatternDirection::Bullish);
+        assert_eq!((hits[1].index, hits[1].pattern), (1, CandlePattern::ShootingStar));
+        assert_eq!(hits[1].direction, PatternDirection::Bearish);
+    }
+
+    #[test]
+    fn test_rolling_zscore() {
+        let z = rolling_zscore(&[1.0, 2.0, 3.0, 3.0, 3.0, 3.0], 3);
+        assert!(z[0].is_nan() && z[1].is_nan());
+        // Window [1, 2, 3]: mean 2, population std sqrt(2/3)
+        assert!((z[2] - 1.0 / (2.0f64 / 3.0).sqrt()).abs() < 1e-12);
+        // Zero variance window
+        assert_eq!(z[5], 0.0);
+        assert!(rolling_zscore(&[1.0, 2.0], 3).iter().all(|v| v.is_nan()));
+    }
+
+    #[test]
+    fn test_rolling_correlation() {
+        let a = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let b = [2.0, 4.0, 6.0, 8.0, 10.0];
+        let inverse: Vec<f64> = b.iter().map(|x| -x).collect();
+
+        let corr = rolling_correlation(&a, &b, 3);
+        assert!(corr[..2].iter().all(|v| v.is_nan()));
+        assert!(corr[2..].iter().all(|v| (v - 1.0).abs() < 1e-12));
+        assert!((rolling_correlation(&a, &inverse, 3)[4] + 1.0).abs() < 1e-12);
+
+        let flat = rolling_correlation(&a, &[5.0; 5], 3);
+        assert!(flat.iter().all(|v| v.is_nan()));
+        assert_eq!(rolling_correlation(&a, &b[..4], 2).len(), 4);
+    }
 }
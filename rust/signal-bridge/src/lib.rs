@@ -6,25 +6,277 @@
 pub mod features;
 pub mod indicators;
 pub mod bridge;
+pub mod rules;
+pub mod model;
 
-pub use features::FeatureEngine;
+pub use features::{Feature, FeatureEngine, Scaler, ScalerMode, MISSING_FEATURE};
 pub use indicators::*;
+pub use rules::{Comparison, SignalRules, ThresholdRule};
+#[cfg(feature = "onnx")]
+pub use model::ModelRunner;
 
+use common::config::SignalConfig;
+use common::messaging::{topics, Message};
+use common::types::{Bar, Signal, SignalAction, Symbol};
 use common::Result;
+use market_data::{MarketDataPublisher, MarketDataSubscriber, MarketMessage};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// Bars kept per symbol for feature computation; enough to warm up MACD(12, 26, 9)
+const FEATURE_WINDOW_BARS: usize = 100;
+
+/// Model scoring a feature vector; `SignalRules` are used when it is absent or fails
+pub trait SignalModel: Send {
+    fn predict(&mut self, symbol: &Symbol, features: &[f64]) -> Result<(SignalAction, f64)>;
+}
 
 /// Main signal bridge service
 pub struct SignalBridgeService {
+    config: SignalConfig,
     feature_engine: FeatureEngine,
+    rules: SignalRules,
+    model: Option<Box<dyn SignalModel>>,
+    windows: HashMap<String, Vec<Bar>>,
+    last_update: HashMap<String, Instant>,
+    shutdown: CancellationToken,
 }
 
 impl SignalBridgeService {
-    pub fn new(_config: common::config::SignalConfig) -> Result<Self> {
+    /// Create the service, loading `model_path` in-process if it is an
+    /// `.onnx` model. Other models are served from Python via the PyO3 bridge.
+    pub fn new(config: SignalConfig) -> Result<Self> {
         let feature_engine = FeatureEngine::new();
+        let model = load_model(&config.model_path, feature_engine.feature_names().len())?;
 
         Ok(Self {
+            config,
             feature_engine,
+            rules: SignalRules::new(),
+            model,
+            windows: HashMap::new(),
+            last_update: HashMap::new(),
+            shutdown: CancellationToken::new(),
         })
     }
+
+    /// Rules used by `run` when no model is set or the model fails
+    pub fn with_rules(mut self, rules: SignalRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub fn with_model(mut self, model: Box<dyn SignalModel>) -> Self {
+        self.model = Some(model);
+        self
+    }
+
+    /// Token that stops `run` when cancelled
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown.clone()
+    }
+
+    /// Subscribe to bars, recompute features per symbol at most once every
+    /// `update_interval_ms`, and publish the resulting signals.
+    ///
+    /// Returns once the shutdown token is cancelled, or with an error if the
+    /// market data subscription closes.
+    pub async fn run(&mut self) -> Result<()> {
+        let subscriber = MarketDataSubscriber::connect(
+            &self.config.zmq_subscribe_address,
+            &[format!("{}.bar", topics::MARKET_DATA)],
+        )?;
+        let publisher = MarketDataPublisher::new(&self.config.zmq_publish_address)?;
+        self.run_with(subscriber, publisher).await
+    }
+
+    /// `run` over an existing subscription and publisher instead of the
+    /// configured addresses
+    pub async fn run_with(
+        &mut self,
+        mut subscriber: MarketDataSubscriber,
+        publisher: MarketDataPublisher,
+    ) -> Result<()> {
+        let shutdown = self.shutdown.clone();
+
+        info!("Signal Bridge running");
+
+        loop {
+            let message = tokio::select! {
+                _ = shutdown.cancelled() => {
+                    info!("Signal Bridge stopping");
+                    return Ok(());
+                }
+                message = subscriber.recv() => message,
+            };
+
+            let bar = match message {
+                Ok(MarketMessage::Bar(bar)) => bar,
+                Ok(_) => continue,
+                Err(e) if subscriber.is_closed() => return Err(e),
+                Err(e) => {
+                    warn!("Failed to receive market data: {}", e);
+                    continue;
+                }
+            };
+
+            if let Some(signal) = self.on_bar(bar, Instant::now()) {
+                if let Err(e) = publisher.publish(Message::SignalGenerated(signal)) {
+                    warn!("Failed to publish signal: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Add a bar to its symbol's window and, unless throttled, produce a signal
+    fn on_bar(&mut self, bar: Bar, now: Instant) -> Option<Signal> {
+        let symbol = bar.symbol.clone();
+        let window = self.windows.entry(symbol.0.clone()).or_default();
+        window.push(bar);
+        if window.len() > FEATURE_WINDOW_BARS {
+            window.remove(0);
+        }
+
+        let interval = Duration::from_millis(self.config.update_interval_ms);
+        if let Some(last) = self.last_update.get(&symbol.0) {
+            if now.duration_since(*last) < interval {
+                return None;
+            }
+        }
+        self.last_update.insert(symbol.0.clone(), now);
+
+        let features = self.feature_engine.compute(&self.windows[&symbol.0], None);
+        let prediction = self.model.as_mut().and_then(|model| {
+            model
+                .predict(&symbol, &features)
+                .map_err(|e| warn!("Model prediction for {} failed: {}", symbol, e))
+                .ok()
+        });
+
+        let signal = match prediction {
+            Some((action, confidence)) => Signal {
+                symbol,
+                action,
+                confidence,
+                features,
+                timestamp: chrono::Utc::now(),
+            },
+            None => self.generate_signal(&symbol, &features, &self.rules),
+        };
+        metrics::counter!("signals_generated_total").increment(1);
+        Some(signal)
+    }
+
+    /// Turn a feature vector into a `Signal` using threshold rules, without
+    /// a model. The features are carried on the signal unchanged.
+    pub fn generate_signal(&self, symbol: &Symbol, features: &[f64], rules: &SignalRules) -> Signal {
+        let (action, confidence) = rules.evaluate(features);
+        Signal {
+            symbol: symbol.clone(),
+            action,
+            confidence,
+            features: features.to_vec(),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+}
+
+#[cfg(feature = "onnx")]
+fn load_model(path: &str, feature_count: usize) -> Result<Option<Box<dyn SignalModel>>> {
+    if !path.ends_with(".onnx") {
+        return Ok(None);
+    }
+
+    let runner = ModelRunner::load(path)?;
+    if let Some(expected) = runner.input_len() {
+        if expected != feature_count {
+            return Err(common::TradingError::Configuration(format!(
+                "Model {} expects {} input features but the feature engine produces {}",
+                path, expected, feature_count
+            )));
+        }
+    }
+    info!("Loaded ONNX model {}", path);
+    Ok(Some(Box::new(runner)))
+}
+
+#[cfg(not(feature = "onnx"))]
+fn load_model(path: &str, _feature_count: usize) -> Result<Option<Box<dyn SignalModel>>> {
+    if path.ends_with(".onnx") {
+        warn!("{} is an ONNX model but signal-bridge was built without the onnx feature", path);
+    }
+    Ok(None)
 }
 
 // PyO3 module is defined in bridge.rs to avoid duplicate symbols
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::types::{Price, Quantity};
+
+    fn create_service(update_interval_ms: u64) -> SignalBridgeService {
+        SignalBridgeService::new(SignalConfig {
+            model_path: String::new(),
+            features: vec!["rsi_14".to_string()],
+            update_interval_ms,
+            zmq_subscribe_address: "tcp://127.0.0.1:5555".to_string(),
+            zmq_publish_address: "tcp://127.0.0.1:5556".to_string(),
+        })
+        .unwrap()
+    }
+
+    fn bar(symbol: &str, close: f64) -> Bar {
+        Bar {
+            symbol: Symbol(symbol.to_string()),
+            open: Price(close),
+            high: Price(close + 1.0),
+            low: Price(close - 1.0),
+            close: Price(close),
+            volume: Quantity(1000.0),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    struct AlwaysBuy;
+
+    impl SignalModel for AlwaysBuy {
+        fn predict(&mut self, _symbol: &Symbol, _features: &[f64]) -> Result<(SignalAction, f64)> {
+            Ok((SignalAction::Buy, 0.9))
+        }
+    }
+
+    #[test]
+    fn test_updates_are_throttled_per_symbol() {
+        let mut service = create_service(1000);
+        let start = Instant::now();
+
+        assert!(service.on_bar(bar("AAPL", 100.0), start).is_some());
+        assert!(service.on_bar(bar("AAPL", 101.0), start + Duration::from_millis(10)).is_none());
+        assert!(service.on_bar(bar("MSFT", 300.0), start + Duration::from_millis(10)).is_some());
+        assert!(service.on_bar(bar("AAPL", 102.0), start + Duration::from_millis(1000)).is_some());
+        assert_eq!(service.windows["AAPL"].len(), 3);
+    }
+
+    #[test]
+    fn test_model_takes_precedence_over_rules() {
+        let mut service = create_service(0);
+        let signal = service.on_bar(bar("AAPL", 100.0), Instant::now()).unwrap();
+        assert_eq!(signal.action, SignalAction::Hold);
+
+        let mut service = create_service(0).with_model(Box::new(AlwaysBuy));
+        let signal = service.on_bar(bar("AAPL", 100.0), Instant::now()).unwrap();
+        assert_eq!((signal.action, signal.confidence), (SignalAction::Buy, 0.9));
+    }
+
+    #[test]
+    fn test_window_is_bounded() {
+        let mut service = create_service(0);
+        for i in 0..(FEATURE_WINDOW_BARS + 10) {
+            service.on_bar(bar("AAPL", 100.0 + i as f64), Instant::now());
+        }
+        assert_eq!(service.windows["AAPL"].len(), FEATURE_WINDOW_BARS);
+    }
+}
@@ -16,7 +16,7 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("Signal Bridge Service starting...");
 
     // Load configuration with validation
-    let config = match SystemConfig::from_file("config/system.json") {
+    let config = match SystemConfig::from_file_with_env("config/system.json") {
         Ok(cfg) => {
             tracing::info!(
                 "Configuration loaded successfully - Environment: {}",
@@ -45,7 +45,7 @@ async fn main() -> anyhow::Result<()> {
     let features_count = config.signal.features.len();
 
     // Initialize service
-    let _service = match SignalBridgeService::new(config.signal) {
+    let mut service = match SignalBridgeService::new(config.signal) {
         Ok(svc) => {
             tracing::info!("✓ Signal Bridge initialized successfully");
             svc
@@ -69,9 +69,21 @@ async fn main() -> anyhow::Result<()> {
 
     tracing::info!("🚀 Signal Bridge ready for Python integration");
 
-    // Keep service running
-    tokio::signal::ctrl_c().await?;
-    tracing::info!("Shutdown signal received, stopping Signal Bridge...");
+    // Stop the run loop on Ctrl-C
+    let shutdown = service.shutdown_token();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            tracing::info!("Shutdown signal received, stopping Signal Bridge...");
+        }
+        shutdown.cancel();
+    });
+
+    if let Err(e) = service.run().await {
+        tracing::error!("Signal Bridge stopped with error: {}", e);
+        let mut h = health.write().await;
+        *h = HealthCheck::unhealthy("signal-bridge", format!("Run loop failed: {}", e));
+        return Err(anyhow::anyhow!("Signal Bridge error: {}", e));
+    }
 
     Ok(())
 }
@@ -0,0 +1,159 @@
+use common::{Result, TradingError, types::SignalAction};
+#[cfg(feature = "onnx")]
+use common::types::Symbol;
+#[cfg(feature = "onnx")]
+use crate::SignalModel;
+
+/// Map a model output vector to an action and confidence.
+///
+/// A single output is a score in [-1, 1]: positive buys, negative sells,
+/// and its magnitude is the confidence. Three outputs are class
+/// probabilities ordered sell, hold, buy; the most likely class wins.
+pub fn interpret_output(output: &[f64]) -> Result<(SignalAction, f64)> {
+    match output {
+        [score] if score.is_finite() => {
+            let action = if *score > 0.0 {
+                SignalAction::Buy
+            } else if *score < 0.0 {
+                SignalAction::Sell
+            } else {
+                SignalAction::Hold
+            };
+            Ok((action, score.abs().min(1.0)))
+        }
+        [sell, hold, buy] => {
+            let (action, prob) = [(SignalAction::Sell, *sell), (SignalAction::Hold, *hold), (SignalAction::Buy, *buy)]
+                .into_iter()
+                .filter(|(_, p)| p.is_finite())
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .ok_or_else(|| TradingError::Parse("Model returned no finite class probability".to_string()))?;
+            Ok((action, prob.clamp(0.0, 1.0)))
+        }
+        _ => Err(TradingError::Parse(format!(
+            "Unsupported model output {:?}: expected a score or 3 class probabilities",
+            output
+        ))),
+    }
+}
+
+/// Runs an ONNX model in-process with onnxruntime
+#[cfg(feature = "onnx")]
+pub struct ModelRunner {
+    session: ort::Session,
+    path: String,
+    input_name: String,
+    output_name: String,
+    input_len: Option<usize>,
+}
+
+#[cfg(feature = "onnx")]
+impl ModelRunner {
+    /// Load a model with a single `[batch, features]` (or `[features]`) float input
+    pub fn load(path: &str) -> Result<Self> {
+        let load_error = |e: ort::Error| TradingError::Configuration(format!("Failed to load model {}: {}", path, e));
+
+        let session = ort::Session::builder()
+            .and_then(|builder| builder.commit_from_file(path))
+            .map_err(load_error)?;
+
+        let (input_name, input_len) = match session.inputs.as_slice() {
+            [input] => {
+                let input_len = match &input.input_type {
+                    ort::ValueType::Tensor { dimensions, .. } => dimensions
+                        .last()
+                        .and_then(|&dim| usize::try_from(dim).ok())
+                        .filter(|&dim| dim > 0),
+                    other => {
+                        return Err(TradingError::Configuration(format!(
+                            "Model {} input must be a tensor, got {:?}",
+                            path, other
+                        )))
+                    }
+                };
+                (input.name.clone(), input_len)
+            }
+            inputs => {
+                return Err(TradingError::Configuration(format!(
+                    "Model {} must have exactly one input, has {}",
+                    path,
+                    inputs.len()
+                )))
+            }
+        };
+
+        let output_name = session
+            .outputs
+            .first()
+            .map(|output| output.name.clone())
+            .ok_or_else(|| TradingError::Configuration(format!("Model {} has no outputs", path)))?;
+
+        Ok(Self {
+            session,
+            path: path.to_string(),
+            input_name,
+            output_name,
+            input_len,
+        })
+    }
+
+    /// Number of features the model expects, if its input shape fixes it
+    pub fn input_len(&self) -> Option<usize> {
+        self.input_len
+    }
+
+    /// Run the model on one feature vector and return its first output, flattened
+    pub fn predict(&self, features: &[f64]) -> Result<Vec<f64>> {
+        if let Some(expected) = self.input_len {
+            if features.len() != expected {
+                return Err(TradingError::Configuration(format!(
+                    "Model {} expects {} input features, got {}",
+                    self.path,
+                    expected,
+                    features.len()
+                )));
+            }
+        }
+
+        let inference_error = |e: ort::Error| TradingError::Unknown(format!("Inference with {} failed: {}", self.path, e));
+
+        let input: Vec<f32> = features.iter().map(|&v| v as f32).collect();
+        let input = ndarray::Array2::from_shape_vec((1, features.len()), input)
+            .map_err(|e| TradingError::Unknown(e.to_string()))?;
+
+        let outputs = ort::inputs![self.input_name.as_str() => input.view()]
+            .and_then(|inputs| self.session.run(inputs))
+            .map_err(inference_error)?;
+        let output = outputs[self.output_name.as_str()]
+            .try_extract_tensor::<f32>()
+            .map_err(inference_error)?;
+
+        Ok(output.iter().map(|&v| v as f64).collect())
+    }
+}
+
+#[cfg(feature = "onnx")]
+impl SignalModel for ModelRunner {
+    fn predict(&mut self, _symbol: &Symbol, features: &[f64]) -> Result<(SignalAction, f64)> {
+        interpret_output(&ModelRunner::predict(self, features)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpret_score() {
+        assert_eq!(interpret_output(&[0.4]).unwrap(), (SignalAction::Buy, 0.4));
+        assert_eq!(interpret_output(&[-2.0]).unwrap(), (SignalAction::Sell, 1.0));
+        assert_eq!(interpret_output(&[0.0]).unwrap().0, SignalAction::Hold);
+        assert!(interpret_output(&[f64::NAN]).is_err());
+    }
+
+    #[test]
+    fn test_interpret_class_probabilities() {
+        assert_eq!(interpret_output(&[0.1, 0.2, 0.7]).unwrap(), (SignalAction::Buy, 0.7));
+        assert_eq!(interpret_output(&[0.6, 0.3, 0.1]).unwrap(), (SignalAction::Sell, 0.6));
+        assert!(interpret_output(&[0.5, 0.5]).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+use common::types::SignalAction;
+use serde::{Deserialize, Serialize};
+
+/// Confidence reported with `Hold` when no rule fires
+pub const DEFAULT_HOLD_CONFIDENCE: f64 = 0.1;
+
+/// Which side of the threshold triggers a rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparison {
+    Above,
+    Below,
+}
+
+/// Fires `action` when feature `feature` crosses `threshold`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdRule {
+    /// Column index into the feature vector
+    pub feature: usize,
+    pub comparison: Comparison,
+    pub threshold: f64,
+    pub action: SignalAction,
+    /// Margin past the threshold at which confidence reaches 1.0
+    pub full_confidence_margin: f64,
+}
+
+impl ThresholdRule {
+    pub fn above(feature: usize, threshold: f64, action: SignalAction, full_confidence_margin: f64) -> Self {
+        Self { feature, comparison: Comparison::Above, threshold, action, full_confidence_margin }
+    }
+
+    pub fn below(feature: usize, threshold: f64, action: SignalAction, full_confidence_margin: f64) -> Self {
+        Self { feature, comparison: Comparison::Below, threshold, action, full_confidence_margin }
+    }
+
+    /// Confidence in (0, 1] if the rule fires, `None` otherwise.
+    /// Missing (NaN) or out-of-range features never fire.
+    fn confidence(&self, features: &[f64]) -> Option<f64> {
+        let value = *features.get(self.feature)?;
+        let margin = match self.comparison {
+            Comparison::Above => value - self.threshold,
+            Comparison::Below => self.threshold - value,
+        };
+        if margin.is_nan() || margin <= 0.0 {
+            return None;
+        }
+        if self.full_confidence_margin <= 0.0 || !self.full_confidence_margin.is_finite() {
+            return Some(1.0);
+        }
+        Some((margin / self.full_confidence_margin).min(1.0))
+    }
+}
+
+/// Threshold rules mapping a feature vector to Buy/Sell/Hold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignalRules {
+    pub rules: Vec<ThresholdRule>,
+    /// Confidence reported with `Hold` when no rule fires
+    pub hold_confidence: f64,
+}
+
+impl SignalRules {
+    pub fn new() -> Self {
+        Self {
+            rules: Vec::new(),
+            hold_confidence: DEFAULT_HOLD_CONFIDENCE,
+        }
+    }
+
+    pub fn with_rule(mut self, rule: ThresholdRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Evaluate every rule; the firing rule with the highest confidence wins.
+    /// Returns `Hold` with `hold_confidence` when nothing fires.
+    pub fn evaluate(&self, features: &[f64]) -> (SignalAction, f64) {
+        self.rules
+            .iter()
+            .filter_map(|rule| rule.confidence(features).map(|c| (rule.action, c)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or((SignalAction::Hold, self.hold_confidence))
+    }
+}
+
+impl Default for SignalRules {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rsi_rules() -> SignalRules {
+        SignalRules::new()
+            .with_rule(ThresholdRule::below(0, 30.0, SignalAction::Buy, 20.0))
+            .with_rule(ThresholdRule::above(0, 70.0, SignalAction::Sell, 20.0))
+    }
+
+    #[test]
+    fn test_confidence_scales_with_margin() {
+        let rules = rsi_rules();
+        assert_eq!(rules.evaluate(&[25.0]), (SignalAction::Buy, 0.25));
+        assert_eq!(rules.evaluate(&[95.0]), (SignalAction::Sell, 1.0));
+    }
+
+    #[test]
+    fn test_hold_when_no_rule_fires() {
+        let rules = rsi_rules();
+        assert_eq!(rules.evaluate(&[50.0]), (SignalAction::Hold, DEFAULT_HOLD_CONFIDENCE));
+        assert_eq!(rules.evaluate(&[f64::NAN]), (SignalAction::Hold, DEFAULT_HOLD_CONFIDENCE));
+        assert_eq!(rules.evaluate(&[]), (SignalAction::Hold, DEFAULT_HOLD_CONFIDENCE));
+    }
+
+    #[test]
+    fn test_strongest_rule_wins() {
+        let rules = rsi_rules().with_rule(ThresholdRule::above(1, 0.0, SignalAction::Buy, 0.01));
+        let (action, confidence) = rules.evaluate(&[72.0, 0.02]);
+        assert_eq!(action, SignalAction::Buy);
+        assert_eq!(confidence, 1.0);
+    }
+}
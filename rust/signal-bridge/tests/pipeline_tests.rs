@@ -0,0 +1,153 @@
+// End-to-end replay: recorded bars -> market data publisher -> signal bridge
+// -> execution engine, in-process over ephemeral ZMQ ports
+use chrono::{Duration as ChronoDuration, TimeZone, Utc};
+use common::config::{ExecutionConfig, SignalConfig};
+use common::messaging::{topics, Message};
+use common::types::{Bar, OrderStatus, Price, Quantity, Side, SignalAction, Symbol};
+use database::{CandleRecord, DatabaseManager, PoolConfig, TimeInterval};
+use execution_engine::{ExecutionEngineService, OrderEvent};
+use market_data::{MarketDataPublisher, MarketDataSubscriber};
+use signal_bridge::{SignalBridgeService, SignalRules, ThresholdRule};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+const EPHEMERAL: &str = "tcp://127.0.0.1:*";
+
+/// Store a steadily rising minute series in an in-memory DuckDB and read it
+/// back as bars, oldest first
+async fn recorded_bars(symbol: &str, count: usize) -> Vec<Bar> {
+    // One connection, since every in-memory connection is its own database
+    let db = DatabaseManager::with_config(":memory:", PoolConfig { max_size: 1, ..Default::default() })
+        .await
+        .unwrap();
+    db.initialize().await.unwrap();
+
+    let start = Utc.with_ymd_and_hms(2024, 1, 2, 14, 30, 0).unwrap();
+    for i in 0..count {
+        let close = 100.0 + i as f64;
+        let candle = CandleRecord::new(
+            start + ChronoDuration::minutes(i as i64),
+            symbol,
+            close - 0.5,
+            close + 0.5,
+            close - 1.0,
+            close,
+            1_000,
+        );
+        db.insert_candle(&candle).await.unwrap();
+    }
+
+    let mut candles = db
+        .get_candles(symbol, TimeInterval::Minute, None, count as i64)
+        .await
+        .unwrap();
+    candles.reverse();
+    candles
+        .into_iter()
+        .map(|c| Bar {
+            symbol: Symbol(c.symbol),
+            open: Price(c.open),
+            high: Price(c.high),
+            low: Price(c.low),
+            close: Price(c.close),
+            volume: Quantity(c.volume as f64),
+            timestamp: c.timestamp,
+        })
+        .collect()
+}
+
+fn paper_config() -> ExecutionConfig {
+    ExecutionConfig {
+        exchange_api_url: "https://paper-api.alpaca.markets".to_string(),
+        api_key: None,
+        api_secret: None,
+        rate_limit_per_second: 100,
+        retry_attempts: 1,
+        retry_delay_ms: 100,
+        paper_trading: true,
+        max_slippage_bps: 50.0,
+        symbol_max_slippage_bps: HashMap::new(),
+        fee_model: Default::default(),
+        instruments: Default::default(),
+        session: None,
+        max_order_age_secs: None,
+    }
+}
+
+#[tokio::test]
+async fn test_replayed_bars_become_routed_orders() {
+    let bars = recorded_bars("AAPL", 20).await;
+    assert_eq!(bars.len(), 20);
+    assert!(bars.windows(2).all(|pair| pair[0].timestamp < pair[1].timestamp));
+
+    // Market data feed
+    let market = MarketDataPublisher::new(EPHEMERAL).unwrap();
+
+    // Signal bridge buying on any positive log return (feature 0)
+    let mut bridge = SignalBridgeService::new(SignalConfig {
+        model_path: String::new(),
+        features: vec!["log_return".to_string()],
+        update_interval_ms: 0,
+        zmq_subscribe_address: market.address().to_string(),
+        zmq_publish_address: EPHEMERAL.to_string(),
+    })
+    .unwrap()
+    .with_rules(SignalRules::new().with_rule(ThresholdRule::above(0, 0.0, SignalAction::Buy, 0.01)));
+    let bridge_shutdown = bridge.shutdown_token();
+    let bars_in =
+        MarketDataSubscriber::connect(market.address(), &[format!("{}.bar", topics::MARKET_DATA)]).unwrap();
+    let signals_out = MarketDataPublisher::new(EPHEMERAL).unwrap();
+    let signal_address = signals_out.address().to_string();
+    let bridge_task = tokio::spawn(async move { bridge.run_with(bars_in, signals_out).await });
+
+    // Paper-mode execution engine trading 10 shares per signal
+    let engine = Arc::new(
+        ExecutionEngineService::new(paper_config())
+            .await
+            .unwrap()
+            .with_signal_orders(Quantity(10.0)),
+    );
+    let mut orders = engine.tracker().subscribe();
+    let signals_in = MarketDataSubscriber::connect(&signal_address, &[topics::SIGNALS.to_string()]).unwrap();
+    let engine_task = tokio::spawn({
+        let engine = engine.clone();
+        async move { engine.run_signals(signals_in).await }
+    });
+
+    // Replay until an order fills; early rounds are dropped while both
+    // subscriptions propagate (ZMQ slow joiner)
+    let mut filled = None;
+    for _ in 0..50 {
+        for bar in &bars {
+            market.publish(Message::BarUpdate(bar.clone())).unwrap();
+        }
+
+        let deadline = tokio::time::sleep(Duration::from_millis(100));
+        tokio::pin!(deadline);
+        while filled.is_none() {
+            tokio::select! {
+                _ = &mut deadline => break,
+                event = orders.recv() => {
+                    if let OrderEvent::Fill(order) = event.unwrap() {
+                        filled = Some(order);
+                    }
+                }
+            }
+        }
+        if filled.is_some() {
+            break;
+        }
+    }
+
+    let order = filled.expect("no signal order was filled");
+    assert_eq!(order.symbol, Symbol("AAPL".to_string()));
+    assert_eq!(order.side, Side::Bid);
+    assert_eq!(order.quantity, Quantity(10.0));
+    assert_eq!(order.status, OrderStatus::Filled);
+
+    bridge_shutdown.cancel();
+    engine.shutdown().begin();
+    bridge_task.await.unwrap().unwrap();
+    engine_task.await.unwrap();
+}
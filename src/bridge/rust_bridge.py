@@ -156,6 +156,56 @@ class RustFeatureComputer:
             logger.error(f"Error computing microstructure features: {e}")
             raise
 
+    def compute_features_batch(self, bars: List[MarketBar], window: int) -> List[List[float]]:
+        """
+        Compute model features for every rolling window of bars in one Rust call.
+
+        Args:
+            bars: List of market data bars, oldest first
+            window: Number of bars in each window
+
+        Returns:
+            One feature row per window (len(bars) - window + 1 rows), with
+            columns ordered as in ``feature_names()``. Missing values are NaN.
+        """
+        try:
+            from signal_bridge import compute_features_batch
+            rust_bars = [bar.to_rust_bar() for bar in bars]
+            features = compute_features_batch(rust_bars, window)
+            logger.debug(f"Computed {len(features)} rolling feature rows for {len(bars)} bars")
+            return features
+        except Exception as e:
+            logger.error(f"Error computing rolling batch features: {e}")
+            raise
+
+    def compute_features_batch_np(self, ohlcv, window: int):
+        """
+        NumPy variant of ``compute_features_batch``.
+
+        Args:
+            ohlcv: float64 array of shape (n, 5) with columns
+                open, high, low, close, volume
+            window: Number of bars in each window
+
+        Returns:
+            float64 array of shape (n - window + 1, len(feature_names()))
+        """
+        try:
+            import numpy as np
+            from signal_bridge import compute_features_batch_np
+            ohlcv = np.ascontiguousarray(ohlcv, dtype=np.float64)
+            features = compute_features_batch_np(ohlcv, window)
+            logger.debug(f"Computed {features.shape[0]} rolling feature rows for {ohlcv.shape[0]} bars")
+            return features
+        except Exception as e:
+            logger.error(f"Error computing rolling batch features: {e}")
+            raise
+
+    def feature_names(self) -> List[str]:
+        """Column names of ``compute_features_batch`` rows, in order."""
+        from signal_bridge import batch_feature_names
+        return batch_feature_names()
+
 
 def test_rust_bridge():
     """Test the Rust bridge functionality."""
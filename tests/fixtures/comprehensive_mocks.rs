@@ -5,216 +5,9 @@ use common::types::*;
 use chrono::{Utc, Duration};
 use rand::Rng;
 
-/// Mock data builder for orders
-pub struct OrderBuilder {
-    id: String,
-    symbol: String,
-    side: OrderSide,
-    order_type: OrderType,
-    quantity: i32,
-    price: Option<f64>,
-    status: OrderStatus,
-}
-
-impl OrderBuilder {
-    pub fn new() -> Self {
-        Self {
-            id: "test-order".to_string(),
-            symbol: "AAPL".to_string(),
-            side: OrderSide::Buy,
-            order_type: OrderType::Market,
-            quantity: 100,
-            price: None,
-            status: OrderStatus::Pending,
-        }
-    }
-
-    pub fn id(mut self, id: &str) -> Self {
-        self.id = id.to_string();
-        self
-    }
-
-    pub fn symbol(mut self, symbol: &str) -> Self {
-        self.symbol = symbol.to_string();
-        self
-    }
-
-    pub fn side(mut self, side: OrderSide) -> Self {
-        self.side = side;
-        self
-    }
-
-    pub fn limit_order(mut self, price: f64) -> Self {
-        self.order_type = OrderType::Limit;
-        self.price = Some(price);
-        self
-    }
-
-    pub fn market_order(mut self) -> Self {
-        self.order_type = OrderType::Market;
-        self.price = None;
-        self
-    }
-
-    pub fn quantity(mut self, qty: i32) -> Self {
-        self.quantity = qty;
-        self
-    }
-
-    pub fn status(mut self, status: OrderStatus) -> Self {
-        self.status = status;
-        self
-    }
-
-    pub fn build(self) -> Order {
-        Order {
-            id: self.id,
-            symbol: self.symbol,
-            side: self.side,
-            order_type: self.order_type,
-            quantity: self.quantity,
-            price: self.price,
-            status: self.status,
-            timestamp: Utc::now(),
-        }
-    }
-}
-
-/// Mock data builder for positions
-pub struct PositionBuilder {
-    symbol: String,
-    quantity: i32,
-    average_price: f64,
-    current_price: f64,
-}
-
-impl PositionBuilder {
-    pub fn new() -> Self {
-        Self {
-            symbol: "AAPL".to_string(),
-            quantity: 100,
-            average_price: 150.00,
-            current_price: 155.00,
-        }
-    }
-
-    pub fn symbol(mut self, symbol: &str) -> Self {
-        self.symbol = symbol.to_string();
-        self
-    }
-
-    pub fn quantity(mut self, qty: i32) -> Self {
-        self.quantity = qty;
-        self
-    }
-
-    pub fn average_price(mut self, price: f64) -> Self {
-        self.average_price = price;
-        self
-    }
-
-    pub fn current_price(mut self, price: f64) -> Self {
-        self.current_price = price;
-        self
-    }
-
-    pub fn build(self) -> Position {
-        let unrealized_pnl = (self.current_price - self.average_price) * self.quantity as f64;
-
-        Position {
-            symbol: self.symbol,
-            quantity: self.quantity,
-            average_price: self.average_price,
-            current_price: self.current_price,
-            unrealized_pnl,
-            realized_pnl: 0.0,
-            timestamp: Utc::now(),
-        }
-    }
-
-    pub fn with_profit(self, profit_percent: f64) -> Self {
-        let current_price = self.average_price * (1.0 + profit_percent / 100.0);
-        self.current_price(current_price)
-    }
-
-    pub fn with_loss(self, loss_percent: f64) -> Self {
-        let current_price = self.average_price * (1.0 - loss_percent / 100.0);
-        self.current_price(current_price)
-    }
-}
-
-/// Mock trade generator
-pub struct TradeBuilder {
-    id: String,
-    order_id: String,
-    symbol: String,
-    side: OrderSide,
-    quantity: i32,
-    price: f64,
-    commission: f64,
-}
-
-impl TradeBuilder {
-    pub fn new() -> Self {
-        Self {
-            id: "trade-1".to_string(),
-            order_id: "order-1".to_string(),
-            symbol: "AAPL".to_string(),
-            side: OrderSide::Buy,
-            quantity: 100,
-            price: 150.00,
-            commission: 1.00,
-        }
-    }
-
-    pub fn id(mut self, id: &str) -> Self {
-        self.id = id.to_string();
-        self
-    }
-
-    pub fn order_id(mut self, order_id: &str) -> Self {
-        self.order_id = order_id.to_string();
-        self
-    }
-
-    pub fn symbol(mut self, symbol: &str) -> Self {
-        self.symbol = symbol.to_string();
-        self
-    }
-
-    pub fn side(mut self, side: OrderSide) -> Self {
-        self.side = side;
-        self
-    }
-
-    pub fn quantity(mut self, qty: i32) -> Self {
-        self.quantity = qty;
-        self
-    }
-
-    pub fn price(mut self, price: f64) -> Self {
-        self.price = price;
-        self
-    }
-
-    pub fn commission(mut self, comm: f64) -> Self {
-        self.commission = comm;
-        self
-    }
-
-    pub fn build(self) -> Trade {
-        Trade {
-            id: self.id,
-            order_id: self.order_id,
-            symbol: self.symbol,
-            side: self.side,
-            quantity: self.quantity,
-            price: self.price,
-            commission: self.commission,
-            timestamp: Utc::now(),
-        }
-    }
-}
+/// Domain object builders live in `common::builders` so every suite
+/// constructs the real `Order`/`Position`/`Trade` types the same way
+pub use common::builders::{OrderBuilder, PositionBuilder, TradeBuilder};
 
 /// Random data generators
 pub struct RandomGenerator;
@@ -223,33 +16,18 @@ impl RandomGenerator {
     pub fn random_order() -> Order {
         let mut rng = rand::thread_rng();
 
-        let side = if rng.gen_bool(0.5) {
-            OrderSide::Buy
-        } else {
-            OrderSide::Sell
-        };
+        let side = if rng.gen_bool(0.5) { Side::Bid } else { Side::Ask };
 
-        let order_type = if rng.gen_bool(0.5) {
-            OrderType::Market
-        } else {
-            OrderType::Limit
-        };
+        let builder = OrderBuilder::new()
+            .order_id(&format!("order-{}", rng.gen::<u64>()))
+            .symbol(&Self::random_symbol())
+            .side(side)
+            .quantity(rng.gen_range(1..1000) as f64);
 
-        let price = if matches!(order_type, OrderType::Limit) {
-            Some(rng.gen_range(50.0..500.0))
+        if rng.gen_bool(0.5) {
+            builder.market().build()
         } else {
-            None
-        };
-
-        Order {
-            id: format!("order-{}", rng.gen::<u64>()),
-            symbol: Self::random_symbol(),
-            side,
-            order_type,
-            quantity: rng.gen_range(1..1000),
-            price,
-            status: OrderStatus::Pending,
-            timestamp: Utc::now(),
+            builder.limit(rng.gen_range(50.0..500.0)).build()
         }
     }
 
@@ -431,18 +209,18 @@ impl ScenarioGenerator {
             let sell_price = buy_price + 5.0; // $5 profit per share
 
             trades.push(TradeBuilder::new()
-                .id(&format!("buy-{}", i))
+                .trade_id(&format!("buy-{}", i))
                 .symbol(symbol)
-                .side(OrderSide::Buy)
-                .quantity(100)
+                .side(Side::Bid)
+                .quantity(100.0)
                 .price(buy_price)
                 .build());
 
             trades.push(TradeBuilder::new()
-                .id(&format!("sell-{}", i))
+                .trade_id(&format!("sell-{}", i))
                 .symbol(symbol)
-                .side(OrderSide::Sell)
-                .quantity(100)
+                .side(Side::Ask)
+                .quantity(100.0)
                 .price(sell_price)
                 .build());
         }
@@ -459,18 +237,18 @@ impl ScenarioGenerator {
             let sell_price = buy_price - 3.0; // $3 loss per share
 
             trades.push(TradeBuilder::new()
-                .id(&format!("buy-{}", i))
+                .trade_id(&format!("buy-{}", i))
                 .symbol(symbol)
-                .side(OrderSide::Buy)
-                .quantity(100)
+                .side(Side::Bid)
+                .quantity(100.0)
                 .price(buy_price)
                 .build());
 
             trades.push(TradeBuilder::new()
-                .id(&format!("sell-{}", i))
+                .trade_id(&format!("sell-{}", i))
                 .symbol(symbol)
-                .side(OrderSide::Sell)
-                .quantity(100)
+                .side(Side::Ask)
+                .quantity(100.0)
                 .price(sell_price)
                 .build());
         }
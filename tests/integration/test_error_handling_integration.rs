@@ -163,6 +163,9 @@ mod error_handling_tests {
             trailing_stop_percent: 3.0,
             enable_circuit_breaker: true,
             max_loss_threshold: 1000.0,
+            circuit_breaker_cooldown_secs: 300,
+            max_price_velocity_pct: 10.0,
+            max_leverage: 2.0,
         };
 
         let mut stop_manager = StopManager::new(risk_config);
@@ -439,6 +442,9 @@ mod error_handling_tests {
             // Error messages should not be empty
             assert!(!msg.is_empty());
 
+            // Every error carries a stable code for alerting
+            assert!(err.error_code().starts_with("E_"));
+
             // Error messages should contain useful context
             println!("Error: {}", msg);
         }
@@ -457,6 +463,9 @@ mod error_handling_tests {
             trailing_stop_percent: 3.0,
             enable_circuit_breaker: true,
             max_loss_threshold: 1000.0,
+            circuit_breaker_cooldown_secs: 300,
+            max_price_velocity_pct: 10.0,
+            max_leverage: 2.0,
         };
 
         assert!(config.enable_circuit_breaker);
@@ -480,6 +489,9 @@ mod error_handling_tests {
             trailing_stop_percent: 3.0,
             enable_circuit_breaker: true,
             max_loss_threshold: 1000.0,
+            circuit_breaker_cooldown_secs: 300,
+            max_price_velocity_pct: 10.0,
+            max_leverage: 2.0,
         };
 
         // Attempt to create position larger than limit
@@ -27,6 +27,9 @@ mod risk_execution_observability_tests {
             trailing_stop_percent: 3.0,
             enable_circuit_breaker: true,
             max_loss_threshold: 1000.0,
+            circuit_breaker_cooldown_secs: 300,
+            max_price_velocity_pct: 10.0,
+            max_leverage: 2.0,
         };
 
         let exec_config = ExecutionConfig {
@@ -0,0 +1,63 @@
+"""
+Benchmark of the NumPy zero-copy indicator path against the list-based API.
+
+Requires the signal_bridge extension module:
+    cd rust && maturin develop --release -m signal-bridge/Cargo.toml
+"""
+
+import time
+
+import numpy as np
+import pytest
+
+signal_bridge = pytest.importorskip("signal_bridge")
+
+N = 1_000_000
+PERIOD = 14
+
+
+@pytest.fixture(scope="module")
+def prices():
+    rng = np.random.default_rng(42)
+    return 100.0 * np.exp(np.cumsum(rng.normal(0.0, 0.001, N)))
+
+
+def _timed(fn, *args):
+    start = time.perf_counter()
+    result = fn(*args)
+    return result, time.perf_counter() - start
+
+
+def test_rsi_numpy_matches_list(prices):
+    np_result = signal_bridge.rsi_np(prices[:1000], PERIOD)
+    list_result = signal_bridge.rsi_list(prices[:1000].tolist(), PERIOD)
+    assert isinstance(np_result, np.ndarray)
+    np.testing.assert_allclose(np_result, list_result)
+
+
+def test_rsi_numpy_faster_than_list(prices):
+    _, np_elapsed = _timed(signal_bridge.rsi_np, prices, PERIOD)
+    _, list_elapsed = _timed(signal_bridge.rsi_list, prices.tolist(), PERIOD)
+
+    print(f"\nRSI over {N:,} prices: numpy {np_elapsed * 1000:.1f}ms, list {list_elapsed * 1000:.1f}ms")
+    assert np_elapsed < list_elapsed
+
+
+def test_non_contiguous_input(prices):
+    strided = prices[:2000:2]
+    np.testing.assert_allclose(
+        signal_bridge.rsi_np(strided, PERIOD),
+        signal_bridge.rsi_np(np.ascontiguousarray(strided), PERIOD),
+    )
+
+
+def test_features_batch_np_shape():
+    n, window = 500, 50
+    close = np.linspace(100.0, 110.0, n)
+    ohlcv = np.column_stack([close, close + 0.5, close - 0.5, close, np.full(n, 1e6)])
+
+    features = signal_bridge.compute_features_batch_np(ohlcv, window)
+    assert features.shape == (n - window + 1, len(signal_bridge.batch_feature_names()))
+
+    with pytest.raises(ValueError):
+        signal_bridge.compute_features_batch_np(ohlcv[:, :4], window)